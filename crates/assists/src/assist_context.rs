@@ -6,7 +6,7 @@ use algo::find_covering_element;
 use base_db::{FileId, FileRange};
 use hir::Semantics;
 use ide_db::{
-    source_change::{SourceChange, SourceFileEdit},
+    source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
     RootDatabase,
 };
 use syntax::{
@@ -221,15 +221,21 @@ impl AssistBuilder {
     }
 
     pub(crate) fn edit_file(&mut self, file_id: FileId) {
+        self.commit();
         self.file_id = file_id;
     }
 
     fn commit(&mut self) {
         let edit = mem::take(&mut self.edit).finish();
-        if !edit.is_empty() {
-            let new_edit = SourceFileEdit { file_id: self.file_id, edit };
-            assert!(!self.change.source_file_edits.iter().any(|it| it.file_id == new_edit.file_id));
-            self.change.source_file_edits.push(new_edit);
+        if edit.is_empty() {
+            return;
+        }
+        let file_id = self.file_id;
+        match self.change.source_file_edits.iter_mut().find(|it| it.file_id == file_id) {
+            Some(existing) => {
+                assert!(existing.edit.union(edit).is_ok(), "overlapping edits in the same file")
+            }
+            None => self.change.source_file_edits.push(SourceFileEdit { file_id, edit }),
         }
     }
 
@@ -268,6 +274,23 @@ impl AssistBuilder {
     pub(crate) fn replace_ast<N: AstNode>(&mut self, old: N, new: N) {
         algo::diff(old.syntax(), new.syntax()).into_text_edit(&mut self.edit)
     }
+    /// Creates a file at `dst`, a path relative to the `anchor` file.
+    pub(crate) fn create_file(
+        &mut self,
+        anchor: FileId,
+        dst: impl Into<String>,
+        content: impl Into<String>,
+    ) {
+        let file_system_edit = FileSystemEdit::CreateFile {
+            anchor,
+            dst: dst.into(),
+            initial_contents: content.into(),
+        };
+        self.change.file_system_edits.push(file_system_edit);
+    }
+    pub(crate) fn delete_file(&mut self, file: FileId) {
+        self.change.file_system_edits.push(FileSystemEdit::DeleteFile { file });
+    }
     pub(crate) fn rewrite(&mut self, rewriter: SyntaxRewriter) {
         let node = rewriter.rewrite_root().unwrap();
         let new = rewriter.rewrite(&node);
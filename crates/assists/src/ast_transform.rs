@@ -48,6 +48,15 @@ pub struct SubstituteTypeParams<'a> {
 }
 
 impl<'a> SubstituteTypeParams<'a> {
+    /// Substitutes the given type parameters, like the generic parameters of
+    /// a function with the types it is called with.
+    pub fn new(
+        source_scope: &'a SemanticsScope<'a>,
+        substs: FxHashMap<hir::TypeParam, ast::Type>,
+    ) -> SubstituteTypeParams<'a> {
+        SubstituteTypeParams { source_scope, substs, previous: Box::new(NullTransformer) }
+    }
+
     pub fn for_trait_impl(
         source_scope: &'a SemanticsScope<'a>,
         // FIXME: there's implicit invariant that `trait_` and  `source_scope` match...
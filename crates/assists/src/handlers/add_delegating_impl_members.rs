@@ -0,0 +1,595 @@
+use hir::{HasSource, HirDisplay};
+use syntax::ast::{
+    self,
+    edit::{self, AstNodeEdit, IndentLevel},
+    make, AstNode, NameOwner,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    ast_transform::{self, AstTransform, QualifyPaths, SubstituteTypeParams},
+    utils::{get_missing_assoc_items, render_snippet, resolve_target_trait, Cursor},
+    AssistId, AssistKind, GroupLabel,
+};
+
+// Assist: add_delegating_impl_members
+//
+// Implements the missing members of a trait by delegating to a field which
+// implements the trait.
+//
+// ```
+// trait Shape {
+//     type Unit;
+//     fn area(&self) -> f64;
+// }
+//
+// struct Square(f64);
+// impl Shape for Square {
+//     type Unit = ();
+//     fn area(&self) -> f64 { self.0 * self.0 }
+// }
+//
+// struct Labeled(Square);
+// impl Shape for Labeled {<|>}
+// ```
+// ->
+// ```
+// trait Shape {
+//     type Unit;
+//     fn area(&self) -> f64;
+// }
+//
+// struct Square(f64);
+// impl Shape for Square {
+//     type Unit = ();
+//     fn area(&self) -> f64 { self.0 * self.0 }
+// }
+//
+// struct Labeled(Square);
+// impl Shape for Labeled {
+//     $0type Unit = <Square as Shape>::Unit;
+//
+//     fn area(&self) -> f64 {
+//         <Square as Shape>::area(&self.0)
+//     }
+// }
+// ```
+pub(crate) fn add_delegating_impl_members(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let db = ctx.db();
+    let impl_def = ctx.find_node_at_offset::<ast::Impl>()?;
+    let impl_item_list = impl_def.assoc_item_list()?;
+    let trait_ = resolve_target_trait(&ctx.sema, &impl_def)?;
+    let trait_ty = impl_def.trait_()?;
+
+    let missing_items = get_missing_assoc_items(&ctx.sema, &impl_def);
+    if missing_items.is_empty() {
+        return None;
+    }
+
+    let impl_ = ctx.sema.to_def(&impl_def)?;
+    let self_ty = impl_.target_ty(db);
+    let strukt = match self_ty.as_adt()? {
+        hir::Adt::Struct(it) => it,
+        _ => return None,
+    };
+    let n_fields = strukt.fields(db).len();
+    let trait_args = impl_.target_trait_args(db);
+    let module = ctx.sema.scope(impl_def.syntax()).module()?;
+
+    let source_scope = ctx.sema.scope_for_def(trait_);
+    let target_scope = ctx.sema.scope(impl_item_list.syntax());
+    let ast_transform = QualifyPaths::new(&target_scope, &source_scope)
+        .or(SubstituteTypeParams::for_trait_impl(&source_scope, trait_, impl_def.clone()));
+    let missing_items: Vec<_> = missing_items
+        .iter()
+        .map(|it| match it {
+            hir::AssocItem::Function(it) => ast::AssocItem::Fn(it.source(db).value),
+            hir::AssocItem::TypeAlias(it) => ast::AssocItem::TypeAlias(it.source(db).value),
+            hir::AssocItem::Const(it) => ast::AssocItem::Const(it.source(db).value),
+        })
+        .map(|it| ast_transform::apply(&*ast_transform, it))
+        .collect();
+
+    let group = GroupLabel("Implement missing members by delegating".to_string());
+    let target = impl_def.syntax().text_range();
+    for (field, field_ty) in self_ty.fields(db) {
+        if !field_ty.impls_trait(db, trait_, &trait_args) {
+            continue;
+        }
+        let field_name = field.name(db).to_string();
+        let field_ty = match field_ty.display_source_code(db, module.into()) {
+            Ok(it) => it,
+            Err(_) => continue,
+        };
+        let delegate = Delegate {
+            field_name: &field_name,
+            field_ty: &field_ty,
+            trait_ty: &trait_ty,
+            is_newtype: n_fields == 1,
+            is_tuple: field_name.parse::<usize>().is_ok(),
+        };
+        // Members which can't be delegated are left to `add_missing_impl_members`.
+        let items: Vec<_> = missing_items.iter().filter_map(|it| delegate.item(it)).collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        acc.add_group(
+            &group,
+            AssistId("add_delegating_impl_members", AssistKind::Generate),
+            format!("Implement missing members by delegating to `self.{}`", field_name),
+            target,
+            |builder| {
+                let n_existing_items = impl_item_list.assoc_items().count();
+                let items = items.into_iter().map(|it| edit::remove_attrs_and_docs(&it));
+                let new_impl_item_list = impl_item_list.append_items(items);
+                let first_new_item = match new_impl_item_list.assoc_items().nth(n_existing_items) {
+                    Some(it) => it,
+                    None => return,
+                };
+
+                let original_range = impl_item_list.syntax().text_range();
+                match ctx.config.snippet_cap {
+                    None => builder.replace(original_range, new_impl_item_list.to_string()),
+                    Some(cap) => builder.replace_snippet(
+                        cap,
+                        original_range,
+                        render_snippet(
+                            cap,
+                            new_impl_item_list.syntax(),
+                            Cursor::Before(first_new_item.syntax()),
+                        ),
+                    ),
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+/// Renders members of the trait which forward to a field.
+struct Delegate<'a> {
+    field_name: &'a str,
+    field_ty: &'a str,
+    trait_ty: &'a ast::Type,
+    /// Whether the field is the only field, so that `Self` can be built from
+    /// the result of a delegated call.
+    is_newtype: bool,
+    is_tuple: bool,
+}
+
+impl Delegate<'_> {
+    /// Returns the delegating version of `item`, or `None` if it can't be
+    /// delegated.
+    fn item(&self, item: &ast::AssocItem) -> Option<ast::AssocItem> {
+        let res = match item {
+            ast::AssocItem::Fn(it) => {
+                let body = make::block_expr(None, Some(self.fn_body(it)?)).indent(IndentLevel(1));
+                ast::AssocItem::Fn(it.with_body(body))
+            }
+            ast::AssocItem::TypeAlias(it) => {
+                let name = it.name()?;
+                let text = format!("type {} = {}::{};", name, self.qualifier(), name);
+                assoc_item_from_text(&text)?
+            }
+            ast::AssocItem::Const(it) => {
+                let name = it.name()?;
+                let ty = it.ty()?;
+                let text = format!("const {}: {} = {}::{};", name, ty, self.qualifier(), name);
+                assoc_item_from_text(&text)?
+            }
+            ast::AssocItem::MacroCall(_) => return None,
+        };
+        Some(res)
+    }
+
+    fn qualifier(&self) -> String {
+        format!("<{} as {}>", self.field_ty, self.trait_ty)
+    }
+
+    /// Returns `<FieldTy as Trait>::method(&self.field, args)`, or `None` if
+    /// the method can't be delegated, e.g. because it has no `self` receiver
+    /// or takes `Self` in a form that can't be unwrapped. The call is
+    /// qualified, as an inherent method of the field's type with the same
+    /// name would win over the trait's method.
+    fn fn_body(&self, func: &ast::Fn) -> Option<ast::Expr> {
+        let name = func.name()?;
+        let param_list = func.param_list()?;
+        let self_param = param_list.self_param()?;
+        if self_param.ty().is_some() {
+            return None;
+        }
+        let receiver = match (self_param.amp_token(), self_param.mut_token()) {
+            (Some(_), Some(_)) => format!("&mut self.{}", self.field_name),
+            (Some(_), None) => format!("&self.{}", self.field_name),
+            (None, _) => format!("self.{}", self.field_name),
+        };
+
+        let mut args = vec![receiver];
+        for param in param_list.params() {
+            let pat = match param.pat()? {
+                ast::Pat::IdentPat(it) if it.pat().is_none() => it.name()?,
+                _ => return None,
+            };
+            let arg = match param.ty()? {
+                ast::Type::RefType(it) if is_self(&it.ty()?) => {
+                    let mutability = if it.mut_token().is_some() { "mut " } else { "" };
+                    format!("&{}{}.{}", mutability, pat, self.field_name)
+                }
+                ty if is_self(&ty) => format!("{}.{}", pat, self.field_name),
+                ty if mentions_self(ty.syntax()) => return None,
+                _ => pat.to_string(),
+            };
+            args.push(arg);
+        }
+
+        let mut call = format!("{}::{}({})", self.qualifier(), name, args.join(", "));
+        if func.async_token().is_some() {
+            call.push_str(".await");
+        }
+        match func.ret_type().and_then(|it| it.ty()) {
+            Some(ty) if is_self(&ty) => {
+                if !self.is_newtype {
+                    return None;
+                }
+                if self.is_tuple {
+                    call = format!("Self({})", call);
+                } else {
+                    call = format!("Self {{ {}: {} }}", self.field_name, call);
+                }
+            }
+            Some(ty) if mentions_self(ty.syntax()) => return None,
+            _ => (),
+        }
+        make::try_expr_from_text(&call)
+    }
+}
+
+fn is_self(ty: &ast::Type) -> bool {
+    ty.syntax().text() == "Self"
+}
+
+/// Whether `Self` occurs anywhere in a type, including `Self::Assoc`
+/// projections, which may differ between the field and the wrapper.
+fn mentions_self(node: &syntax::SyntaxNode) -> bool {
+    node.descendants()
+        .filter_map(ast::Path::cast)
+        .any(|path| path.qualifier().is_none() && path.syntax().text() == "Self")
+}
+
+fn assoc_item_from_text(text: &str) -> Option<ast::AssocItem> {
+    let file = ast::SourceFile::parse(&format!("impl S {{ {} }}", text)).tree();
+    file.syntax().descendants().find_map(ast::AssocItem::cast)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn delegates_methods_and_assoc_items() {
+        check_assist(
+            add_delegating_impl_members,
+            r#"
+trait Stack<T> {
+    type Iter;
+    const CAPACITY: usize;
+    fn push(&mut self, item: T);
+    fn pop(&mut self) -> Option<T>;
+    fn peek(&self) -> Option<&T> { None }
+    fn merged(self, other: &Self) -> Self;
+}
+
+struct Vec<T>(T);
+impl<T> Stack<T> for Vec<T> {
+    type Iter = ();
+    const CAPACITY: usize = 0;
+    fn push(&mut self, item: T) {}
+    fn pop(&mut self) -> Option<T> { None }
+    fn merged(self, other: &Self) -> Self { self }
+}
+
+struct Bounded<T> {
+    items: Vec<T>,
+    limit: usize,
+}
+
+impl<T> Stack<T> for Bounded<T> {<|>
+    fn push(&mut self, item: T) {}
+}
+"#,
+            r#"
+trait Stack<T> {
+    type Iter;
+    const CAPACITY: usize;
+    fn push(&mut self, item: T);
+    fn pop(&mut self) -> Option<T>;
+    fn peek(&self) -> Option<&T> { None }
+    fn merged(self, other: &Self) -> Self;
+}
+
+struct Vec<T>(T);
+impl<T> Stack<T> for Vec<T> {
+    type Iter = ();
+    const CAPACITY: usize = 0;
+    fn push(&mut self, item: T) {}
+    fn pop(&mut self) -> Option<T> { None }
+    fn merged(self, other: &Self) -> Self { self }
+}
+
+struct Bounded<T> {
+    items: Vec<T>,
+    limit: usize,
+}
+
+impl<T> Stack<T> for Bounded<T> {
+    fn push(&mut self, item: T) {}
+
+    $0type Iter = <Vec<T> as Stack<T>>::Iter;
+
+    const CAPACITY: usize = <Vec<T> as Stack<T>>::CAPACITY;
+
+    fn pop(&mut self) -> Option<T> {
+        <Vec<T> as Stack<T>>::pop(&mut self.items)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        <Vec<T> as Stack<T>>::peek(&self.items)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wraps_self_in_newtype() {
+        check_assist(
+            add_delegating_impl_members,
+            r#"
+trait Merge {
+    fn merge(self, other: Self) -> Self;
+}
+
+struct Inner;
+impl Merge for Inner {
+    fn merge(self, other: Self) -> Self { self }
+}
+
+struct Outer { inner: Inner }
+impl Merge for Outer {<|>}
+"#,
+            r#"
+trait Merge {
+    fn merge(self, other: Self) -> Self;
+}
+
+struct Inner;
+impl Merge for Inner {
+    fn merge(self, other: Self) -> Self { self }
+}
+
+struct Outer { inner: Inner }
+impl Merge for Outer {
+    $0fn merge(self, other: Self) -> Self {
+        Self { inner: <Inner as Merge>::merge(self.inner, other.inner) }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delegates_self_args() {
+        check_assist(
+            add_delegating_impl_members,
+            r#"
+trait Compare {
+    fn same(&self, other: &Self) -> bool;
+    fn absorb(&mut self, other: &mut Self, mut extra: Self);
+    fn boxed(self: Box<Self>) -> bool;
+    fn pair(&self, other: (Self, Self)) -> bool;
+}
+
+struct Inner;
+impl Compare for Inner {
+    fn same(&self, other: &Self) -> bool { true }
+    fn absorb(&mut self, other: &mut Self, mut extra: Self) {}
+    fn boxed(self: Box<Self>) -> bool { true }
+    fn pair(&self, other: (Self, Self)) -> bool { true }
+}
+
+struct Outer(Inner);
+impl Compare for Outer {<|>}
+"#,
+            r#"
+trait Compare {
+    fn same(&self, other: &Self) -> bool;
+    fn absorb(&mut self, other: &mut Self, mut extra: Self);
+    fn boxed(self: Box<Self>) -> bool;
+    fn pair(&self, other: (Self, Self)) -> bool;
+}
+
+struct Inner;
+impl Compare for Inner {
+    fn same(&self, other: &Self) -> bool { true }
+    fn absorb(&mut self, other: &mut Self, mut extra: Self) {}
+    fn boxed(self: Box<Self>) -> bool { true }
+    fn pair(&self, other: (Self, Self)) -> bool { true }
+}
+
+struct Outer(Inner);
+impl Compare for Outer {
+    $0fn same(&self, other: &Self) -> bool {
+        <Inner as Compare>::same(&self.0, &other.0)
+    }
+
+    fn absorb(&mut self, other: &mut Self, mut extra: Self) {
+        <Inner as Compare>::absorb(&mut self.0, &mut other.0, extra.0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn awaits_async_methods() {
+        check_assist(
+            add_delegating_impl_members,
+            r#"
+trait Fetch {
+    async fn fetch(&self, key: u32) -> u32;
+}
+
+struct Remote;
+impl Fetch for Remote {
+    async fn fetch(&self, key: u32) -> u32 { key }
+}
+
+struct Cached { remote: Remote }
+impl Fetch for Cached {<|>}
+"#,
+            r#"
+trait Fetch {
+    async fn fetch(&self, key: u32) -> u32;
+}
+
+struct Remote;
+impl Fetch for Remote {
+    async fn fetch(&self, key: u32) -> u32 { key }
+}
+
+struct Cached { remote: Remote }
+impl Fetch for Cached {
+    $0async fn fetch(&self, key: u32) -> u32 {
+        <Remote as Fetch>::fetch(&self.remote, key).await
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn returning_self_needs_newtype() {
+        check_assist(
+            add_delegating_impl_members,
+            r#"
+trait Double {
+    fn double(&self) -> Self;
+}
+
+struct Inner;
+impl Double for Inner {
+    fn double(&self) -> Self { Inner }
+}
+
+struct Tuple(Inner);
+impl Double for Tuple {<|>}
+
+struct Pair { inner: Inner, count: u32 }
+impl Double for Pair {}
+"#,
+            r#"
+trait Double {
+    fn double(&self) -> Self;
+}
+
+struct Inner;
+impl Double for Inner {
+    fn double(&self) -> Self { Inner }
+}
+
+struct Tuple(Inner);
+impl Double for Tuple {
+    $0fn double(&self) -> Self {
+        Self(<Inner as Double>::double(&self.0))
+    }
+}
+
+struct Pair { inner: Inner, count: u32 }
+impl Double for Pair {}
+"#,
+        );
+        check_assist_not_applicable(
+            add_delegating_impl_members,
+            r#"
+trait Double {
+    fn double(&self) -> Self;
+}
+
+struct Inner;
+impl Double for Inner {
+    fn double(&self) -> Self { Inner }
+}
+
+struct Pair { inner: Inner, count: u32 }
+impl Double for Pair {<|>}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_members_which_cant_be_delegated() {
+        check_assist(
+            add_delegating_impl_members,
+            r#"
+trait Collection {
+    type Item;
+    fn new() -> Self;
+    fn first(&self) -> Option<Self::Item>;
+    fn len(&self) -> usize;
+}
+
+struct Inner;
+impl Collection for Inner {
+    type Item = u8;
+    fn new() -> Self { Inner }
+    fn first(&self) -> Option<Self::Item> { None }
+    fn len(&self) -> usize { 0 }
+}
+
+struct Outer(Inner);
+impl Collection for Outer {<|>}
+"#,
+            r#"
+trait Collection {
+    type Item;
+    fn new() -> Self;
+    fn first(&self) -> Option<Self::Item>;
+    fn len(&self) -> usize;
+}
+
+struct Inner;
+impl Collection for Inner {
+    type Item = u8;
+    fn new() -> Self { Inner }
+    fn first(&self) -> Option<Self::Item> { None }
+    fn len(&self) -> usize { 0 }
+}
+
+struct Outer(Inner);
+impl Collection for Outer {
+    $0type Item = <Inner as Collection>::Item;
+
+    fn len(&self) -> usize {
+        <Inner as Collection>::len(&self.0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_implementing_field() {
+        check_assist_not_applicable(
+            add_delegating_impl_members,
+            r#"
+trait Tr { fn f(&self); }
+struct Inner;
+struct Outer(Inner);
+impl Tr for Outer {<|>}
+"#,
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use hir::{AsAssocItem, AssocItemContainer, Mutability};
+use itertools::Itertools;
+use syntax::ast::{
+    self,
+    edit::{AstNodeEdit, IndentLevel},
+    make, ArgListOwner, AstNode, LoopBodyOwner,
+};
+
+use crate::{utils::FamousDefs, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_for_loop_with_for_each
+//
+// Converts a `for` loop into a `for_each` call on the iterator.
+//
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     for<|> v in x {
+//         let y = v * 2;
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     x.into_iter().for_each(|v| {
+//         let y = v * 2;
+//     });
+// }
+// ```
+pub(crate) fn convert_for_loop_with_for_each(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let for_loop = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let pat = for_loop.pat()?;
+    let iterable = for_loop.iterable()?;
+    let body = for_loop.loop_body()?;
+    if body.syntax().text_range().start() < ctx.offset() {
+        return None;
+    }
+    if ctx.sema.has_non_local_control_flow(&body.clone().into())? {
+        return None;
+    }
+
+    let receiver = iterator_of(ctx, &iterable)?;
+    let mut buf = format!("{}.for_each(|{}| {})", receiver, pat, body);
+    // Unlike the loop, the call is not block-like, so it needs a semicolon to
+    // be a statement. The loop's value is `()`, so the tail of a block can
+    // become a statement as well.
+    let needs_semicolon = match for_loop.syntax().parent() {
+        Some(parent) => match ast::ExprStmt::cast(parent.clone()) {
+            Some(stmt) => stmt.semicolon_token().is_none(),
+            None => ast::BlockExpr::cast(parent).is_some(),
+        },
+        None => false,
+    };
+    if needs_semicolon {
+        buf.push(';');
+    }
+
+    let target = for_loop.syntax().text_range();
+    acc.add(
+        AssistId("convert_for_loop_with_for_each", AssistKind::RefactorRewrite),
+        "Replace this for loop with `Iterator::for_each`",
+        target,
+        |builder| builder.replace(target, buf),
+    )
+}
+
+// Assist: convert_iter_for_each_to_for
+//
+// Converts a `for_each` call on an iterator into a `for` loop.
+//
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     x.iter().for_each<|>(|v| {
+//         let y = v * 2;
+//     });
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = vec![1, 2, 3];
+//     for v in x.iter() {
+//         let y = v * 2;
+//     }
+// }
+// ```
+pub(crate) fn convert_iter_for_each_to_for(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let method = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = method.name_ref()?;
+    if name_ref.text() != "for_each" {
+        return None;
+    }
+    // Other `for_each` methods, like the ones of parallel iterators, can't be
+    // turned into a loop.
+    let callee = ctx.sema.resolve_method_call(&method)?;
+    let krate = ctx.sema.scope(method.syntax()).module()?.krate();
+    let iterator = FamousDefs(&ctx.sema, krate).core_iter_Iterator()?;
+    match callee.as_assoc_item(ctx.db()).map(|it| it.container(ctx.db())) {
+        Some(AssocItemContainer::Trait(it)) if it == iterator => (),
+        _ => return None,
+    }
+    let closure = match method.arg_list()?.args().exactly_one().ok()? {
+        ast::Expr::ClosureExpr(it) => it,
+        _ => return None,
+    };
+    let pat = closure.param_list()?.params().exactly_one().ok()?.pat()?;
+    let body = closure.body()?;
+    let receiver = method.expr()?;
+    if ctx.offset() > method.arg_list()?.syntax().text_range().start() {
+        return None;
+    }
+    // A `return` in the closure would become a `return` from the enclosing
+    // function, and `?` or `.await` would change their meaning.
+    if ctx.sema.has_non_local_control_flow(&body)? {
+        return None;
+    }
+
+    // The value of the call is `()`, so it can only be replaced if it is not
+    // used as an expression.
+    let parent = method.syntax().parent()?;
+    let (target, indent) = match ast::ExprStmt::cast(parent.clone()) {
+        Some(stmt) => (stmt.syntax().text_range(), IndentLevel::from_node(stmt.syntax())),
+        None => {
+            let block = ast::BlockExpr::cast(parent)?;
+            if block.expr()?.syntax() != method.syntax() {
+                return None;
+            }
+            (method.syntax().text_range(), IndentLevel::from_node(method.syntax()))
+        }
+    };
+
+    let body = match body {
+        ast::Expr::BlockExpr(it) => it,
+        it => make::block_expr(Some(make::expr_stmt(it).into()), None).indent(indent),
+    };
+    let receiver = match receiver {
+        ast::Expr::ParenExpr(it) => match it.expr()? {
+            ast::Expr::RecordExpr(_) => ast::Expr::ParenExpr(it),
+            inner => inner,
+        },
+        it => it,
+    };
+
+    acc.add(
+        AssistId("convert_iter_for_each_to_for", AssistKind::RefactorRewrite),
+        "Replace this `Iterator::for_each` with a for loop",
+        name_ref.syntax().text_range(),
+        |builder| builder.replace(target, format!("for {} in {} {}", pat, receiver, body)),
+    )
+}
+
+/// Turns the iterable of a `for` loop into an expression which `for_each` can
+/// be called on, adding `.iter()`, `.iter_mut()` or `.into_iter()` unless it
+/// already is an iterator.
+fn iterator_of(ctx: &AssistContext, iterable: &ast::Expr) -> Option<String> {
+    let db = ctx.db();
+    let ty = ctx.sema.type_of_expr(iterable)?;
+    let krate = ctx.sema.scope(iterable.syntax()).module()?.krate();
+    let is_iterator = match FamousDefs(&ctx.sema, krate).core_iter_Iterator() {
+        Some(iterator) => ty.impls_trait(db, iterator, &[]),
+        None => false,
+    };
+    if is_iterator {
+        return Some(receiver_text(iterable));
+    }
+
+    if let Some((pointee, mutability)) = ty.as_reference() {
+        let method = match mutability {
+            Mutability::Shared => "iter",
+            Mutability::Mut => "iter_mut",
+        };
+        let traits_in_scope = ctx.sema.scope(iterable.syntax()).traits_in_scope();
+        let has_method = pointee
+            .iterate_method_candidates(db, krate, &traits_in_scope, None, |_, func| {
+                if func.name(db).to_string() == method && func.has_self_param(db) {
+                    Some(())
+                } else {
+                    None
+                }
+            })
+            .is_some();
+        if has_method {
+            let receiver = match iterable {
+                ast::Expr::RefExpr(it) => it.expr()?,
+                _ => iterable.clone(),
+            };
+            return Some(format!("{}.{}()", receiver_text(&receiver), method));
+        }
+    }
+
+    Some(format!("{}.into_iter()", receiver_text(iterable)))
+}
+
+fn receiver_text(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::ArrayExpr(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::Literal(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::PathExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::TupleExpr(_) => expr.to_string(),
+        _ => format!("({})", expr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    fn check_with_core(assist: crate::handlers::Handler, before: &str, after: &str) {
+        let before = &format!("//- /main.rs crate:main deps:core{}{}", before, FamousDefs::FIXTURE);
+        check_assist(assist, before, after);
+    }
+
+    #[test]
+    fn for_loop_over_iterator() {
+        check_with_core(
+            convert_for_loop_with_for_each,
+            r#"
+struct Counter;
+impl Iterator for Counter {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+
+fn main() {
+    for<|> x in Counter {
+        let y = x;
+    }
+    let z = 0;
+}
+"#,
+            r#"
+struct Counter;
+impl Iterator for Counter {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+
+fn main() {
+    Counter.for_each(|x| {
+        let y = x;
+    });
+    let z = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_loop_over_references() {
+        let before = r#"
+struct Bag;
+impl Bag {
+    fn iter(&self) -> Counter { Counter }
+}
+struct Counter;
+
+fn main() {
+    let bag = Bag;
+    let other = &Bag;
+    for x in &bag {}
+    for x in other {}
+    for x in &mut bag {}
+}
+"#;
+        check_assist(
+            convert_for_loop_with_for_each,
+            &before.replace("for x in &bag", "for<|> x in &bag"),
+            &before.replace("for x in &bag {}", "bag.iter().for_each(|x| {});"),
+        );
+        check_assist(
+            convert_for_loop_with_for_each,
+            &before.replace("for x in other", "for<|> x in other"),
+            &before.replace("for x in other {}", "other.iter().for_each(|x| {});"),
+        );
+        check_assist(
+            convert_for_loop_with_for_each,
+            &before.replace("for x in &mut", "for<|> x in &mut"),
+            &before.replace("for x in &mut bag {}", "(&mut bag).into_iter().for_each(|x| {});"),
+        );
+    }
+
+    #[test]
+    fn for_loop_with_nested_control_flow() {
+        check_assist(
+            convert_for_loop_with_for_each,
+            r#"
+fn main() {
+    <|>for x in 0..10 {
+        loop { break; }
+        let f = || return;
+    }
+}
+"#,
+            r#"
+fn main() {
+    (0..10).into_iter().for_each(|x| {
+        loop { break; }
+        let f = || return;
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_loop_with_control_flow_not_applicable() {
+        check_assist_not_applicable(
+            convert_for_loop_with_for_each,
+            r#"
+fn main() {
+    for<|> x in 0..10 {
+        if x > 5 { break; }
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_for_loop_with_for_each,
+            r#"
+fn main() {
+    'outer: for<|> x in 0..10 {
+        for y in 0..x { continue 'outer; }
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_for_loop_with_for_each,
+            r#"
+fn main() -> Option<()> {
+    for<|> x in 0..10 {
+        x.checked_add(1)?;
+    }
+    None
+}
+"#,
+        );
+    }
+
+    const COUNTER: &str = r#"
+struct Counter;
+impl Iterator for Counter {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+"#;
+
+    fn check_for_each(before: &str, after: &str) {
+        check_with_core(
+            convert_iter_for_each_to_for,
+            &format!("{}{}", COUNTER, before),
+            &format!("{}{}", COUNTER, after),
+        );
+    }
+
+    fn check_for_each_not_applicable(before: &str) {
+        let before = &format!(
+            "//- /main.rs crate:main deps:core{}{}{}",
+            COUNTER,
+            before,
+            FamousDefs::FIXTURE
+        );
+        check_assist_not_applicable(convert_iter_for_each_to_for, before);
+    }
+
+    #[test]
+    fn for_each_with_expr_body() {
+        check_for_each(
+            r#"
+fn main() {
+    {
+        let v = Counter;
+        v.for_each<|>(|x| drop(x));
+    }
+}
+"#,
+            r#"
+fn main() {
+    {
+        let v = Counter;
+        for x in v {
+            drop(x);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_with_block_body() {
+        check_for_each(
+            r#"
+fn main() {
+    (Counter).for_each<|>(|(a, b)| {
+        let c = a + b;
+    })
+}
+"#,
+            r#"
+fn main() {
+    for (a, b) in Counter {
+        let c = a + b;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_not_applicable() {
+        check_for_each_not_applicable(
+            r#"
+fn main() {
+    Counter.for_each<|>(|x| if x > 5 { return; });
+}
+"#,
+        );
+        check_for_each_not_applicable(
+            r#"
+fn main() {
+    let r = Counter.for_each<|>(|x| {});
+}
+"#,
+        );
+        check_for_each_not_applicable(
+            r#"
+fn main() {
+    Counter.for_each(print<|>);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn for_each_not_applicable_to_other_methods() {
+        check_for_each_not_applicable(
+            r#"
+struct Pool;
+impl Pool {
+    fn for_each<F>(&self, f: F) {}
+}
+
+fn main() {
+    Pool.for_each<|>(|x| {});
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,761 @@
+use std::ops::Range;
+
+use base_db::FileId;
+use hir::{Adt, HasSource, ModuleDef};
+use ide_db::{defs::Definition, search::ReferenceKind};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use syntax::{
+    algo::find_node_at_offset,
+    ast::{self, edit::IndentLevel, ArgListOwner, AstNode, GenericParamsOwner, NameOwner},
+    TextRange,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_tuple_struct_to_named_struct
+//
+// Converts a tuple struct to a struct with named fields, updating all of its
+// constructors, patterns and field accesses. Constructors used as functions are
+// turned into closures.
+//
+// ```
+// struct Point<|>(f32, f32);
+//
+// fn origin() -> Point {
+//     let p = Point(0.0, 0.0);
+//     let Point(x, _) = p;
+//     Point(x, p.1)
+// }
+// ```
+// ->
+// ```
+// struct Point { field1: f32, field2: f32 }
+//
+// fn origin() -> Point {
+//     let p = Point { field1: 0.0, field2: 0.0 };
+//     let Point { field1: x, field2: _ } = p;
+//     Point { field1: x, field2: p.field2 }
+// }
+// ```
+pub(crate) fn convert_tuple_struct_to_named_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::TupleFieldList(it) => it,
+        ast::FieldList::RecordFieldList(_) => return None,
+    };
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+    let names = (1..=field_list.fields().count()).map(|idx| format!("field{}", idx)).collect_vec();
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_tuple_struct_to_named_struct", AssistKind::RefactorRewrite),
+        "Convert to named struct",
+        target,
+        |builder| {
+            let mut edits = Edits::default();
+
+            for (field, name) in strukt_def.fields(ctx.db()).into_iter().zip(names.iter()) {
+                for reference in Definition::Field(field).find_usages(&ctx.sema, None) {
+                    let file_range = reference.file_range;
+                    edits.file(ctx, file_range.file_id).replace(file_range.range, name.clone());
+                }
+            }
+
+            let mut usages = Vec::new();
+            for (file_id, path) in struct_paths(ctx, strukt_def) {
+                let parent = match path.syntax().parent() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if let Some(pat) = ast::TupleStructPat::cast(parent.clone()) {
+                    usages.push((file_id, pat.syntax().clone()));
+                } else if let Some(path_expr) = ast::PathExpr::cast(parent) {
+                    match path_expr.syntax().parent().and_then(ast::CallExpr::cast) {
+                        Some(call) => usages.push((file_id, call.syntax().clone())),
+                        // `Point` used as a function, like in `iter.map(Point)`
+                        None => usages.push((file_id, path_expr.syntax().clone())),
+                    }
+                }
+            }
+            usages.sort_by_key(|(_, node)| node.text_range().len());
+
+            for (file_id, node) in usages {
+                let file_edits = edits.file(ctx, file_id);
+                let res = if let Some(call) = ast::CallExpr::cast(node.clone()) {
+                    call.arg_list().and_then(|it| named_arg_list(file_edits, &names, &it))
+                } else if let Some(path_expr) = ast::PathExpr::cast(node.clone()) {
+                    Some(constructor_closure(file_edits, &names, &path_expr))
+                } else {
+                    ast::TupleStructPat::cast(node)
+                        .and_then(|it| named_field_pat(file_edits, &names, &it))
+                };
+                if let Some((range, text)) = res {
+                    file_edits.replace(range, text);
+                }
+            }
+
+            let (range, text) = named_field_list(&strukt, &field_list, &names);
+            edits.file(ctx, ctx.frange.file_id).replace(range, text);
+            edits.apply(builder);
+        },
+    )
+}
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts a struct with named fields to a tuple struct, updating all of its
+// constructors, patterns and field accesses.
+//
+// ```
+// struct Point<|> { x: f32, y: f32 }
+//
+// fn origin() -> Point {
+//     let p = Point { x: 0.0, y: 0.0 };
+//     let Point { y, .. } = p;
+//     Point { y, x: p.x }
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// fn origin() -> Point {
+//     let p = Point(0.0, 0.0);
+//     let Point(_, y, ..) = p;
+//     Point(p.0, y)
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+    let n_fields = field_list.fields().count();
+    let struct_paths = struct_paths(ctx, strukt_def);
+    // `..base` is copied into each field taken from it, which must not
+    // evaluate it more than once.
+    let repeats_base = struct_paths
+        .iter()
+        .filter_map(|(_, path)| path.syntax().parent().and_then(ast::RecordExpr::cast))
+        .filter_map(|record| record.record_expr_field_list())
+        .any(|it| match it.spread() {
+            Some(base) => n_fields.saturating_sub(it.fields().count()) > 1 && !is_place_expr(&base),
+            None => false,
+        });
+    if repeats_base {
+        return None;
+    }
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        "Convert to tuple struct",
+        target,
+        |builder| {
+            let db = ctx.db();
+            let fields = strukt_def.fields(db);
+            let mut edits = Edits::default();
+
+            // Fields in record literals and patterns are rewritten together
+            // with the whole literal or pattern, so only field accesses are
+            // of interest here.
+            for (idx, field) in fields.iter().enumerate() {
+                for reference in Definition::Field(*field).find_usages(&ctx.sema, None) {
+                    if reference.kind != ReferenceKind::Other {
+                        continue;
+                    }
+                    let file_range = reference.file_range;
+                    let source_file = ctx.sema.parse(file_range.file_id);
+                    let name_ref = find_node_at_offset::<ast::NameRef>(
+                        source_file.syntax(),
+                        file_range.range.start(),
+                    );
+                    let is_field_access = name_ref
+                        .and_then(|it| it.syntax().parent())
+                        .filter(|it| ast::FieldExpr::can_cast(it.kind()))
+                        .is_some();
+                    if is_field_access {
+                        edits
+                            .file(ctx, file_range.file_id)
+                            .replace(file_range.range, idx.to_string());
+                    }
+                }
+            }
+
+            let mut usages = Vec::new();
+            for (file_id, path) in struct_paths {
+                let parent = match path.syntax().parent() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if ast::RecordExpr::can_cast(parent.kind())
+                    || ast::RecordPat::can_cast(parent.kind())
+                {
+                    usages.push((file_id, parent));
+                }
+            }
+            usages.sort_by_key(|(_, node)| node.text_range().len());
+
+            for (file_id, node) in usages {
+                let file_edits = edits.file(ctx, file_id);
+                let res = if let Some(record) = ast::RecordExpr::cast(node.clone()) {
+                    tuple_arg_list(ctx, file_edits, &fields, &record)
+                } else {
+                    ast::RecordPat::cast(node)
+                        .and_then(|it| tuple_field_pat(ctx, file_edits, &fields, &it))
+                };
+                if let Some((range, text)) = res {
+                    file_edits.replace(range, text);
+                }
+            }
+
+            let (range, text) = tuple_field_list(&strukt, &field_list);
+            edits.file(ctx, ctx.frange.file_id).replace(range, text);
+            edits.apply(builder);
+        },
+    )
+}
+
+/// The edits of every file touched by the conversion.
+#[derive(Default)]
+struct Edits {
+    files: FxHashMap<FileId, FileEdits>,
+}
+
+impl Edits {
+    fn file(&mut self, ctx: &AssistContext, file_id: FileId) -> &mut FileEdits {
+        self.files.entry(file_id).or_insert_with(|| FileEdits {
+            text: ctx.sema.parse(file_id).syntax().text().to_string(),
+            edits: Vec::new(),
+        })
+    }
+
+    fn apply(self, builder: &mut AssistBuilder) {
+        for (file_id, file_edits) in self.files.into_iter().sorted_by_key(|(it, _)| *it) {
+            builder.edit_file(file_id);
+            for (range, text) in file_edits.edits {
+                builder.replace(range, text);
+            }
+        }
+    }
+}
+
+/// Usages are rewritten innermost first, so that a rewritten node can pick up
+/// the edits already made inside of it, for example in `Point(Point(1, 2).0, 3)`.
+struct FileEdits {
+    text: String,
+    edits: Vec<(TextRange, String)>,
+}
+
+impl FileEdits {
+    /// Returns the text of `range` with the edits inside of it applied.
+    fn render(&self, range: TextRange) -> String {
+        let mut res = self.text[range].to_string();
+        let inner = self
+            .edits
+            .iter()
+            .filter(|(it, _)| range.contains_range(*it))
+            .sorted_by_key(|(it, _)| it.start())
+            .collect_vec();
+        for (edit_range, text) in inner.into_iter().rev() {
+            res.replace_range(Range::<usize>::from(*edit_range - range.start()), text);
+        }
+        res
+    }
+
+    fn replace(&mut self, range: TextRange, text: String) {
+        self.edits.retain(|(it, _)| !range.contains_range(*it));
+        self.edits.push((range, text));
+    }
+}
+
+/// Finds the paths which refer to the struct, including `Self` in its impls.
+fn struct_paths(ctx: &AssistContext, strukt: hir::Struct) -> Vec<(FileId, ast::Path)> {
+    let db = ctx.db();
+    let mut res = Vec::new();
+
+    let def = Definition::ModuleDef(ModuleDef::Adt(Adt::Struct(strukt)));
+    for reference in def.find_usages(&ctx.sema, None) {
+        let file_range = reference.file_range;
+        let source_file = ctx.sema.parse(file_range.file_id);
+        let path =
+            find_node_at_offset::<ast::NameRef>(source_file.syntax(), file_range.range.start())
+                .and_then(|it| it.syntax().ancestors().find_map(ast::Path::cast));
+        res.extend(path.map(|it| (file_range.file_id, it)));
+    }
+
+    for impl_def in hir::ImplDef::all_in_crate(db, strukt.module(db).krate()) {
+        if impl_def.target_ty(db).as_adt() != Some(Adt::Struct(strukt)) {
+            continue;
+        }
+        let source = impl_def.source(db);
+        if source.file_id.call_node(db).is_some() {
+            continue;
+        }
+        let file_id = source.file_id.original_file(db);
+        let source_file = ctx.sema.parse(file_id);
+        let impl_node = find_node_at_offset::<ast::Impl>(
+            source_file.syntax(),
+            source.value.syntax().text_range().start(),
+        );
+        let self_paths = impl_node
+            .into_iter()
+            .flat_map(|it| it.syntax().descendants())
+            .filter_map(ast::Path::cast)
+            .filter(|path| path.qualifier().is_none())
+            .filter(|path| {
+                matches!(path.segment().and_then(|it| it.name_ref()), Some(it) if it.text() == "Self")
+            });
+        res.extend(self_paths.map(|it| (file_id, it)));
+    }
+
+    res
+}
+
+/// Formats a field list, keeping it on one line unless it was multiline.
+fn format_list(
+    node: &impl AstNode,
+    open: &str,
+    close: &str,
+    items: impl IntoIterator<Item = String>,
+) -> String {
+    let items = items.into_iter().collect_vec();
+    if items.is_empty() {
+        return format!("{}{}", open, close);
+    }
+    if !node.syntax().text().contains_char('\n') {
+        let (open, close) = if open == "{" { ("{ ", " }") } else { (open, close) };
+        return format!("{}{}{}", open, items.join(", "), close);
+    }
+    let indent = IndentLevel::from_node(node.syntax());
+    let mut buf = open.to_string();
+    for item in items {
+        buf.push_str(&format!("\n{}{},", indent + 1, item));
+    }
+    buf.push_str(&format!("\n{}{}", indent, close));
+    buf
+}
+
+fn named_arg_list(
+    file_edits: &FileEdits,
+    names: &[String],
+    arg_list: &ast::ArgList,
+) -> Option<(TextRange, String)> {
+    let args = arg_list
+        .args()
+        .zip(names)
+        .map(|(arg, name)| format!("{}: {}", name, file_edits.render(arg.syntax().text_range())));
+    Some((arg_list.syntax().text_range(), format!(" {}", format_list(arg_list, "{", "}", args))))
+}
+
+fn constructor_closure(
+    file_edits: &FileEdits,
+    names: &[String],
+    path_expr: &ast::PathExpr,
+) -> (TextRange, String) {
+    let range = path_expr.syntax().text_range();
+    let closure =
+        format!("|{}| {} {{ {} }}", names.join(", "), file_edits.render(range), names.join(", "));
+    let needs_parens = match path_expr.syntax().parent() {
+        Some(parent) => {
+            !(ast::ArgList::can_cast(parent.kind()) || ast::LetStmt::can_cast(parent.kind()))
+        }
+        None => false,
+    };
+    let closure = if needs_parens { format!("({})", closure) } else { closure };
+    (range, closure)
+}
+
+fn named_field_pat(
+    file_edits: &FileEdits,
+    names: &[String],
+    pat: &ast::TupleStructPat,
+) -> Option<(TextRange, String)> {
+    let l_paren = pat.l_paren_token()?;
+    let pats = pat.fields().collect_vec();
+    let rest = pats.iter().position(|it| matches!(it, ast::Pat::RestPat(_)));
+
+    let mut fields = Vec::new();
+    for (idx, field) in pats.iter().enumerate() {
+        let name = match rest {
+            Some(rest) if idx == rest => continue,
+            Some(rest) if idx > rest => names.get(names.len() + idx - pats.len())?,
+            _ => names.get(idx)?,
+        };
+        fields.push(format!("{}: {}", name, file_edits.render(field.syntax().text_range())));
+    }
+    if rest.is_some() {
+        fields.push("..".to_string());
+    }
+
+    let range = TextRange::new(l_paren.text_range().start(), pat.syntax().text_range().end());
+    Some((range, format!(" {}", format_list(pat, "{", "}", fields))))
+}
+
+fn tuple_arg_list(
+    ctx: &AssistContext,
+    file_edits: &FileEdits,
+    fields: &[hir::Field],
+    record: &ast::RecordExpr,
+) -> Option<(TextRange, String)> {
+    let field_list = record.record_expr_field_list()?;
+    let mut args = vec![None; fields.len()];
+    for field in field_list.fields() {
+        let (def, _) = ctx.sema.resolve_record_field(&field)?;
+        let idx = fields.iter().position(|it| *it == def)?;
+        let expr = field.expr()?;
+        args[idx] = Some(file_edits.render(expr.syntax().text_range()));
+    }
+
+    // Fields taken from the base expression of `..base` become field accesses.
+    let spread = field_list.spread().map(|it| {
+        let text = file_edits.render(it.syntax().text_range());
+        match it {
+            ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_) => text,
+            _ => format!("({})", text),
+        }
+    });
+    let args = args
+        .into_iter()
+        .enumerate()
+        .map(|(idx, arg)| arg.or_else(|| spread.as_ref().map(|base| format!("{}.{}", base, idx))))
+        .collect::<Option<Vec<_>>>()?;
+
+    let start = record.path()?.syntax().text_range().end();
+    let range = TextRange::new(start, record.syntax().text_range().end());
+    Some((range, format_list(&field_list, "(", ")", args)))
+}
+
+/// Whether `expr` denotes a place, which can be accessed repeatedly without
+/// evaluating anything twice.
+fn is_place_expr(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(_) => true,
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_place_expr(&it)),
+        ast::Expr::ParenExpr(it) => it.expr().map_or(false, |it| is_place_expr(&it)),
+        _ => false,
+    }
+}
+
+fn tuple_field_pat(
+    ctx: &AssistContext,
+    file_edits: &FileEdits,
+    fields: &[hir::Field],
+    pat: &ast::RecordPat,
+) -> Option<(TextRange, String)> {
+    let field_list = pat.record_pat_field_list()?;
+    let mut pats = vec![None; fields.len()];
+    for field in field_list.fields() {
+        let def = ctx.sema.resolve_record_field_pat(&field)?;
+        let idx = fields.iter().position(|it| *it == def)?;
+        pats[idx] = Some(file_edits.render(field.pat()?.syntax().text_range()));
+    }
+
+    let has_rest = field_list.dotdot_token().is_some();
+    if has_rest {
+        while let Some(None) = pats.last() {
+            pats.pop();
+        }
+    }
+    let mut pats = pats.into_iter().map(|it| it.unwrap_or_else(|| "_".to_string())).collect_vec();
+    if has_rest {
+        pats.push("..".to_string());
+    }
+
+    let start = pat.path()?.syntax().text_range().end();
+    let range = TextRange::new(start, pat.syntax().text_range().end());
+    Some((range, format_list(&field_list, "(", ")", pats)))
+}
+
+fn named_field_list(
+    strukt: &ast::Struct,
+    field_list: &ast::TupleFieldList,
+    names: &[String],
+) -> (TextRange, String) {
+    let fields = field_list.fields().zip(names).map(|(field, name)| {
+        let text = field.syntax().text().to_string();
+        match field.ty() {
+            Some(ty) => {
+                let ty_start =
+                    ty.syntax().text_range().start() - field.syntax().text_range().start();
+                let ty_start = usize::from(ty_start);
+                format!("{}{}: {}", &text[..ty_start], name, &text[ty_start..])
+            }
+            None => text,
+        }
+    });
+    let fields = format_list(field_list, "{", "}", fields);
+
+    // The where clause goes before the fields, on its own line if it is
+    // multiline.
+    let buf = match strukt.where_clause() {
+        Some(where_clause) if where_clause.syntax().text().contains_char('\n') => {
+            format!("\n{} {}", where_clause, fields)
+        }
+        Some(where_clause) => format!(" {} {}", where_clause, fields),
+        None => format!(" {}", fields),
+    };
+    let start = field_list.syntax().text_range().start();
+    let range = TextRange::new(start, strukt.syntax().text_range().end());
+    (range, buf)
+}
+
+fn tuple_field_list(
+    strukt: &ast::Struct,
+    field_list: &ast::RecordFieldList,
+) -> (TextRange, String) {
+    let fields = field_list.fields().map(|field| {
+        let text = field.syntax().text().to_string();
+        match (field.name(), field.ty()) {
+            (Some(name), Some(ty)) => {
+                let field_start = field.syntax().text_range().start();
+                let name_start = usize::from(name.syntax().text_range().start() - field_start);
+                let ty_start = usize::from(ty.syntax().text_range().start() - field_start);
+                format!("{}{}", &text[..name_start], &text[ty_start..])
+            }
+            _ => text,
+        }
+    });
+    let mut buf = format_list(field_list, "(", ")", fields);
+    if let Some(where_clause) = strukt.where_clause() {
+        buf.push_str(&format!(" {}", where_clause));
+    }
+    buf.push(';');
+
+    // Replace everything after the name and the generic parameters, so that
+    // the where clause ends up after the fields.
+    let start = strukt
+        .generic_param_list()
+        .map(|it| it.syntax().text_range().end())
+        .or_else(|| strukt.name().map(|it| it.syntax().text_range().end()))
+        .unwrap_or_else(|| field_list.syntax().text_range().start());
+    let range = TextRange::new(start, strukt.syntax().text_range().end());
+    (range, buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn tuple_to_named_updates_usages() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+pub struct <|>Pair<T>(pub T, T);
+
+impl<T> Pair<T> {
+    fn new(a: T, b: T) -> Self {
+        Self(a, b)
+    }
+    fn swap(self) -> Self {
+        let Pair(a, b) = self;
+        Pair(b, a)
+    }
+}
+
+mod inner {
+    fn f(p: super::Pair<u8>) -> u8 {
+        match super::Pair(super::Pair(1, 2).0, p.1) {
+            super::Pair(.., b) => b,
+        }
+    }
+}
+"#,
+            r#"
+pub struct Pair<T> { pub field1: T, field2: T }
+
+impl<T> Pair<T> {
+    fn new(a: T, b: T) -> Self {
+        Self { field1: a, field2: b }
+    }
+    fn swap(self) -> Self {
+        let Pair { field1: a, field2: b } = self;
+        Pair { field1: b, field2: a }
+    }
+}
+
+mod inner {
+    fn f(p: super::Pair<u8>) -> u8 {
+        match super::Pair { field1: super::Pair { field1: 1, field2: 2 }.field1, field2: p.field2 } {
+            super::Pair { field2: b, .. } => b,
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_to_named_turns_constructor_values_into_closures() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Point<|>(i32, i32);
+
+impl Point {
+    fn pairs(v: Vec<(i32, i32)>) -> Vec<Self> {
+        let f = Self;
+        v.into_iter().map(|(x, y)| f(x, y)).collect()
+    }
+}
+
+fn apply(f: fn(i32, i32) -> Point) -> Point {
+    f(1, 2)
+}
+
+fn main() {
+    apply(Point);
+    let g = Point as fn(i32, i32) -> Point;
+}
+"#,
+            r#"
+struct Point { field1: i32, field2: i32 }
+
+impl Point {
+    fn pairs(v: Vec<(i32, i32)>) -> Vec<Self> {
+        let f = |field1, field2| Self { field1, field2 };
+        v.into_iter().map(|(x, y)| f(x, y)).collect()
+    }
+}
+
+fn apply(f: fn(i32, i32) -> Point) -> Point {
+    f(1, 2)
+}
+
+fn main() {
+    apply(|field1, field2| Point { field1, field2 });
+    let g = (|field1, field2| Point { field1, field2 }) as fn(i32, i32) -> Point;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_to_named_with_where_clause() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Wrap<|><T>(
+    T,
+)
+where
+    T: Clone;
+"#,
+            r#"
+struct Wrap<T>
+where
+    T: Clone {
+    field1: T,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_to_tuple_updates_usages() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+#[derive(Clone)]
+struct <|>Point {
+    x: i32,
+    pub(crate) y: i32,
+}
+
+impl Point {
+    fn flip(&self) -> Self {
+        Self { y: self.x, x: self.y }
+    }
+    fn with_x(&self, x: i32) -> Point {
+        Point { x, ..self.clone() }
+    }
+}
+
+fn f(p: Point) -> i32 {
+    let Point { y: b, .. } = p;
+    let Point { x, y } = Point { x: 1, y: b };
+    x + y
+}
+"#,
+            r#"
+#[derive(Clone)]
+struct Point(
+    i32,
+    pub(crate) i32,
+);
+
+impl Point {
+    fn flip(&self) -> Self {
+        Self(self.1, self.0)
+    }
+    fn with_x(&self, x: i32) -> Point {
+        Point(x, self.clone().1)
+    }
+}
+
+fn f(p: Point) -> i32 {
+    let Point(_, b, ..) = p;
+    let Point(x, y) = Point(1, b);
+    x + y
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_to_tuple_with_generics() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"struct <|>Wrap<T> where T: Copy { inner: T }"#,
+            r#"struct Wrap<T>(T) where T: Copy;"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_of_fields() {
+        check_assist_not_applicable(
+            convert_tuple_struct_to_named_struct,
+            r#"struct Point(f32<|>, f32);"#,
+        );
+        check_assist_not_applicable(
+            convert_named_struct_to_tuple_struct,
+            r#"struct Point { x<|>: f32 }"#,
+        );
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct <|>Unit;"#);
+    }
+
+    #[test]
+    fn not_applicable_when_base_would_be_repeated() {
+        check_assist_not_applicable(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct <|>Point { x: i32, y: i32, z: i32 }
+fn make() -> Point { Point { x: 0, y: 0, z: 0 } }
+fn f(x: i32) -> Point {
+    Point { x, ..make() }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use hir::{Adt, HasVisibility, Local, ModuleDef, PathResolution, StructKind};
+use ide_db::{defs::Definition, search::Reference};
+use rustc_hash::FxHashSet;
+use syntax::{
+    algo::find_node_at_offset,
+    ast::{self, make, AstNode, FieldKind, NameOwner},
+    SyntaxKind::{
+        BLOCK_EXPR, CLOSURE_EXPR, FN, FOR_EXPR, IF_EXPR, LET_STMT, MATCH_ARM, WHILE_EXPR,
+    },
+};
+
+use crate::{ast_transform::path_to_ast, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: destructure_binding
+//
+// Destructures a binding of a struct or tuple type into its fields, replacing
+// field accesses on the binding with the new bindings.
+//
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn main() {
+//     let <|>p = Point { x: 1, y: 2 };
+//     let sum = p.x + p.y;
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// fn main() {
+//     let Point { x, y } = Point { x: 1, y: 2 };
+//     let sum = x + y;
+// }
+// ```
+pub(crate) fn destructure_binding(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let ident_pat = ctx.find_node_at_offset::<ast::IdentPat>()?;
+    if ident_pat.pat().is_some() || ident_pat.ref_token().is_some() {
+        return None;
+    }
+    let db = ctx.db();
+    let local = ctx.sema.to_def(&ident_pat)?;
+    let ty = local.ty(db);
+    let shape = match ty.as_adt() {
+        Some(Adt::Struct(strukt)) => {
+            let module = ctx.sema.scope(ident_pat.syntax()).module()?;
+            let all_fields = strukt.fields(db);
+            let fields = all_fields
+                .iter()
+                .filter(|field| field.is_visible_from(db, module))
+                .map(|field| field.name(db).to_string())
+                .collect::<Vec<_>>();
+            let has_private_fields = fields.len() < all_fields.len();
+            let path = path_to_ast(module.find_use_path(db, ModuleDef::Adt(strukt.into()))?);
+            match strukt.kind(db) {
+                StructKind::Record => Shape::Record { path, fields, has_private_fields },
+                // Tuple structs with private fields can't be matched at all.
+                StructKind::Tuple if !has_private_fields => {
+                    Shape::TupleStruct { path, n_fields: fields.len() }
+                }
+                StructKind::Tuple | StructKind::Unit => return None,
+            }
+        }
+        Some(_) => return None,
+        None => match ty.tuple_fields(db).len() {
+            n_fields if n_fields >= 2 => Shape::Tuple { n_fields },
+            _ => return None,
+        },
+    };
+
+    let usages = Definition::Local(local)
+        .find_usages(&ctx.sema, None)
+        .iter()
+        .map(|reference| field_access(ctx, reference))
+        .collect::<Option<Vec<_>>>()?;
+
+    // The new bindings must not shadow anything the usages or the rest of the
+    // binding's scope refer to.
+    let mut names_in_scope = names_used_after(ctx, &ident_pat, local);
+    for (field_expr, _) in &usages {
+        ctx.sema.scope(field_expr.syntax()).process_all_names(&mut |name, _| {
+            names_in_scope.insert(name.to_string());
+        });
+    }
+    let binding_name = ident_pat.name()?.text().to_string();
+    let is_mut = ident_pat.mut_token().is_some();
+    let (pat, fields) = shape.render(&binding_name, is_mut, &names_in_scope);
+
+    let replacements = usages
+        .into_iter()
+        .map(|(field_expr, field)| {
+            let (_, binding) = fields.iter().find(|(name, _)| *name == field)?;
+            Some((field_expr.syntax().text_range(), binding.clone()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let target = ident_pat.syntax().text_range();
+    acc.add(
+        AssistId("destructure_binding", AssistKind::RefactorRewrite),
+        "Destructure binding",
+        target,
+        |builder| {
+            builder.replace(target, pat.to_string());
+            for (range, binding) in replacements {
+                builder.replace(range, binding);
+            }
+        },
+    )
+}
+
+enum Shape {
+    Record { path: ast::Path, fields: Vec<String>, has_private_fields: bool },
+    TupleStruct { path: ast::Path, n_fields: usize },
+    Tuple { n_fields: usize },
+}
+
+impl Shape {
+    /// Returns the destructuring pattern, and pairs of field names and the
+    /// names they are bound to.
+    fn render(
+        self,
+        binding_name: &str,
+        is_mut: bool,
+        names_in_scope: &FxHashSet<String>,
+    ) -> (ast::Pat, Vec<(String, String)>) {
+        match self {
+            Shape::Record { path, fields, has_private_fields } => {
+                let fields = fields
+                    .into_iter()
+                    .map(|field| {
+                        let binding = if names_in_scope.contains(&field) {
+                            format!("{}_{}", binding_name, field)
+                        } else {
+                            field.clone()
+                        };
+                        (field, binding)
+                    })
+                    .collect::<Vec<_>>();
+                let pat_fields = fields.iter().map(|(field, binding)| {
+                    let name_ref =
+                        if field == binding { None } else { Some(make::name_ref(field)) };
+                    make::record_pat_field(name_ref, binding_pat(binding, is_mut))
+                });
+                let field_list = make::record_pat_field_list(pat_fields, has_private_fields);
+                (make::record_pat_with_fields(path, field_list).into(), fields)
+            }
+            Shape::TupleStruct { path, n_fields } => {
+                let fields = tuple_fields(n_fields, binding_name, names_in_scope);
+                let pats = fields.iter().map(|(_, binding)| binding_pat(binding, is_mut));
+                (make::tuple_struct_pat(path, pats).into(), fields)
+            }
+            Shape::Tuple { n_fields } => {
+                let fields = tuple_fields(n_fields, binding_name, names_in_scope);
+                let pats = fields.iter().map(|(_, binding)| binding_pat(binding, is_mut));
+                (make::tuple_pat(pats).into(), fields)
+            }
+        }
+    }
+}
+
+fn tuple_fields(
+    n_fields: usize,
+    binding_name: &str,
+    names_in_scope: &FxHashSet<String>,
+) -> Vec<(String, String)> {
+    (0..n_fields)
+        .map(|idx| {
+            let binding = format!("_{}", idx);
+            let binding = if names_in_scope.contains(&binding) {
+                format!("{}{}", binding_name, binding)
+            } else {
+                binding
+            };
+            (idx.to_string(), binding)
+        })
+        .collect()
+}
+
+fn binding_pat(name: &str, is_mut: bool) -> ast::Pat {
+    let name = make::name(name);
+    let pat = if is_mut { make::ident_mut_pat(name) } else { make::ident_pat(name) };
+    pat.into()
+}
+
+/// Returns the names of the values referred to after the binding in its
+/// scope, other than the binding itself, which the new bindings would shadow.
+fn names_used_after(
+    ctx: &AssistContext,
+    ident_pat: &ast::IdentPat,
+    local: Local,
+) -> FxHashSet<String> {
+    let mut start = ident_pat.syntax().text_range().end();
+    let mut scope = None;
+    for node in ident_pat.syntax().ancestors() {
+        match node.kind() {
+            // The bindings of a `let` aren't visible in its initializer.
+            LET_STMT => start = node.text_range().end(),
+            BLOCK_EXPR | MATCH_ARM | CLOSURE_EXPR | FN | FOR_EXPR | IF_EXPR | WHILE_EXPR => {
+                scope = Some(node);
+                break;
+            }
+            _ => (),
+        }
+    }
+    scope
+        .into_iter()
+        .flat_map(|it| it.descendants())
+        .filter(|it| it.text_range().start() >= start)
+        .filter_map(ast::PathExpr::cast)
+        .filter_map(|it| it.path())
+        .filter(|path| path.qualifier().is_none())
+        .filter(|path| match ctx.sema.resolve_path(path) {
+            Some(PathResolution::Local(it)) => it != local,
+            Some(_) => true,
+            None => false,
+        })
+        .filter_map(|path| Some(path.segment()?.name_ref()?.text().to_string()))
+        .collect()
+}
+
+/// Returns the field access expression `reference` is part of, and the name of
+/// the accessed field. Any other usage of the binding can't be expressed after
+/// destructuring it.
+fn field_access(ctx: &AssistContext, reference: &Reference) -> Option<(ast::FieldExpr, String)> {
+    if reference.file_range.file_id != ctx.frange.file_id {
+        return None;
+    }
+    let source_file = ctx.sema.parse(ctx.frange.file_id);
+    let name_ref: ast::NameRef =
+        find_node_at_offset(source_file.syntax(), reference.file_range.range.start())?;
+    let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+    let field_expr = path_expr.syntax().parent().and_then(ast::FieldExpr::cast)?;
+    let field = match field_expr.field_access()? {
+        FieldKind::Name(name_ref) => name_ref.text().to_string(),
+        FieldKind::Index(token) => token.text().to_string(),
+    };
+    Some((field_expr, field))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn destructures_tuple_struct_in_param() {
+        check_assist(
+            destructure_binding,
+            r#"
+struct Pair(u32, u32);
+
+fn sum(mut <|>pair: Pair) -> u32 {
+    pair.0 += 1;
+    pair.0 + pair.1
+}
+"#,
+            r#"
+struct Pair(u32, u32);
+
+fn sum(Pair(mut _0, mut _1): Pair) -> u32 {
+    _0 += 1;
+    _0 + _1
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructures_tuple() {
+        check_assist(
+            destructure_binding,
+            r#"
+fn main() {
+    let <|>t = (1, "a", 2.0);
+    let _ = t.1;
+}
+"#,
+            r#"
+fn main() {
+    let (_0, _1, _2) = (1, "a", 2.0);
+    let _ = _1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn uses_rest_pattern_for_private_fields() {
+        check_assist(
+            destructure_binding,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, y: i32, pub z: i32 }
+    pub fn origin() -> Point { Point { x: 0, y: 0, z: 0 } }
+}
+
+fn main() {
+    let x = 1;
+    let <|>p = geo::origin();
+    let sum = p.x + p.z + x;
+}
+"#,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, y: i32, pub z: i32 }
+    pub fn origin() -> Point { Point { x: 0, y: 0, z: 0 } }
+}
+
+fn main() {
+    let x = 1;
+    let geo::Point { x: p_x, z, .. } = geo::origin();
+    let sum = p_x + z + x;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn avoids_shadowing_names_used_later() {
+        check_assist(
+            destructure_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let x = 1;
+    let <|>p = Point { x: 1, y: 2 };
+    let _ = x;
+}
+"#,
+            r#"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let x = 1;
+    let Point { x: p_x, y } = Point { x: 1, y: 2 };
+    let _ = x;
+}
+"#,
+        );
+        check_assist(
+            destructure_binding,
+            r#"
+fn f(_0: u32, <|>t: (u32, u32)) -> u32 {
+    t.1 + _0
+}
+"#,
+            r#"
+fn f(_0: u32, (t_0, _1): (u32, u32)) -> u32 {
+    _1 + _0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_other_usages() {
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+fn take(p: Point) {}
+fn main() {
+    let <|>p = Point { x: 1, y: 2 };
+    let x = p.x;
+    take(p);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+mod m { pub struct Wrapper(pub i32, i32); }
+fn f(<|>w: m::Wrapper) {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,1499 @@
+use hir::{HirDisplay, Local, Module, Mutability, PathResolution, Type, TypeRef};
+use ide_db::{defs::Definition, RootDatabase};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode, GenericParamsOwner, NameOwner},
+    match_ast, NodeOrToken, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{utils::split_lines, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_function
+//
+// Extracts selected statements into new function.
+//
+// ```
+// fn main() {
+//     let n = 1;
+//     <|>let m = n + 2;
+//     let k = m * n;<|>
+//     let g = 3;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let n = 1;
+//     fun_name(n);
+//     let g = 3;
+// }
+//
+// fn $0fun_name(n: i32) {
+//     let m = n + 2;
+//     let k = m * n;
+// }
+// ```
+pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let selection = ctx.frange.range;
+    if selection.is_empty() {
+        return None;
+    }
+    let node = match ctx.covering_element() {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let body = FunctionBody::from_node(node, selection)?;
+
+    let parent_fn = body.first_node().ancestors().find_map(ast::Fn::cast)?;
+    let parent_body = parent_fn.body()?;
+    if !parent_body.syntax().text_range().contains_range(body.text_range())
+        || parent_body.syntax().text_range() == body.text_range()
+    {
+        return None;
+    }
+
+    let module = ctx.sema.scope(body.first_node()).module()?;
+    let flow = ControlFlow::of(&body)?;
+    let self_param = self_param(ctx, &parent_fn, &body);
+    let params = params(ctx, &body);
+    let outputs = outputs(ctx, &body);
+    let value_ty =
+        body.tail_expr().and_then(|it| ctx.sema.type_of_expr(&it)).filter(|ty| !ty.is_unit());
+    let ret = RetKind::new(&parent_fn, &flow, value_ty.is_some(), outputs.is_empty())?;
+
+    let target = body.text_range();
+    acc.add(
+        AssistId("extract_function", AssistKind::RefactorExtract),
+        "Extract into function",
+        target,
+        move |builder| {
+            let fun = Function { self_param, params, outputs, value_ty, ret };
+            let indent = IndentLevel::from_node(body.first_node());
+            let call = fun.make_call_site(ctx, &body, indent);
+            builder.replace(target, call);
+
+            let (anchor, fn_indent) = match (&fun.self_param, parent_fn.syntax().parent()) {
+                (None, Some(parent)) if ast::AssocItemList::can_cast(parent.kind()) => {
+                    let impl_ = parent.ancestors().find_map(ast::Impl::cast);
+                    let anchor = impl_.map_or(parent_fn.syntax().clone(), |it| it.syntax().clone());
+                    let indent = IndentLevel::from_node(&anchor);
+                    (anchor, indent)
+                }
+                _ => (parent_fn.syntax().clone(), IndentLevel::from_node(parent_fn.syntax())),
+            };
+            let fn_def = fun.make_fn(ctx, module, &parent_fn, &body, &flow, fn_indent);
+            let offset = anchor.text_range().end();
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(
+                    cap,
+                    offset,
+                    fn_def.replacen("fn fun_name", "fn $0fun_name", 1),
+                ),
+                None => builder.insert(offset, fn_def),
+            }
+        },
+    )
+}
+
+/// The part of the parent function that is extracted.
+enum FunctionBody {
+    /// A single expression.
+    Expr(ast::Expr),
+    /// A range of statements of a block, possibly ending with the tail
+    /// expression of the block.
+    Span { elements: Vec<SyntaxNode>, tail: Option<ast::Expr> },
+}
+
+impl FunctionBody {
+    fn from_node(node: SyntaxNode, selection: TextRange) -> Option<FunctionBody> {
+        let block = match_ast! {
+            match node {
+                ast::BlockExpr(it) => Some(it),
+                ast::LetStmt(it) => it.syntax().parent().and_then(ast::BlockExpr::cast),
+                ast::ExprStmt(it) => it.syntax().parent().and_then(ast::BlockExpr::cast),
+                _ => None,
+            }
+        };
+        if let Some(block) = block {
+            let interior = TextRange::new(
+                block.l_curly_token()?.text_range().end(),
+                block.r_curly_token()?.text_range().start(),
+            );
+            if interior.contains_range(selection) {
+                return FunctionBody::from_block(&block, selection);
+            }
+        }
+
+        let expr = node.ancestors().find_map(ast::Expr::cast)?;
+        if matches!(expr, ast::Expr::PathExpr(_))
+            && expr.syntax().parent()?.kind() == SyntaxKind::FN
+        {
+            return None;
+        }
+        Some(FunctionBody::Expr(expr))
+    }
+
+    fn from_block(block: &ast::BlockExpr, selection: TextRange) -> Option<FunctionBody> {
+        let overlaps =
+            |range: TextRange| range.start() < selection.end() && selection.start() < range.end();
+
+        let mut elements = Vec::new();
+        for stmt in block.statements() {
+            let range = stmt.syntax().text_range();
+            if selection.contains_range(range) {
+                elements.push(stmt.syntax().clone());
+            } else if overlaps(range) {
+                return None;
+            }
+        }
+        let tail = match block.expr() {
+            Some(expr) if selection.contains_range(expr.syntax().text_range()) => Some(expr),
+            Some(expr) if overlaps(expr.syntax().text_range()) => return None,
+            _ => None,
+        };
+        if elements.is_empty() && tail.is_none() {
+            return None;
+        }
+        Some(FunctionBody::Span { elements, tail })
+    }
+
+    fn first_node(&self) -> &SyntaxNode {
+        match self {
+            FunctionBody::Expr(expr) => expr.syntax(),
+            FunctionBody::Span { elements, tail } => match elements.first() {
+                Some(it) => it,
+                None => tail.as_ref().unwrap().syntax(),
+            },
+        }
+    }
+
+    fn nodes(&self) -> Vec<SyntaxNode> {
+        match self {
+            FunctionBody::Expr(expr) => vec![expr.syntax().clone()],
+            FunctionBody::Span { elements, tail } => {
+                elements.iter().cloned().chain(tail.iter().map(|it| it.syntax().clone())).collect()
+            }
+        }
+    }
+
+    fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+        self.nodes().into_iter().flat_map(|it| it.descendants())
+    }
+
+    fn text_range(&self) -> TextRange {
+        let nodes = self.nodes();
+        let first = nodes.first().unwrap().text_range();
+        let last = nodes.last().unwrap().text_range();
+        first.cover(last)
+    }
+
+    fn tail_expr(&self) -> Option<ast::Expr> {
+        match self {
+            FunctionBody::Expr(expr) => Some(expr.clone()),
+            FunctionBody::Span { tail, .. } => tail.clone(),
+        }
+    }
+
+    fn contains_node(&self, node: &SyntaxNode) -> bool {
+        self.text_range().contains_range(node.text_range())
+    }
+}
+
+/// How control leaves the extracted code, other than by reaching its end.
+struct ControlFlow {
+    returns: Vec<ast::ReturnExpr>,
+    breaks: Vec<ast::BreakExpr>,
+    continues: Vec<ast::ContinueExpr>,
+    tries: Vec<ast::TryExpr>,
+}
+
+impl ControlFlow {
+    /// Returns `None` if the control flow can't be expressed by a function,
+    /// e.g. because of a labeled `break`.
+    fn of(body: &FunctionBody) -> Option<ControlFlow> {
+        let mut res = ControlFlow {
+            returns: Vec::new(),
+            breaks: Vec::new(),
+            continues: Vec::new(),
+            tries: Vec::new(),
+        };
+        for node in body.nodes() {
+            res.visit(&node, false)?;
+        }
+        Some(res)
+    }
+
+    fn visit(&mut self, node: &SyntaxNode, in_loop: bool) -> Option<()> {
+        // Closures and items have their own `return`s and `?`s
+        if ast::ClosureExpr::can_cast(node.kind()) || ast::Item::can_cast(node.kind()) {
+            return Some(());
+        }
+        match_ast! {
+            match node {
+                ast::EffectExpr(it) => if it.async_token().is_some() || it.try_token().is_some() {
+                    return Some(());
+                },
+                ast::ReturnExpr(it) => self.returns.push(it),
+                ast::TryExpr(it) => self.tries.push(it),
+                ast::BreakExpr(it) => if !in_loop {
+                    if it.lifetime_token().is_some() {
+                        return None;
+                    }
+                    self.breaks.push(it);
+                },
+                ast::ContinueExpr(it) => if !in_loop {
+                    if it.lifetime_token().is_some() {
+                        return None;
+                    }
+                    self.continues.push(it);
+                },
+                _ => (),
+            }
+        }
+        let in_loop = in_loop
+            || matches!(
+                node.kind(),
+                SyntaxKind::LOOP_EXPR | SyntaxKind::WHILE_EXPR | SyntaxKind::FOR_EXPR
+            );
+        for child in node.children() {
+            self.visit(&child, in_loop)?;
+        }
+        Some(())
+    }
+
+    fn exits(&self) -> usize {
+        self.returns.len() + self.breaks.len() + self.continues.len()
+    }
+}
+
+/// How the result of the new function is communicated back to the call site.
+enum RetKind {
+    /// The value of the extracted code, if any, is returned as is.
+    Plain,
+    /// The extracted code uses `?`, so its value is wrapped into the `Option`
+    /// or `Result` type of the parent function.
+    Try { ret_type: ast::PathType, wrapper: &'static str },
+    /// The extracted code has a `return`, `break` or `continue` without a
+    /// value, `true` is returned when it is taken.
+    Bool { exit: &'static str },
+    /// The extracted code has a `return` with a value, `Some(value)` is
+    /// returned when it is taken.
+    Option,
+}
+
+impl RetKind {
+    fn new(
+        parent_fn: &ast::Fn,
+        flow: &ControlFlow,
+        has_value: bool,
+        no_outputs: bool,
+    ) -> Option<RetKind> {
+        if !flow.tries.is_empty() {
+            if flow.exits() > 0 {
+                return None;
+            }
+            let ret_type = match parent_fn.ret_type()?.ty()? {
+                ast::Type::PathType(it) => it,
+                _ => return None,
+            };
+            let segment = ret_type.path()?.segment()?;
+            segment.generic_arg_list()?.generic_args().next()?;
+            let wrapper = if segment.name_ref()?.text() == "Option" { "Some" } else { "Ok" };
+            return Some(RetKind::Try { ret_type, wrapper });
+        }
+        if flow.exits() == 0 {
+            return Some(RetKind::Plain);
+        }
+        if has_value || !no_outputs {
+            return None;
+        }
+
+        let kinds = [!flow.returns.is_empty(), !flow.breaks.is_empty(), !flow.continues.is_empty()];
+        if kinds.iter().filter(|&&it| it).count() > 1 {
+            return None;
+        }
+        let with_value = flow.returns.iter().filter(|it| it.expr().is_some()).count();
+        if with_value == flow.returns.len() && with_value > 0 {
+            return Some(RetKind::Option);
+        }
+        if with_value > 0 || flow.breaks.iter().any(|it| it.expr().is_some()) {
+            return None;
+        }
+        let exit = if !flow.returns.is_empty() {
+            "return"
+        } else if !flow.breaks.is_empty() {
+            "break"
+        } else {
+            "continue"
+        };
+        Some(RetKind::Bool { exit })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ParamKind {
+    Value,
+    MutValue,
+    SharedRef,
+    MutRef,
+}
+
+struct Param {
+    local: Local,
+    kind: ParamKind,
+}
+
+struct Function {
+    /// The self parameter of the new method, if the extracted code uses
+    /// `self`.
+    self_param: Option<String>,
+    params: Vec<Param>,
+    /// Locals declared by the extracted code which are still used after it.
+    outputs: Vec<Local>,
+    value_ty: Option<Type>,
+    ret: RetKind,
+}
+
+impl Function {
+    fn make_call_site(
+        &self,
+        ctx: &AssistContext,
+        body: &FunctionBody,
+        indent: IndentLevel,
+    ) -> String {
+        let db = ctx.db();
+        let args = self
+            .params
+            .iter()
+            .map(|param| {
+                let name = local_name(db, param.local);
+                match param.kind {
+                    ParamKind::Value | ParamKind::MutValue => name,
+                    ParamKind::SharedRef => format!("&{}", name),
+                    ParamKind::MutRef => format!("&mut {}", name),
+                }
+            })
+            .join(", ");
+        let mut call = match self.self_param {
+            Some(_) => format!("self.fun_name({})", args),
+            None => format!("fun_name({})", args),
+        };
+        if let RetKind::Try { .. } = self.ret {
+            call.push('?');
+        }
+
+        let is_expr = match body {
+            FunctionBody::Expr(_) => true,
+            FunctionBody::Span { tail, .. } => tail.is_some(),
+        };
+        match &self.ret {
+            RetKind::Bool { exit } => {
+                format!("if {} {{\n{}{};\n{}}}", call, indent + 1, exit, indent)
+            }
+            RetKind::Option => {
+                format!(
+                    "if let Some(value) = {} {{\n{}return value;\n{}}}",
+                    call,
+                    indent + 1,
+                    indent
+                )
+            }
+            RetKind::Plain | RetKind::Try { .. } if is_expr => call,
+            RetKind::Plain | RetKind::Try { .. } => match self.outputs.as_slice() {
+                [] => format!("{};", call),
+                [var] => format!("let {} = {};", output_binding(db, *var), call),
+                vars => format!(
+                    "let ({}) = {};",
+                    vars.iter().map(|it| output_binding(db, *it)).join(", "),
+                    call
+                ),
+            },
+        }
+    }
+
+    fn make_fn(
+        &self,
+        ctx: &AssistContext,
+        module: Module,
+        parent_fn: &ast::Fn,
+        body: &FunctionBody,
+        flow: &ControlFlow,
+        indent: IndentLevel,
+    ) -> String {
+        let db = ctx.db();
+        let render_ty = |ty: &Type| match ty.display_source_code(db, module.into()) {
+            Ok(it) if !ty.contains_unknown() => it,
+            _ => "_".to_string(),
+        };
+
+        let mut params = Vec::new();
+        let mut signature_tys = Vec::new();
+        params.extend(self.self_param.clone());
+        for param in &self.params {
+            let name = local_name(db, param.local);
+            let ty = render_ty(&param.local.ty(db));
+            signature_tys.push(make::ty(&ty));
+            params.push(match param.kind {
+                ParamKind::Value => format!("{}: {}", name, ty),
+                ParamKind::MutValue => format!("mut {}: {}", name, ty),
+                ParamKind::SharedRef => format!("{}: &{}", name, ty),
+                ParamKind::MutRef => format!("{}: &mut {}", name, ty),
+            });
+        }
+
+        let value_ty = match (&self.value_ty, self.outputs.as_slice()) {
+            (Some(ty), _) => Some(render_ty(ty)),
+            (None, []) => None,
+            (None, [var]) => Some(render_ty(&var.ty(db))),
+            (None, vars) => {
+                Some(format!("({})", vars.iter().map(|it| render_ty(&it.ty(db))).join(", ")))
+            }
+        };
+        let ret_ty = match &self.ret {
+            RetKind::Plain => value_ty,
+            RetKind::Try { ret_type, .. } => {
+                Some(replace_first_generic_arg(ret_type, value_ty.as_deref().unwrap_or("()")))
+            }
+            RetKind::Bool { .. } => Some("bool".to_string()),
+            RetKind::Option => {
+                let ty = flow
+                    .returns
+                    .iter()
+                    .find_map(|it| ctx.sema.type_of_expr(&it.expr()?))
+                    .map_or_else(|| "_".to_string(), |ty| render_ty(&ty));
+                Some(format!("Option<{}>", ty))
+            }
+        };
+
+        signature_tys.extend(ret_ty.as_deref().map(make::ty));
+        let generics = generics_for(parent_fn, &signature_tys);
+
+        let mut buf = format!("\n\n{}fn fun_name", indent);
+        if let Some((params, _)) = &generics {
+            format_to!(buf, "<{}>", params);
+        }
+        format_to!(buf, "({})", params.join(", "));
+        if let Some(ret_ty) = ret_ty {
+            format_to!(buf, " -> {}", ret_ty);
+        }
+        if let Some((_, Some(where_clause))) = &generics {
+            format_to!(buf, " {}", where_clause);
+        }
+        format_to!(buf, " {{\n{}", indent + 1);
+        buf.push_str(&self.body_text(ctx, body, flow, indent + 1));
+        format_to!(buf, "\n{}}}", indent);
+        buf
+    }
+
+    fn body_text(
+        &self,
+        ctx: &AssistContext,
+        body: &FunctionBody,
+        flow: &ControlFlow,
+        indent: IndentLevel,
+    ) -> String {
+        let db = ctx.db();
+        let mut edits: Vec<(TextRange, String)> = Vec::new();
+
+        // Parameters which are now references have to be dereferenced.
+        for param in self.params.iter().filter(|it| it.kind == ParamKind::MutRef) {
+            for path_expr in local_usages(ctx, body, param.local) {
+                if is_auto_derefed(&path_expr) {
+                    continue;
+                }
+                let name = local_name(db, param.local);
+                let shorthand = path_expr.syntax().parent().and_then(ast::RecordExprField::cast);
+                let text = match shorthand {
+                    Some(field) if field.name_ref().is_none() => format!("{}: *{}", name, name),
+                    _ => format!("*{}", name),
+                };
+                edits.push((path_expr.syntax().text_range(), text));
+            }
+        }
+
+        let tail = body.tail_expr();
+        match &self.ret {
+            RetKind::Plain => {}
+            RetKind::Try { wrapper, .. } => {
+                if let Some(tail) = &tail {
+                    let range = tail.syntax().text_range();
+                    edits.push((TextRange::empty(range.start()), format!("{}(", wrapper)));
+                    edits.push((TextRange::empty(range.end()), ")".to_string()));
+                }
+            }
+            RetKind::Bool { .. } => {
+                let exits = flow
+                    .returns
+                    .iter()
+                    .map(|it| it.syntax().text_range())
+                    .chain(flow.breaks.iter().map(|it| it.syntax().text_range()))
+                    .chain(flow.continues.iter().map(|it| it.syntax().text_range()));
+                edits.extend(exits.map(|range| (range, "return true".to_string())));
+            }
+            RetKind::Option => {
+                for value in flow.returns.iter().filter_map(|it| it.expr()) {
+                    let range = value.syntax().text_range();
+                    edits.push((TextRange::empty(range.start()), "Some(".to_string()));
+                    edits.push((TextRange::empty(range.end()), ")".to_string()));
+                }
+            }
+        }
+
+        let range = body.text_range();
+        let text = body.nodes().first().unwrap().ancestors().last().unwrap().text().to_string();
+        let mut res = String::new();
+        let mut cursor = range.start();
+        edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+        for (edit_range, replacement) in edits {
+            res.push_str(&text[TextRange::new(cursor, edit_range.start())]);
+            res.push_str(&replacement);
+            cursor = edit_range.end();
+        }
+        res.push_str(&text[TextRange::new(cursor, range.end())]);
+
+        let old_indent = IndentLevel::from_node(body.first_node()).to_string();
+        let mut reindented = String::new();
+        for (idx, (line, in_string)) in split_lines(&res).into_iter().enumerate() {
+            if idx > 0 {
+                reindented.push('\n');
+            }
+            match line.strip_prefix(&old_indent) {
+                Some(line) if idx > 0 && !in_string => format_to!(reindented, "{}{}", indent, line),
+                _ => reindented.push_str(line),
+            }
+        }
+        let mut res = reindented;
+
+        let outputs = match self.outputs.as_slice() {
+            [] => None,
+            [var] => Some(local_name(db, *var)),
+            vars => Some(format!("({})", vars.iter().map(|it| local_name(db, *it)).join(", "))),
+        };
+        let new_tail = match &self.ret {
+            RetKind::Plain if tail.is_none() => outputs,
+            RetKind::Try { wrapper, .. } if tail.is_none() => {
+                Some(format!("{}({})", wrapper, outputs.unwrap_or_else(|| "()".to_string())))
+            }
+            RetKind::Bool { .. } => Some("false".to_string()),
+            RetKind::Option => Some("None".to_string()),
+            _ => None,
+        };
+        if let Some(new_tail) = new_tail {
+            format_to!(res, "\n{}{}", indent, new_tail);
+        }
+        res
+    }
+}
+
+fn self_param(ctx: &AssistContext, parent_fn: &ast::Fn, body: &FunctionBody) -> Option<String> {
+    let parent_self = parent_fn.param_list()?.self_param()?;
+    let usages = body
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|it| match it.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Local(local)) => local.is_self(ctx.db()),
+            _ => false,
+        })
+        .collect::<Vec<_>>();
+    if usages.is_empty() {
+        return None;
+    }
+
+    let by_ref = parent_self.amp_token().is_some();
+    let mutated = usages.iter().any(|it| is_mutated(ctx, it));
+    let res = match (by_ref, parent_self.mut_token().is_some() && mutated) {
+        (true, true) => "&mut self",
+        (true, false) => "&self",
+        (false, true) => "mut self",
+        (false, false) => "self",
+    };
+    Some(res.to_string())
+}
+
+/// Returns the locals used, but not declared, by the extracted code, and how
+/// they should be passed to the new function.
+fn params(ctx: &AssistContext, body: &FunctionBody) -> Vec<Param> {
+    let db = ctx.db();
+    let mut locals: Vec<Local> = Vec::new();
+    for path_expr in body.descendants().filter_map(ast::PathExpr::cast) {
+        let local = match path_expr.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        if local.is_self(db) || locals.contains(&local) {
+            continue;
+        }
+        let declared_inside = match local.source(db).value {
+            either::Either::Left(pat) => body.contains_node(pat.syntax()),
+            either::Either::Right(_) => false,
+        };
+        if !declared_inside {
+            locals.push(local);
+        }
+    }
+
+    locals
+        .into_iter()
+        .map(|local| {
+            let ty = local.ty(db);
+            let mutated = local_usages(ctx, body, local).iter().any(|it| is_mutated(ctx, it));
+            let kind = if ty.as_reference().is_some() {
+                ParamKind::Value
+            } else if mutated && is_used_after(ctx, body, local) {
+                ParamKind::MutRef
+            } else if mutated {
+                ParamKind::MutValue
+            } else if !ty.is_copy(db) && is_used_after(ctx, body, local) {
+                ParamKind::SharedRef
+            } else {
+                ParamKind::Value
+            };
+            Param { local, kind }
+        })
+        .collect()
+}
+
+/// Returns the locals declared by the extracted code which are used after it.
+fn outputs(ctx: &AssistContext, body: &FunctionBody) -> Vec<Local> {
+    body.nodes()
+        .iter()
+        .filter(|it| ast::LetStmt::can_cast(it.kind()))
+        .flat_map(|it| it.descendants())
+        .filter_map(ast::IdentPat::cast)
+        .filter_map(|it| ctx.sema.to_def(&it))
+        .filter(|local| is_used_after(ctx, body, *local))
+        .collect()
+}
+
+fn local_usages(ctx: &AssistContext, body: &FunctionBody, local: Local) -> Vec<ast::PathExpr> {
+    body.descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|it| match it.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Local(it)) => it == local,
+            _ => false,
+        })
+        .collect()
+}
+
+fn is_used_after(ctx: &AssistContext, body: &FunctionBody, local: Local) -> bool {
+    let end = body.text_range().end();
+    Definition::Local(local).find_usages(&ctx.sema, None).iter().any(|reference| {
+        reference.file_range.file_id == ctx.frange.file_id
+            && reference.file_range.range.start() >= end
+    })
+}
+
+/// Checks whether the place starting with `path_expr` is mutated, e.g. by
+/// `x.field = 92`, `&mut x` or `x.push(92)`.
+fn is_mutated(ctx: &AssistContext, path_expr: &ast::PathExpr) -> bool {
+    let mut place = ast::Expr::from(path_expr.clone());
+    let parent = loop {
+        let parent = match place.syntax().parent() {
+            Some(it) => it,
+            None => return false,
+        };
+        match_ast! {
+            match parent {
+                ast::FieldExpr(it) => place = it.into(),
+                ast::IndexExpr(it) => {
+                    if it.base().as_ref() != Some(&place) {
+                        break parent;
+                    }
+                    place = it.into()
+                },
+                _ => break parent,
+            }
+        }
+    };
+    match_ast! {
+        match parent {
+            ast::BinExpr(it) => {
+                matches!(it.op_kind(), Some(op) if op.is_assignment()) && it.lhs() == Some(place)
+            },
+            ast::RefExpr(it) => it.mut_token().is_some(),
+            ast::MethodCallExpr(it) => {
+                if it.expr() != Some(place) {
+                    return false;
+                }
+                let func = match ctx.sema.resolve_method_call(&it) {
+                    Some(it) => it,
+                    None => return false,
+                };
+                func.has_self_param(ctx.db())
+                    && matches!(
+                        func.params(ctx.db()).first(),
+                        Some(TypeRef::Reference(_, Mutability::Mut))
+                    )
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Checks whether a reference is dereferenced automatically where
+/// `path_expr` is used, that is when it is a method receiver or the base of a
+/// field access.
+fn is_auto_derefed(path_expr: &ast::PathExpr) -> bool {
+    let expr = Some(ast::Expr::from(path_expr.clone()));
+    match path_expr.syntax().parent() {
+        Some(parent) => match_ast! {
+            match parent {
+                ast::MethodCallExpr(it) => it.expr() == expr,
+                ast::FieldExpr(it) => it.expr() == expr,
+                ast::IndexExpr(it) => it.base() == expr,
+                _ => false,
+            }
+        },
+        None => false,
+    }
+}
+
+fn local_name(db: &RootDatabase, local: Local) -> String {
+    local.name(db).map_or_else(|| "_".to_string(), |it| it.to_string())
+}
+
+fn output_binding(db: &RootDatabase, local: Local) -> String {
+    if local.is_mut(db) {
+        format!("mut {}", local_name(db, local))
+    } else {
+        local_name(db, local)
+    }
+}
+
+/// Renders `Result<T, E>` as `Result<ty, E>`, `Option<T>` as `Option<ty>`
+/// and so on.
+fn replace_first_generic_arg(path_type: &ast::PathType, ty: &str) -> String {
+    let text = path_type.syntax().text().to_string();
+    let start = path_type.syntax().text_range().start();
+    let first_arg = path_type
+        .path()
+        .and_then(|it| it.segment())
+        .and_then(|it| it.generic_arg_list())
+        .and_then(|it| it.generic_args().next());
+    match first_arg {
+        Some(arg) => {
+            let range = arg.syntax().text_range() - start;
+            let range = usize::from(range.start())..usize::from(range.end());
+            let mut res = text;
+            res.replace_range(range, ty);
+            res
+        }
+        None => text,
+    }
+}
+
+/// Returns the generic parameters and where clause of `parent_fn` which are
+/// needed for the parameter and return types `tys` of the new function.
+fn generics_for(parent_fn: &ast::Fn, tys: &[ast::Type]) -> Option<(String, Option<String>)> {
+    let generic_params = parent_fn.generic_param_list()?.generic_params().collect::<Vec<_>>();
+    let preds =
+        parent_fn.where_clause().into_iter().flat_map(|it| it.predicates()).collect::<Vec<_>>();
+    let names = generic_params.iter().filter_map(generic_param_name).collect::<FxHashSet<_>>();
+
+    let mut used = FxHashSet::default();
+    for ty in tys {
+        collect_generic_params(ty.syntax(), &names, &mut used);
+    }
+    // The bounds of used parameters can mention further parameters, like the
+    // `U` in `T: Into<U>`.
+    loop {
+        let n_used = used.len();
+        for param in &generic_params {
+            if matches!(generic_param_name(param), Some(it) if used.contains(&it)) {
+                collect_generic_params(param.syntax(), &names, &mut used);
+            }
+        }
+        for pred in &preds {
+            if where_pred_is_used(pred, &names, &used) {
+                collect_generic_params(pred.syntax(), &names, &mut used);
+            }
+        }
+        if used.len() == n_used {
+            break;
+        }
+    }
+
+    let params = generic_params
+        .iter()
+        .filter(|param| matches!(generic_param_name(param), Some(it) if used.contains(&it)))
+        .map(|it| it.syntax().text().to_string())
+        .collect::<Vec<_>>();
+    if params.is_empty() {
+        return None;
+    }
+    let preds = preds
+        .iter()
+        .filter(|pred| where_pred_is_used(pred, &names, &used))
+        .map(|it| it.syntax().text().to_string())
+        .collect::<Vec<_>>();
+    let where_clause =
+        if preds.is_empty() { None } else { Some(format!("where {}", preds.join(", "))) };
+    Some((params.join(", "), where_clause))
+}
+
+/// Returns the name of a type or const parameter, or the lifetime of a
+/// lifetime parameter, like `'a`.
+fn generic_param_name(param: &ast::GenericParam) -> Option<String> {
+    let name = match param {
+        ast::GenericParam::TypeParam(it) => it.name()?.text().to_string(),
+        ast::GenericParam::ConstParam(it) => it.name()?.text().to_string(),
+        ast::GenericParam::LifetimeParam(it) => it.lifetime_token()?.text().to_string(),
+    };
+    Some(name)
+}
+
+/// Whether the bounded type or lifetime of a where predicate mentions any of
+/// the `used` parameters.
+fn where_pred_is_used(
+    pred: &ast::WherePred,
+    names: &FxHashSet<String>,
+    used: &FxHashSet<String>,
+) -> bool {
+    let mut subject = FxHashSet::default();
+    match (pred.ty(), pred.lifetime_token()) {
+        (Some(ty), _) => collect_generic_params(ty.syntax(), names, &mut subject),
+        (None, Some(lifetime)) => {
+            subject.insert(lifetime.text().to_string());
+        }
+        (None, None) => (),
+    }
+    subject.iter().any(|it| used.contains(it))
+}
+
+/// Collects the generic parameters, out of `names`, which are referred to by
+/// a path or lifetime within `node`.
+fn collect_generic_params(
+    node: &SyntaxNode,
+    names: &FxHashSet<String>,
+    acc: &mut FxHashSet<String>,
+) {
+    for element in node.descendants_with_tokens() {
+        let name = match element {
+            NodeOrToken::Node(node) => match ast::Path::cast(node) {
+                Some(path) if path.qualifier().is_none() => {
+                    match path.segment().and_then(|it| it.name_ref()) {
+                        Some(name_ref) => name_ref.text().to_string(),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            },
+            NodeOrToken::Token(token) if token.kind() == SyntaxKind::LIFETIME => {
+                token.text().to_string()
+            }
+            NodeOrToken::Token(_) => continue,
+        };
+        if names.contains(&name) {
+            acc.insert(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_statements_no_params() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    <|>let k = 1;
+    let m = k + 2;<|>
+}
+"#,
+            r#"
+fn foo() {
+    fun_name();
+}
+
+fn $0fun_name() {
+    let k = 1;
+    let m = k + 2;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_params_and_output() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let n = 1;
+    <|>let m = n + 2;
+    let k = m * n;<|>
+    let g = k + 3;
+}
+"#,
+            r#"
+fn foo() {
+    let n = 1;
+    let k = fun_name(n);
+    let g = k + 3;
+}
+
+fn $0fun_name(n: i32) -> i32 {
+    let m = n + 2;
+    let k = m * n;
+    k
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_multiple_outputs() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    <|>let a = 1;
+    let mut b = 2;<|>
+    b += a;
+}
+"#,
+            r#"
+fn foo() {
+    let (a, mut b) = fun_name();
+    b += a;
+}
+
+fn $0fun_name() -> (i32, i32) {
+    let a = 1;
+    let mut b = 2;
+    (a, b)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_expr() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let n = 1;
+    let k = <|>n * 2<|>;
+}
+"#,
+            r#"
+fn foo() {
+    let n = 1;
+    let k = fun_name(n);
+}
+
+fn $0fun_name(n: i32) -> i32 {
+    n * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_block_tail() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() -> i32 {
+    let n = 1;
+    <|>let m = n + 1;
+    m * 2<|>
+}
+"#,
+            r#"
+fn foo() -> i32 {
+    let n = 1;
+    fun_name(n)
+}
+
+fn $0fun_name(n: i32) -> i32 {
+    let m = n + 1;
+    m * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn mutated_and_used_after_is_passed_by_mut_ref() {
+        check_assist(
+            extract_function,
+            r#"
+struct S { n: i32 }
+fn foo() {
+    let mut n = 1;
+    <|>n += 1;
+    let s = S { n };<|>
+    let m = n;
+}
+"#,
+            r#"
+struct S { n: i32 }
+fn foo() {
+    let mut n = 1;
+    fun_name(&mut n);
+    let m = n;
+}
+
+fn $0fun_name(n: &mut i32) {
+    *n += 1;
+    let s = S { n: *n };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn mutated_and_not_used_after_is_passed_by_value() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let mut n = 1;
+    <|>n += 1;
+    let m = n;<|>
+}
+"#,
+            r#"
+fn foo() {
+    let mut n = 1;
+    fun_name(n);
+}
+
+fn $0fun_name(mut n: i32) {
+    n += 1;
+    let m = n;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn non_copy_used_after_is_passed_by_ref() {
+        check_assist(
+            extract_function,
+            r#"
+struct S;
+impl S { fn len(&self) -> usize { 0 } }
+fn foo() {
+    let s = S;
+    <|>let n = s.len();<|>
+    let t = s;
+}
+"#,
+            r#"
+struct S;
+impl S { fn len(&self) -> usize { 0 } }
+fn foo() {
+    let s = S;
+    fun_name(&s);
+    let t = s;
+}
+
+fn $0fun_name(s: &S) {
+    let n = s.len();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn mut_method_receiver_is_not_derefed() {
+        check_assist(
+            extract_function,
+            r#"
+struct S;
+impl S { fn push(&mut self, n: i32) {} }
+fn foo() {
+    let mut s = S;
+    <|>s.push(1);<|>
+    let t = s;
+}
+"#,
+            r#"
+struct S;
+impl S { fn push(&mut self, n: i32) {} }
+fn foo() {
+    let mut s = S;
+    fun_name(&mut s);
+    let t = s;
+}
+
+fn $0fun_name(s: &mut S) {
+    s.push(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_method() {
+        check_assist(
+            extract_function,
+            r#"
+struct S { n: i32 }
+impl S {
+    fn foo(&mut self) {
+        let k = 1;
+        <|>self.n += k;<|>
+    }
+}
+"#,
+            r#"
+struct S { n: i32 }
+impl S {
+    fn foo(&mut self) {
+        let k = 1;
+        self.fun_name(k);
+    }
+
+    fn $0fun_name(&mut self, k: i32) {
+        self.n += k;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_from_method_without_self_goes_after_impl() {
+        check_assist(
+            extract_function,
+            r#"
+struct S;
+impl S {
+    fn foo(&self) {
+        <|>let k = 1;<|>
+    }
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self) {
+        fun_name();
+    }
+}
+
+fn $0fun_name() {
+    let k = 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_try() {
+        check_assist(
+            extract_function,
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo() -> Option<i32> {
+    let n = Some(1);
+    <|>let k = n?;
+    let m = k + 1;<|>
+    Some(m)
+}
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo() -> Option<i32> {
+    let n = Some(1);
+    let m = fun_name(n)?;
+    Some(m)
+}
+
+fn $0fun_name(n: Option<i32>) -> Option<i32> {
+    let k = n?;
+    let m = k + 1;
+    Some(m)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_try_in_result() {
+        check_assist(
+            extract_function,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn foo() -> Result<(), i64> {
+    let n: Result<i32, i64> = Ok(1);
+    <|>let k = n?;<|>
+    Ok(())
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn foo() -> Result<(), i64> {
+    let n: Result<i32, i64> = Ok(1);
+    fun_name(n)?;
+    Ok(())
+}
+
+fn $0fun_name(n: Result<i32, i64>) -> Result<(), i64> {
+    let k = n?;
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_return() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    let n = 1;
+    <|>if n > 0 {
+        return;
+    }<|>
+    let m = 2;
+}
+"#,
+            r#"
+fn foo() {
+    let n = 1;
+    if fun_name(n) {
+        return;
+    }
+    let m = 2;
+}
+
+fn $0fun_name(n: i32) -> bool {
+    if n > 0 {
+        return true;
+    }
+    false
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_break() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    loop {
+        let n = 1;
+        <|>if n > 0 {
+            break;
+        }
+        loop {
+            break;
+        }<|>
+    }
+}
+"#,
+            r#"
+fn foo() {
+    loop {
+        let n = 1;
+        if fun_name(n) {
+            break;
+        }
+    }
+}
+
+fn $0fun_name(n: i32) -> bool {
+    if n > 0 {
+        return true;
+    }
+    loop {
+        break;
+    }
+    false
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_return_value() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() -> i32 {
+    let n = 1;
+    <|>if n > 0 {
+        return n;
+    }<|>
+    0
+}
+"#,
+            r#"
+fn foo() -> i32 {
+    let n = 1;
+    if let Some(value) = fun_name(n) {
+        return value;
+    }
+    0
+}
+
+fn $0fun_name(n: i32) -> Option<i32> {
+    if n > 0 {
+        return Some(n);
+    }
+    None
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn return_in_closure_is_ignored() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    <|>let f = || { return 1; };<|>
+}
+"#,
+            r#"
+fn foo() {
+    fun_name();
+}
+
+fn $0fun_name() {
+    let f = || { return 1; };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn copies_used_generic_params() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo<T, U>(t: T, u: U) where T: Clone {
+    <|>let k = t.clone();<|>
+}
+"#,
+            r#"
+fn foo<T, U>(t: T, u: U) where T: Clone {
+    fun_name(t);
+}
+
+fn $0fun_name<T>(t: T) where T: Clone {
+    let k = t.clone();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn copies_generic_params_of_bounds_and_lifetimes() {
+        check_assist(
+            extract_function,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn foo<'a, T: Into<U>, U, V>(t: T, n: Result<i32, &'a str>, v: V) -> Result<(), &'a str>
+where
+    U: Clone,
+{
+    <|>let k = n?;
+    let u: U = t.into();<|>
+    Ok(())
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+fn foo<'a, T: Into<U>, U, V>(t: T, n: Result<i32, &'a str>, v: V) -> Result<(), &'a str>
+where
+    U: Clone,
+{
+    fun_name(n, t)?;
+    Ok(())
+}
+
+fn $0fun_name<'a, T: Into<U>, U>(n: Result<i32, &str>, t: T) -> Result<(), &'a str> where U: Clone {
+    let k = n?;
+    let u: U = t.into();
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_keeps_multiline_strings_intact() {
+        check_assist(
+            extract_function,
+            r#"
+fn foo() {
+    if true {
+        <|>let s = "a
+        b";
+        let r = r"c
+    d";<|>
+    }
+}
+"#,
+            r#"
+fn foo() {
+    if true {
+        fun_name();
+    }
+}
+
+fn $0fun_name() {
+    let s = "a
+        b";
+    let r = r"c
+    d";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_partial_statements() {
+        check_assist_not_applicable(
+            extract_function,
+            r#"
+fn foo() {
+    let k = 1<|>;
+    let m = 2;<|>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_labeled_break() {
+        check_assist_not_applicable(
+            extract_function,
+            r#"
+fn foo() {
+    'outer: loop {
+        <|>break 'outer;<|>
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_of_fn() {
+        check_assist_not_applicable(extract_function, r#"const C: i32 = <|>1 + 1<|>;"#);
+    }
+}
@@ -109,7 +109,7 @@ fn insert_import(
         insert_use_statement(
             path.syntax(),
             &mod_path.to_string(),
-            ctx,
+            &ctx.sema,
             builder.text_edit_builder(),
         );
     }
@@ -0,0 +1,363 @@
+use syntax::ast::{self, AstNode, NameOwner, StructKind, VisibilityOwner};
+
+use crate::{
+    utils::{existing_fn_names, find_impl_block, impl_insertion},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_getter
+//
+// Generate a getter method.
+//
+// ```
+// struct Person {
+//     nam<|>e: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn $0name(&self) -> &String {
+//         &self.name
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_getter(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_accessors(acc, ctx, AccessorKind::Getter)
+}
+
+// Assist: generate_getter_mut
+//
+// Generate a mut getter method.
+//
+// ```
+// struct Person {
+//     nam<|>e: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn $0name_mut(&mut self) -> &mut String {
+//         &mut self.name
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_getter_mut(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_accessors(acc, ctx, AccessorKind::GetterMut)
+}
+
+// Assist: generate_setter
+//
+// Generate a setter method.
+//
+// ```
+// struct Person {
+//     nam<|>e: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn $0set_name(&mut self, name: String) {
+//         self.name = name;
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_setter(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_accessors(acc, ctx, AccessorKind::Setter)
+}
+
+#[derive(Clone, Copy)]
+enum AccessorKind {
+    Getter,
+    GetterMut,
+    Setter,
+}
+
+impl AccessorKind {
+    fn id(self) -> &'static str {
+        match self {
+            AccessorKind::Getter => "generate_getter",
+            AccessorKind::GetterMut => "generate_getter_mut",
+            AccessorKind::Setter => "generate_setter",
+        }
+    }
+
+    fn label(self, several: bool) -> &'static str {
+        match (self, several) {
+            (AccessorKind::Getter, false) => "Generate a getter method",
+            (AccessorKind::Getter, true) => "Generate getter methods",
+            (AccessorKind::GetterMut, false) => "Generate a mut getter method",
+            (AccessorKind::GetterMut, true) => "Generate mut getter methods",
+            (AccessorKind::Setter, false) => "Generate a setter method",
+            (AccessorKind::Setter, true) => "Generate setter methods",
+        }
+    }
+
+    fn fn_name(self, field_name: &str) -> String {
+        match self {
+            AccessorKind::Getter => field_name.to_string(),
+            AccessorKind::GetterMut => format!("{}_mut", field_name),
+            AccessorKind::Setter => format!("set_{}", field_name),
+        }
+    }
+
+    fn render(self, vis: &str, field_name: &str, ty: &ast::Type) -> String {
+        let fn_name = self.fn_name(field_name);
+        match self {
+            AccessorKind::Getter => format!(
+                "    {}fn {}(&self) -> &{} {{\n        &self.{}\n    }}",
+                vis, fn_name, ty, field_name
+            ),
+            AccessorKind::GetterMut => format!(
+                "    {}fn {}(&mut self) -> &mut {} {{\n        &mut self.{}\n    }}",
+                vis, fn_name, ty, field_name
+            ),
+            AccessorKind::Setter => format!(
+                "    {}fn {}(&mut self, {}: {}) {{\n        self.{} = {};\n    }}",
+                vis, fn_name, field_name, ty, field_name, field_name
+            ),
+        }
+    }
+}
+
+/// Generates accessors for the field under the cursor, or for all fields if
+/// the cursor is on the name of the struct. Fields which already have an
+/// accessor of this kind are skipped.
+fn generate_accessors(acc: &mut Assists, ctx: &AssistContext, kind: AccessorKind) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(named) => named,
+        _ => return None,
+    };
+
+    let (fields, target) = match ctx.find_node_at_offset::<ast::RecordField>() {
+        Some(field) => {
+            let target = field.syntax().text_range();
+            (vec![field], target)
+        }
+        None => {
+            let name = strukt.name()?;
+            if !name.syntax().text_range().contains_inclusive(ctx.offset()) {
+                return None;
+            }
+            (field_list.fields().collect(), name.syntax().text_range())
+        }
+    };
+
+    let adt = ast::AdtDef::Struct(strukt.clone());
+    let impl_def = find_impl_block(ctx, &adt, &[])?;
+    let existing = existing_fn_names(impl_def.as_ref());
+    let fields = fields
+        .into_iter()
+        .filter_map(|field| Some((field.name()?.text().to_string(), field.ty()?)))
+        .filter(|(name, _)| !existing.contains(&kind.fn_name(name)))
+        .collect::<Vec<_>>();
+    let several = fields.len() > 1;
+    let first_fn_name = kind.fn_name(&fields.first()?.0);
+
+    acc.add(AssistId(kind.id(), AssistKind::Generate), kind.label(several), target, |builder| {
+        let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+        let methods = fields
+            .iter()
+            .map(|(name, ty)| kind.render(&vis, name, ty))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let (offset, mut buf) = impl_insertion(&adt, impl_def.as_ref(), &methods);
+
+        match ctx.config.snippet_cap {
+            None => builder.insert(offset, buf),
+            Some(cap) => {
+                buf = buf.replacen(
+                    &format!("fn {}(", first_fn_name),
+                    &format!("fn $0{}(", first_fn_name),
+                    1,
+                );
+                builder.insert_snippet(cap, offset, buf);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_generate_getter_from_field() {
+        check_assist(
+            generate_getter,
+            r#"
+struct Context<T: Clone> {
+    dat<|>a: T,
+}
+"#,
+            r#"
+struct Context<T: Clone> {
+    data: T,
+}
+
+impl<T: Clone> Context<T> {
+    fn $0data(&self) -> &T {
+        &self.data
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_getter_mut_from_field() {
+        check_assist(
+            generate_getter_mut,
+            r#"
+pub(crate) struct Context {
+    dat<|>a: u32,
+}
+"#,
+            r#"
+pub(crate) struct Context {
+    data: u32,
+}
+
+impl Context {
+    pub(crate) fn $0data_mut(&mut self) -> &mut u32 {
+        &mut self.data
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_setter_into_existing_impl() {
+        check_assist(
+            generate_setter,
+            r#"
+struct Context {
+    dat<|>a: u32,
+}
+
+impl Context {
+    fn new() -> Self { Context { data: 0 } }
+}
+"#,
+            r#"
+struct Context {
+    data: u32,
+}
+
+impl Context {
+    fn new() -> Self { Context { data: 0 } }
+
+    fn $0set_data(&mut self, data: u32) {
+        self.data = data;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_getters_for_all_fields() {
+        check_assist(
+            generate_getter,
+            r#"
+struct Con<|>text {
+    data: u32,
+    count: usize,
+}
+
+impl Context {
+    fn count(&self) -> usize { self.count }
+}
+"#,
+            r#"
+struct Context {
+    data: u32,
+    count: usize,
+}
+
+impl Context {
+    fn count(&self) -> usize { self.count }
+
+    fn $0data(&self) -> &u32 {
+        &self.data
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_getters_into_empty_impl() {
+        check_assist(
+            generate_getter,
+            r#"
+struct Con<|>text {
+    a: u32,
+    b: u32,
+}
+
+impl Context {}
+"#,
+            r#"
+struct Context {
+    a: u32,
+    b: u32,
+}
+
+impl Context {
+    fn $0a(&self) -> &u32 {
+        &self.a
+    }
+
+    fn b(&self) -> &u32 {
+        &self.b
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_not_applicable_if_getter_exists() {
+        check_assist_not_applicable(
+            generate_getter,
+            r#"
+struct Context {
+    dat<|>a: u32,
+}
+
+impl Context {
+    fn data(&self) -> &u32 { &self.data }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_not_applicable_for_tuple_struct() {
+        check_assist_not_applicable(generate_setter, r#"struct Context(u3<|>2);"#);
+    }
+}
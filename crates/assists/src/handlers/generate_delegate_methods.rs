@@ -0,0 +1,505 @@
+use hir::{AsAssocItem, AssocItemContainer, HasSource, HasVisibility, PathResolution};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, AstNode, GenericParamsOwner, NameOwner, StructKind, VisibilityOwner},
+};
+
+use crate::{
+    ast_transform::{self, AstTransform, QualifyPaths, SubstituteTypeParams},
+    utils::{existing_fn_names, find_impl_block, impl_insertion},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_delegate_methods
+//
+// Generate methods which delegate to the inherent methods of a field.
+//
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 {
+//         self.0
+//     }
+// }
+//
+// struct Person {
+//     ag<|>e: Age,
+// }
+// ```
+// ->
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 {
+//         self.0
+//     }
+// }
+//
+// struct Person {
+//     age: Age,
+// }
+//
+// impl Person {
+//     fn $0age(&self) -> u8 {
+//         self.age.age()
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let db = ctx.db();
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    if !matches!(strukt.kind(), StructKind::Record(_)) {
+        return None;
+    }
+    let field = ctx.find_node_at_offset::<ast::RecordField>()?;
+    let field_name = field.name()?.text().to_string();
+    let field_ty = ctx.sema.to_def(&field)?.signature_ty(db);
+    let adt = field_ty.as_adt()?;
+    let module = ctx.sema.to_def(&strukt)?.module(db);
+
+    let ast_adt = ast::AdtDef::Struct(strukt.clone());
+    let impl_def = find_impl_block(ctx, &ast_adt, &[])?;
+    let mut seen = existing_fn_names(impl_def.as_ref()).into_iter().collect::<FxHashSet<_>>();
+    let mut methods = Vec::new();
+    field_ty.iterate_method_candidates(
+        db,
+        module.krate(),
+        &FxHashSet::default(),
+        None,
+        |_, func| {
+            let is_inherent = match func.as_assoc_item(db).map(|it| it.container(db)) {
+                Some(AssocItemContainer::ImplDef(it)) => {
+                    it.target_trait(db).is_none() && it.target_ty(db).as_adt() == Some(adt)
+                }
+                _ => false,
+            };
+            if is_inherent
+                && func.has_self_param(db)
+                && func.is_visible_from(db, module)
+                && seen.insert(func.name(db).to_string())
+            {
+                methods.push(func);
+            }
+            None::<()>
+        },
+    );
+    let target_scope = ctx.sema.scope(strukt.syntax());
+    let methods = methods
+        .into_iter()
+        .filter_map(|func| {
+            // Only methods written in a file can be copied, not macro-generated ones.
+            let src = func.source(db);
+            let file_id = src.file_id.original_file(db);
+            let method = find_node_at_range::<ast::Fn>(
+                ctx.sema.parse(file_id).syntax(),
+                src.value.syntax().text_range(),
+            )?;
+            if method.syntax().text_range() != src.value.syntax().text_range() {
+                return None;
+            }
+            Some((func, method))
+        })
+        .sorted_by_key(|(_, it)| it.syntax().text_range().start())
+        .filter_map(|(func, method)| {
+            render_delegate(ctx, &target_scope, func, &method, field.ty(), &field_name)
+        })
+        .collect::<Vec<_>>();
+    if methods.is_empty() {
+        return None;
+    }
+
+    let target = field.syntax().text_range();
+    acc.add(
+        AssistId("generate_delegate_methods", AssistKind::Generate),
+        format!("Generate delegate methods for `{}`", field_name),
+        target,
+        |builder| {
+            let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+            let methods = methods.iter().map(|it| format!("    {}{}", vis, it)).join("\n\n");
+            let (offset, buf) = impl_insertion(&ast_adt, impl_def.as_ref(), &methods);
+
+            match ctx.config.snippet_cap {
+                None => builder.insert(offset, buf),
+                Some(cap) => builder.insert_snippet(cap, offset, buf.replacen("fn ", "fn $0", 1)),
+            }
+        },
+    )
+}
+
+/// Renders a method with the signature of `method` which calls it on
+/// `self.field`. The generic parameters of the field type's impl are replaced
+/// with the arguments of `field_ty` and paths are qualified for the wrapper's
+/// scope. Returns `None` if the signature can't be expressed that way.
+fn render_delegate(
+    ctx: &AssistContext,
+    target_scope: &hir::SemanticsScope,
+    func: hir::Function,
+    method: &ast::Fn,
+    field_ty: Option<ast::Type>,
+    field_name: &str,
+) -> Option<String> {
+    let name = method.name()?;
+    let param_list = method.param_list()?;
+    let self_param = param_list.self_param()?;
+    if self_param.ty().is_some() {
+        return None;
+    }
+
+    let substs = impl_substs(ctx, method, field_ty).unwrap_or_default();
+    let own_params = hir::GenericDef::from(func).params(ctx.db());
+    let signature = param_list
+        .syntax()
+        .descendants()
+        .chain(method.ret_type().into_iter().flat_map(|it| it.syntax().descendants()))
+        .chain(method.generic_param_list().into_iter().flat_map(|it| it.syntax().descendants()))
+        .chain(method.where_clause().into_iter().flat_map(|it| it.syntax().descendants()));
+    for path in signature.filter_map(ast::Path::cast) {
+        if path.segment().map_or(false, |it| it.syntax().text() == "Self") {
+            return None;
+        }
+        match ctx.sema.resolve_path(&path) {
+            Some(PathResolution::SelfType(_)) => return None,
+            Some(PathResolution::TypeParam(it))
+                if !own_params.contains(&it) && !substs.contains_key(&it) =>
+            {
+                return None
+            }
+            _ => (),
+        }
+    }
+
+    let source_scope = ctx.sema.scope(method.syntax());
+    let transform = QualifyPaths::new(target_scope, &source_scope)
+        .or(SubstituteTypeParams::new(&source_scope, substs));
+
+    let mut params = vec![self_param.syntax().text().to_string()];
+    let mut args = Vec::new();
+    for param in param_list.params() {
+        let pat = match param.pat()? {
+            ast::Pat::IdentPat(it) if it.pat().is_none() => it,
+            _ => return None,
+        };
+        args.push(pat.name()?.text().to_string());
+        params.push(ast_transform::apply(&*transform, param).to_string());
+    }
+
+    let mut buf = String::new();
+    if method.const_token().is_some() {
+        buf.push_str("const ");
+    }
+    if method.async_token().is_some() {
+        buf.push_str("async ");
+    }
+    if method.unsafe_token().is_some() {
+        buf.push_str("unsafe ");
+    }
+    format_to!(buf, "fn {}", name);
+    if let Some(generic_params) = method.generic_param_list() {
+        format_to!(buf, "{}", ast_transform::apply(&*transform, generic_params));
+    }
+    format_to!(buf, "({})", params.join(", "));
+    if let Some(ret_type) = method.ret_type() {
+        format_to!(buf, " {}", ast_transform::apply(&*transform, ret_type));
+    }
+    if let Some(where_clause) = method.where_clause() {
+        format_to!(buf, " {}", ast_transform::apply(&*transform, where_clause));
+    }
+    format_to!(buf, " {{\n        ");
+    if method.unsafe_token().is_some() {
+        buf.push_str("unsafe { ");
+    }
+    format_to!(buf, "self.{}.{}({})", field_name, name, args.join(", "));
+    if method.async_token().is_some() {
+        buf.push_str(".await");
+    }
+    if method.unsafe_token().is_some() {
+        buf.push_str(" }");
+    }
+    buf.push_str("\n    }");
+    Some(buf)
+}
+
+/// Maps the type parameters of the impl containing `method` to the generic
+/// arguments of `field_ty`, e.g. `T` to `u32` for `impl<T> Foo<T>` and a field
+/// of type `Foo<u32>`.
+fn impl_substs(
+    ctx: &AssistContext,
+    method: &ast::Fn,
+    field_ty: Option<ast::Type>,
+) -> Option<FxHashMap<hir::TypeParam, ast::Type>> {
+    let impl_def = method.syntax().ancestors().find_map(ast::Impl::cast)?;
+    let impl_args = type_args(impl_def.self_ty()?)?;
+    let field_args = type_args(field_ty?)?;
+    let substs = impl_args
+        .into_iter()
+        .zip(field_args)
+        .filter_map(|(impl_arg, field_arg)| {
+            let path = match impl_arg {
+                ast::Type::PathType(it) => it.path()?,
+                _ => return None,
+            };
+            match ctx.sema.resolve_path(&path)? {
+                PathResolution::TypeParam(it) => Some((it, field_arg)),
+                _ => None,
+            }
+        })
+        .collect();
+    Some(substs)
+}
+
+fn type_args(ty: ast::Type) -> Option<Vec<ast::Type>> {
+    let path = match ty {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let generic_args = path.segment()?.generic_arg_list()?;
+    let res = generic_args
+        .generic_args()
+        .filter_map(|it| match it {
+            ast::GenericArg::TypeArg(it) => it.ty(),
+            _ => None,
+        })
+        .collect();
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_generate_delegate_methods() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+struct Age(u8);
+impl Age {
+    fn new(age: u8) -> Self { Age(age) }
+    fn age(&self) -> u8 { self.0 }
+    fn set_age(&mut self, age: u8) { self.0 = age; }
+    fn into_inner<T: From<u8>>(self) -> T where T: Copy { T::from(self.0) }
+}
+
+struct Person {
+    ag<|>e: Age,
+}
+"#,
+            r#"
+struct Age(u8);
+impl Age {
+    fn new(age: u8) -> Self { Age(age) }
+    fn age(&self) -> u8 { self.0 }
+    fn set_age(&mut self, age: u8) { self.0 = age; }
+    fn into_inner<T: From<u8>>(self) -> T where T: Copy { T::from(self.0) }
+}
+
+struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn $0age(&self) -> u8 {
+        self.age.age()
+    }
+
+    fn set_age(&mut self, age: u8) {
+        self.age.set_age(age)
+    }
+
+    fn into_inner<T: From<u8>>(self) -> T where T: Copy {
+        self.age.into_inner()
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_skips_existing_and_trait_methods() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+trait Named { fn name(&self) -> String; }
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 { self.0 }
+    fn is_adult(&self) -> bool { self.0 >= 18 }
+}
+impl Named for Age {
+    fn name(&self) -> String { String::new() }
+}
+
+pub struct Person {
+    ag<|>e: Age,
+}
+
+impl Person {
+    fn age(&self) -> u8 { 0 }
+}
+"#,
+            r#"
+trait Named { fn name(&self) -> String; }
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 { self.0 }
+    fn is_adult(&self) -> bool { self.0 >= 18 }
+}
+impl Named for Age {
+    fn name(&self) -> String { String::new() }
+}
+
+pub struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn age(&self) -> u8 { 0 }
+
+    pub fn $0is_adult(&self) -> bool {
+        self.age.is_adult()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_skips_methods_mentioning_self() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+#[derive(Clone)]
+struct Age(u8);
+impl Age {
+    fn dup(&self) -> Self { self.clone() }
+    fn merge(&mut self, other: &Age) { self.0 += other.0; }
+    fn is_older(&self, other: &Self) -> bool { self.0 > other.0 }
+}
+
+struct Person {
+    ag<|>e: Age,
+}
+"#,
+            r#"
+#[derive(Clone)]
+struct Age(u8);
+impl Age {
+    fn dup(&self) -> Self { self.clone() }
+    fn merge(&mut self, other: &Age) { self.0 += other.0; }
+    fn is_older(&self, other: &Self) -> bool { self.0 > other.0 }
+}
+
+struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn $0merge(&mut self, other: &Age) {
+        self.age.merge(other)
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_substitutes_impl_params() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+struct Wrapper<T>(T);
+impl<T> Wrapper<T> {
+    fn get(&self) -> &T { &self.0 }
+    fn set(&mut self, value: T) { self.0 = value; }
+}
+
+struct Person {
+    ag<|>e: Wrapper<u32>,
+}
+"#,
+            r#"
+struct Wrapper<T>(T);
+impl<T> Wrapper<T> {
+    fn get(&self) -> &T { &self.0 }
+    fn set(&mut self, value: T) { self.0 = value; }
+}
+
+struct Person {
+    age: Wrapper<u32>,
+}
+
+impl Person {
+    fn $0get(&self) -> &u32 {
+        self.age.get()
+    }
+
+    fn set(&mut self, value: u32) {
+        self.age.set(value)
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_qualifies_paths() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+mod age {
+    pub struct Years(pub u8);
+    pub struct Age(Years);
+    impl Age {
+        pub fn years(&self) -> &Years { &self.0 }
+    }
+}
+
+struct Person {
+    ag<|>e: age::Age,
+}
+"#,
+            r#"
+mod age {
+    pub struct Years(pub u8);
+    pub struct Age(Years);
+    impl Age {
+        pub fn years(&self) -> &Years { &self.0 }
+    }
+}
+
+struct Person {
+    age: age::Age,
+}
+
+impl Person {
+    fn $0years(&self) -> &age::Years {
+        self.age.years()
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_not_applicable_without_methods() {
+        check_assist_not_applicable(
+            generate_delegate_methods,
+            r#"
+struct Age(u8);
+struct Person {
+    ag<|>e: Age,
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use stdx::to_lower_snake_case;
+use syntax::ast::{
+    self,
+    edit::{AstNodeEdit, IndentLevel},
+    make, AstNode, NameOwner, StructKind, VisibilityOwner,
+};
+
+use crate::{
+    utils::{find_impl_block, impl_insertion},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_enum_is_method
+//
+// Generate an `is_` method for an enum variant.
+//
+// ```
+// enum Version {
+//  Undefined,
+//  Minor<|>,
+//  Major,
+// }
+// ```
+// ->
+// ```
+// enum Version {
+//  Undefined,
+//  Minor,
+//  Major,
+// }
+//
+// impl Version {
+//     fn $0is_minor(&self) -> bool {
+//         match self {
+//             Self::Minor => true,
+//             _ => false,
+//         }
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_enum_is_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_enum_match_method(acc, ctx, MethodKind::Is)
+}
+
+// Assist: generate_enum_as_method
+//
+// Generate an `as_` method for an enum variant.
+//
+// ```
+// enum Value {
+//  Number(i32),
+//  Text(String)<|>,
+// }
+// ```
+// ->
+// ```
+// enum Value {
+//  Number(i32),
+//  Text(String),
+// }
+//
+// impl Value {
+//     fn $0as_text(&self) -> Option<&String> {
+//         match self {
+//             Self::Text(v) => Some(v),
+//             _ => None,
+//         }
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_enum_as_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_enum_match_method(acc, ctx, MethodKind::As)
+}
+
+// Assist: generate_enum_into_method
+//
+// Generate an `into_` method for an enum variant.
+//
+// ```
+// enum Value {
+//  Number(i32),
+//  Text(String)<|>,
+// }
+// ```
+// ->
+// ```
+// enum Value {
+//  Number(i32),
+//  Text(String),
+// }
+//
+// impl Value {
+//     fn $0into_text(self) -> Result<String, Self> {
+//         match self {
+//             Self::Text(v) => Ok(v),
+//             _ => Err(self),
+//         }
+//     }
+// }
+//
+// ```
+pub(crate) fn generate_enum_into_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    generate_enum_match_method(acc, ctx, MethodKind::Into)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MethodKind {
+    Is,
+    As,
+    Into,
+}
+
+impl MethodKind {
+    fn id(self) -> &'static str {
+        match self {
+            MethodKind::Is => "generate_enum_is_method",
+            MethodKind::As => "generate_enum_as_method",
+            MethodKind::Into => "generate_enum_into_method",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            MethodKind::Is => "Generate an `is_` method for this enum variant",
+            MethodKind::As => "Generate an `as_` method for this enum variant",
+            MethodKind::Into => "Generate an `into_` method for this enum variant",
+        }
+    }
+
+    fn fn_name(self, variant_name: &str) -> String {
+        let prefix = match self {
+            MethodKind::Is => "is",
+            MethodKind::As => "as",
+            MethodKind::Into => "into",
+        };
+        format!("{}_{}", prefix, to_lower_snake_case(variant_name))
+    }
+}
+
+fn generate_enum_match_method(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+    kind: MethodKind,
+) -> Option<()> {
+    let variant = ctx.find_node_at_offset::<ast::Variant>()?;
+    let variant_name = variant.name()?;
+    let parent_enum = variant.parent_enum();
+    let fn_name = kind.fn_name(variant_name.text());
+    let method = render_method(kind, &variant, &fn_name, parent_enum.visibility())?;
+
+    let adt = ast::AdtDef::Enum(parent_enum);
+    let impl_def = find_impl_block(ctx, &adt, &[&fn_name])?;
+
+    let target = variant.syntax().text_range();
+    acc.add(AssistId(kind.id(), AssistKind::Generate), kind.label(), target, |builder| {
+        let method = method.indent(IndentLevel(1)).to_string();
+        let (offset, mut buf) = impl_insertion(&adt, impl_def.as_ref(), &format!("    {}", method));
+
+        match ctx.config.snippet_cap {
+            None => builder.insert(offset, buf),
+            Some(cap) => {
+                buf = buf.replacen(&format!("fn {}(", fn_name), &format!("fn $0{}(", fn_name), 1);
+                builder.insert_snippet(cap, offset, buf);
+            }
+        }
+    })
+}
+
+/// Builds `fn <fn_name>(&self) -> <ret_ty> { match self { <pat> => <hit>, _ => <miss> } }`.
+/// Returns `None` for `as_` and `into_` methods of unit variants, which have
+/// nothing to return.
+fn render_method(
+    kind: MethodKind,
+    variant: &ast::Variant,
+    fn_name: &str,
+    vis: Option<ast::Visibility>,
+) -> Option<ast::Fn> {
+    let path = make::path_qualified(
+        make::path_unqualified(make::path_segment(make::name_ref("Self"))),
+        make::path_segment(make::name_ref(variant.name()?.text())),
+    );
+
+    let (names, tys): (Vec<String>, Vec<ast::Type>) = match variant.kind() {
+        StructKind::Unit => (Vec::new(), Vec::new()),
+        StructKind::Tuple(field_list) => {
+            let fields = field_list.fields().collect::<Vec<_>>();
+            let names = match fields.len() {
+                1 => vec!["v".to_string()],
+                _ => (0..fields.len()).map(|idx| format!("v{}", idx)).collect(),
+            };
+            (names, fields.iter().map(|it| it.ty()).collect::<Option<_>>()?)
+        }
+        StructKind::Record(field_list) => field_list
+            .fields()
+            .map(|it| Some((it.name()?.text().to_string(), it.ty()?)))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .unzip(),
+    };
+
+    let bindings = if kind == MethodKind::Is {
+        vec![ast::Pat::from(make::rest_pat())]
+    } else {
+        names.iter().map(|it| make::ident_pat(make::name(it)).into()).collect()
+    };
+    let pat = match variant.kind() {
+        StructKind::Unit => make::path_pat(path),
+        StructKind::Tuple(_) => make::tuple_struct_pat(path, bindings).into(),
+        StructKind::Record(_) => make::record_pat(path, bindings).into(),
+    };
+
+    let value = match names.len() {
+        0 if kind != MethodKind::Is => return None,
+        1 => expr_from_name(&names[0]),
+        _ => make::expr_tuple(names.iter().map(|it| expr_from_name(it))),
+    };
+    let value_ty = |by_ref: bool| {
+        let tys = tys.iter().map(|ty| if by_ref { format!("&{}", ty) } else { ty.to_string() });
+        match tys.len() {
+            1 => tys.collect::<String>(),
+            _ => format!("({})", tys.collect::<Vec<_>>().join(", ")),
+        }
+    };
+    let (ret_ty, hit, miss) = match kind {
+        MethodKind::Is => (
+            "bool".to_string(),
+            make::expr_literal("true").into(),
+            make::expr_literal("false").into(),
+        ),
+        MethodKind::As => (
+            format!("Option<{}>", value_ty(true)),
+            make::expr_call(expr_from_name("Some"), make::arg_list(Some(value))),
+            expr_from_name("None"),
+        ),
+        MethodKind::Into => (
+            format!("Result<{}, Self>", value_ty(false)),
+            make::expr_call(expr_from_name("Ok"), make::arg_list(Some(value))),
+            make::expr_call(expr_from_name("Err"), make::arg_list(Some(expr_self()))),
+        ),
+    };
+
+    let match_expr = make::expr_match(
+        expr_self(),
+        make::match_arm_list(vec![
+            make::match_arm(Some(pat), hit),
+            make::match_arm(Some(make::wildcard_pat().into()), miss),
+        ]),
+    );
+    let body = make::block_expr(None, Some(match_expr.indent(IndentLevel(1))));
+    let params = make::param_list(Some(make::self_param(kind != MethodKind::Into)), None);
+    let ret_type = make::ret_type(make::ty(&ret_ty));
+    Some(make::fn_(vis, make::name(fn_name), None, params, Some(ret_type), body))
+}
+
+fn expr_from_name(name: &str) -> ast::Expr {
+    make::expr_path(make::path_unqualified(make::path_segment(make::name_ref(name))))
+}
+
+fn expr_self() -> ast::Expr {
+    make::expr_path(make::path_unqualified(make::path_segment_self()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn is_method_for_record_variant() {
+        check_assist(
+            generate_enum_is_method,
+            r#"
+pub(crate) enum Shape {
+    Circle { radius: f64 },
+    Rect {<|> width: f64, height: f64 },
+}
+"#,
+            r#"
+pub(crate) enum Shape {
+    Circle { radius: f64 },
+    Rect { width: f64, height: f64 },
+}
+
+impl Shape {
+    pub(crate) fn $0is_rect(&self) -> bool {
+        match self {
+            Self::Rect { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn as_method_into_existing_impl() {
+        check_assist(
+            generate_enum_as_method,
+            r#"
+enum Token<'a, T> {
+    Ident(&'a str),
+    Pair(T, u32)<|>,
+}
+
+impl<'a, T> Token<'a, T> {
+    fn len(&self) -> usize { 0 }
+}
+"#,
+            r#"
+enum Token<'a, T> {
+    Ident(&'a str),
+    Pair(T, u32),
+}
+
+impl<'a, T> Token<'a, T> {
+    fn len(&self) -> usize { 0 }
+
+    fn $0as_pair(&self) -> Option<(&T, &u32)> {
+        match self {
+            Self::Pair(v0, v1) => Some((v0, v1)),
+            _ => None,
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn into_method_for_record_variant() {
+        check_assist(
+            generate_enum_into_method,
+            r#"
+enum Shape {
+    Circle { radius: f64 }<|>,
+    Square(f64),
+}
+
+impl Shape {}
+"#,
+            r#"
+enum Shape {
+    Circle { radius: f64 },
+    Square(f64),
+}
+
+impl Shape {
+    fn $0into_circle(self) -> Result<f64, Self> {
+        match self {
+            Self::Circle { radius } => Ok(radius),
+            _ => Err(self),
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_unit_variant_or_existing_method() {
+        check_assist_not_applicable(
+            generate_enum_as_method,
+            r#"
+enum Version { Minor<|>, Major }
+"#,
+        );
+        check_assist_not_applicable(
+            generate_enum_is_method,
+            r#"
+enum Version { Minor<|>, Major }
+impl Version {
+    fn is_minor(&self) -> bool { true }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,991 @@
+use hir::{HasSource, HirDisplay, Local, PathResolution};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, edit::IndentLevel, make, ArgListOwner, AstNode, NameOwner},
+    match_ast, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{
+    ast_transform::{AstTransform, QualifyPaths, SubstituteTypeParams},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: inline_call
+//
+// Inlines a function or method body at the call site.
+//
+// ```
+// fn add(a: u32, b: u32) -> u32 { a + b }
+// fn main() {
+//     let x = add<|>(1, 2);
+// }
+// ```
+// ->
+// ```
+// fn add(a: u32, b: u32) -> u32 { a + b }
+// fn main() {
+//     let x = 1 + 2;
+// }
+// ```
+pub(crate) fn inline_call(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = CallSite::at_offset(ctx)?;
+    let function = call.resolve(ctx)?;
+    let name = function.name(ctx.db());
+
+    // Only functions written in a file can be inlined, not macro-generated ones.
+    let src = function.source(ctx.db());
+    let callee_file = src.file_id.original_file(ctx.db());
+    let callee = find_node_at_range::<ast::Fn>(
+        ctx.sema.parse(callee_file).syntax(),
+        src.value.syntax().text_range(),
+    )?;
+    if callee.syntax().text_range() != src.value.syntax().text_range()
+        || callee.async_token().is_some()
+    {
+        return None;
+    }
+    let body = callee.body()?;
+    if has_early_exit(body.syntax()) {
+        return None;
+    }
+
+    let mut args = call.args();
+    let param_list = callee.param_list()?;
+    let self_arg = match (param_list.self_param(), &call) {
+        (Some(_), CallSite::Method(it)) => Some(it.expr()?),
+        (Some(_), CallSite::Call(_)) if !args.is_empty() => Some(args.remove(0)),
+        (None, _) => None,
+        _ => return None,
+    };
+    let params = param_list.params().collect::<Vec<_>>();
+    if params.len() != args.len() {
+        return None;
+    }
+
+    let type_substs = type_substs(ctx, function, &call, &params, &args);
+    if !can_transplant(ctx, &body, &type_substs, call.syntax()) {
+        return None;
+    }
+
+    let target = call.syntax().text_range();
+    acc.add(
+        AssistId("inline_call", AssistKind::RefactorInline),
+        format!("Inline `{}`", name),
+        target,
+        |builder| {
+            let source_scope = ctx.sema.scope(body.syntax());
+            let target_scope = ctx.sema.scope(call.syntax());
+            let transform = QualifyPaths::new(&target_scope, &source_scope)
+                .or(SubstituteTypeParams::new(&source_scope, type_substs));
+            let inliner = Inliner::new(ctx, &callee, self_arg.as_ref(), &args);
+            let replacement =
+                inliner.inline(ctx, &callee, self_arg, args, call.syntax(), &*transform);
+            builder.replace(target, replacement);
+        },
+    )
+}
+
+/// Finds the types the generic parameters of `function` are instantiated with
+/// at the call site, either from the turbofish or from the arguments for
+/// parameters whose type is a generic parameter.
+fn type_substs(
+    ctx: &AssistContext,
+    function: hir::Function,
+    call: &CallSite,
+    params: &[ast::Param],
+    args: &[ast::Expr],
+) -> FxHashMap<hir::TypeParam, ast::Type> {
+    let generic_params = hir::GenericDef::from(function).params(ctx.db());
+    if let Some(generic_args) = call.generic_arg_list() {
+        let type_args = generic_args.generic_args().filter_map(|it| match it {
+            ast::GenericArg::TypeArg(it) => it.ty(),
+            _ => None,
+        });
+        return generic_params.into_iter().zip(type_args).collect();
+    }
+
+    let module = match ctx.sema.scope(call.syntax()).module() {
+        Some(it) => it,
+        None => return FxHashMap::default(),
+    };
+    let mut res = FxHashMap::default();
+    for (param, arg) in params.iter().zip(args) {
+        let path = match param.ty() {
+            Some(ast::Type::PathType(it)) => it.path(),
+            _ => None,
+        };
+        let type_param = match path.and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::TypeParam(it)) if generic_params.contains(&it) => it,
+            _ => continue,
+        };
+        let arg_ty = ctx
+            .sema
+            .type_of_expr(arg)
+            .and_then(|ty| ty.display_source_code(ctx.db(), module.into()).ok());
+        if let Some(arg_ty) = arg_ty {
+            res.insert(type_param, make::ty(&arg_ty));
+        }
+    }
+    res
+}
+
+/// Checks that the paths in `body` can be rewritten to mean the same at the
+/// call site: generic parameters need to be substituted, which is only done
+/// in types, and `Self` is only valid inside of its impl.
+fn can_transplant(
+    ctx: &AssistContext,
+    body: &ast::BlockExpr,
+    type_substs: &FxHashMap<hir::TypeParam, ast::Type>,
+    call: &SyntaxNode,
+) -> bool {
+    let call_impl = call.ancestors().find_map(ast::Impl::cast).and_then(|it| ctx.sema.to_def(&it));
+    body.syntax().descendants().filter_map(ast::Path::cast).all(|path| {
+        match ctx.sema.resolve_path(&path) {
+            Some(PathResolution::TypeParam(it)) => {
+                let is_type =
+                    matches!(path.syntax().parent(), Some(it) if ast::PathType::can_cast(it.kind()));
+                is_type && type_substs.contains_key(&it)
+            }
+            Some(PathResolution::SelfType(it)) => call_impl == Some(it),
+            _ => true,
+        }
+    })
+}
+
+enum CallSite {
+    Call(ast::CallExpr),
+    Method(ast::MethodCallExpr),
+}
+
+impl CallSite {
+    /// Finds the call whose callee is under the cursor.
+    fn at_offset(ctx: &AssistContext) -> Option<CallSite> {
+        let name_ref = ctx.find_node_at_offset::<ast::NameRef>()?;
+        let parent = name_ref.syntax().parent()?;
+        if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+            return Some(CallSite::Method(call));
+        }
+        let path = ast::PathSegment::cast(parent)?.parent_path();
+        let path_expr = ast::PathExpr::cast(path.syntax().parent()?)?;
+        let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+        Some(CallSite::Call(call))
+    }
+
+    fn resolve(&self, ctx: &AssistContext) -> Option<hir::Function> {
+        match self {
+            CallSite::Call(call) => {
+                let path = match call.expr()? {
+                    ast::Expr::PathExpr(it) => it.path()?,
+                    _ => return None,
+                };
+                match ctx.sema.resolve_path(&path)? {
+                    PathResolution::Def(hir::ModuleDef::Function(it)) => Some(it),
+                    PathResolution::AssocItem(hir::AssocItem::Function(it)) => Some(it),
+                    _ => None,
+                }
+            }
+            CallSite::Method(call) => ctx.sema.resolve_method_call(call),
+        }
+    }
+
+    fn generic_arg_list(&self) -> Option<ast::GenericArgList> {
+        match self {
+            CallSite::Call(call) => match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?.segment()?.generic_arg_list(),
+                _ => None,
+            },
+            CallSite::Method(call) => call.generic_arg_list(),
+        }
+    }
+
+    fn args(&self) -> Vec<ast::Expr> {
+        let arg_list = match self {
+            CallSite::Call(it) => it.arg_list(),
+            CallSite::Method(it) => it.arg_list(),
+        };
+        arg_list.into_iter().flat_map(|it| it.args()).collect()
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            CallSite::Call(it) => it.syntax(),
+            CallSite::Method(it) => it.syntax(),
+        }
+    }
+}
+
+/// Collects what is needed to rewrite the body of the callee: the usages of
+/// its locals and the new names of locals which would capture names used by
+/// the arguments.
+struct Inliner {
+    usages: FxHashMap<Local, Vec<ast::PathExpr>>,
+    self_usages: Vec<ast::PathExpr>,
+    renames: FxHashMap<Local, String>,
+    /// Names which are used by the arguments or by the body.
+    taken: FxHashSet<String>,
+}
+
+impl Inliner {
+    fn new(
+        ctx: &AssistContext,
+        callee: &ast::Fn,
+        self_arg: Option<&ast::Expr>,
+        args: &[ast::Expr],
+    ) -> Inliner {
+        let db = ctx.db();
+        let mut usages: FxHashMap<Local, Vec<ast::PathExpr>> = FxHashMap::default();
+        let mut self_usages = Vec::new();
+        let mut taken = FxHashSet::default();
+        for path_expr in callee.syntax().descendants().filter_map(ast::PathExpr::cast) {
+            if let Some(name) = path_expr.path().and_then(|it| it.segment()?.name_ref()) {
+                taken.insert(name.text().to_string());
+            }
+            match path_expr.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+                Some(PathResolution::Local(local)) if local.is_self(db) => {
+                    self_usages.push(path_expr)
+                }
+                Some(PathResolution::Local(local)) => {
+                    usages.entry(local).or_default().push(path_expr)
+                }
+                _ => (),
+            }
+        }
+
+        // A local of the callee captures a name of the caller if the argument
+        // which mentions it ends up in its scope.
+        let caller_names = self_arg
+            .into_iter()
+            .chain(args)
+            .flat_map(|it| it.syntax().descendants())
+            .filter_map(ast::NameRef::cast)
+            .map(|it| it.text().to_string())
+            .collect::<FxHashSet<_>>();
+        taken.extend(caller_names.iter().cloned());
+
+        let mut res = Inliner { usages, self_usages, renames: FxHashMap::default(), taken };
+        let callee_locals = callee
+            .syntax()
+            .descendants()
+            .filter_map(ast::IdentPat::cast)
+            .filter_map(|it| ctx.sema.to_def(&it))
+            .unique()
+            .collect::<Vec<_>>();
+        for local in callee_locals {
+            let name = match local.name(db) {
+                Some(it) => it.to_string(),
+                None => continue,
+            };
+            if caller_names.contains(&name) {
+                let new_name = res.fresh_name(&name);
+                res.renames.insert(local, new_name);
+            }
+        }
+        res
+    }
+
+    fn fresh_name(&mut self, name: &str) -> String {
+        let new_name = (1..)
+            .map(|idx| format!("{}{}", name, idx))
+            .find(|it| !self.taken.contains(it))
+            .unwrap();
+        self.taken.insert(new_name.clone());
+        new_name
+    }
+
+    fn inline(
+        mut self,
+        ctx: &AssistContext,
+        callee: &ast::Fn,
+        self_arg: Option<ast::Expr>,
+        args: Vec<ast::Expr>,
+        call: &SyntaxNode,
+        transform: &dyn AstTransform,
+    ) -> String {
+        let db = ctx.db();
+        let mut edits: Vec<(TextRange, String)> = Vec::new();
+        let mut lets = Vec::new();
+
+        if let Some(body) = callee.body() {
+            transform_paths(ctx, body.syntax(), transform, &mut edits);
+        }
+
+        for (local, new_name) in &self.renames {
+            if let either::Either::Left(pat) = local.source(db).value {
+                if let Some(name) = pat.name() {
+                    edits.push((name.syntax().text_range(), new_name.clone()));
+                }
+            }
+            for usage in self.usages.get(local).into_iter().flatten() {
+                edits.push((usage.syntax().text_range(), replacement_for(usage, new_name)));
+            }
+        }
+
+        let param_list = match callee.param_list() {
+            Some(it) => it,
+            None => return String::new(),
+        };
+        if let (Some(self_param), Some(self_arg)) = (param_list.self_param(), self_arg) {
+            let arg_is_ref =
+                matches!(ctx.sema.type_of_expr(&self_arg), Some(ty) if ty.as_reference().is_some());
+            let prefix = match (self_param.amp_token(), self_param.mut_token()) {
+                _ if arg_is_ref => "",
+                (Some(_), Some(_)) => "&mut ",
+                (Some(_), None) => "&",
+                (None, _) => "",
+            };
+            let arg_text = self_arg.syntax().text().to_string();
+            if is_pure(&self_arg) && self.self_usages.len() <= 1 {
+                for usage in &self.self_usages {
+                    let text = if prefix.is_empty() || is_auto_derefed(usage) {
+                        parenthesize(usage, arg_text.clone(), needs_parens(&self_arg))
+                    } else {
+                        parenthesize(usage, format!("{}{}", prefix, arg_text), true)
+                    };
+                    edits.push((usage.syntax().text_range(), text));
+                }
+            } else {
+                let this = if self.taken.contains("this") {
+                    self.fresh_name("this")
+                } else {
+                    "this".to_string()
+                };
+                let mutability =
+                    if self_param.amp_token().is_none() && self_param.mut_token().is_some() {
+                        "mut "
+                    } else {
+                        ""
+                    };
+                lets.push(format!("let {}{} = {}{};", mutability, this, prefix, arg_text));
+                for usage in &self.self_usages {
+                    edits.push((usage.syntax().text_range(), this.clone()));
+                }
+            }
+        }
+
+        let text = callee.syntax().ancestors().last().unwrap().text().to_string();
+        for (param, arg) in param_list.params().zip(args) {
+            let pat = match param.pat() {
+                Some(it) => it,
+                None => continue,
+            };
+            let local = match &pat {
+                ast::Pat::IdentPat(it)
+                    if it.mut_token().is_none()
+                        && it.ref_token().is_none()
+                        && it.pat().is_none() =>
+                {
+                    ctx.sema.to_def(it)
+                }
+                _ => None,
+            };
+            let arg_text = arg.syntax().text().to_string();
+            if let Some(local) = local {
+                let usages = self.usages.get(&local).cloned().unwrap_or_default();
+                if is_pure(&arg) && usages.len() <= 1 {
+                    for usage in usages {
+                        let arg_text = parenthesize(&usage, arg_text.clone(), needs_parens(&arg));
+                        edits.retain(|(range, _)| *range != usage.syntax().text_range());
+                        edits.push((
+                            usage.syntax().text_range(),
+                            replacement_for(&usage, &arg_text),
+                        ));
+                    }
+                    continue;
+                }
+                if usages.is_empty() {
+                    lets.push(format!("let _ = {};", arg_text));
+                    continue;
+                }
+            }
+            let pat_text = apply_edits(&text, pat.syntax().text_range(), &edits);
+            lets.push(format!("let {} = {};", pat_text, arg_text));
+        }
+
+        let body = match callee.body() {
+            Some(it) => it,
+            None => return String::new(),
+        };
+        render_body(&text, &body, &edits, &lets, call)
+    }
+}
+
+/// Records the edits which qualify the paths below `node` for the call site
+/// and substitute the generic parameters of the callee. Locals are left to the `Inliner`.
+fn transform_paths(
+    ctx: &AssistContext,
+    node: &SyntaxNode,
+    transform: &dyn AstTransform,
+    edits: &mut Vec<(TextRange, String)>,
+) {
+    for child in node.children() {
+        let resolution = ast::Path::cast(child.clone()).and_then(|it| ctx.sema.resolve_path(&it));
+        if matches!(resolution, Some(PathResolution::Local(_))) {
+            continue;
+        }
+        match transform.get_substitution(&child) {
+            Some(replacement) => {
+                if replacement.text() != child.text() {
+                    edits.push((child.text_range(), replacement.to_string()));
+                }
+            }
+            None => transform_paths(ctx, &child, transform, edits),
+        }
+    }
+}
+
+/// Renders the body of the callee so that it can replace `call`.
+fn render_body(
+    text: &str,
+    body: &ast::BlockExpr,
+    edits: &[(TextRange, String)],
+    lets: &[String],
+    call: &SyntaxNode,
+) -> String {
+    let old_indent = IndentLevel::from_node(body.syntax());
+    let new_indent = IndentLevel::from_node(call);
+    let reindent =
+        |text: String| text.replace(&format!("\n{}", old_indent), &format!("\n{}", new_indent));
+
+    match body.expr() {
+        Some(tail) if lets.is_empty() && body.statements().next().is_none() => {
+            let tail_text = reindent(apply_edits(text, tail.syntax().text_range(), edits));
+            let in_expr = matches!(call.parent(), Some(it) if ast::Expr::can_cast(it.kind()));
+            if in_expr && needs_parens(&tail) {
+                return format!("({})", tail_text);
+            }
+            return tail_text;
+        }
+        _ => (),
+    }
+
+    let body_text = apply_edits(text, body.syntax().text_range(), edits);
+    if body_text.contains('\n') {
+        let body_text = reindent(body_text);
+        let mut buf = String::from("{");
+        for let_stmt in lets {
+            format_to!(buf, "\n{}{}", new_indent + 1, let_stmt);
+        }
+        buf.push_str(&body_text[1..]);
+        return buf;
+    }
+
+    // `{ a + b }` is rendered as a block with one line per statement.
+    let stmts = body.statements().map(|it| apply_edits(text, it.syntax().text_range(), edits));
+    let tail = body.expr().map(|it| apply_edits(text, it.syntax().text_range(), edits));
+    let mut buf = String::from("{");
+    for line in lets.iter().cloned().chain(stmts).chain(tail) {
+        format_to!(buf, "\n{}{}", new_indent + 1, line);
+    }
+    format_to!(buf, "\n{}}}", new_indent);
+    buf
+}
+
+fn needs_parens(expr: &ast::Expr) -> bool {
+    !matches!(
+        expr,
+        ast::Expr::Literal(_)
+            | ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::TupleExpr(_)
+            | ast::Expr::ArrayExpr(_)
+            | ast::Expr::BlockExpr(_)
+            | ast::Expr::MacroCall(_)
+            | ast::Expr::RecordExpr(_)
+            | ast::Expr::TryExpr(_)
+    )
+}
+
+/// Renders `range` of `text` with the given replacements applied.
+fn apply_edits(text: &str, range: TextRange, edits: &[(TextRange, String)]) -> String {
+    let mut edits = edits.iter().filter(|(it, _)| range.contains_range(*it)).collect::<Vec<_>>();
+    edits.sort_by_key(|(it, _)| it.start());
+    let mut res = String::new();
+    let mut cursor = range.start();
+    for (edit_range, replacement) in edits {
+        res.push_str(&text[TextRange::new(cursor, edit_range.start())]);
+        res.push_str(replacement);
+        cursor = edit_range.end();
+    }
+    res.push_str(&text[TextRange::new(cursor, range.end())]);
+    res
+}
+
+/// Wraps `text` into parentheses if it replaces `usage` inside of another
+/// expression.
+fn parenthesize(usage: &ast::PathExpr, text: String, needs_parens: bool) -> String {
+    let in_expr = matches!(usage.syntax().parent(), Some(it) if ast::Expr::can_cast(it.kind()));
+    if needs_parens && in_expr {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+/// Returns the text which replaces `usage`, taking care of record field
+/// shorthands like `S { a }`.
+fn replacement_for(usage: &ast::PathExpr, text: &str) -> String {
+    let shorthand = usage
+        .syntax()
+        .parent()
+        .and_then(ast::RecordExprField::cast)
+        .filter(|it| it.name_ref().is_none());
+    match shorthand {
+        Some(_) => format!("{}: {}", usage.syntax().text(), text),
+        None => text.to_string(),
+    }
+}
+
+/// Checks whether evaluating `expr` has no side effects, so that it can be
+/// moved into the body of the callee.
+fn is_pure(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(_) | ast::Expr::PathExpr(_) => true,
+        ast::Expr::RefExpr(it) => it.expr().filter(is_pure).is_some(),
+        ast::Expr::FieldExpr(it) => it.expr().filter(is_pure).is_some(),
+        ast::Expr::ParenExpr(it) => it.expr().filter(is_pure).is_some(),
+        _ => false,
+    }
+}
+
+/// Checks whether a reference is dereferenced automatically where `path_expr`
+/// is used.
+fn is_auto_derefed(path_expr: &ast::PathExpr) -> bool {
+    let expr = Some(ast::Expr::from(path_expr.clone()));
+    match path_expr.syntax().parent() {
+        Some(parent) => match_ast! {
+            match parent {
+                ast::MethodCallExpr(it) => it.expr() == expr,
+                ast::FieldExpr(it) => it.expr() == expr,
+                _ => false,
+            }
+        },
+        None => false,
+    }
+}
+
+/// Checks whether `node` leaves the function early, which can't be expressed
+/// once the body is inlined.
+fn has_early_exit(node: &SyntaxNode) -> bool {
+    node.children().any(|child| match child.kind() {
+        SyntaxKind::RETURN_EXPR | SyntaxKind::TRY_EXPR => true,
+        SyntaxKind::CLOSURE_EXPR => false,
+        kind if ast::Item::can_cast(kind) => false,
+        _ => has_early_exit(&child),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_simple_expression() {
+        check_assist(
+            inline_call,
+            r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = add<|>(1, 2) * 3;
+}
+"#,
+            r#"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = (1 + 2) * 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn binds_arguments_used_twice_or_with_side_effects() {
+        check_assist(
+            inline_call,
+            r#"
+fn make() -> u32 { 0 }
+fn square(a: u32, b: u32) -> u32 {
+    let c = a * a;
+    c + b
+}
+fn main() {
+    let n = 1;
+    let x = square<|>(n, make());
+}
+"#,
+            r#"
+fn make() -> u32 { 0 }
+fn square(a: u32, b: u32) -> u32 {
+    let c = a * a;
+    c + b
+}
+fn main() {
+    let n = 1;
+    let x = {
+        let a = n;
+        let b = make();
+        let c = a * a;
+        c + b
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_argument_with_side_effects_is_kept() {
+        check_assist(
+            inline_call,
+            r#"
+fn make() -> u32 { 0 }
+fn ignore(_a: u32) {}
+fn main() {
+    ignore<|>(make());
+}
+"#,
+            r#"
+fn make() -> u32 { 0 }
+fn ignore(_a: u32) {}
+fn main() {
+    {
+        let _ = make();
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn renames_clashing_locals() {
+        check_assist(
+            inline_call,
+            r#"
+fn foo(a: u32) -> u32 {
+    let x = 1;
+    a + x
+}
+fn main() {
+    let x = 2;
+    let y = foo<|>(x);
+}
+"#,
+            r#"
+fn foo(a: u32) -> u32 {
+    let x = 1;
+    a + x
+}
+fn main() {
+    let x = 2;
+    let y = {
+        let x1 = 1;
+        x + x1
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn mut_param_is_bound() {
+        check_assist(
+            inline_call,
+            r#"
+fn inc(mut a: u32) -> u32 {
+    a += 1;
+    a
+}
+fn main() {
+    let y = inc<|>(1);
+}
+"#,
+            r#"
+fn inc(mut a: u32) -> u32 {
+    a += 1;
+    a
+}
+fn main() {
+    let y = {
+        let mut a = 1;
+        a += 1;
+        a
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_method_with_path_receiver() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn get(&self) -> u32 { self.n }
+}
+fn main() {
+    let s = S { n: 1 };
+    let n = s.get<|>();
+}
+"#,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn get(&self) -> u32 { self.n }
+}
+fn main() {
+    let s = S { n: 1 };
+    let n = s.n;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_method_with_complex_receiver() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn sum(&mut self, k: u32) -> u32 {
+        self.n += k;
+        self.n
+    }
+}
+fn make() -> S { S { n: 0 } }
+fn main() {
+    let n = make().sum<|>(2);
+}
+"#,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn sum(&mut self, k: u32) -> u32 {
+        self.n += k;
+        self.n
+    }
+}
+fn make() -> S { S { n: 0 } }
+fn main() {
+    let n = {
+        let this = &mut make();
+        this.n += 2;
+        this.n
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_method_called_as_function() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn get(&self) -> u32 { self.n }
+}
+fn main() {
+    let s = S { n: 1 };
+    let n = S::get<|>(&s);
+}
+"#,
+            r#"
+struct S { n: u32 }
+impl S {
+    fn get(&self) -> u32 { self.n }
+}
+fn main() {
+    let s = S { n: 1 };
+    let n = (&s).n;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn record_field_shorthand() {
+        check_assist(
+            inline_call,
+            r#"
+struct S { n: u32 }
+fn new(n: u32) -> S { S { n } }
+fn main() {
+    let s = new<|>(92);
+}
+"#,
+            r#"
+struct S { n: u32 }
+fn new(n: u32) -> S { S { n } }
+fn main() {
+    let s = S { n: 92 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_paths_of_callee_in_other_module() {
+        check_assist(
+            inline_call,
+            r#"
+mod m {
+    pub struct S;
+    pub fn log() {}
+    pub fn make() -> S {
+        log();
+        S
+    }
+}
+fn main() {
+    let s = m::make<|>();
+}
+"#,
+            r#"
+mod m {
+    pub struct S;
+    pub fn log() {}
+    pub fn make() -> S {
+        log();
+        S
+    }
+}
+fn main() {
+    let s = {
+        m::log();
+        m::S
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn substitutes_generic_params() {
+        check_assist(
+            inline_call,
+            r#"
+struct W<T>(T);
+fn wrap<T>(x: T) -> W<T> {
+    let w: W<T> = W(x);
+    w
+}
+fn main() {
+    let a = wrap<|>(92u32);
+    let b = wrap::<u8>(1);
+}
+"#,
+            r#"
+struct W<T>(T);
+fn wrap<T>(x: T) -> W<T> {
+    let w: W<T> = W(x);
+    w
+}
+fn main() {
+    let a = {
+        let w: W<u32> = W(92u32);
+        w
+    };
+    let b = wrap::<u8>(1);
+}
+"#,
+        );
+        check_assist(
+            inline_call,
+            r#"
+struct W<T>(T);
+fn wrap<T>(x: T) -> W<T> {
+    let w: W<T> = W(x);
+    w
+}
+fn main() {
+    let b = wrap<|>::<u8>(1);
+}
+"#,
+            r#"
+struct W<T>(T);
+fn wrap<T>(x: T) -> W<T> {
+    let w: W<T> = W(x);
+    w
+}
+fn main() {
+    let b = {
+        let w: W<u8> = W(1);
+        w
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_generic_param_in_expr() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+trait Default { fn default() -> Self; }
+fn make<T: Default>() -> T { T::default() }
+fn main() {
+    let x: u32 = make<|>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_self_outside_of_impl() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+struct S;
+impl S {
+    fn new() -> Self { Self }
+}
+fn main() {
+    let s = S::new<|>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_return() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+fn foo(a: u32) -> u32 {
+    if a > 0 {
+        return 1;
+    }
+    0
+}
+fn main() {
+    foo<|>(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_of_callee() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+fn foo(a: u32) -> u32 { a }
+fn main() {
+    foo(<|>1);
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use base_db::FileLoader;
+use hir::ModuleSource;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, ModuleItemOwner, NameOwner},
+    SmolStr, TextRange,
+};
+
+use crate::{utils::split_lines, AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: move_module_to_file
+//
+// Moves inline module's contents to a separate file.
+//
+// ```
+// mod <|>foo {
+//     fn t() {}
+// }
+// ```
+// ->
+// ```
+// mod foo;
+// ```
+pub(crate) fn move_module_to_file(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module = ctx.find_node_at_offset::<ast::Module>()?;
+    let item_list = module.item_list()?;
+    if item_list.syntax().text_range().start() <= ctx.offset() {
+        return None;
+    }
+    let name = module.name()?;
+    let name_text = name.text().to_string();
+
+    let hir_module = ctx.sema.to_def(&module)?;
+    let parent = hir_module.parent(ctx.db())?;
+    let hir_name = hir_module.name(ctx.db())?;
+
+    // `#[path]` on an inline module names a directory, on `mod foo;` it names
+    // the file, so the attribute has to point to the `mod.rs` in that
+    // directory.
+    let new_attr = path_attr(&module).map(|(_, attr_path)| {
+        let mut new_attr = attr_path.to_string();
+        if !(new_attr.is_empty() || new_attr.ends_with('/')) {
+            new_attr.push('/');
+        }
+        new_attr.push_str("mod.rs");
+        SmolStr::from(new_attr)
+    });
+    let candidates = parent
+        .mod_decl_candidate_files(ctx.db(), &hir_name, new_attr.as_ref())
+        .into_iter()
+        .map(|dst| {
+            let child_prefix = if new_attr.is_some() || dst.ends_with("mod.rs") {
+                None
+            } else {
+                Some(name_text.clone())
+            };
+            (dst, new_attr.clone(), child_prefix)
+        })
+        .collect::<Vec<_>>();
+
+    let anchor = ctx.frange.file_id;
+    let l_curly = item_list.l_curly_token()?;
+    let r_curly = item_list.r_curly_token()?;
+    let target = TextRange::new(module.syntax().text_range().start(), l_curly.text_range().end());
+    let group = GroupLabel("Move module to file".to_string());
+    for (dst, new_attr, child_prefix) in candidates {
+        if ctx.db().resolve_path(anchor, &dst).is_some() {
+            continue;
+        }
+        acc.add_group(
+            &group,
+            AssistId("move_module_to_file", AssistKind::RefactorExtract),
+            format!("Move module to `{}`", dst),
+            target,
+            |builder| {
+                let contents = module_contents(&item_list, child_prefix.as_deref());
+                let indent = IndentLevel::from_node(module.syntax()) + 1;
+                let contents = dedent(&contents, indent);
+
+                if let (Some(new_attr), Some((literal, _))) = (new_attr, path_attr(&module)) {
+                    builder.replace(literal.syntax().text_range(), format!("\"{}\"", new_attr));
+                }
+                let body_range =
+                    TextRange::new(name.syntax().text_range().end(), r_curly.text_range().end());
+                builder.replace(body_range, ";");
+                builder.create_file(anchor, dst, contents);
+            },
+        );
+    }
+    Some(())
+}
+
+// Assist: inline_module_file
+//
+// Moves the contents of a module's file into an inline module.
+//
+// ```
+// //- /main.rs
+// mod <|>foo;
+// //- /foo.rs
+// fn t() {}
+// ```
+// ->
+// ```
+// mod foo {
+//     fn t() {}
+// }
+// ```
+pub(crate) fn inline_module_file(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module = ctx.find_node_at_offset::<ast::Module>()?;
+    if module.item_list().is_some() || path_attr(&module).is_some() {
+        return None;
+    }
+    let name = module.name()?;
+    let semicolon = module.semicolon_token()?;
+    let hir_module = ctx.sema.to_def(&module)?;
+    let source = hir_module.definition_source(ctx.db());
+    let file = source.file_id.original_file(ctx.db());
+    let source_file = match source.value {
+        ModuleSource::SourceFile(it) => it,
+        ModuleSource::Module(_) => return None,
+    };
+
+    // In `foo.rs`, `#[path]` is relative to the directory of the file, while
+    // in `mod foo {}` it is relative to `foo/`, which undoes the adjustment
+    // done by `move_module_to_file`.
+    let mut edits = Vec::new();
+    if !hir_module.is_mod_rs(ctx.db()) {
+        let prefix = format!("{}/", name.text());
+        for child in source_file.items().filter_map(|it| match it {
+            ast::Item::Module(it) => Some(it),
+            _ => None,
+        }) {
+            if let Some((literal, attr_path)) = path_attr(&child) {
+                let attr_path = attr_path.strip_prefix(&prefix)?;
+                edits.push((literal.syntax().text_range(), format!("\"{}\"", attr_path)));
+            }
+        }
+    }
+
+    let target = module.syntax().text_range();
+    acc.add(
+        AssistId("inline_module_file", AssistKind::RefactorInline),
+        "Inline module file",
+        target,
+        |builder| {
+            let text = source_file.syntax().text().to_string();
+            let contents = apply_edits(&text, edits);
+            let contents = contents.trim_end();
+            let indent = IndentLevel::from_node(module.syntax());
+
+            let mut body = String::from(" {");
+            if !contents.is_empty() {
+                for (line, in_string) in split_lines(contents) {
+                    body.push('\n');
+                    if in_string {
+                        body.push_str(line);
+                    } else if !line.trim().is_empty() {
+                        body.push_str(&format!("{}{}", indent + 1, line));
+                    }
+                }
+                body.push_str(&format!("\n{}", indent));
+            }
+            body.push('}');
+
+            let range =
+                TextRange::new(name.syntax().text_range().end(), semicolon.text_range().end());
+            builder.replace(range, body);
+            builder.delete_file(file);
+        },
+    )
+}
+
+/// Returns the literal and the value of the `#[path = "..."]` attribute.
+fn path_attr(module: &ast::Module) -> Option<(ast::Literal, SmolStr)> {
+    module.attrs().find_map(|attr| {
+        let (key, value) = attr.as_simple_key_value()?;
+        if key != "path" {
+            return None;
+        }
+        Some((attr.literal()?, value))
+    })
+}
+
+/// Returns the text inside of the braces of an inline module. If
+/// `child_prefix` is set, it is prepended to the `#[path]` attributes of the
+/// child modules, because they become relative to the directory of the new
+/// file instead of to the directory of the module.
+fn module_contents(item_list: &ast::ItemList, child_prefix: Option<&str>) -> String {
+    let mut edits = Vec::new();
+    if let Some(prefix) = child_prefix {
+        for child in item_list.items().filter_map(|it| match it {
+            ast::Item::Module(it) => Some(it),
+            _ => None,
+        }) {
+            if let Some((literal, attr_path)) = path_attr(&child) {
+                let attr_path = attr_path.strip_prefix("./").unwrap_or(&attr_path);
+                let text = format!("\"{}/{}\"", prefix, attr_path);
+                edits.push((literal.syntax().text_range(), text));
+            }
+        }
+    }
+    let start = item_list.syntax().text_range().start();
+    let edits = edits.into_iter().map(|(range, text)| (range - start, text)).collect();
+    let text = apply_edits(&item_list.syntax().text().to_string(), edits);
+
+    let inner = text.strip_prefix('{').unwrap_or(&text);
+    let inner = inner.strip_suffix('}').unwrap_or(inner);
+    inner.trim_start_matches(&[' ', '\t'][..]).trim_start_matches('\n').trim_end().to_string()
+}
+
+fn dedent(text: &str, indent: IndentLevel) -> String {
+    let indent = indent.to_string();
+    let mut res = String::new();
+    for (line, in_string) in split_lines(text) {
+        if in_string {
+            res.push_str(line);
+        } else {
+            res.push_str(line.strip_prefix(&indent).unwrap_or_else(|| line.trim_start()));
+        }
+        res.push('\n');
+    }
+    res
+}
+
+fn apply_edits(text: &str, mut edits: Vec<(TextRange, String)>) -> String {
+    let mut res = text.to_string();
+    edits.sort_by_key(|(range, _)| range.start());
+    for (range, replacement) in edits.into_iter().rev() {
+        res.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_from_root() {
+        check_assist(
+            move_module_to_file,
+            r#"
+mod <|>tests {
+    #[test] fn t() {}
+}
+"#,
+            r#"
+mod tests;
+//- tests/mod.rs
+#[test] fn t() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_nested_from_non_dir_owner() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod a;
+//- /a.rs
+mod b {
+    mod <|>c {
+        fn f() {
+            let x = 1;
+        }
+
+        struct S;
+    }
+}
+//- /a/b/c/mod.rs
+"#,
+            r#"
+mod b {
+    mod c;
+}
+//- a/b/c.rs
+fn f() {
+    let x = 1;
+}
+
+struct S;
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_to_mod_rs_when_file_exists() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod <|>foo {
+    fn f() {}
+}
+//- /foo.rs
+"#,
+            r#"
+mod foo;
+//- foo/mod.rs
+fn f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn adjusts_path_attributes() {
+        check_assist(
+            move_module_to_file,
+            r#"
+#[path = "impl"]
+mod <|>foo {
+    #[path = "./x.rs"]
+    mod bar;
+}
+"#,
+            r#"
+#[path = "impl/mod.rs"]
+mod foo;
+//- impl/mod.rs
+#[path = "./x.rs"]
+mod bar;
+"#,
+        );
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /main.rs
+mod <|>foo {
+    #[path = "./x.rs"]
+    mod bar;
+}
+//- /foo/mod.rs
+"#,
+            r#"
+mod foo;
+//- foo.rs
+#[path = "foo/x.rs"]
+mod bar;
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_multiline_strings() {
+        check_assist(
+            move_module_to_file,
+            r#"
+mod <|>foo {
+    const S: &str = "a
+  b
+    c";
+    fn f() {}
+}
+"#,
+            r#"
+mod foo;
+//- foo/mod.rs
+const S: &str = "a
+  b
+    c";
+fn f() {}
+"#,
+        );
+        check_assist(
+            inline_module_file,
+            r#"
+//- /main.rs
+mod a {
+    mod <|>foo;
+}
+//- /a/foo.rs
+const S: &str = r"a
+b";
+"#,
+            r#"
+mod a {
+    mod foo {
+        const S: &str = r"a
+b";
+    }
+}
+//- deleted /a/foo.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_of_module() {
+        check_assist_not_applicable(
+            move_module_to_file,
+            r#"
+mod foo {
+    fn <|>f() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_module_from_file() {
+        check_assist(
+            inline_module_file,
+            r#"
+//- /main.rs
+mod a {
+    mod <|>foo;
+}
+//- /a/foo.rs
+#[path = "foo/x.rs"]
+mod bar;
+
+fn f() {}
+"#,
+            r#"
+mod a {
+    mod foo {
+        #[path = "x.rs"]
+        mod bar;
+
+        fn f() {}
+    }
+}
+//- deleted /a/foo.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_empty_module_file() {
+        check_assist(
+            inline_module_file,
+            r#"
+//- /main.rs
+mod <|>foo;
+//- /foo/mod.rs
+"#,
+            r#"
+mod foo {}
+//- deleted /foo/mod.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unresolved_module() {
+        check_assist_not_applicable(inline_module_file, r#"mod <|>foo;"#);
+    }
+}
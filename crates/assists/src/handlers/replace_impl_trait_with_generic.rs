@@ -0,0 +1,432 @@
+use hir::{AsAssocItem, AssocItemContainer, ModuleDef, PathResolution, ScopeDef};
+use ide_db::defs::Definition;
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::{
+    algo::find_node_at_offset,
+    ast::{self, GenericParamsOwner, NameOwner, TypeBoundsOwner},
+    AstNode, Direction, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: replace_impl_trait_with_generic
+//
+// Replaces an `impl Trait` argument with a named type parameter.
+//
+// ```
+// fn foo(bar: <|>impl Bar) {}
+// ```
+// ->
+// ```
+// fn foo<B: Bar>(bar: B) {}
+// ```
+pub(crate) fn replace_impl_trait_with_generic(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let impl_trait = ctx.find_node_at_offset::<ast::ImplTraitType>()?;
+    let param = impl_trait.syntax().ancestors().find_map(ast::Param::cast)?;
+    let fn_ = param.syntax().parent()?.parent().and_then(ast::Fn::cast)?;
+    let bounds = impl_trait.type_bound_list()?;
+    let name = fresh_type_param_name(ctx, &fn_, &bounds)?;
+
+    let target = impl_trait.syntax().text_range();
+    acc.add(
+        AssistId("replace_impl_trait_with_generic", AssistKind::RefactorRewrite),
+        "Replace impl trait with generic",
+        target,
+        |builder| {
+            builder.replace(target, name.clone());
+            match fn_.where_clause() {
+                Some(where_clause) => {
+                    add_generic_param(builder, &fn_, &name);
+                    add_where_pred(builder, &where_clause, &format!("{}: {}", name, bounds));
+                }
+                None => add_generic_param(builder, &fn_, &format!("{}: {}", name, bounds)),
+            }
+        },
+    )
+}
+
+// Assist: replace_generic_with_impl_trait
+//
+// Replaces a type parameter which is only used as the type of one argument
+// with `impl Trait`.
+//
+// ```
+// fn foo<B<|>: Bar>(bar: B) {}
+// ```
+// ->
+// ```
+// fn foo(bar: impl Bar) {}
+// ```
+pub(crate) fn replace_generic_with_impl_trait(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let type_param = ctx.find_node_at_offset::<ast::TypeParam>()?;
+    let generic_param_list = type_param.syntax().parent().and_then(ast::GenericParamList::cast)?;
+    let fn_ = generic_param_list.syntax().parent().and_then(ast::Fn::cast)?;
+    let param_list = fn_.param_list()?;
+    let type_param_def = ctx.sema.to_def(&type_param)?;
+    let resolves_to_param = |path: &ast::Path| {
+        path.qualifier().is_none()
+            && ctx.sema.resolve_path(path) == Some(PathResolution::TypeParam(type_param_def))
+    };
+
+    let mut bounds =
+        type_param.type_bound_list().into_iter().flat_map(|it| it.bounds()).collect_vec();
+    let mut where_preds = Vec::new();
+    for pred in fn_.where_clause().into_iter().flat_map(|it| it.predicates()) {
+        let is_param = match pred.ty() {
+            Some(ast::Type::PathType(it)) => it.path().filter(|it| resolves_to_param(it)).is_some(),
+            _ => false,
+        };
+        if !is_param {
+            continue;
+        }
+        if pred.for_token().is_some() {
+            return None;
+        }
+        bounds.extend(pred.type_bound_list().into_iter().flat_map(|it| it.bounds()));
+        where_preds.push(pred);
+    }
+    // `impl ?Sized` is not a thing.
+    if bounds.iter().any(|it| it.question_mark_token().is_some()) {
+        return None;
+    }
+
+    // The parameter must be used exactly once, in the type of an argument.
+    let usages = fn_
+        .syntax()
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|it| resolves_to_param(it))
+        .filter(|it| !where_preds.iter().any(|pred| is_inside(it.syntax(), pred.syntax())))
+        .collect_vec();
+    let usage = match usages.as_slice() {
+        [it] => it.syntax().parent().and_then(ast::PathType::cast)?,
+        _ => return None,
+    };
+    if !is_inside(usage.syntax(), param_list.syntax()) {
+        return None;
+    }
+    let in_nested_type =
+        usage.syntax().ancestors().take_while(|it| it != param_list.syntax()).any(|it| {
+            matches!(
+                it.kind(),
+                SyntaxKind::FN_PTR_TYPE
+                    | SyntaxKind::IMPL_TRAIT_TYPE
+                    | SyntaxKind::DYN_TRAIT_TYPE
+                    | SyntaxKind::PARAM_LIST
+            )
+        });
+    if in_nested_type {
+        return None;
+    }
+
+    // Callers which specify the parameter with a turbofish would break.
+    let func = ctx.sema.to_def(&fn_)?;
+    let callers = Definition::ModuleDef(ModuleDef::Function(func)).find_usages(&ctx.sema, None);
+    for reference in callers {
+        let file_range = reference.file_range;
+        let source_file = ctx.sema.parse(file_range.file_id);
+        let name_ref =
+            find_node_at_offset::<ast::NameRef>(source_file.syntax(), file_range.range.start())?;
+        let parent = name_ref.syntax().parent()?;
+        if has_turbofish(&parent) {
+            return None;
+        }
+    }
+
+    let target = type_param.syntax().text_range();
+    acc.add(
+        AssistId("replace_generic_with_impl_trait", AssistKind::RefactorRewrite),
+        "Replace generic with impl trait",
+        target,
+        |builder| {
+            let bounds = match bounds.len() {
+                0 => "Sized".to_string(),
+                _ => bounds.iter().join(" + "),
+            };
+            let mut impl_trait = format!("impl {}", bounds);
+            let is_whole_type = usage.syntax().parent().and_then(ast::Param::cast).is_some();
+            if bounds.contains(" + ") && !is_whole_type {
+                impl_trait = format!("({})", impl_trait);
+            }
+            builder.replace(usage.syntax().text_range(), impl_trait);
+
+            if generic_param_list.generic_params().count() == 1 {
+                builder.delete(generic_param_list.syntax().text_range());
+            } else {
+                builder.delete(range_with_comma(type_param.syntax()));
+            }
+            if let Some(where_clause) = fn_.where_clause() {
+                if where_clause.predicates().count() == where_preds.len() {
+                    builder.delete(range_with_leading_ws(where_clause.syntax()));
+                } else {
+                    for pred in &where_preds {
+                        builder.delete(range_with_comma(pred.syntax()));
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Picks a name for a new type parameter which doesn't clash with the
+/// generic parameters of the function and its parents, or with other names in
+/// scope. The first letter of the trait is preferred.
+fn fresh_type_param_name(
+    ctx: &AssistContext,
+    fn_: &ast::Fn,
+    bounds: &ast::TypeBoundList,
+) -> Option<String> {
+    let db = ctx.db();
+    let func = ctx.sema.to_def(fn_)?;
+    let mut defs = vec![hir::GenericDef::from(func)];
+    match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Trait(it)) => defs.push(it.into()),
+        Some(AssocItemContainer::ImplDef(it)) => defs.push(it.into()),
+        None => (),
+    }
+
+    let mut taken = FxHashSet::default();
+    for def in defs {
+        taken.extend(def.params(db).into_iter().map(|it| it.name(db).to_string()));
+    }
+    ctx.sema.scope(fn_.syntax()).process_all_names(&mut |name, def| {
+        if !matches!(def, ScopeDef::Local(_)) {
+            taken.insert(name.to_string());
+        }
+    });
+
+    let preferred = bounds.bounds().find_map(|bound| {
+        let path = match bound.ty()? {
+            ast::Type::PathType(it) => it.path()?,
+            _ => return None,
+        };
+        let first = path.segment()?.name_ref()?.text().chars().next()?;
+        Some(first.to_ascii_uppercase().to_string())
+    });
+    preferred
+        .into_iter()
+        .chain(Some("T".to_string()))
+        .chain((1..).map(|idx| format!("T{}", idx)))
+        .find(|it| !taken.contains(it))
+}
+
+fn add_generic_param(builder: &mut AssistBuilder, fn_: &ast::Fn, param: &str) {
+    match fn_.generic_param_list().and_then(|it| it.r_angle_token()) {
+        Some(r_angle) => builder.insert(r_angle.text_range().start(), format!(", {}", param)),
+        None => {
+            if let Some(name) = fn_.name() {
+                builder.insert(name.syntax().text_range().end(), format!("<{}>", param))
+            }
+        }
+    }
+}
+
+fn add_where_pred(builder: &mut AssistBuilder, where_clause: &ast::WhereClause, pred: &str) {
+    let last = match where_clause.predicates().last() {
+        Some(it) => it,
+        None => {
+            builder.insert(where_clause.syntax().text_range().end(), format!(" {}", pred));
+            return;
+        }
+    };
+    let ws = last.syntax().prev_sibling_or_token().and_then(|it| it.into_token());
+    let text = match ws.filter(|it| it.kind() == SyntaxKind::WHITESPACE) {
+        Some(ws) if ws.text().contains('\n') => format!(",{}{}", ws.text(), pred),
+        _ => format!(", {}", pred),
+    };
+    builder.insert(last.syntax().text_range().end(), text);
+}
+
+/// Whether the path is part of a call with explicit generic arguments, like
+/// `foo::<u8>()` or `x.foo::<u8>()`.
+fn has_turbofish(parent: &SyntaxNode) -> bool {
+    if let Some(segment) = ast::PathSegment::cast(parent.clone()) {
+        return segment.generic_arg_list().is_some();
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return call.generic_arg_list().is_some();
+    }
+    false
+}
+
+fn is_inside(node: &SyntaxNode, container: &SyntaxNode) -> bool {
+    container.text_range().contains_range(node.text_range())
+}
+
+/// The range of an element of a comma-separated list, together with the
+/// comma separating it from its neighbours.
+fn range_with_comma(node: &SyntaxNode) -> TextRange {
+    let not_trivia = |it: &syntax::SyntaxElement| !it.kind().is_trivia();
+    let next = node
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .find(not_trivia)
+        .filter(|it| it.kind() == SyntaxKind::COMMA);
+    let prev = node
+        .siblings_with_tokens(Direction::Prev)
+        .skip(1)
+        .find(not_trivia)
+        .filter(|it| it.kind() == SyntaxKind::COMMA);
+    match (prev, next) {
+        (Some(prev), Some(next)) => {
+            TextRange::new(prev.text_range().end(), next.text_range().end())
+        }
+        (None, Some(next)) => {
+            let end = next
+                .as_token()
+                .and_then(|it| it.next_sibling_or_token())
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .map_or(next.text_range().end(), |it| it.text_range().end());
+            TextRange::new(node.text_range().start(), end)
+        }
+        (Some(prev), None) => TextRange::new(prev.text_range().start(), node.text_range().end()),
+        (None, None) => node.text_range(),
+    }
+}
+
+fn range_with_leading_ws(node: &SyntaxNode) -> TextRange {
+    match node.prev_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE) {
+        Some(ws) => TextRange::new(ws.text_range().start(), node.text_range().end()),
+        None => node.text_range(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn impl_trait_to_generic() {
+        check_assist(
+            replace_impl_trait_with_generic,
+            r#"
+trait Iterator { type Item; }
+fn foo<T>(t: T, it: impl Iterator<Item = u8> + <|>Clone) {}
+"#,
+            r#"
+trait Iterator { type Item; }
+fn foo<T, I: Iterator<Item = u8> + Clone>(t: T, it: I) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn impl_trait_to_generic_avoids_clashes() {
+        check_assist(
+            replace_impl_trait_with_generic,
+            r#"
+trait Debug {}
+struct D;
+struct S<T>(T);
+impl<T> S<T> {
+    fn show(&self, x: &<|>impl Debug)
+    where
+        T: Debug,
+    {
+    }
+}
+"#,
+            r#"
+trait Debug {}
+struct D;
+struct S<T>(T);
+impl<T> S<T> {
+    fn show<T1>(&self, x: &T1)
+    where
+        T: Debug,
+        T1: Debug,
+    {
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn impl_trait_in_return_position_not_applicable() {
+        check_assist_not_applicable(
+            replace_impl_trait_with_generic,
+            r#"
+trait Tr {}
+fn foo() -> impl <|>Tr {}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_impl_trait() {
+        check_assist(
+            replace_generic_with_impl_trait,
+            r#"
+trait Tr {}
+trait Other {}
+fn foo<'a, T<|>: Tr, U>(x: &T, u: U)
+where
+    U: Other,
+    T: Other + 'a,
+{
+    foo(x, u)
+}
+"#,
+            r#"
+trait Tr {}
+trait Other {}
+fn foo<'a, U>(x: &(impl Tr + Other + 'a), u: U)
+where
+    U: Other,
+{
+    foo(x, u)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_impl_trait_removes_where_clause() {
+        check_assist(
+            replace_generic_with_impl_trait,
+            r#"
+fn foo<T<|>>(x: T) where T: Clone {}
+"#,
+            r#"
+fn foo(x: impl Clone) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_impl_trait_not_applicable() {
+        // Used twice.
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            r#"fn foo<T<|>>(x: T, y: T) {}"#,
+        );
+        // Used in the return type.
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            r#"fn foo<T<|>>(x: T) -> T { x }"#,
+        );
+        // Used in a function pointer type.
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            r#"fn foo<T<|>>(f: fn(T)) {}"#,
+        );
+        // Specified by a caller.
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            r#"
+fn foo<T<|>>(x: T) {}
+fn bar() { foo::<u8>(0) }
+"#,
+        );
+    }
+}
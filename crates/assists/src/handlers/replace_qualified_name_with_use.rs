@@ -48,14 +48,14 @@ pub(crate) fn replace_qualified_name_with_use(
         "Replace qualified path with use",
         target,
         |builder| {
-            let container = match find_insert_use_container(path.syntax(), ctx) {
+            let container = match find_insert_use_container(path.syntax(), &ctx.sema) {
                 Some(c) => c,
                 None => return,
             };
             insert_use_statement(
                 path.syntax(),
                 &path_to_import.to_string(),
-                ctx,
+                &ctx.sema,
                 builder.text_edit_builder(),
             );
 
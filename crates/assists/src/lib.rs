@@ -130,6 +130,7 @@ mod handlers {
     pub(crate) type Handler = fn(&mut Assists, &AssistContext) -> Option<()>;
 
     mod add_custom_impl;
+    mod add_delegating_impl_members;
     mod add_explicit_type;
     mod add_missing_impl_members;
     mod add_turbo_fish;
@@ -137,8 +138,12 @@ mod handlers {
     mod auto_import;
     mod change_return_type_to_result;
     mod change_visibility;
+    mod convert_iter_for_each_to_for;
+    mod convert_struct_kind;
+    mod destructure_binding;
     mod early_return;
     mod expand_glob_import;
+    mod extract_function;
     mod extract_struct_from_enum_variant;
     mod extract_variable;
     mod fill_match_arms;
@@ -146,11 +151,15 @@ mod handlers {
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod generate_accessors;
+    mod generate_delegate_methods;
     mod generate_derive;
+    mod generate_enum_match_method;
     mod generate_from_impl_for_enum;
     mod generate_function;
     mod generate_impl;
     mod generate_new;
+    mod inline_call;
     mod inline_local_variable;
     mod introduce_named_lifetime;
     mod invert_if;
@@ -158,11 +167,13 @@ mod handlers {
     mod merge_match_arms;
     mod move_bounds;
     mod move_guard;
+    mod move_module_to_file;
     mod raw_string;
     mod remove_dbg;
     mod remove_mut;
     mod reorder_fields;
     mod replace_if_let_with_match;
+    mod replace_impl_trait_with_generic;
     mod replace_let_with_if_let;
     mod replace_qualified_name_with_use;
     mod replace_unwrap_with_match;
@@ -173,14 +184,21 @@ mod handlers {
         &[
             // These are alphabetic for the foolish consistency
             add_custom_impl::add_custom_impl,
+            add_delegating_impl_members::add_delegating_impl_members,
             add_explicit_type::add_explicit_type,
             add_turbo_fish::add_turbo_fish,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             change_return_type_to_result::change_return_type_to_result,
             change_visibility::change_visibility,
+            convert_iter_for_each_to_for::convert_for_loop_with_for_each,
+            convert_iter_for_each_to_for::convert_iter_for_each_to_for,
+            convert_struct_kind::convert_named_struct_to_tuple_struct,
+            convert_struct_kind::convert_tuple_struct_to_named_struct,
+            destructure_binding::destructure_binding,
             early_return::convert_to_guarded_return,
             expand_glob_import::expand_glob_import,
+            extract_function::extract_function,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
             extract_variable::extract_variable,
             fill_match_arms::fill_match_arms,
@@ -188,11 +206,19 @@ mod handlers {
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
+            generate_accessors::generate_getter,
+            generate_accessors::generate_getter_mut,
+            generate_accessors::generate_setter,
+            generate_delegate_methods::generate_delegate_methods,
             generate_derive::generate_derive,
+            generate_enum_match_method::generate_enum_as_method,
+            generate_enum_match_method::generate_enum_into_method,
+            generate_enum_match_method::generate_enum_is_method,
             generate_from_impl_for_enum::generate_from_impl_for_enum,
             generate_function::generate_function,
             generate_impl::generate_impl,
             generate_new::generate_new,
+            inline_call::inline_call,
             inline_local_variable::inline_local_variable,
             introduce_named_lifetime::introduce_named_lifetime,
             invert_if::invert_if,
@@ -201,6 +227,8 @@ mod handlers {
             move_bounds::move_bounds_to_where_clause,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_module_to_file::inline_module_file,
+            move_module_to_file::move_module_to_file,
             raw_string::add_hash,
             raw_string::make_raw_string,
             raw_string::make_usual_string,
@@ -209,6 +237,8 @@ mod handlers {
             remove_mut::remove_mut,
             reorder_fields::reorder_fields,
             replace_if_let_with_match::replace_if_let_with_match,
+            replace_impl_trait_with_generic::replace_generic_with_impl_trait,
+            replace_impl_trait_with_generic::replace_impl_trait_with_generic,
             replace_let_with_if_let::replace_let_with_if_let,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             replace_unwrap_with_match::replace_unwrap_with_match,
@@ -2,12 +2,12 @@ mod generated;
 
 use base_db::{fixture::WithFixture, FileId, FileRange, SourceDatabaseExt};
 use hir::Semantics;
-use ide_db::RootDatabase;
+use ide_db::{source_change::FileSystemEdit, RootDatabase};
 use syntax::TextRange;
 use test_utils::{assert_eq_text, extract_offset, extract_range};
 
 use crate::{handlers::Handler, Assist, AssistConfig, AssistContext, AssistKind, Assists};
-use stdx::trim_indent;
+use stdx::{format_to, trim_indent};
 
 pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
     RootDatabase::with_single_file(text)
@@ -85,6 +85,21 @@ fn check(handler: Handler, before: &str, expected: ExpectedResult) {
 
             let mut actual = db.file_text(change.file_id).as_ref().to_owned();
             change.edit.apply(&mut actual);
+            for file_system_edit in source_change.file_system_edits {
+                match file_system_edit {
+                    FileSystemEdit::CreateFile { dst, initial_contents, .. } => {
+                        format_to!(actual, "//- {}\n{}", dst, initial_contents)
+                    }
+                    FileSystemEdit::DeleteFile { file } => {
+                        let source_root = db.source_root(db.file_source_root(file));
+                        let path = source_root.path_for_file(&file).unwrap();
+                        format_to!(actual, "//- deleted {}\n", path)
+                    }
+                    FileSystemEdit::MoveFile { .. } | FileSystemEdit::MoveDir { .. } => {
+                        unreachable!()
+                    }
+                }
+            }
             assert_eq_text!(after, &actual);
         }
         (Some(assist), ExpectedResult::Target(target)) => {
@@ -112,6 +127,12 @@ fn assist_order_field_struct() {
         assists.next().expect("expected assist").assist.label,
         "Change visibility to pub(crate)"
     );
+    assert_eq!(assists.next().expect("expected assist").assist.label, "Generate a getter method");
+    assert_eq!(
+        assists.next().expect("expected assist").assist.label,
+        "Generate a mut getter method"
+    );
+    assert_eq!(assists.next().expect("expected assist").assist.label, "Generate a setter method");
     assert_eq!(assists.next().expect("expected assist").assist.label, "Add `#[derive]`");
 }
 
@@ -21,6 +21,49 @@ impl Debug for S {
     )
 }
 
+#[test]
+fn doctest_add_delegating_impl_members() {
+    check_doc_test(
+        "add_delegating_impl_members",
+        r#####"
+trait Shape {
+    type Unit;
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+impl Shape for Square {
+    type Unit = ();
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+
+struct Labeled(Square);
+impl Shape for Labeled {<|>}
+"#####,
+        r#####"
+trait Shape {
+    type Unit;
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+impl Shape for Square {
+    type Unit = ();
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+
+struct Labeled(Square);
+impl Shape for Labeled {
+    $0type Unit = <Square as Shape>::Unit;
+
+    fn area(&self) -> f64 {
+        <Square as Shape>::area(&self.0)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check_doc_test(
@@ -203,6 +246,77 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_for_loop_with_for_each() {
+    check_doc_test(
+        "convert_for_loop_with_for_each",
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    for<|> v in x {
+        let y = v * 2;
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    x.into_iter().for_each(|v| {
+        let y = v * 2;
+    });
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_iter_for_each_to_for() {
+    check_doc_test(
+        "convert_iter_for_each_to_for",
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    x.iter().for_each<|>(|v| {
+        let y = v * 2;
+    });
+}
+"#####,
+        r#####"
+fn main() {
+    let x = vec![1, 2, 3];
+    for v in x.iter() {
+        let y = v * 2;
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Point<|> { x: f32, y: f32 }
+
+fn origin() -> Point {
+    let p = Point { x: 0.0, y: 0.0 };
+    let Point { y, .. } = p;
+    Point { y, x: p.x }
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+fn origin() -> Point {
+    let p = Point(0.0, 0.0);
+    let Point(_, y, ..) = p;
+    Point(p.0, y)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -227,6 +341,54 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_tuple_struct_to_named_struct() {
+    check_doc_test(
+        "convert_tuple_struct_to_named_struct",
+        r#####"
+struct Point<|>(f32, f32);
+
+fn origin() -> Point {
+    let p = Point(0.0, 0.0);
+    let Point(x, _) = p;
+    Point(x, p.1)
+}
+"#####,
+        r#####"
+struct Point { field1: f32, field2: f32 }
+
+fn origin() -> Point {
+    let p = Point { field1: 0.0, field2: 0.0 };
+    let Point { field1: x, field2: _ } = p;
+    Point { field1: x, field2: p.field2 }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_binding() {
+    check_doc_test(
+        "destructure_binding",
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let <|>p = Point { x: 1, y: 2 };
+    let sum = p.x + p.y;
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let Point { x, y } = Point { x: 1, y: 2 };
+    let sum = x + y;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_expand_glob_import() {
     check_doc_test(
@@ -254,6 +416,33 @@ fn qux(bar: Bar, baz: Baz) {}
     )
 }
 
+#[test]
+fn doctest_extract_function() {
+    check_doc_test(
+        "extract_function",
+        r#####"
+fn main() {
+    let n = 1;
+    <|>let m = n + 2;
+    let k = m * n;<|>
+    let g = 3;
+}
+"#####,
+        r#####"
+fn main() {
+    let n = 1;
+    fun_name(n);
+    let g = 3;
+}
+
+fn $0fun_name(n: i32) {
+    let m = n + 2;
+    let k = m * n;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_struct_from_enum_variant() {
     check_doc_test(
@@ -383,6 +572,44 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_delegate_methods() {
+    check_doc_test(
+        "generate_delegate_methods",
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+struct Person {
+    ag<|>e: Age,
+}
+"#####,
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn $0age(&self) -> u8 {
+        self.age.age()
+    }
+}
+
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_derive() {
     check_doc_test(
@@ -403,6 +630,95 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_generate_enum_as_method() {
+    check_doc_test(
+        "generate_enum_as_method",
+        r#####"
+enum Value {
+ Number(i32),
+ Text(String)<|>,
+}
+"#####,
+        r#####"
+enum Value {
+ Number(i32),
+ Text(String),
+}
+
+impl Value {
+    fn $0as_text(&self) -> Option<&String> {
+        match self {
+            Self::Text(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_enum_into_method() {
+    check_doc_test(
+        "generate_enum_into_method",
+        r#####"
+enum Value {
+ Number(i32),
+ Text(String)<|>,
+}
+"#####,
+        r#####"
+enum Value {
+ Number(i32),
+ Text(String),
+}
+
+impl Value {
+    fn $0into_text(self) -> Result<String, Self> {
+        match self {
+            Self::Text(v) => Ok(v),
+            _ => Err(self),
+        }
+    }
+}
+
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_enum_is_method() {
+    check_doc_test(
+        "generate_enum_is_method",
+        r#####"
+enum Version {
+ Undefined,
+ Minor<|>,
+ Major,
+}
+"#####,
+        r#####"
+enum Version {
+ Undefined,
+ Minor,
+ Major,
+}
+
+impl Version {
+    fn $0is_minor(&self) -> bool {
+        match self {
+            Self::Minor => true,
+            _ => false,
+        }
+    }
+}
+
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_from_impl_for_enum() {
     check_doc_test(
@@ -449,6 +765,54 @@ fn bar(arg: &str, baz: Baz) {
     )
 }
 
+#[test]
+fn doctest_generate_getter() {
+    check_doc_test(
+        "generate_getter",
+        r#####"
+struct Person {
+    nam<|>e: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn $0name(&self) -> &String {
+        &self.name
+    }
+}
+
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_getter_mut() {
+    check_doc_test(
+        "generate_getter_mut",
+        r#####"
+struct Person {
+    nam<|>e: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn $0name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+}
+
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_impl() {
     check_doc_test(
@@ -492,6 +856,49 @@ impl<T: Clone> Ctx<T> {
     )
 }
 
+#[test]
+fn doctest_generate_setter() {
+    check_doc_test(
+        "generate_setter",
+        r#####"
+struct Person {
+    nam<|>e: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn $0set_name(&mut self, name: String) {
+        self.name = name;
+    }
+}
+
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_call() {
+    check_doc_test(
+        "inline_call",
+        r#####"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = add<|>(1, 2);
+}
+"#####,
+        r#####"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn main() {
+    let x = 1 + 2;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_local_variable() {
     check_doc_test(
@@ -510,6 +917,24 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_module_file() {
+    check_doc_test(
+        "inline_module_file",
+        r#####"
+//- /main.rs
+mod <|>foo;
+//- /foo.rs
+fn t() {}
+"#####,
+        r#####"
+mod foo {
+    fn t() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_lifetime() {
     check_doc_test(
@@ -699,6 +1124,21 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_module_to_file() {
+    check_doc_test(
+        "move_module_to_file",
+        r#####"
+mod <|>foo {
+    fn t() {}
+}
+"#####,
+        r#####"
+mod foo;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_dbg() {
     check_doc_test(
@@ -765,6 +1205,19 @@ const test: Foo = Foo {foo: 1, bar: 0}
     )
 }
 
+#[test]
+fn doctest_replace_generic_with_impl_trait() {
+    check_doc_test(
+        "replace_generic_with_impl_trait",
+        r#####"
+fn foo<B<|>: Bar>(bar: B) {}
+"#####,
+        r#####"
+fn foo(bar: impl Bar) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check_doc_test(
@@ -793,6 +1246,19 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_replace_impl_trait_with_generic() {
+    check_doc_test(
+        "replace_impl_trait_with_generic",
+        r#####"
+fn foo(bar: <|>impl Bar) {}
+"#####,
+        r#####"
+fn foo<B: Bar>(bar: B) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_let_with_if_let() {
     check_doc_test(
@@ -7,16 +7,40 @@ use hir::{Adt, Crate, Enum, ScopeDef, Semantics, Trait, Type};
 use ide_db::RootDatabase;
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
+use stdx::format_to;
 use syntax::{
-    ast::{self, make, NameOwner},
-    AstNode,
+    ast::{self, make, GenericParamsOwner, NameOwner},
+    tokenize, AstNode,
     SyntaxKind::*,
-    SyntaxNode, TextSize, T,
+    SyntaxNode, TextRange, TextSize, T,
 };
 
-use crate::assist_config::SnippetCap;
+use crate::{assist_config::SnippetCap, AssistContext};
 
-pub(crate) use insert_use::{find_insert_use_container, insert_use_statement};
+pub(crate) use insert_use::find_insert_use_container;
+pub use insert_use::insert_use_statement;
+
+/// Splits `text` into lines, along with whether the line starts inside of a
+/// string literal, whose contents must not be re-indented.
+pub(crate) fn split_lines(text: &str) -> Vec<(&str, bool)> {
+    let mut strings = Vec::new();
+    let mut offset = TextSize::from(0);
+    for token in tokenize(text).0 {
+        if matches!(token.kind, STRING | BYTE_STRING | RAW_STRING | RAW_BYTE_STRING) {
+            strings.push(TextRange::at(offset, token.len));
+        }
+        offset += token.len;
+    }
+
+    let mut res = Vec::new();
+    let mut offset = TextSize::from(0);
+    for line in text.split_terminator('\n') {
+        let in_string = strings.iter().any(|it| it.start() < offset && offset < it.end());
+        res.push((line.strip_suffix('\r').unwrap_or(line), in_string));
+        offset += TextSize::of(line) + TextSize::of('\n');
+    }
+    res
+}
 
 pub(crate) fn unwrap_trivial_block(block: ast::BlockExpr) -> ast::Expr {
     extract_trivial_expression(&block)
@@ -165,6 +189,136 @@ pub(crate) fn vis_offset(node: &SyntaxNode) -> TextSize {
         .unwrap_or_else(|| node.text_range().start())
 }
 
+// Generates the surrounding `impl Type { <code> }` including type and lifetime
+// parameters
+pub(crate) fn generate_impl_text(adt: &ast::AdtDef, code: &str) -> String {
+    let type_params = adt.generic_param_list();
+    let mut buf = String::with_capacity(code.len());
+    buf.push_str("\n\nimpl");
+    if let Some(type_params) = &type_params {
+        format_to!(buf, "{}", type_params.syntax());
+    }
+    buf.push(' ');
+    buf.push_str(adt.name().unwrap().text().as_str());
+    if let Some(type_params) = type_params {
+        let lifetime_params = type_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime_token())
+            .map(|it| it.text().clone());
+        let type_params =
+            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).format(", "))
+    }
+
+    format_to!(buf, " {{\n{}\n}}\n", code);
+
+    buf
+}
+
+// Uses a syntax-driven approach to find any impl blocks for the struct, enum
+// or union that exist within the module/file
+//
+// Returns `None` if we've found an existing fn with one of the `names`
+//
+// FIXME: change the fn checking to a more semantic approach when that's more
+// viable (e.g. we process proc macros, etc)
+pub(crate) fn find_impl_block(
+    ctx: &AssistContext,
+    adt: &ast::AdtDef,
+    names: &[&str],
+) -> Option<Option<ast::Impl>> {
+    let db = ctx.db();
+    let module = adt.syntax().ancestors().find(|node| {
+        ast::Module::can_cast(node.kind()) || ast::SourceFile::can_cast(node.kind())
+    })?;
+
+    let adt_def: Adt = match adt {
+        ast::AdtDef::Enum(it) => ctx.sema.to_def(it)?.into(),
+        ast::AdtDef::Struct(it) => ctx.sema.to_def(it)?.into(),
+        ast::AdtDef::Union(it) => ctx.sema.to_def(it)?.into(),
+    };
+
+    let block = module.descendants().filter_map(ast::Impl::cast).find_map(|impl_blk| {
+        let blk = ctx.sema.to_def(&impl_blk)?;
+
+        // FIXME: handle e.g. `struct S<T>; impl<U> S<U> {}`
+        // (we currently use the wrong type parameter)
+        // also we wouldn't want to use e.g. `impl S<u32>`
+        let same_ty = match blk.target_ty(db).as_adt() {
+            Some(def) => def == adt_def,
+            None => false,
+        };
+        let not_trait_impl = blk.target_trait(db).is_none();
+
+        if !(same_ty && not_trait_impl) {
+            None
+        } else {
+            Some(impl_blk)
+        }
+    });
+
+    if let Some(ref impl_blk) = block {
+        if has_any_fn(impl_blk, names) {
+            return None;
+        }
+    }
+
+    Some(block)
+}
+
+fn has_any_fn(imp: &ast::Impl, names: &[&str]) -> bool {
+    if let Some(il) = imp.assoc_item_list() {
+        for item in il.assoc_items() {
+            if let ast::AssocItem::Fn(f) = item {
+                if let Some(name) = f.name() {
+                    if names.iter().any(|n| name.text().eq_ignore_ascii_case(n)) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    false
+}
+
+pub(crate) fn existing_fn_names(impl_def: Option<&ast::Impl>) -> Vec<String> {
+    impl_def
+        .and_then(|it| it.assoc_item_list())
+        .into_iter()
+        .flat_map(|it| it.assoc_items())
+        .filter_map(|item| match item {
+            ast::AssocItem::Fn(f) => Some(f.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns where and what to insert to add `methods` to `impl_def`, or to a
+/// new impl if there is none.
+pub(crate) fn impl_insertion(
+    adt: &ast::AdtDef,
+    impl_def: Option<&ast::Impl>,
+    methods: &str,
+) -> (TextSize, String) {
+    let item_list = impl_def.and_then(|it| it.assoc_item_list());
+    match item_list.as_ref().and_then(|it| Some((it, it.r_curly_token()?))) {
+        Some((item_list, r_curly)) => {
+            let offset = r_curly.text_range().start();
+            let before = TextRange::new(item_list.syntax().text_range().start(), offset);
+            let mut buf = String::new();
+            if item_list.assoc_items().next().is_some()
+                || !item_list.syntax().text().slice(before - before.start()).contains_char('\n')
+            {
+                buf.push('\n');
+            }
+            format_to!(buf, "{}\n", methods);
+            (offset, buf)
+        }
+        None => (adt.syntax().text_range().end(), generate_impl_text(adt, methods)),
+    }
+}
+
 pub(crate) fn invert_boolean_expression(expr: ast::Expr) -> ast::Expr {
     if let Some(expr) = invert_special_case(&expr) {
         return expr;
@@ -250,12 +404,20 @@ pub mod convert {
     }
 }
 
+pub mod iter {
+    pub trait Iterator {
+        type Item;
+        fn next(&mut self) -> crate::option::Option<Self::Item>;
+        fn for_each<F>(self, f: F) {}
+    }
+}
+
 pub mod option {
     pub enum Option<T> { None, Some(T)}
 }
 
 pub mod prelude {
-    pub use crate::{convert::From, option::Option::{self, *}};
+    pub use crate::{convert::From, iter::Iterator, option::Option::{self, *}};
 }
 #[prelude_import]
 pub use prelude::*;
@@ -265,6 +427,10 @@ pub use prelude::*;
         self.find_trait("core:convert:From")
     }
 
+    pub(crate) fn core_iter_Iterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:Iterator")
+    }
+
     pub(crate) fn core_option_Option(&self) -> Option<Enum> {
         self.find_enum("core:option:Option")
     }
@@ -5,6 +5,8 @@
 use std::iter::successors;
 
 use either::Either;
+use hir::Semantics;
+use ide_db::RootDatabase;
 use syntax::{
     ast::{self, NameOwner, VisibilityOwner},
     AstNode, AstToken, Direction, SmolStr,
@@ -13,14 +15,12 @@ use syntax::{
 };
 use text_edit::TextEditBuilder;
 
-use crate::assist_context::AssistContext;
-
 /// Determines the containing syntax node in which to insert a `use` statement affecting `position`.
 pub(crate) fn find_insert_use_container(
     position: &SyntaxNode,
-    ctx: &AssistContext,
+    sema: &Semantics<RootDatabase>,
 ) -> Option<Either<ast::ItemList, ast::SourceFile>> {
-    ctx.sema.ancestors_with_macros(position.clone()).find_map(|n| {
+    sema.ancestors_with_macros(position.clone()).find_map(|n| {
         if let Some(module) = ast::Module::cast(n.clone()) {
             return module.item_list().map(|it| Either::Left(it));
         }
@@ -31,15 +31,15 @@ pub(crate) fn find_insert_use_container(
 /// Creates and inserts a use statement for the given path to import.
 /// The use statement is inserted in the scope most appropriate to the
 /// the cursor position given, additionally merged with the existing use imports.
-pub(crate) fn insert_use_statement(
+pub fn insert_use_statement(
     // Ideally the position of the cursor, used to
     position: &SyntaxNode,
     path_to_import: &str,
-    ctx: &AssistContext,
+    sema: &Semantics<RootDatabase>,
     builder: &mut TextEditBuilder,
 ) {
     let target = path_to_import.split("::").map(SmolStr::new).collect::<Vec<_>>();
-    let container = find_insert_use_container(position, ctx);
+    let container = find_insert_use_container(position, sema);
 
     if let Some(container) = container {
         let syntax = container.either(|l| l.syntax().clone(), |r| r.syntax().clone());
@@ -2,47 +2,51 @@
 use std::{iter, sync::Arc};
 
 use arrayvec::ArrayVec;
-use base_db::{CrateId, Edition, FileId};
+use base_db::{CrateId, Edition, FileId, ProcMacroKind};
 use either::Either;
 use hir_def::{
     adt::ReprKind,
     adt::StructKind,
     adt::VariantData,
+    body::Body,
     builtin_type::BuiltinType,
     docs::Documentation,
-    expr::{BindingAnnotation, Pat, PatId},
+    expr::{BindingAnnotation, ExprId, Pat, PatId},
     import_map,
-    path::ModPath,
+    path::{ModPath, Path},
     per_ns::PerNs,
     resolver::{HasResolver, Resolver},
     src::HasSource as _,
     type_ref::{Mutability, TypeRef},
     AdtId, AssocContainerId, ConstId, DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule,
     ImplId, LocalEnumVariantId, LocalFieldId, LocalModuleId, Lookup, ModuleId, StaticId, StructId,
-    TraitId, TypeAliasId, TypeParamId, UnionId,
+    TraitId, TypeAliasId, TypeParamId, UnionId, VariantId,
 };
 use hir_expand::{
     diagnostics::DiagnosticSink,
+    hygiene::Hygiene,
     name::{name, AsName},
     MacroDefId, MacroDefKind,
 };
 use hir_ty::{
     autoderef,
     display::{HirDisplayError, HirFormatter},
-    method_resolution, ApplicationTy, CallableDefId, Canonical, FnSig, GenericPredicate,
-    InEnvironment, Substs, TraitEnvironment, Ty, TyDefId, TypeCtor,
+    method_resolution, ApplicationTy, CallableDefId, Canonical, CaptureKind, CapturedItem,
+    ClosureKind, FnSig, GenericPredicate, InEnvironment, Substs, TraitEnvironment, Ty, TyDefId,
+    TypeCtor,
 };
 use rustc_hash::FxHashSet;
 use stdx::impl_from;
 use syntax::{
     ast::{self, AttrsOwner, NameOwner},
-    AstNode,
+    AstNode, SmolStr,
 };
 
 use crate::{
     db::{DefDatabase, HirDatabase},
     has_source::HasSource,
-    HirDisplay, InFile, Name,
+    source_analyzer::{resolve_hir_path, resolve_hir_path_qualifier},
+    HirDisplay, InFile, Name, PathResolution,
 };
 
 /// hir::Crate describes a single crate. It's the main interface with which
@@ -104,15 +108,9 @@ impl Crate {
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
-        query: &str,
+        query: import_map::Query,
     ) -> impl Iterator<Item = Either<ModuleDef, MacroDef>> {
-        import_map::search_dependencies(
-            db,
-            self.into(),
-            import_map::Query::new(query).anchor_end().case_sensitive().limit(40),
-        )
-        .into_iter()
-        .map(|item| match item {
+        import_map::search_dependencies(db, self.into(), query).into_iter().map(|item| match item {
             ItemInNs::Types(mod_id) | ItemInNs::Values(mod_id) => Either::Left(mod_id.into()),
             ItemInNs::Macros(mac_id) => Either::Right(mac_id.into()),
         })
@@ -261,6 +259,19 @@ impl Module {
         Some(self.with_module_id(parent_id))
     }
 
+    /// Returns the paths at which the file of a `mod name;` declaration in
+    /// this module is looked up, in order, relative to the file of this
+    /// module. `attr_path` is the value of its `#[path]` attribute.
+    pub fn mod_decl_candidate_files(
+        self,
+        db: &dyn HirDatabase,
+        name: &Name,
+        attr_path: Option<&SmolStr>,
+    ) -> Vec<String> {
+        let def_map = db.crate_def_map(self.id.krate);
+        def_map.mod_decl_candidate_files(db.upcast(), self.id.local_id, name, attr_path)
+    }
+
     pub fn path_to_root(self, db: &dyn HirDatabase) -> Vec<Module> {
         let mut res = vec![self];
         let mut curr = self;
@@ -316,14 +327,18 @@ impl Module {
                         m.diagnostics(db, sink)
                     }
                 }
-                _ => (),
+                _ => hir_ty::diagnostics::validate_module_item(db, decl.into(), sink),
             }
         }
 
         for impl_def in self.impl_defs(db) {
             for item in impl_def.items(db) {
-                if let AssocItem::Function(f) = item {
-                    f.diagnostics(db, sink);
+                match item {
+                    AssocItem::Function(f) => f.diagnostics(db, sink),
+                    AssocItem::Const(c) => {
+                        hir_ty::diagnostics::validate_module_item(db, c.id.into(), sink)
+                    }
+                    AssocItem::TypeAlias(_) => (),
                 }
             }
         }
@@ -424,6 +439,10 @@ impl Struct {
             .collect()
     }
 
+    pub fn kind(self, db: &dyn HirDatabase) -> StructKind {
+        db.struct_data(self.id).variant_data.kind()
+    }
+
     pub fn ty(self, db: &dyn HirDatabase) -> Type {
         Type::from_def(db, self.id.lookup(db.upcast()).container.module(db.upcast()).krate, self.id)
     }
@@ -679,7 +698,9 @@ impl Function {
     }
 
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
-        hir_ty::diagnostics::validate_body(db, self.id.into(), sink)
+        Body::add_diagnostics(db.upcast(), self.id.into(), sink);
+        hir_ty::diagnostics::validate_module_item(db, self.id.into(), sink);
+        hir_ty::diagnostics::validate_body(db, self.id.into(), sink);
     }
 }
 
@@ -826,12 +847,21 @@ impl MacroDef {
 
     /// Indicate it is a proc-macro
     pub fn is_proc_macro(&self) -> bool {
-        matches!(self.id.kind, MacroDefKind::CustomDerive(_))
+        matches!(self.id.kind, MacroDefKind::ProcMacro(_))
+    }
+
+    /// Indicate it is `include!`, `include_str!` or `include_bytes!`
+    pub fn is_include_macro(&self) -> bool {
+        matches!(self.id.kind, MacroDefKind::BuiltInEager(it) if it.is_include())
     }
 
     /// Indicate it is a derive macro
     pub fn is_derive_macro(&self) -> bool {
-        matches!(self.id.kind, MacroDefKind::CustomDerive(_) | MacroDefKind::BuiltInDerive(_))
+        match self.id.kind {
+            MacroDefKind::ProcMacro(it) => it.kind() == ProcMacroKind::CustomDerive,
+            MacroDefKind::BuiltInDerive(_) => true,
+            _ => false,
+        }
     }
 }
 
@@ -1098,6 +1128,26 @@ impl ImplDef {
         }
     }
 
+    /// The generic arguments of the implemented trait, not including `Self`.
+    pub fn target_trait_args(self, db: &dyn HirDatabase) -> Vec<Type> {
+        let trait_ref = match db.impl_trait(self.id) {
+            Some(it) => it.subst(&Substs::type_params(db, self.id)),
+            None => return Vec::new(),
+        };
+        let resolver = self.id.resolver(db.upcast());
+        let environment = TraitEnvironment::lower(db, &resolver);
+        let krate = self.id.lookup(db.upcast()).container.module(db.upcast()).krate;
+        trait_ref
+            .substs
+            .iter()
+            .skip(1)
+            .map(|ty| Type {
+                krate,
+                ty: InEnvironment { value: ty.clone(), environment: environment.clone() },
+            })
+            .collect()
+    }
+
     pub fn items(self, db: &dyn HirDatabase) -> Vec<AssocItem> {
         db.impl_data(self.id).items.iter().map(|it| (*it).into()).collect()
     }
@@ -1194,6 +1244,17 @@ impl Type {
         )
     }
 
+    /// If this is a reference type `&T` or `&mut T`, returns `T` and the
+    /// mutability of the reference.
+    pub fn as_reference(&self) -> Option<(Type, Mutability)> {
+        match &self.ty.value {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(mutability), parameters }) => {
+                Some((self.derived(parameters[0].clone()), *mutability))
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_unknown(&self) -> bool {
         matches!(self.ty.value, Ty::Unknown)
     }
@@ -1220,6 +1281,26 @@ impl Type {
         )
     }
 
+    /// Checks whether the type implements `Copy`, so that it can be used
+    /// after being passed by value.
+    pub fn is_copy(&self, db: &dyn HirDatabase) -> bool {
+        let krate = self.krate;
+
+        let copy_trait = match db.lang_item(krate, "copy".into()).and_then(|it| it.as_trait()) {
+            Some(it) => it,
+            None => return false,
+        };
+
+        let canonical_ty = Canonical { value: self.ty.value.clone(), kinds: Arc::new([]) };
+        method_resolution::implements_trait(
+            &canonical_ty,
+            db,
+            self.ty.environment.clone(),
+            krate,
+            copy_trait,
+        )
+    }
+
     pub fn impls_trait(&self, db: &dyn HirDatabase, trait_: Trait, args: &[Type]) -> bool {
         let trait_ref = hir_ty::TraitRef {
             trait_: trait_.id,
@@ -1254,6 +1335,15 @@ impl Type {
         matches!(&self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { .. }, .. }))
     }
 
+    pub fn as_closure(&self) -> Option<Closure> {
+        match &self.ty.value {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { def, expr }, .. }) => {
+                Some(Closure { owner: *def, expr: *expr })
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_fn(&self) -> bool {
         matches!(&self.ty.value,
             Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(..), .. }) |
@@ -1545,6 +1635,58 @@ impl HirDisplay for Type {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Closure {
+    owner: DefWithBodyId,
+    expr: ExprId,
+}
+
+impl Closure {
+    pub fn kind(self, db: &dyn HirDatabase) -> ClosureKind {
+        let infer = db.infer(self.owner);
+        infer.closure_captures(self.expr).map_or(ClosureKind::Fn, |it| it.kind)
+    }
+
+    pub fn captures(self, db: &dyn HirDatabase) -> Vec<ClosureCapture> {
+        let infer = db.infer(self.owner);
+        let captures = match infer.closure_captures(self.expr) {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
+        captures
+            .captures
+            .iter()
+            .map(|capture| ClosureCapture { owner: self.owner, capture: capture.clone() })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClosureCapture {
+    owner: DefWithBodyId,
+    capture: CapturedItem,
+}
+
+impl ClosureCapture {
+    pub fn local(&self) -> Local {
+        Local { parent: self.owner, pat_id: self.capture.place.local }
+    }
+
+    pub fn kind(&self) -> CaptureKind {
+        self.capture.kind
+    }
+
+    /// The captured place, like `a.b.0`.
+    pub fn display_place(&self, db: &dyn HirDatabase) -> String {
+        let mut res = self.local().name(db).map_or_else(String::new, |it| it.to_string());
+        for field in &self.capture.place.projections {
+            res.push('.');
+            res.push_str(&field.to_string());
+        }
+        res
+    }
+}
+
 // FIXME: closures
 #[derive(Debug)]
 pub struct Callable {
@@ -1686,6 +1828,54 @@ impl_from!(
     for AttrDef
 );
 
+impl AttrDef {
+    /// Resolves the path of an intra-doc link, like `crate::foo::Bar` or
+    /// `Vec::push`, relative to the scope of this item.
+    pub fn resolve_doc_path(self, db: &dyn HirDatabase, link: &str) -> Option<PathResolution> {
+        let resolver = self.resolver(db)?;
+        let path = ast::Path::parse(link).ok()?;
+        let path = Path::from_src(path, &Hygiene::new_unhygienic())?;
+        if let Some(res) = resolve_hir_path(db, &resolver, &path) {
+            return Some(res);
+        }
+
+        // Associated items are not found by name resolution alone.
+        let name = path.segments().last()?.name.clone();
+        let items = match resolve_hir_path_qualifier(db, &resolver, &path.qualifier()?)? {
+            PathResolution::Def(ModuleDef::Trait(it)) => it.items(db),
+            PathResolution::Def(ModuleDef::Adt(it)) => {
+                let krate = Crate { id: resolver.krate()? };
+                let mut items = Vec::new();
+                it.ty(db).iterate_assoc_items(db, krate, |item| {
+                    items.push(item);
+                    None::<()>
+                });
+                items
+            }
+            _ => return None,
+        };
+        let item = items.into_iter().find(|it| it.name(db).as_ref() == Some(&name))?;
+        Some(PathResolution::AssocItem(item))
+    }
+
+    fn resolver(self, hir_db: &dyn HirDatabase) -> Option<Resolver> {
+        let db = hir_db.upcast();
+        let resolver = match self {
+            AttrDef::Module(it) => it.id.resolver(db),
+            AttrDef::Field(it) => VariantId::from(it.parent).resolver(db),
+            AttrDef::Adt(it) => AdtId::from(it).resolver(db),
+            AttrDef::Function(it) => it.id.resolver(db),
+            AttrDef::EnumVariant(it) => it.parent.id.resolver(db),
+            AttrDef::Static(it) => it.id.resolver(db),
+            AttrDef::Const(it) => it.id.resolver(db),
+            AttrDef::Trait(it) => it.id.resolver(db),
+            AttrDef::TypeAlias(it) => it.id.resolver(db),
+            AttrDef::MacroDef(it) => it.module(hir_db)?.id.resolver(db),
+        };
+        Some(resolver)
+    }
+}
+
 pub trait HasAttrs {
     fn attrs(self, db: &dyn HirDatabase) -> Attrs;
 }
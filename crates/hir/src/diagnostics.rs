@@ -1,6 +1,9 @@
 //! FIXME: write short doc here
-pub use hir_def::diagnostics::UnresolvedModule;
+pub use hir_def::diagnostics::{
+    UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall, UnresolvedModule, UnresolvedPath,
+};
 pub use hir_expand::diagnostics::{Diagnostic, DiagnosticSink, DiagnosticSinkBuilder};
 pub use hir_ty::diagnostics::{
-    MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkInTailExpr, NoSuchField,
+    CaseType, IdentType, IncorrectCase, MismatchedArgCount, MissingFields, MissingMatchArms,
+    MissingOkInTailExpr, NoSuchField, TypeMismatch, TypeMismatchFix,
 };
@@ -32,10 +32,11 @@ mod has_source;
 
 pub use crate::{
     code_model::{
-        Adt, AsAssocItem, AssocItem, AssocItemContainer, AttrDef, Callable, CallableKind, Const,
-        Crate, CrateDependency, DefWithBody, Docs, Enum, EnumVariant, Field, FieldSource, Function,
-        GenericDef, HasAttrs, HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, ScopeDef,
-        Static, Struct, Trait, Type, TypeAlias, TypeParam, Union, VariantDef, Visibility,
+        Adt, AsAssocItem, AssocItem, AssocItemContainer, AttrDef, Callable, CallableKind, Closure,
+        ClosureCapture, Const, Crate, CrateDependency, DefWithBody, Docs, Enum, EnumVariant, Field,
+        FieldSource, Function, GenericDef, HasAttrs, HasVisibility, ImplDef, Local, MacroDef,
+        Module, ModuleDef, ScopeDef, Static, Struct, Trait, Type, TypeAlias, TypeParam, Union,
+        VariantDef, Visibility,
     },
     has_source::HasSource,
     semantics::{original_range, PathResolution, Semantics, SemanticsScope},
@@ -47,6 +48,7 @@ pub use hir_def::{
     body::scope::ExprScopes,
     builtin_type::BuiltinType,
     docs::Documentation,
+    import_map,
     nameres::ModuleSource,
     path::ModPath,
     type_ref::{Mutability, TypeRef},
@@ -55,7 +57,7 @@ pub use hir_expand::{
     name::Name, HirFileId, InFile, MacroCallId, MacroCallLoc, /* FIXME */ MacroDefId,
     MacroFile, Origin,
 };
-pub use hir_ty::display::HirDisplay;
+pub use hir_ty::{display::HirDisplay, CaptureKind, ClosureKind};
 
 // These are negative re-exports: pub using these names is forbidden, they
 // should remain private to hir internals.
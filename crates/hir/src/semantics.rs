@@ -9,7 +9,7 @@ use hir_def::{
     resolver::{self, HasResolver, Resolver, TypeNs},
     AsMacroCall, FunctionId, TraitId, VariantId,
 };
-use hir_expand::{hygiene::Hygiene, name::AsName, ExpansionInfo};
+use hir_expand::{builtin_macro, hygiene::Hygiene, name::AsName, ExpansionInfo};
 use hir_ty::associated_type_shorthand_candidates;
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -188,6 +188,14 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.type_of_self(param)
     }
 
+    /// Checks whether control can leave `expr` other than by evaluating it to
+    /// completion, that is via `return`, `?`, `.await`, or a `break` or
+    /// `continue` targeting a loop outside of `expr`. Closures are not looked
+    /// into. Returns `None` if `expr` is not part of a body.
+    pub fn has_non_local_control_flow(&self, expr: &ast::Expr) -> Option<bool> {
+        self.imp.has_non_local_control_flow(expr)
+    }
+
     pub fn resolve_method_call(&self, call: &ast::MethodCallExpr) -> Option<Function> {
         self.imp.resolve_method_call(call).map(Function::from)
     }
@@ -215,6 +223,12 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.resolve_macro_call(macro_call)
     }
 
+    /// Resolves the path argument of an `include!`-like macro call to the file
+    /// it includes.
+    pub fn resolve_include_path(&self, macro_call: &ast::MacroCall, path: &str) -> Option<FileId> {
+        self.imp.resolve_include_path(macro_call, path)
+    }
+
     pub fn resolve_path(&self, path: &ast::Path) -> Option<PathResolution> {
         self.imp.resolve_path(path)
     }
@@ -410,6 +424,10 @@ impl<'db> SemanticsImpl<'db> {
         self.analyze(param.syntax()).type_of_self(self.db, &param)
     }
 
+    fn has_non_local_control_flow(&self, expr: &ast::Expr) -> Option<bool> {
+        self.analyze(expr.syntax()).has_non_local_control_flow(self.db, expr)
+    }
+
     fn resolve_method_call(&self, call: &ast::MethodCallExpr) -> Option<FunctionId> {
         self.analyze(call.syntax()).resolve_method_call(self.db, call)
     }
@@ -443,6 +461,11 @@ impl<'db> SemanticsImpl<'db> {
         sa.resolve_macro_call(self.db, macro_call)
     }
 
+    fn resolve_include_path(&self, macro_call: &ast::MacroCall, path: &str) -> Option<FileId> {
+        let call_site = self.find_file(macro_call.syntax().clone()).file_id.original_file(self.db);
+        builtin_macro::resolve_include_path(self.db.upcast(), call_site, path)
+    }
+
     fn resolve_path(&self, path: &ast::Path) -> Option<PathResolution> {
         self.analyze(path.syntax()).resolve_path(self.db, path)
     }
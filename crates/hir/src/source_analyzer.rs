@@ -12,12 +12,16 @@ use hir_def::{
         scope::{ExprScopes, ScopeId},
         Body, BodySourceMap,
     },
-    expr::{ExprId, Pat, PatId},
+    expr::{Expr, ExprId, Pat, PatId},
     path::{ModPath, Path, PathKind},
     resolver::{resolver_for_scope, Resolver, TypeNs, ValueNs},
     AsMacroCall, DefWithBodyId, FieldId, FunctionId, LocalFieldId, VariantId,
 };
-use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
+use hir_expand::{
+    hygiene::Hygiene,
+    name::{AsName, Name},
+    HirFileId, InFile,
+};
 use hir_ty::{
     diagnostics::{record_literal_missing_fields, record_pattern_missing_fields},
     InferenceResult, Substs, Ty,
@@ -138,6 +142,16 @@ impl SourceAnalyzer {
         Type::new_with_resolver(db, &self.resolver, ty)
     }
 
+    pub(crate) fn has_non_local_control_flow(
+        &self,
+        db: &dyn HirDatabase,
+        expr: &ast::Expr,
+    ) -> Option<bool> {
+        let expr_id = self.expr_id(db, expr)?;
+        let body = self.body.as_ref()?;
+        Some(has_non_local_control_flow(body, expr_id, 0, &mut Vec::new()))
+    }
+
     pub(crate) fn resolve_method_call(
         &self,
         db: &dyn HirDatabase,
@@ -365,6 +379,44 @@ impl SourceAnalyzer {
     }
 }
 
+/// `loop_depth` counts the unlabeled loops entered so far, `labels` the labels
+/// of the loops and blocks entered so far.
+fn has_non_local_control_flow(
+    body: &Body,
+    expr: ExprId,
+    loop_depth: usize,
+    labels: &mut Vec<Name>,
+) -> bool {
+    let (loop_depth, label) = match &body[expr] {
+        Expr::Return { .. } | Expr::Try { .. } | Expr::Await { .. } => return true,
+        Expr::Continue { label } | Expr::Break { label, .. } => {
+            let leaves = match label {
+                Some(label) => !labels.contains(label),
+                None => loop_depth == 0,
+            };
+            if leaves {
+                return true;
+            }
+            (loop_depth, None)
+        }
+        Expr::Lambda { .. } => return false,
+        Expr::Loop { label, .. } | Expr::While { label, .. } | Expr::For { label, .. } => {
+            (loop_depth + 1, label.clone())
+        }
+        Expr::Block { label, .. } => (loop_depth, label.clone()),
+        _ => (loop_depth, None),
+    };
+
+    let n_labels = labels.len();
+    labels.extend(label);
+    let mut res = false;
+    body[expr].walk_child_exprs(|child| {
+        res = res || has_non_local_control_flow(body, child, loop_depth, labels)
+    });
+    labels.truncate(n_labels);
+    res
+}
+
 fn scope_for(
     scopes: &ExprScopes,
     source_map: &BodySourceMap,
@@ -479,6 +531,8 @@ pub(crate) fn resolve_hir_path(
                 ValueNs::StructId(it) => PathResolution::Def(Struct::from(it).into()),
                 ValueNs::EnumVariantId(it) => PathResolution::Def(EnumVariant::from(it).into()),
                 ValueNs::ImplSelf(impl_id) => PathResolution::SelfType(impl_id.into()),
+                // FIXME: expose const generic parameters in the `hir` API
+                ValueNs::GenericParam(_) => return None,
             };
             Some(res)
         });
@@ -508,7 +562,7 @@ pub(crate) fn resolve_hir_path(
 /// }
 /// ```
 /// then we know that `foo` in `my::foo::Bar` refers to the module, not the function.
-fn resolve_hir_path_qualifier(
+pub(crate) fn resolve_hir_path_qualifier(
     db: &dyn HirDatabase,
     resolver: &Resolver,
     path: &Path,
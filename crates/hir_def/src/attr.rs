@@ -97,6 +97,7 @@ impl Attrs {
             |docs_text| Attr {
                 input: Some(AttrInput::Literal(SmolStr::new(docs_text))),
                 path: ModPath::from(hir_expand::name!(doc)),
+                index: None,
             },
         );
         let mut attrs = owner.attrs().peekable();
@@ -104,19 +105,29 @@ impl Attrs {
             // Avoid heap allocation
             None
         } else {
-            Some(attrs.flat_map(|ast| Attr::from_src(ast, hygiene)).chain(docs).collect())
+            Some(
+                attrs
+                    .enumerate()
+                    .flat_map(|(idx, ast)| Attr::from_src(ast, idx as u32, hygiene))
+                    .chain(docs)
+                    .collect(),
+            )
         };
         Attrs { entries }
     }
 
+    /// Appends the attributes of `other`, like the ones of an `extern` block to
+    /// those of its items. Their indices refer to a different owner, so they
+    /// are dropped.
     pub fn merge(&self, other: Attrs) -> Attrs {
         match (&self.entries, &other.entries) {
             (None, None) => Attrs { entries: None },
-            (Some(entries), None) | (None, Some(entries)) => {
-                Attrs { entries: Some(entries.clone()) }
-            }
-            (Some(a), Some(b)) => {
-                Attrs { entries: Some(a.iter().chain(b.iter()).cloned().collect()) }
+            (Some(entries), None) => Attrs { entries: Some(entries.clone()) },
+            (a, Some(b)) => {
+                let b = b.iter().map(|attr| Attr { index: None, ..attr.clone() });
+                Attrs {
+                    entries: Some(a.iter().flat_map(|it| it.iter()).cloned().chain(b).collect()),
+                }
             }
         }
     }
@@ -138,6 +149,10 @@ impl Attrs {
 pub struct Attr {
     pub(crate) path: ModPath,
     pub(crate) input: Option<AttrInput>,
+    /// Index of the attribute among the `#[...]` attributes of its owner,
+    /// including ones which failed to lower. `None` for doc comments and
+    /// attributes merged in from an enclosing `extern` block.
+    pub(crate) index: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -149,7 +164,7 @@ pub enum AttrInput {
 }
 
 impl Attr {
-    fn from_src(ast: ast::Attr, hygiene: &Hygiene) -> Option<Attr> {
+    fn from_src(ast: ast::Attr, index: u32, hygiene: &Hygiene) -> Option<Attr> {
         let path = ModPath::from_src(ast.path()?, hygiene)?;
         let input = if let Some(lit) = ast.literal() {
             // FIXME: escape? raw string?
@@ -160,7 +175,7 @@ impl Attr {
         } else {
             None
         };
-        Some(Attr { path, input })
+        Some(Attr { path, input, index: Some(index) })
     }
 }
 
@@ -1,5 +1,6 @@
 //! Defines `Body`: a lowered representation of bodies of functions, statics and
 //! consts.
+mod diagnostics;
 mod lower;
 pub mod scope;
 
@@ -10,7 +11,10 @@ use base_db::CrateId;
 use cfg::CfgOptions;
 use drop_bomb::DropBomb;
 use either::Either;
-use hir_expand::{ast_id_map::AstIdMap, hygiene::Hygiene, AstId, HirFileId, InFile, MacroDefId};
+use hir_expand::{
+    ast_id_map::AstIdMap, diagnostics::DiagnosticSink, hygiene::Hygiene, AstId, HirFileId, InFile,
+    MacroDefId,
+};
 use rustc_hash::FxHashMap;
 use syntax::{ast, AstNode, AstPtr};
 use test_utils::mark;
@@ -257,6 +261,11 @@ impl Body {
         db.body_with_source_map(def).0
     }
 
+    /// Adds the diagnostics for the body of `owner` to `sink`.
+    pub fn add_diagnostics(db: &dyn DefDatabase, owner: DefWithBodyId, sink: &mut DiagnosticSink) {
+        diagnostics::validate_paths(db, owner, sink);
+    }
+
     fn new(
         db: &dyn DefDatabase,
         def: DefWithBodyId,
@@ -0,0 +1,47 @@
+//! Diagnostics for problems found in lowered bodies.
+
+use hir_expand::diagnostics::DiagnosticSink;
+use syntax::{ast, AstPtr};
+
+use crate::{
+    db::DefDatabase, diagnostics::UnresolvedPath, expr::Expr, resolver::resolver_for_expr,
+    DefWithBodyId,
+};
+
+/// Reports path expressions which don't resolve to anything in the value
+/// namespace, not even partially.
+pub(super) fn validate_paths(
+    db: &dyn DefDatabase,
+    owner: DefWithBodyId,
+    sink: &mut DiagnosticSink,
+) {
+    let (body, source_map) = db.body_with_source_map(owner);
+    for (expr_id, expr) in body.exprs.iter() {
+        let path = match expr {
+            Expr::Path(it) => it,
+            _ => continue,
+        };
+        // Type-anchored paths like `<T as Trait>::f` are resolved during inference.
+        if path.type_anchor().is_some() {
+            continue;
+        }
+        let resolver = resolver_for_expr(db, owner, expr_id);
+        if resolver.resolve_path_in_value_ns(db, path.mod_path()).is_some() {
+            continue;
+        }
+
+        let src = match source_map.expr_syntax(expr_id) {
+            Ok(it) => it,
+            Err(_) => continue,
+        };
+        let root = match db.parse_or_expand(src.file_id) {
+            Some(it) => it,
+            None => continue,
+        };
+        if let ast::Expr::PathExpr(path_expr) = src.value.to_node(&root) {
+            if let Some(path) = path_expr.path() {
+                sink.push(UnresolvedPath { file: src.file_id, node: AstPtr::new(&path) });
+            }
+        }
+    }
+}
@@ -487,7 +487,11 @@ impl ExprCollector<'_> {
                 let ret_type =
                     e.ret_type().and_then(|r| r.ty()).map(|it| TypeRef::from_ast(&self.ctx(), it));
                 let body = self.collect_expr_opt(e.body());
-                self.alloc_expr(Expr::Lambda { args, arg_types, ret_type, body }, syntax_ptr)
+                let is_move = e.move_token().is_some();
+                self.alloc_expr(
+                    Expr::Lambda { args, arg_types, ret_type, body, is_move },
+                    syntax_ptr,
+                )
             }
             ast::Expr::BinExpr(e) => {
                 let lhs = self.collect_expr_opt(e.lhs());
@@ -523,7 +527,7 @@ impl ExprCollector<'_> {
                 }
             }
 
-            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.kind().into()), syntax_ptr),
+            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.into()), syntax_ptr),
             ast::Expr::IndexExpr(e) => {
                 let base = self.collect_expr_opt(e.base());
                 let index = self.collect_expr_opt(e.index());
@@ -818,7 +822,7 @@ impl ExprCollector<'_> {
             }
             ast::Pat::LiteralPat(lit) => {
                 if let Some(ast_lit) = lit.literal() {
-                    let expr = Expr::Literal(ast_lit.kind().into());
+                    let expr = Expr::Literal(ast_lit.clone().into());
                     let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
                     let expr_id = self.alloc_expr(expr, expr_ptr);
                     Pat::Lit(expr_id)
@@ -908,20 +912,23 @@ impl From<ast::BinOp> for BinaryOp {
     }
 }
 
-impl From<ast::LiteralKind> for Literal {
-    fn from(ast_lit_kind: ast::LiteralKind) -> Self {
-        match ast_lit_kind {
+impl From<ast::Literal> for Literal {
+    fn from(ast_lit: ast::Literal) -> Self {
+        match ast_lit.kind() {
             LiteralKind::IntNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinInt::from_suffix(&it));
+                let value = ast_lit.int_value().map_or(0, |it| it as u64);
 
-                Literal::Int(Default::default(), known_name)
+                Literal::Int(value, known_name)
             }
             LiteralKind::FloatNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinFloat::from_suffix(&it));
 
                 Literal::Float(Default::default(), known_name)
             }
-            LiteralKind::ByteString => Literal::ByteString(Default::default()),
+            LiteralKind::ByteString => {
+                Literal::ByteString(ast_lit.byte_string_value().unwrap_or_default())
+            }
             LiteralKind::String => Literal::String(Default::default()),
             LiteralKind::Byte => Literal::Int(Default::default(), Some(BuiltinInt::U8)),
             LiteralKind::Bool(val) => Literal::Bool(val),
@@ -0,0 +1,110 @@
+//! Attributes which are known to the compiler.
+//!
+//! These never resolve to attribute macros, so name resolution doesn't need to
+//! defer the items they are attached to.
+
+use hir_expand::name::Name;
+
+use crate::path::{ModPath, PathKind};
+
+/// Inert attributes and built-in attribute macros, in no particular order.
+const BUILTIN_ATTRIBUTES: &[&str] = &[
+    // Conditional compilation
+    "cfg",
+    "cfg_attr",
+    // Testing
+    "test",
+    "bench",
+    "test_case",
+    "ignore",
+    "should_panic",
+    // Derives
+    "derive",
+    "automatically_derived",
+    // Macros
+    "macro_export",
+    "macro_use",
+    "proc_macro",
+    "proc_macro_derive",
+    "proc_macro_attribute",
+    "global_allocator",
+    "cfg_accessible",
+    // Lints
+    "warn",
+    "allow",
+    "forbid",
+    "deny",
+    "must_use",
+    "deprecated",
+    // Crate properties
+    "crate_name",
+    "crate_type",
+    "feature",
+    "no_core",
+    "no_main",
+    "no_std",
+    "no_implicit_prelude",
+    "recursion_limit",
+    "type_length_limit",
+    "windows_subsystem",
+    "register_attr",
+    "register_tool",
+    // Modules
+    "path",
+    "prelude_import",
+    // ABI, linking, symbols, and FFI
+    "link",
+    "link_name",
+    "link_section",
+    "link_ordinal",
+    "export_name",
+    "no_link",
+    "no_mangle",
+    "repr",
+    "used",
+    "linkage",
+    "ffi_returns_twice",
+    "thread_local",
+    // Code generation
+    "inline",
+    "cold",
+    "no_builtins",
+    "target_feature",
+    "track_caller",
+    "naked",
+    "optimize",
+    // Documentation
+    "doc",
+    // Runtime
+    "panic_handler",
+    "lang",
+    "start",
+    "main",
+    // Type system
+    "non_exhaustive",
+    "fundamental",
+    "marker",
+    "may_dangle",
+    // Stability
+    "stable",
+    "unstable",
+    "allow_internal_unstable",
+    "allow_internal_unsafe",
+];
+
+/// Tools whose attributes, like `#[rustfmt::skip]`, are never macros.
+const TOOL_MODULES: &[&str] = &["rustfmt", "clippy", "rust_analyzer"];
+
+/// Returns `true` if an attribute with the given path is known to never be an
+/// attribute macro invocation.
+pub(crate) fn is_builtin_or_tool_attr(path: &ModPath) -> bool {
+    if path.kind != PathKind::Plain {
+        return false;
+    }
+    let is_known = |name: &Name, known: &[&str]| known.contains(&name.to_string().as_str());
+    match path.segments.as_slice() {
+        [name] => is_known(name, BUILTIN_ATTRIBUTES) || name.to_string().starts_with("rustc_"),
+        [tool, _, ..] => is_known(tool, TOOL_MODULES),
+        [] => true,
+    }
+}
@@ -28,3 +28,220 @@ impl Diagnostic for UnresolvedModule {
         self
     }
 }
+
+#[derive(Debug)]
+pub struct UnresolvedExternCrate {
+    pub file: HirFileId,
+    pub item: AstPtr<ast::ExternCrate>,
+}
+
+impl Diagnostic for UnresolvedExternCrate {
+    fn name(&self) -> &'static str {
+        "unresolved-extern-crate"
+    }
+    fn message(&self) -> String {
+        "unresolved extern crate".to_string()
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.item.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    // The crate may just be missing from the crate graph, like the sysroot.
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct UnresolvedImport {
+    pub file: HirFileId,
+    pub node: AstPtr<ast::UseTree>,
+}
+
+impl Diagnostic for UnresolvedImport {
+    fn name(&self) -> &'static str {
+        "unresolved-import"
+    }
+    fn message(&self) -> String {
+        "unresolved import".to_string()
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.node.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    // Imports may refer to items the name resolution doesn't see yet, like
+    // the output of `cfg_if!` or of proc macros.
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct UnresolvedMacroCall {
+    pub file: HirFileId,
+    pub node: AstPtr<ast::MacroCall>,
+}
+
+impl Diagnostic for UnresolvedMacroCall {
+    fn name(&self) -> &'static str {
+        "unresolved-macro-call"
+    }
+    fn message(&self) -> String {
+        "unresolved macro call".to_string()
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.node.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    // Macros may come from imports which can't be resolved yet.
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct UnresolvedPath {
+    pub file: HirFileId,
+    pub node: AstPtr<ast::Path>,
+}
+
+impl Diagnostic for UnresolvedPath {
+    fn name(&self) -> &'static str {
+        "unresolved-path"
+    }
+    fn message(&self) -> String {
+        "unresolved path".to_string()
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.node.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    // Paths may come from items the name resolution doesn't see yet, like
+    // the output of unexpanded proc macros.
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_db::fixture::WithFixture;
+
+    use crate::test_db::TestDB;
+
+    fn check_diagnostics(ra_fixture: &str) {
+        let db: TestDB = TestDB::with_files(ra_fixture);
+        db.check_diagnostics();
+    }
+
+    #[test]
+    fn unresolved_import() {
+        check_diagnostics(
+            r"
+            use does_exist;
+            use does_not_exist;
+              //^^^^^^^^^^^^^^ unresolved import
+
+            mod does_exist {}
+            ",
+        );
+    }
+
+    #[test]
+    fn unresolved_import_in_use_tree() {
+        check_diagnostics(
+            r"
+            use {does_exist, does_not_exist};
+                           //^^^^^^^^^^^^^^ unresolved import
+
+            use does_exist::{Exists, DoesntExist, self};
+                                   //^^^^^^^^^^^ unresolved import
+
+            mod does_exist {
+                pub struct Exists;
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn unresolved_extern_crate() {
+        check_diagnostics(
+            r"
+            //- /main.rs crate:main deps:core
+            extern crate core;
+              extern crate doesnotexist;
+            //^^^^^^^^^^^^^^^^^^^^^^^^^^ unresolved extern crate
+            //- /lib.rs crate:core
+            ",
+        );
+    }
+
+    #[test]
+    fn imports_from_unresolved_extern_crate_are_not_reported() {
+        check_diagnostics(
+            r"
+              extern crate doesnotexist as renamed;
+            //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ unresolved extern crate
+
+            use doesnotexist::Foo;
+            use renamed::Bar;
+            ",
+        );
+    }
+
+    #[test]
+    fn unresolved_macro_call() {
+        check_diagnostics(
+            r"
+            macro_rules! m { () => {} }
+            m!();
+              not_a_macro!();
+            //^^^^^^^^^^^^^^^ unresolved macro call
+            ",
+        );
+    }
+
+    #[test]
+    fn unresolved_path() {
+        check_diagnostics(
+            r"
+            struct S;
+            fn f() {}
+            fn main() {
+                let x = 92;
+                x;
+                f();
+                S;
+                S::new();
+                y;
+              //^ unresolved path
+                nope::f();
+              //^^^^^^^ unresolved path
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn unresolved_path_with_type_anchor_is_not_reported() {
+        check_diagnostics(
+            r"
+            trait Tr { fn f(); }
+            fn main() {
+                <u8 as Tr>::f();
+                not_a_local;
+              //^^^^^^^^^^^ unresolved path
+            }
+            ",
+        );
+    }
+}
@@ -146,6 +146,8 @@ pub enum Expr {
         arg_types: Vec<Option<TypeRef>>,
         ret_type: Option<TypeRef>,
         body: ExprId,
+        /// Whether this is a `move` closure, which captures everything by value.
+        is_move: bool,
     },
     Tuple {
         exprs: Vec<ExprId>,
@@ -61,12 +61,12 @@ use base_db::{CrateId, Edition, FileId};
 use hir_expand::{diagnostics::DiagnosticSink, name::Name, InFile};
 use rustc_hash::FxHashMap;
 use stdx::format_to;
-use syntax::ast;
+use syntax::{ast, SmolStr};
 
 use crate::{
     db::DefDatabase,
     item_scope::{BuiltinShadowMode, ItemScope},
-    nameres::{diagnostics::DefDiagnostic, path_resolution::ResolveMode},
+    nameres::{diagnostics::DefDiagnostic, mod_resolution::ModDir, path_resolution::ResolveMode},
     path::ModPath,
     per_ns::PerNs,
     AstId, LocalModuleId, ModuleDefId, ModuleId,
@@ -212,6 +212,42 @@ impl CrateDefMap {
             .map(|(id, _data)| id)
     }
 
+    /// Returns the paths at which the file of a `mod name;` declaration in
+    /// `module` is looked up, in order, relative to the file of `module`.
+    pub fn mod_decl_candidate_files(
+        &self,
+        db: &dyn DefDatabase,
+        module: LocalModuleId,
+        name: &Name,
+        attr_path: Option<&SmolStr>,
+    ) -> Vec<String> {
+        self.mod_dir(db, module).candidate_files(name, attr_path)
+    }
+
+    /// Reconstructs the `ModDir` the collector used for `module`.
+    fn mod_dir(&self, db: &dyn DefDatabase, module: LocalModuleId) -> ModDir {
+        let data = &self.modules[module];
+        let parent = match data.parent {
+            Some(it) => it,
+            None => return ModDir::root(),
+        };
+        let name = match self.modules[parent].children.iter().find(|(_, &it)| it == module) {
+            Some((name, _)) => name,
+            None => return ModDir::root(),
+        };
+        let attrs = db.attrs(ModuleId { krate: self.krate, local_id: module }.into());
+        let attr_path = attrs.by_key("path").string_value();
+        match data.origin {
+            ModuleOrigin::CrateRoot { .. } => ModDir::root(),
+            ModuleOrigin::File { is_mod_rs, .. } => {
+                ModDir::for_file(name, is_mod_rs, attr_path.is_some())
+            }
+            ModuleOrigin::Inline { .. } => {
+                self.mod_dir(db, parent).descend_into_definition(name, attr_path)
+            }
+        }
+    }
+
     pub(crate) fn resolve_path(
         &self,
         db: &dyn DefDatabase,
@@ -287,10 +323,18 @@ pub enum ModuleSource {
 }
 
 mod diagnostics {
-    use hir_expand::diagnostics::DiagnosticSink;
+    use hir_expand::{diagnostics::DiagnosticSink, hygiene::Hygiene, InFile};
     use syntax::{ast, AstPtr};
 
-    use crate::{db::DefDatabase, diagnostics::UnresolvedModule, nameres::LocalModuleId, AstId};
+    use crate::{
+        db::DefDatabase,
+        diagnostics::{
+            UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall, UnresolvedModule,
+        },
+        nameres::LocalModuleId,
+        path::ModPath,
+        AstId,
+    };
 
     #[derive(Debug, PartialEq, Eq)]
     pub(super) enum DefDiagnostic {
@@ -299,20 +343,44 @@ mod diagnostics {
             declaration: AstId<ast::Module>,
             candidate: String,
         },
+        UnresolvedExternCrate {
+            module: LocalModuleId,
+            ast: AstId<ast::ExternCrate>,
+        },
+        UnresolvedImport {
+            module: LocalModuleId,
+            ast: AstId<ast::Use>,
+            /// Index of the unresolved `UseTree`, in the order in which
+            /// `ModPath::expand_use_item` visits them.
+            index: usize,
+        },
+        UnresolvedMacroCall {
+            module: LocalModuleId,
+            ast: AstId<ast::MacroCall>,
+        },
     }
 
     impl DefDiagnostic {
+        fn module(&self) -> LocalModuleId {
+            match self {
+                DefDiagnostic::UnresolvedModule { module, .. }
+                | DefDiagnostic::UnresolvedExternCrate { module, .. }
+                | DefDiagnostic::UnresolvedImport { module, .. }
+                | DefDiagnostic::UnresolvedMacroCall { module, .. } => *module,
+            }
+        }
+
         pub(super) fn add_to(
             &self,
             db: &dyn DefDatabase,
             target_module: LocalModuleId,
             sink: &mut DiagnosticSink,
         ) {
+            if self.module() != target_module {
+                return;
+            }
             match self {
-                DefDiagnostic::UnresolvedModule { module, declaration, candidate } => {
-                    if *module != target_module {
-                        return;
-                    }
+                DefDiagnostic::UnresolvedModule { declaration, candidate, .. } => {
                     let decl = declaration.to_node(db.upcast());
                     sink.push(UnresolvedModule {
                         file: declaration.file_id,
@@ -320,6 +388,36 @@ mod diagnostics {
                         candidate: candidate.clone(),
                     })
                 }
+                DefDiagnostic::UnresolvedExternCrate { ast, .. } => {
+                    let item = ast.to_node(db.upcast());
+                    sink.push(UnresolvedExternCrate {
+                        file: ast.file_id,
+                        item: AstPtr::new(&item),
+                    });
+                }
+                DefDiagnostic::UnresolvedImport { ast, index, .. } => {
+                    let use_item = ast.to_node(db.upcast());
+                    let hygiene = Hygiene::new(db.upcast(), ast.file_id);
+                    let mut cur = 0;
+                    let mut tree = None;
+                    ModPath::expand_use_item(
+                        InFile::new(ast.file_id, use_item),
+                        &hygiene,
+                        |_mod_path, use_tree, _is_glob, _alias| {
+                            if cur == *index {
+                                tree = Some(use_tree.clone());
+                            }
+                            cur += 1;
+                        },
+                    );
+                    if let Some(tree) = tree {
+                        sink.push(UnresolvedImport { file: ast.file_id, node: AstPtr::new(&tree) });
+                    }
+                }
+                DefDiagnostic::UnresolvedMacroCall { ast, .. } => {
+                    let node = ast.to_node(db.upcast());
+                    sink.push(UnresolvedMacroCall { file: ast.file_id, node: AstPtr::new(&node) });
+                }
             }
         }
     }
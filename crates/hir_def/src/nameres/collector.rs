@@ -3,7 +3,9 @@
 //! `DefCollector::collect` contains the fixed-point iteration loop which
 //! resolves imports and expands macros.
 
-use base_db::{CrateId, FileId, ProcMacroId};
+use std::iter;
+
+use base_db::{CrateId, FileId, ProcMacroId, ProcMacroKind};
 use cfg::CfgOptions;
 use hir_expand::{
     ast_id_map::FileAstId,
@@ -11,19 +13,18 @@ use hir_expand::{
     builtin_macro::find_builtin_macro,
     name::{name, AsName, Name},
     proc_macro::ProcMacroExpander,
-    HirFileId, MacroCallId, MacroDefId, MacroDefKind,
+    HirFileId, InFile, MacroCallId, MacroCallKind, MacroDefId, MacroDefKind,
 };
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::ast;
 use test_utils::mark;
 
 use crate::{
     attr::Attrs,
+    builtin_attr::is_builtin_or_tool_attr,
     db::DefDatabase,
     item_scope::{ImportType, PerNsGlobImports},
-    item_tree::{
-        self, FileItemTreeId, ItemTree, ItemTreeId, MacroCall, Mod, ModItem, ModKind, StructDefKind,
-    },
+    item_tree::{self, ItemTree, ItemTreeId, MacroCall, Mod, ModItem, ModKind, StructDefKind},
     nameres::{
         diagnostics::DefDiagnostic, mod_resolution::ModDir, path_resolution::ReachedFixedPoint,
         BuiltinShadowMode, CrateDefMap, ModuleData, ModuleOrigin, ResolveMode,
@@ -69,10 +70,22 @@ pub(super) fn collect_defs(db: &dyn DefDatabase, mut def_map: CrateDefMap) -> Cr
         .map(|(idx, it)| {
             // FIXME: a hacky way to create a Name from string.
             let name = tt::Ident { text: it.name.clone(), id: tt::TokenId::unspecified() };
-            (name.as_name(), ProcMacroExpander::new(def_map.krate, ProcMacroId(idx as u32)))
+            let expander = ProcMacroExpander::new(def_map.krate, ProcMacroId(idx as u32), it.kind);
+            (name.as_name(), expander)
         })
         .collect();
 
+    // Attribute macros can only come from proc-macro crates we depend on, so
+    // only items with attributes named like one of those have to wait for
+    // their attributes to resolve.
+    let attr_macro_names = crate_graph
+        .transitive_deps(def_map.krate)
+        .chain(iter::once(def_map.krate))
+        .flat_map(|krate| crate_graph[krate].proc_macro.iter())
+        .filter(|it| it.kind == ProcMacroKind::Attr)
+        .map(|it| tt::Ident { text: it.name.clone(), id: tt::TokenId::unspecified() }.as_name())
+        .collect();
+
     let mut collector = DefCollector {
         db,
         def_map,
@@ -81,7 +94,10 @@ pub(super) fn collect_defs(db: &dyn DefDatabase, mut def_map: CrateDefMap) -> Cr
         resolved_imports: Vec::new(),
 
         unexpanded_macros: Vec::new(),
+        unexpanded_derives: Vec::new(),
         unexpanded_attribute_macros: Vec::new(),
+        skip_attrs: FxHashMap::default(),
+        attr_macro_names,
         mod_dirs: FxHashMap::default(),
         cfg_options,
         proc_macros,
@@ -111,6 +127,12 @@ impl PartialResolvedImport {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ImportSource {
+    Import(ItemTreeId<item_tree::Import>),
+    ExternCrate(ItemTreeId<item_tree::ExternCrate>),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct Import {
     pub path: ModPath,
@@ -120,11 +142,12 @@ struct Import {
     pub is_prelude: bool,
     pub is_extern_crate: bool,
     pub is_macro_use: bool,
+    pub source: ImportSource,
 }
 
 impl Import {
-    fn from_use(tree: &ItemTree, id: FileItemTreeId<item_tree::Import>) -> Self {
-        let it = &tree[id];
+    fn from_use(tree: &ItemTree, id: ItemTreeId<item_tree::Import>) -> Self {
+        let it = &tree[id.value];
         let visibility = &tree[it.visibility];
         Self {
             path: it.path.clone(),
@@ -134,11 +157,12 @@ impl Import {
             is_prelude: it.is_prelude,
             is_extern_crate: false,
             is_macro_use: false,
+            source: ImportSource::Import(id),
         }
     }
 
-    fn from_extern_crate(tree: &ItemTree, id: FileItemTreeId<item_tree::ExternCrate>) -> Self {
-        let it = &tree[id];
+    fn from_extern_crate(tree: &ItemTree, id: ItemTreeId<item_tree::ExternCrate>) -> Self {
+        let it = &tree[id.value];
         let visibility = &tree[it.visibility];
         Self {
             path: it.path.clone(),
@@ -148,6 +172,7 @@ impl Import {
             is_prelude: false,
             is_extern_crate: true,
             is_macro_use: it.is_macro_use,
+            source: ImportSource::ExternCrate(id),
         }
     }
 }
@@ -173,6 +198,18 @@ struct DeriveDirective {
     ast_id: AstIdWithPath<ast::Item>,
 }
 
+/// An item annotated with an attribute which might be an attribute macro.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct AttrDirective {
+    module_id: LocalModuleId,
+    /// The annotated item, and the path of the attribute.
+    ast_id: AstIdWithPath<ast::Item>,
+    mod_item: ModItem,
+    /// Index of the attribute among the item's `#[...]` attributes.
+    attr_index: u32,
+    depth: usize,
+}
+
 struct DefData<'a> {
     id: ModuleDefId,
     name: &'a Name,
@@ -188,7 +225,14 @@ struct DefCollector<'a> {
     unresolved_imports: Vec<ImportDirective>,
     resolved_imports: Vec<ImportDirective>,
     unexpanded_macros: Vec<MacroDirective>,
-    unexpanded_attribute_macros: Vec<DeriveDirective>,
+    unexpanded_derives: Vec<DeriveDirective>,
+    unexpanded_attribute_macros: Vec<AttrDirective>,
+    /// Items whose attributes up to (and including) the given index are known
+    /// not to be attribute macro invocations.
+    skip_attrs: FxHashMap<InFile<ModItem>, u32>,
+    /// Names of the attribute macros this crate could use. Renaming imports of
+    /// attribute macros aren't taken into account.
+    attr_macro_names: FxHashSet<Name>,
     mod_dirs: FxHashMap<LocalModuleId, ModDir>,
     cfg_options: &'a CfgOptions,
     proc_macros: Vec<(Name, ProcMacroExpander)>,
@@ -218,7 +262,10 @@ impl DefCollector<'_> {
             self.resolve_imports();
 
             match self.resolve_macros() {
-                ReachedFixedPoint::Yes => break,
+                ReachedFixedPoint::Yes => match self.reseed_with_unresolved_attributes() {
+                    ReachedFixedPoint::Yes => break,
+                    ReachedFixedPoint::No => i += 1,
+                },
                 ReachedFixedPoint::No => i += 1,
             }
             if i == FIXED_POINT_LIMIT {
@@ -243,6 +290,8 @@ impl DefCollector<'_> {
         self.unresolved_imports.extend(partial_resolved);
         self.resolve_imports();
 
+        self.emit_diagnostics();
+
         let unresolved_imports = std::mem::replace(&mut self.unresolved_imports, Vec::new());
         // show unresolved imports in completion, etc
         for directive in unresolved_imports {
@@ -261,7 +310,7 @@ impl DefCollector<'_> {
             let macro_id = MacroDefId {
                 ast_id: None,
                 krate: Some(krate),
-                kind: MacroDefKind::CustomDerive(expander),
+                kind: MacroDefKind::ProcMacro(expander),
                 local_inner: false,
             };
 
@@ -420,7 +469,11 @@ impl DefCollector<'_> {
                     .as_ident()
                     .expect("extern crate should have been desugared to one-element path"),
             );
-            PartialResolvedImport::Resolved(res)
+            if res.is_none() {
+                PartialResolvedImport::Unresolved
+            } else {
+                PartialResolvedImport::Resolved(res)
+            }
         } else {
             let res = self.def_map.resolve_path_fp_with_macro(
                 self.db,
@@ -671,6 +724,7 @@ impl DefCollector<'_> {
 
     fn resolve_macros(&mut self) -> ReachedFixedPoint {
         let mut macros = std::mem::replace(&mut self.unexpanded_macros, Vec::new());
+        let mut derives = std::mem::take(&mut self.unexpanded_derives);
         let mut attribute_macros =
             std::mem::replace(&mut self.unexpanded_attribute_macros, Vec::new());
         let mut resolved = Vec::new();
@@ -701,10 +755,10 @@ impl DefCollector<'_> {
 
             true
         });
-        attribute_macros.retain(|directive| {
+        derives.retain(|directive| {
             if let Some(call_id) =
                 directive.ast_id.as_call_id(self.db, self.def_map.krate, |path| {
-                    self.resolve_attribute_macro(&directive, &path)
+                    self.resolve_derive_macro(directive, &path)
                 })
             {
                 resolved.push((directive.module_id, call_id, 0));
@@ -714,8 +768,36 @@ impl DefCollector<'_> {
 
             true
         });
+        attribute_macros.retain(|directive| {
+            let resolved_res = self.def_map.resolve_path_fp_with_macro(
+                self.db,
+                ResolveMode::Other,
+                directive.module_id,
+                &directive.ast_id.path,
+                BuiltinShadowMode::Module,
+            );
+            let def = match resolved_res.resolved_def.take_macros() {
+                Some(def) => def,
+                None => return true,
+            };
+            match def.kind {
+                MacroDefKind::ProcMacro(expander) if expander.kind() == ProcMacroKind::Attr => {}
+                _ => return true,
+            }
+            let name = match directive.ast_id.path.segments.last() {
+                Some(it) => it.to_string(),
+                None => return true,
+            };
+
+            let kind = MacroCallKind::Attr(directive.ast_id.ast_id, name, directive.attr_index);
+            let call_id = def.as_lazy_macro(self.db.upcast(), self.def_map.krate, kind);
+            resolved.push((directive.module_id, call_id.into(), directive.depth + 1));
+            res = ReachedFixedPoint::No;
+            false
+        });
 
         self.unexpanded_macros = macros;
+        self.unexpanded_derives = derives;
         self.unexpanded_attribute_macros = attribute_macros;
 
         for (module_id, macro_call_id, depth) in resolved {
@@ -725,7 +807,7 @@ impl DefCollector<'_> {
         res
     }
 
-    fn resolve_attribute_macro(
+    fn resolve_derive_macro(
         &self,
         directive: &DeriveDirective,
         path: &ModPath,
@@ -749,6 +831,38 @@ impl DefCollector<'_> {
         resolved_res.resolved_def.take_macros()
     }
 
+    /// Collects all items still waiting on an attribute, treating the attribute
+    /// as inert.
+    ///
+    /// This is called once the fixed point is reached, so none of these
+    /// attributes can resolve to an attribute macro anymore.
+    fn reseed_with_unresolved_attributes(&mut self) -> ReachedFixedPoint {
+        let directives = std::mem::take(&mut self.unexpanded_attribute_macros);
+        if directives.is_empty() {
+            return ReachedFixedPoint::Yes;
+        }
+
+        mark::hit!(unresolved_attribute_fallback);
+        for directive in directives {
+            let file_id = directive.ast_id.ast_id.file_id;
+            self.skip_attrs.insert(InFile::new(file_id, directive.mod_item), directive.attr_index);
+
+            let item_tree = self.db.item_tree(file_id);
+            let mod_dir = self.mod_dirs[&directive.module_id].clone();
+            ModCollector {
+                def_collector: &mut *self,
+                macro_depth: directive.depth,
+                module_id: directive.module_id,
+                file_id,
+                item_tree: &item_tree,
+                mod_dir,
+            }
+            .collect(&[directive.mod_item]);
+        }
+
+        ReachedFixedPoint::No
+    }
+
     fn collect_macro_expansion(
         &mut self,
         module_id: LocalModuleId,
@@ -774,6 +888,57 @@ impl DefCollector<'_> {
         .collect(item_tree.top_level_items());
     }
 
+    /// Reports the imports and macro calls which are still unresolved after
+    /// reaching the fixed point.
+    fn emit_diagnostics(&mut self) {
+        // An unresolved `extern crate` is reported once, rather than once for
+        // every import that goes through it.
+        let mut unresolved_extern_crates = FxHashSet::default();
+        for directive in &self.unresolved_imports {
+            if let ImportSource::ExternCrate(id) = directive.import.source {
+                let item_tree = self.db.item_tree(id.file_id);
+                let extern_crate = &item_tree[id.value];
+                if let Some(name) = extern_crate.path.segments.first() {
+                    unresolved_extern_crates.insert(name.clone());
+                }
+                if let Some(ImportAlias::Alias(alias)) = &extern_crate.alias {
+                    unresolved_extern_crates.insert(alias.clone());
+                }
+                self.def_map.diagnostics.push(DefDiagnostic::UnresolvedExternCrate {
+                    module: directive.module_id,
+                    ast: AstId::new(id.file_id, extern_crate.ast_id),
+                });
+            }
+        }
+
+        for directive in &self.unresolved_imports {
+            if let ImportSource::Import(id) = directive.import.source {
+                let item_tree = self.db.item_tree(id.file_id);
+                let import = &item_tree[id.value];
+                match (import.path.segments.first(), &import.path.kind) {
+                    (Some(krate), PathKind::Plain) | (Some(krate), PathKind::Abs)
+                        if unresolved_extern_crates.contains(krate) =>
+                    {
+                        continue
+                    }
+                    _ => {}
+                }
+                self.def_map.diagnostics.push(DefDiagnostic::UnresolvedImport {
+                    module: directive.module_id,
+                    ast: AstId::new(id.file_id, import.ast_id),
+                    index: import.index,
+                });
+            }
+        }
+
+        for directive in &self.unexpanded_macros {
+            self.def_map.diagnostics.push(DefDiagnostic::UnresolvedMacroCall {
+                module: directive.module_id,
+                ast: directive.ast_id.ast_id,
+            });
+        }
+    }
+
     fn finish(self) -> CrateDefMap {
         self.def_map
     }
@@ -820,6 +985,10 @@ impl ModCollector<'_, '_> {
         for &item in items {
             let attrs = self.item_tree.attrs(item.into());
             if self.is_cfg_enabled(attrs) {
+                if self.collect_attr_macro_invocation(attrs, item) {
+                    continue;
+                }
+
                 let module =
                     ModuleId { krate: self.def_collector.def_map.krate, local_id: self.module_id };
                 let container = ContainerId::ModuleId(module);
@@ -830,14 +999,20 @@ impl ModCollector<'_, '_> {
                     ModItem::Import(import_id) => {
                         self.def_collector.unresolved_imports.push(ImportDirective {
                             module_id: self.module_id,
-                            import: Import::from_use(&self.item_tree, import_id),
+                            import: Import::from_use(
+                                self.item_tree,
+                                ItemTreeId::new(self.file_id, import_id),
+                            ),
                             status: PartialResolvedImport::Unresolved,
                         })
                     }
                     ModItem::ExternCrate(import_id) => {
                         self.def_collector.unresolved_imports.push(ImportDirective {
                             module_id: self.module_id,
-                            import: Import::from_extern_crate(&self.item_tree, import_id),
+                            import: Import::from_extern_crate(
+                                self.item_tree,
+                                ItemTreeId::new(self.file_id, import_id),
+                            ),
                             status: PartialResolvedImport::Unresolved,
                         })
                     }
@@ -871,9 +1046,6 @@ impl ModCollector<'_, '_> {
                     ModItem::Struct(id) => {
                         let it = &self.item_tree[id];
 
-                        // FIXME: check attrs to see if this is an attribute macro invocation;
-                        // in which case we don't add the invocation, just a single attribute
-                        // macro invocation
                         self.collect_derives(attrs, it.ast_id.upcast());
 
                         def = Some(DefData {
@@ -888,9 +1060,6 @@ impl ModCollector<'_, '_> {
                     ModItem::Union(id) => {
                         let it = &self.item_tree[id];
 
-                        // FIXME: check attrs to see if this is an attribute macro invocation;
-                        // in which case we don't add the invocation, just a single attribute
-                        // macro invocation
                         self.collect_derives(attrs, it.ast_id.upcast());
 
                         def = Some(DefData {
@@ -905,9 +1074,6 @@ impl ModCollector<'_, '_> {
                     ModItem::Enum(id) => {
                         let it = &self.item_tree[id];
 
-                        // FIXME: check attrs to see if this is an attribute macro invocation;
-                        // in which case we don't add the invocation, just a single attribute
-                        // macro invocation
                         self.collect_derives(attrs, it.ast_id.upcast());
 
                         def = Some(DefData {
@@ -1113,12 +1279,65 @@ impl ModCollector<'_, '_> {
 
                 let ast_id = AstIdWithPath::new(self.file_id, ast_id, path);
                 self.def_collector
-                    .unexpanded_attribute_macros
+                    .unexpanded_derives
                     .push(DeriveDirective { module_id: self.module_id, ast_id });
             }
         }
     }
 
+    /// Checks whether `item` is annotated with an attribute which might be an
+    /// attribute macro invocation. If so, collection of the item is deferred
+    /// until the attribute is resolved, and `true` is returned.
+    fn collect_attr_macro_invocation(&mut self, attrs: &Attrs, item: ModItem) -> bool {
+        if self.def_collector.attr_macro_names.is_empty() {
+            return false;
+        }
+        let ast_id: FileAstId<ast::Item> = match item {
+            ModItem::Function(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Struct(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Union(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Enum(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Const(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Static(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Trait(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Impl(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::TypeAlias(it) => self.item_tree[it].ast_id.upcast(),
+            ModItem::Import(_)
+            | ModItem::ExternCrate(_)
+            | ModItem::Mod(_)
+            | ModItem::MacroCall(_) => return false,
+        };
+
+        let skipped = self.def_collector.skip_attrs.get(&InFile::new(self.file_id, item)).copied();
+        let attr_macro_names = &self.def_collector.attr_macro_names;
+        let invocation = attrs.iter().find_map(|attr| {
+            let index = attr.index?;
+            if matches!(skipped, Some(skipped) if index <= skipped)
+                || is_builtin_or_tool_attr(&attr.path)
+            {
+                return None;
+            }
+            let name = attr.path.segments.last()?;
+            if !attr_macro_names.contains(name) {
+                return None;
+            }
+            Some((index, attr))
+        });
+        let (attr_index, attr) = match invocation {
+            Some(it) => it,
+            None => return false,
+        };
+
+        self.def_collector.unexpanded_attribute_macros.push(AttrDirective {
+            module_id: self.module_id,
+            ast_id: AstIdWithPath::new(self.file_id, ast_id, attr.path.clone()),
+            mod_item: item,
+            attr_index,
+            depth: self.macro_depth,
+        });
+        true
+    }
+
     fn collect_macro(&mut self, mac: &MacroCall) {
         let mut ast_id = AstIdWithPath::new(self.file_id, mac.ast_id, mac.path.clone());
 
@@ -1221,7 +1440,10 @@ mod tests {
             unresolved_imports: Vec::new(),
             resolved_imports: Vec::new(),
             unexpanded_macros: Vec::new(),
+            unexpanded_derives: Vec::new(),
             unexpanded_attribute_macros: Vec::new(),
+            skip_attrs: FxHashMap::default(),
+            attr_macro_names: FxHashSet::default(),
             mod_dirs: FxHashMap::default(),
             cfg_options: &CfgOptions::default(),
             proc_macros: Default::default(),
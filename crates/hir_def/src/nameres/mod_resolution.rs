@@ -21,6 +21,15 @@ impl ModDir {
         ModDir { dir_path: DirPath::empty(), root_non_dir_owner: false }
     }
 
+    /// The directory of the module in the file which `mod name;` resolved to.
+    pub(super) fn for_file(name: &Name, is_mod_rs: bool, has_attr_path: bool) -> ModDir {
+        if is_mod_rs || has_attr_path {
+            ModDir::root()
+        } else {
+            ModDir { dir_path: DirPath::new(format!("{}/", name)), root_non_dir_owner: true }
+        }
+    }
+
     pub(super) fn descend_into_definition(
         &self,
         name: &Name,
@@ -52,31 +61,30 @@ impl ModDir {
     ) -> Result<(FileId, bool, ModDir), String> {
         let file_id = file_id.original_file(db.upcast());
 
-        let mut candidate_files = Vec::new();
-        match attr_path {
-            Some(attr_path) => {
-                candidate_files.push(self.dir_path.join_attr(attr_path, self.root_non_dir_owner))
-            }
-            None => {
-                candidate_files.push(format!("{}{}.rs", self.dir_path.0, name));
-                candidate_files.push(format!("{}{}/mod.rs", self.dir_path.0, name));
-            }
-        };
-
+        let mut candidate_files = self.candidate_files(name, attr_path);
         for candidate in candidate_files.iter() {
             if let Some(file_id) = db.resolve_path(file_id, candidate.as_str()) {
                 let is_mod_rs = candidate.ends_with("mod.rs");
-
-                let (dir_path, root_non_dir_owner) = if is_mod_rs || attr_path.is_some() {
-                    (DirPath::empty(), false)
-                } else {
-                    (DirPath::new(format!("{}/", name)), true)
-                };
-                return Ok((file_id, is_mod_rs, ModDir { dir_path, root_non_dir_owner }));
+                let mod_dir = ModDir::for_file(name, is_mod_rs, attr_path.is_some());
+                return Ok((file_id, is_mod_rs, mod_dir));
             }
         }
         Err(candidate_files.remove(0))
     }
+
+    /// Returns the paths at which the file of `mod name;` is looked up, in
+    /// order, relative to the file containing the declaration.
+    pub(super) fn candidate_files(&self, name: &Name, attr_path: Option<&SmolStr>) -> Vec<String> {
+        match attr_path {
+            Some(attr_path) => {
+                vec![self.dir_path.join_attr(attr_path, self.root_non_dir_owner)]
+            }
+            None => vec![
+                format!("{}{}.rs", self.dir_path.0, name),
+                format!("{}{}/mod.rs", self.dir_path.0, name),
+            ],
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
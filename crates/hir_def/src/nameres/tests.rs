@@ -6,7 +6,7 @@ mod primitives;
 
 use std::sync::Arc;
 
-use base_db::{fixture::WithFixture, SourceDatabase};
+use base_db::{fixture::WithFixture, CrateGraph, ProcMacro, ProcMacroKind, SourceDatabase};
 use expect::{expect, Expect};
 use test_utils::mark;
 
@@ -667,3 +667,137 @@ b! { static = #[] (); }
         "#]],
     );
 }
+
+/// An attribute macro which replaces the annotated item with its arguments.
+#[derive(Debug)]
+struct ReplaceItem;
+
+impl tt::TokenExpander for ReplaceItem {
+    fn expand(
+        &self,
+        _item: &tt::Subtree,
+        attr: Option<&tt::Subtree>,
+    ) -> Result<tt::Subtree, tt::ExpansionError> {
+        Ok(attr.cloned().unwrap_or_default())
+    }
+}
+
+/// Like `check`, but the crate named `proc` defines the `replace` attribute macro.
+fn check_with_attr_macro(ra_fixture: &str, expect: Expect) {
+    let mut db = TestDB::with_files(ra_fixture);
+    let fixture_graph = db.crate_graph();
+    let mut krates = fixture_graph.iter().collect::<Vec<_>>();
+    krates.sort();
+
+    let mut crate_graph = CrateGraph::default();
+    for &krate in &krates {
+        let data = &fixture_graph[krate];
+        let proc_macro = if data.display_name.as_deref() == Some("proc") {
+            vec![ProcMacro {
+                name: "replace".into(),
+                kind: ProcMacroKind::Attr,
+                expander: Arc::new(ReplaceItem),
+            }]
+        } else {
+            Vec::new()
+        };
+        crate_graph.add_crate_root(
+            data.root_file_id,
+            data.edition,
+            data.display_name.clone(),
+            data.cfg_options.clone(),
+            data.env.clone(),
+            proc_macro,
+        );
+    }
+    for &krate in &krates {
+        for dep in &fixture_graph[krate].dependencies {
+            crate_graph.add_dep(krate, dep.name.clone(), dep.crate_id).unwrap();
+        }
+    }
+    db.set_crate_graph(Arc::new(crate_graph));
+
+    let actual = db.crate_def_map(krates[0]).dump();
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn expand_attribute_macro() {
+    check_with_attr_macro(
+        r#"
+//- /main.rs crate:main deps:proc
+use proc::replace;
+
+#[proc::replace(pub struct Replaced;)]
+struct Original;
+
+#[derive(Clone)]
+#[replace(fn generated() {} struct Other;)]
+fn original() {}
+
+//- /proc.rs crate:proc
+"#,
+        expect![[r#"
+            crate
+            Other: t v
+            Replaced: t v
+            generated: v
+            replace: m
+        "#]],
+    );
+}
+
+#[test]
+fn unresolved_attributes_are_inert() {
+    mark::check!(unresolved_attribute_fallback);
+    check_with_attr_macro(
+        r#"
+//- /main.rs crate:main deps:proc
+use self::S as T;
+
+#[unknown]
+struct S;
+
+#[allow(dead_code)]
+#[serde(rename_all = "camelCase")]
+#[rustfmt::skip]
+enum E {}
+
+#[proc::replace(struct U;)]
+#[unknown]
+fn f() {}
+
+#[replace]
+struct V;
+
+//- /proc.rs crate:proc
+"#,
+        expect![[r#"
+            crate
+            E: t
+            S: t v
+            T: t v
+            U: t v
+            V: t v
+        "#]],
+    );
+}
+
+#[test]
+fn attribute_macro_after_unparsable_attribute() {
+    check_with_attr_macro(
+        r#"
+//- /main.rs crate:main deps:proc
+#[doc = "docs"]
+#[::]
+#[proc::replace(struct Replaced;)]
+fn original() {}
+
+//- /proc.rs crate:proc
+"#,
+        expect![[r#"
+            crate
+            Replaced: t v
+        "#]],
+    );
+}
@@ -2,7 +2,11 @@
 //! be directly created from an ast::TypeRef, without further queries.
 use syntax::ast::{self};
 
-use crate::{body::LowerCtx, path::Path};
+use crate::{
+    body::LowerCtx,
+    expr::{ArithOp, BinaryOp},
+    path::Path,
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Mutability {
@@ -59,7 +63,7 @@ pub enum TypeRef {
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
     Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    Array(Box<TypeRef>, ConstRef),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>, bool /*varargs*/),
@@ -69,6 +73,17 @@ pub enum TypeRef {
     Error,
 }
 
+/// A constant expression in a type, like the length of an array. Unlike
+/// expressions in bodies, these aren't lowered to `Expr`s, so only the forms
+/// that can be evaluated without a body are kept.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ConstRef {
+    Literal(u128),
+    Path(Path),
+    BinaryOp { lhs: Box<ConstRef>, rhs: Box<ConstRef>, op: ArithOp },
+    Unknown,
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TypeBound {
     Path(Path),
@@ -100,7 +115,8 @@ impl TypeRef {
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
             ast::Type::ArrayType(inner) => {
-                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.ty())))
+                let len = ConstRef::from_ast_opt(ctx, inner.expr());
+                TypeRef::Array(Box::new(TypeRef::from_ast_opt(ctx, inner.ty())), len)
             }
             ast::Type::SliceType(inner) => {
                 TypeRef::Slice(Box::new(TypeRef::from_ast_opt(&ctx, inner.ty())))
@@ -164,7 +180,7 @@ impl TypeRef {
                 }
                 TypeRef::RawPtr(type_ref, _)
                 | TypeRef::Reference(type_ref, _)
-                | TypeRef::Array(type_ref)
+                | TypeRef::Array(type_ref, _)
                 | TypeRef::Slice(type_ref) => go(&type_ref, f),
                 TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
                     for bound in bounds {
@@ -206,6 +222,36 @@ impl TypeRef {
     }
 }
 
+impl ConstRef {
+    pub(crate) fn from_ast_opt(ctx: &LowerCtx, node: Option<ast::Expr>) -> Self {
+        node.map_or(ConstRef::Unknown, |node| ConstRef::from_ast(ctx, node))
+    }
+
+    fn from_ast(ctx: &LowerCtx, node: ast::Expr) -> Self {
+        match node {
+            ast::Expr::Literal(lit) => lit.int_value().map_or(ConstRef::Unknown, ConstRef::Literal),
+            ast::Expr::PathExpr(path) => path
+                .path()
+                .and_then(|it| ctx.lower_path(it))
+                .map_or(ConstRef::Unknown, ConstRef::Path),
+            ast::Expr::ParenExpr(inner) => ConstRef::from_ast_opt(ctx, inner.expr()),
+            // `{ N }`, as needed for const generic arguments
+            ast::Expr::BlockExpr(block) if block.statements().next().is_none() => {
+                ConstRef::from_ast_opt(ctx, block.expr())
+            }
+            ast::Expr::BinExpr(bin) => match bin.op_kind().map(BinaryOp::from) {
+                Some(BinaryOp::ArithOp(op)) => ConstRef::BinaryOp {
+                    lhs: Box::new(ConstRef::from_ast_opt(ctx, bin.lhs())),
+                    rhs: Box::new(ConstRef::from_ast_opt(ctx, bin.rhs())),
+                    op,
+                },
+                _ => ConstRef::Unknown,
+            },
+            _ => ConstRef::Unknown,
+        }
+    }
+}
+
 pub(crate) fn type_bounds_from_ast(
     lower_ctx: &LowerCtx,
     type_bounds_opt: Option<ast::TypeBoundList>,
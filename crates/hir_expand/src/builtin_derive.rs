@@ -285,7 +285,7 @@ mod tests {
         let loc = MacroCallLoc {
             def,
             krate: CrateId(0),
-            kind: MacroCallKind::Attr(attr_id, name.to_string()),
+            kind: MacroCallKind::Derive(attr_id, name.to_string()),
         };
 
         let id: MacroCallId = db.intern_macro(loc).into();
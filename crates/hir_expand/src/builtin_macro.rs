@@ -60,6 +60,17 @@ macro_rules! register_builtin {
     };
 }
 
+impl EagerExpander {
+    /// Whether this is `include!`, `include_str!` or `include_bytes!`, whose
+    /// argument is a path resolved by `resolve_include_path`.
+    pub fn is_include(&self) -> bool {
+        matches!(
+            self,
+            EagerExpander::Include | EagerExpander::IncludeBytes | EagerExpander::IncludeStr
+        )
+    }
+}
+
 pub fn find_builtin_macro(
     ident: &name::Name,
     krate: CrateId,
@@ -294,6 +305,12 @@ fn concat_expand(
     Ok((quote!(#text), FragmentKind::Expr))
 }
 
+/// Resolves the path argument of an `include!`-like macro called in
+/// `call_site`, which is relative to the directory of that file.
+pub fn resolve_include_path(db: &dyn AstDatabase, call_site: FileId, path: &str) -> Option<FileId> {
+    db.resolve_path(call_site, path)
+}
+
 fn relative_file(
     db: &dyn AstDatabase,
     call_id: MacroCallId,
@@ -301,7 +318,7 @@ fn relative_file(
     allow_recursion: bool,
 ) -> Option<FileId> {
     let call_site = call_id.as_file().original_file(db);
-    let res = db.resolve_path(call_site, path)?;
+    let res = resolve_include_path(db, call_site, path)?;
     // Prevent include itself
     if res == call_site && !allow_recursion {
         None
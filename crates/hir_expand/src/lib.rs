@@ -246,7 +246,7 @@ pub enum MacroDefKind {
     // FIXME: maybe just Builtin and rename BuiltinFnLikeExpander to BuiltinExpander
     BuiltInDerive(BuiltinDeriveExpander),
     BuiltInEager(EagerExpander),
-    CustomDerive(ProcMacroExpander),
+    ProcMacro(ProcMacroExpander),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -259,21 +259,25 @@ pub struct MacroCallLoc {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MacroCallKind {
     FnLike(AstId<ast::MacroCall>),
-    Attr(AstId<ast::Item>, String),
+    /// `#[derive(Name)]` on an item.
+    Derive(AstId<ast::Item>, String),
+    /// `#[name(...)]` attribute macro invocation on an item. The `u32` is the
+    /// index of the invoking attribute among the item's outer attributes.
+    Attr(AstId<ast::Item>, String, u32),
 }
 
 impl MacroCallKind {
     fn file_id(&self) -> HirFileId {
         match self {
             MacroCallKind::FnLike(ast_id) => ast_id.file_id,
-            MacroCallKind::Attr(ast_id, _) => ast_id.file_id,
+            MacroCallKind::Derive(ast_id, _) | MacroCallKind::Attr(ast_id, ..) => ast_id.file_id,
         }
     }
 
     fn node(&self, db: &dyn db::AstDatabase) -> InFile<SyntaxNode> {
         match self {
             MacroCallKind::FnLike(ast_id) => ast_id.with_value(ast_id.to_node(db).syntax().clone()),
-            MacroCallKind::Attr(ast_id, _) => {
+            MacroCallKind::Derive(ast_id, _) | MacroCallKind::Attr(ast_id, ..) => {
                 ast_id.with_value(ast_id.to_node(db).syntax().clone())
             }
         }
@@ -284,7 +288,9 @@ impl MacroCallKind {
             MacroCallKind::FnLike(ast_id) => {
                 Some(ast_id.to_node(db).token_tree()?.syntax().clone())
             }
-            MacroCallKind::Attr(ast_id, _) => Some(ast_id.to_node(db).syntax().clone()),
+            MacroCallKind::Derive(ast_id, _) | MacroCallKind::Attr(ast_id, ..) => {
+                Some(ast_id.to_node(db).syntax().clone())
+            }
         }
     }
 }
@@ -162,6 +162,8 @@ pub mod known {
         ops,
         future,
         result,
+        option,
+        convert,
         boxed,
         // Components of known path (type name)
         IntoIterator,
@@ -170,6 +172,8 @@ pub mod known {
         Ok,
         Future,
         Result,
+        Option,
+        Into,
         Output,
         Target,
         Box,
@@ -1,13 +1,14 @@
 //! Proc Macro Expander stub
 
-use crate::{db::AstDatabase, LazyMacroId};
-use base_db::{CrateId, ProcMacroId};
+use crate::{db::AstDatabase, LazyMacroId, MacroCallKind};
+use base_db::{CrateId, ProcMacroId, ProcMacroKind};
 use tt::buffer::{Cursor, TokenBuffer};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct ProcMacroExpander {
     krate: CrateId,
     proc_macro_id: ProcMacroId,
+    kind: ProcMacroKind,
 }
 
 macro_rules! err {
@@ -20,14 +21,22 @@ macro_rules! err {
 }
 
 impl ProcMacroExpander {
-    pub fn new(krate: CrateId, proc_macro_id: ProcMacroId) -> ProcMacroExpander {
-        ProcMacroExpander { krate, proc_macro_id }
+    pub fn new(
+        krate: CrateId,
+        proc_macro_id: ProcMacroId,
+        kind: ProcMacroKind,
+    ) -> ProcMacroExpander {
+        ProcMacroExpander { krate, proc_macro_id, kind }
+    }
+
+    pub fn kind(&self) -> ProcMacroKind {
+        self.kind
     }
 
     pub fn expand(
         self,
         db: &dyn AstDatabase,
-        _id: LazyMacroId,
+        id: LazyMacroId,
         tt: &tt::Subtree,
     ) -> Result<tt::Subtree, mbe::ExpandError> {
         let krate_graph = db.crate_graph();
@@ -35,12 +44,21 @@ impl ProcMacroExpander {
             .proc_macro
             .get(self.proc_macro_id.0 as usize)
             .clone()
-            .ok_or_else(|| err!("No derive macro found."))?;
+            .ok_or_else(|| err!("No proc macro found."))?;
 
-        let tt = remove_derive_attrs(tt)
-            .ok_or_else(|| err!("Fail to remove derive for custom derive"))?;
+        match db.lookup_intern_macro(id).kind {
+            MacroCallKind::Attr(_, _, attr_index) => {
+                let (item, attr_args) = split_attr_invocation(tt, attr_index)
+                    .ok_or_else(|| err!("Fail to find the attribute macro invocation"))?;
+                proc_macro.expander.expand(&item, Some(&attr_args)).map_err(mbe::ExpandError::from)
+            }
+            _ => {
+                let tt = remove_derive_attrs(tt)
+                    .ok_or_else(|| err!("Fail to remove derive for custom derive"))?;
 
-        proc_macro.expander.expand(&tt, None).map_err(mbe::ExpandError::from)
+                proc_macro.expander.expand(&tt, None).map_err(mbe::ExpandError::from)
+            }
+        }
     }
 }
 
@@ -100,6 +118,48 @@ fn remove_derive_attrs(tt: &tt::Subtree) -> Option<tt::Subtree> {
     Some(result)
 }
 
+/// Removes the `attr_index`-th outer attribute from the annotated item, and
+/// returns the item together with the arguments of the removed attribute.
+///
+/// Attributes desugared from doc comments are not counted, as they don't have
+/// a source token.
+fn split_attr_invocation(tt: &tt::Subtree, attr_index: u32) -> Option<(tt::Subtree, tt::Subtree)> {
+    let mut item = tt::Subtree { delimiter: tt.delimiter, token_trees: Vec::new() };
+    let mut attr_args = None;
+    let mut seen_attrs = 0;
+    let mut token_trees = tt.token_trees.iter();
+
+    while let Some(token_tree) = token_trees.next() {
+        if attr_args.is_none() {
+            if let tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct { char: '#', .. })) = token_tree {
+                if let Some(tt::TokenTree::Subtree(attr)) = token_trees.as_slice().first() {
+                    let is_source_attr = matches!(
+                        attr.delimiter,
+                        Some(tt::Delimiter { kind: tt::DelimiterKind::Bracket, id })
+                            if id != tt::TokenId::unspecified()
+                    );
+                    if is_source_attr {
+                        if seen_attrs == attr_index {
+                            token_trees.next();
+                            attr_args = Some(match attr.token_trees.last() {
+                                Some(tt::TokenTree::Subtree(args)) if args.delimiter.is_some() => {
+                                    tt::Subtree { delimiter: None, ..args.clone() }
+                                }
+                                _ => tt::Subtree::default(),
+                            });
+                            continue;
+                        }
+                        seen_attrs += 1;
+                    }
+                }
+            }
+        }
+        item.token_trees.push(token_tree.clone());
+    }
+
+    Some((item, attr_args?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +196,57 @@ SUBTREE $
     IDENT   bar 18
     PUNCH   : [alone] 19
     IDENT   u32 20
+"#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_split_attr_invocation() {
+        let tt = mbe::parse_to_token_tree(
+            r#"
+    /// Docs
+    #[allow(unused)]
+    #[tokio::main(flavor = "current_thread")]
+    #[inline]
+    fn main() {}
+"#,
+        )
+        .unwrap()
+        .0;
+        let (item, args) = split_attr_invocation(&tt, 1).unwrap();
+
+        assert_eq_text!(
+            &format!("{:#?}", item),
+            r#"
+SUBTREE $
+  PUNCH   # [alone] 4294967295
+  SUBTREE [] 4294967295
+    IDENT   doc 4294967295
+    PUNCH   = [alone] 4294967295
+    LITERAL " Docs" 4294967295
+  PUNCH   # [alone] 0
+  SUBTREE [] 1
+    IDENT   allow 2
+    SUBTREE () 3
+      IDENT   unused 4
+  PUNCH   # [alone] 15
+  SUBTREE [] 16
+    IDENT   inline 17
+  IDENT   fn 18
+  IDENT   main 19
+  SUBTREE () 20
+  SUBTREE {} 21
+"#
+            .trim()
+        );
+        assert_eq_text!(
+            &format!("{:#?}", args),
+            r#"
+SUBTREE $
+  IDENT   flavor 12
+  PUNCH   = [alone] 13
+  LITERAL "current_thread" 14
 "#
             .trim()
         );
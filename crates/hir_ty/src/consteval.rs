@@ -0,0 +1,138 @@
+//! A tiny evaluator for the constant expressions that show up in types, most
+//! notably array lengths. It only understands integer literals, arithmetic and
+//! references to other `const` items; anything else is considered unknown.
+
+use std::convert::TryFrom;
+
+use hir_def::{
+    body::Body,
+    expr::{ArithOp, BinaryOp, Expr, ExprId, Literal},
+    resolver::{resolver_for_expr, Resolver, ValueNs},
+    type_ref::ConstRef,
+    ConstId, ConstParamId, DefWithBodyId,
+};
+
+use crate::{db::HirDatabase, Ty, TypeCtor};
+
+/// The value of a constant used as a generic argument, like the length of an
+/// array.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ConstScalar {
+    /// A known value, like the `4` in `[u8; 4]`.
+    Usize(u64),
+    /// A const generic parameter referred to from within its item, like the
+    /// `N` in `fn foo<const N: usize>(a: [u8; N])`. This is the constant
+    /// equivalent of `Ty::Placeholder`.
+    Param(ConstParamId),
+}
+
+impl ConstScalar {
+    pub fn to_ty(self) -> Ty {
+        Ty::simple(TypeCtor::Const(self))
+    }
+}
+
+/// Returns the type-level representation of an evaluated constant, which is
+/// `Ty::Unknown` if the evaluation failed.
+pub(crate) fn usize_const(value: Option<u64>) -> Ty {
+    value.map_or(Ty::Unknown, |it| ConstScalar::Usize(it).to_ty())
+}
+
+pub(crate) fn const_eval_query(db: &dyn HirDatabase, def: ConstId) -> Option<u64> {
+    let body = db.body(def.into());
+    eval_body_expr(db, def.into(), &body, body.body_expr)
+}
+
+pub(crate) fn const_eval_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &ConstId,
+) -> Option<u64> {
+    None
+}
+
+/// Evaluates an expression in a body, e.g. the length of `[0; 4]`.
+pub(crate) fn eval_body_expr(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: &Body,
+    expr: ExprId,
+) -> Option<u64> {
+    match &body[expr] {
+        Expr::Literal(Literal::Int(value, _)) => Some(*value),
+        Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::ArithOp(op)) } => {
+            let lhs = eval_body_expr(db, owner, body, *lhs)?;
+            let rhs = eval_body_expr(db, owner, body, *rhs)?;
+            eval_arith(*op, lhs, rhs)
+        }
+        Expr::Block { statements, tail: Some(tail), .. } if statements.is_empty() => {
+            eval_body_expr(db, owner, body, *tail)
+        }
+        // FIXME: truncate the value to the target type
+        Expr::Cast { expr, .. } => eval_body_expr(db, owner, body, *expr),
+        Expr::Path(path) => {
+            let resolver = resolver_for_expr(db.upcast(), owner, expr);
+            match resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path())? {
+                ValueNs::ConstId(it) => db.const_eval(it),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the length of a repeat expression like `[0; N]` as a type-level
+/// constant, see `TypeCtor::Const`.
+pub(crate) fn repeat_len(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: &Body,
+    expr: ExprId,
+) -> Ty {
+    if let Expr::Path(path) = &body[expr] {
+        let resolver = resolver_for_expr(db.upcast(), owner, expr);
+        let resolved = resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path());
+        if let Some(ValueNs::GenericParam(param_id)) = resolved {
+            return ConstScalar::Param(param_id).to_ty();
+        }
+    }
+    usize_const(eval_body_expr(db, owner, body, expr))
+}
+
+/// Evaluates a constant in a type, e.g. the length of `[u8; 4]`.
+pub(crate) fn eval_const_ref(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    const_ref: &ConstRef,
+) -> Option<u64> {
+    match const_ref {
+        ConstRef::Literal(value) => u64::try_from(*value).ok(),
+        ConstRef::BinaryOp { lhs, rhs, op } => {
+            let lhs = eval_const_ref(db, resolver, lhs)?;
+            let rhs = eval_const_ref(db, resolver, rhs)?;
+            eval_arith(*op, lhs, rhs)
+        }
+        ConstRef::Path(path) => {
+            match resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path())? {
+                ValueNs::ConstId(it) => db.const_eval(it),
+                _ => None,
+            }
+        }
+        ConstRef::Unknown => None,
+    }
+}
+
+fn eval_arith(op: ArithOp, lhs: u64, rhs: u64) -> Option<u64> {
+    match op {
+        ArithOp::Add => lhs.checked_add(rhs),
+        ArithOp::Mul => lhs.checked_mul(rhs),
+        ArithOp::Sub => lhs.checked_sub(rhs),
+        ArithOp::Div => lhs.checked_div(rhs),
+        ArithOp::Rem => lhs.checked_rem(rhs),
+        ArithOp::Shl => lhs.checked_shl(u32::try_from(rhs).ok()?),
+        ArithOp::Shr => lhs.checked_shr(u32::try_from(rhs).ok()?),
+        ArithOp::BitXor => Some(lhs ^ rhs),
+        ArithOp::BitOr => Some(lhs | rhs),
+        ArithOp::BitAnd => Some(lhs & rhs),
+    }
+}
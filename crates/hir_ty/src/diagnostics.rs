@@ -1,11 +1,12 @@
 //! FIXME: write short doc here
+mod decl_check;
 mod expr;
 mod match_check;
 mod unsafe_check;
 
 use std::any::Any;
 
-use hir_def::DefWithBodyId;
+use hir_def::{DefWithBodyId, ModuleDefId};
 use hir_expand::diagnostics::{Diagnostic, DiagnosticSink};
 use hir_expand::{name::Name, HirFileId, InFile};
 use stdx::format_to;
@@ -13,7 +14,20 @@ use syntax::{ast, AstPtr, SyntaxNodePtr};
 
 use crate::db::HirDatabase;
 
-pub use crate::diagnostics::expr::{record_literal_missing_fields, record_pattern_missing_fields};
+pub use crate::diagnostics::{
+    decl_check::{CaseType, IdentType},
+    expr::{record_literal_missing_fields, record_pattern_missing_fields},
+};
+
+pub fn validate_module_item(
+    db: &dyn HirDatabase,
+    owner: ModuleDefId,
+    sink: &mut DiagnosticSink<'_>,
+) {
+    let _p = profile::span("validate_module_item");
+    let mut validator = decl_check::DeclValidator::new(owner, sink);
+    validator.validate_item(db);
+}
 
 pub fn validate_body(db: &dyn HirDatabase, owner: DefWithBodyId, sink: &mut DiagnosticSink<'_>) {
     let _p = profile::span("validate_body");
@@ -231,6 +245,76 @@ impl Diagnostic for MismatchedArgCount {
     }
 }
 
+/// A way of turning the actual type of an expression into the expected one,
+/// offered as a fix for [`TypeMismatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeMismatchFix {
+    /// `expr` -> `&expr` or `&mut expr`
+    AddReference { mutable: bool },
+    /// `expr` -> `expr.into()`
+    CallInto,
+    /// `expr` -> `Some(expr)`
+    WrapInSome,
+}
+
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub expected: String,
+    pub actual: String,
+    pub fix: Option<TypeMismatchFix>,
+}
+
+impl Diagnostic for TypeMismatch {
+    fn name(&self) -> &'static str {
+        "type-mismatch"
+    }
+    fn message(&self) -> String {
+        format!("Type mismatch: expected `{}`, found `{}`", self.expected, self.actual)
+    }
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+    fn is_experimental(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct IncorrectCase {
+    pub file: HirFileId,
+    pub ident: AstPtr<ast::Name>,
+    pub expected_case: CaseType,
+    pub ident_type: IdentType,
+    pub ident_text: String,
+    pub suggested_text: String,
+}
+
+impl Diagnostic for IncorrectCase {
+    fn name(&self) -> &'static str {
+        "incorrect-ident-case"
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "{} `{}` should have {} name, e.g. `{}`",
+            self.ident_type, self.ident_text, self.expected_case, self.suggested_text
+        )
+    }
+
+    fn display_source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.ident.clone().into())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use base_db::{fixture::WithFixture, FileId, SourceDatabase, SourceDatabaseExt};
@@ -242,7 +326,10 @@ mod tests {
     use rustc_hash::FxHashMap;
     use syntax::{TextRange, TextSize};
 
-    use crate::{diagnostics::validate_body, test_db::TestDB};
+    use crate::{
+        diagnostics::{validate_body, validate_module_item},
+        test_db::TestDB,
+    };
 
     impl TestDB {
         fn diagnostics<F: FnMut(&dyn Diagnostic)>(&self, mut cb: F) {
@@ -251,8 +338,10 @@ mod tests {
                 let crate_def_map = self.crate_def_map(krate);
 
                 let mut fns = Vec::new();
+                let mut decls = Vec::new();
                 for (module_id, _) in crate_def_map.modules.iter() {
                     for decl in crate_def_map[module_id].scope.declarations() {
+                        decls.push(decl);
                         if let ModuleDefId::FunctionId(f) = decl {
                             fns.push(f)
                         }
@@ -261,13 +350,23 @@ mod tests {
                     for impl_id in crate_def_map[module_id].scope.impls() {
                         let impl_data = self.impl_data(impl_id);
                         for item in impl_data.items.iter() {
-                            if let AssocItemId::FunctionId(f) = item {
-                                fns.push(*f)
+                            match item {
+                                AssocItemId::FunctionId(f) => {
+                                    decls.push((*f).into());
+                                    fns.push(*f)
+                                }
+                                AssocItemId::ConstId(c) => decls.push((*c).into()),
+                                AssocItemId::TypeAliasId(_) => (),
                             }
                         }
                     }
                 }
 
+                for decl in decls {
+                    let mut sink = DiagnosticSinkBuilder::new().build(&mut cb);
+                    validate_module_item(self, decl, &mut sink);
+                }
+
                 for f in fns {
                     let mut sink = DiagnosticSinkBuilder::new().build(&mut cb);
                     validate_body(self, f.into(), &mut sink);
@@ -457,6 +556,33 @@ fn baz(s: S) -> i32 {
         )
     }
 
+    #[test]
+    fn type_mismatch() {
+        check_diagnostics(
+            r#"
+fn foo() -> u32 {
+    let x: u32 = "hello";
+               //^^^^^^^ Type mismatch: expected `u32`, found `&str`
+    if x == 0 { return 1; }
+    true
+  //^^^^ Type mismatch: expected `u32`, found `bool`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_type_mismatch_with_unknown_types() {
+        check_diagnostics(
+            r#"
+fn foo() -> u32 {
+    let x: u32 = Unresolved::new();
+    unresolved_fn()
+}
+"#,
+        );
+    }
+
     #[test]
     fn break_outside_of_loop() {
         check_diagnostics(
@@ -0,0 +1,683 @@
+//! Provides validators for the naming conventions of item declarations.
+//!
+//! This includes the following items:
+//!
+//! - functions and methods, including those declared in traits (e.g. `fn foo()`)
+//! - function arguments and local bindings (e.g. `let x = foo();`)
+//! - structs, enums and their variants (e.g. `enum Foo { Variant }`)
+//! - struct fields (e.g. `struct Foo { field: u8 }`)
+//! - constants and statics (e.g. `const FOO: u8 = 10;`)
+//!
+//! The checks are equivalent to `rustc`'s `non_snake_case`,
+//! `non_camel_case_types` and `non_upper_case_globals` lints, and respect
+//! `#[allow(...)]` attributes on the item and its enclosing modules.
+
+mod case_conv;
+
+use std::fmt;
+
+use hir_def::{
+    adt::VariantData,
+    attr::Attrs,
+    expr::Pat,
+    nameres::ModuleSource,
+    src::{HasChildSource, HasSource},
+    AdtId, AssocContainerId, AssocItemId, AttrDefId, ConstId, EnumId, FunctionId, HasModule,
+    Lookup, ModuleDefId, ModuleId, StaticId, StructId, TraitId,
+};
+use hir_expand::{diagnostics::DiagnosticSink, HirFileId};
+use syntax::{
+    ast::{self, NameOwner},
+    AstNode, AstPtr, SyntaxKind, SyntaxNode,
+};
+
+use crate::{
+    db::HirDatabase,
+    diagnostics::{decl_check::case_conv::*, IncorrectCase},
+};
+
+mod allow {
+    pub(super) const NON_SNAKE_CASE: &str = "non_snake_case";
+    pub(super) const NON_UPPER_CASE_GLOBAL: &str = "non_upper_case_globals";
+    pub(super) const NON_CAMEL_CASE_TYPES: &str = "non_camel_case_types";
+    pub(super) const NONSTANDARD_STYLE: &str = "nonstandard_style";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseType {
+    // `some_var`
+    LowerSnakeCase,
+    // `SOME_CONST`
+    UpperSnakeCase,
+    // `SomeStruct`
+    UpperCamelCase,
+}
+
+impl fmt::Display for CaseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            CaseType::LowerSnakeCase => "snake_case",
+            CaseType::UpperSnakeCase => "UPPER_SNAKE_CASE",
+            CaseType::UpperCamelCase => "CamelCase",
+        };
+
+        write!(f, "{}", repr)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentType {
+    Argument,
+    Constant,
+    Enum,
+    Field,
+    Function,
+    StaticVariable,
+    Structure,
+    Variable,
+    Variant,
+}
+
+impl fmt::Display for IdentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            IdentType::Argument => "Argument",
+            IdentType::Constant => "Constant",
+            IdentType::Enum => "Enum",
+            IdentType::Field => "Field",
+            IdentType::Function => "Function",
+            IdentType::StaticVariable => "Static variable",
+            IdentType::Structure => "Structure",
+            IdentType::Variable => "Variable",
+            IdentType::Variant => "Variant",
+        };
+
+        write!(f, "{}", repr)
+    }
+}
+
+pub(super) struct DeclValidator<'a, 'b: 'a> {
+    owner: ModuleDefId,
+    sink: &'a mut DiagnosticSink<'b>,
+}
+
+impl<'a, 'b> DeclValidator<'a, 'b> {
+    pub(super) fn new(
+        owner: ModuleDefId,
+        sink: &'a mut DiagnosticSink<'b>,
+    ) -> DeclValidator<'a, 'b> {
+        DeclValidator { owner, sink }
+    }
+
+    pub(super) fn validate_item(&mut self, db: &dyn HirDatabase) {
+        match self.owner {
+            ModuleDefId::FunctionId(func) => self.validate_func(db, func),
+            ModuleDefId::AdtId(AdtId::StructId(strukt)) => self.validate_struct(db, strukt),
+            ModuleDefId::AdtId(AdtId::EnumId(enum_id)) => self.validate_enum(db, enum_id),
+            ModuleDefId::ConstId(konst) => self.validate_const(db, konst),
+            ModuleDefId::StaticId(statik) => self.validate_static(db, statik),
+            ModuleDefId::TraitId(trait_id) => self.validate_trait(db, trait_id),
+            _ => (),
+        }
+    }
+
+    fn validate_func(&mut self, db: &dyn HirDatabase, func: FunctionId) {
+        let loc = func.lookup(db.upcast());
+        if is_allowed(db, func.into(), loc.container.module(db.upcast()), allow::NON_SNAKE_CASE) {
+            return;
+        }
+
+        // The names of trait impl methods are dictated by the trait, so only
+        // their bodies are checked.
+        let is_trait_impl = match loc.container {
+            AssocContainerId::ImplId(impl_id) => db.impl_data(impl_id).target_trait.is_some(),
+            _ => false,
+        };
+        let data = db.function_data(func);
+        if let (false, Some(replacement)) =
+            (is_trait_impl, to_lower_snake_case(&data.name.to_string()))
+        {
+            let fn_src = loc.source(db.upcast());
+            // Functions from `extern` blocks are named after the foreign symbol.
+            let is_foreign = is_in_extern_block(fn_src.value.syntax());
+            if !is_foreign {
+                self.push(
+                    fn_src.file_id,
+                    fn_src.value.name(),
+                    IdentType::Function,
+                    CaseType::LowerSnakeCase,
+                    data.name.to_string(),
+                    replacement,
+                );
+            }
+        }
+
+        let (body, source_map) = db.body_with_source_map(func.into());
+        for (pat_id, pat) in body.pats.iter() {
+            let name = match pat {
+                Pat::Bind { name, .. } => name,
+                _ => continue,
+            };
+            let replacement = match to_lower_snake_case(&name.to_string()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let source_ptr = match source_map.pat_syntax(pat_id) {
+                Ok(it) => it,
+                Err(_) => continue,
+            };
+            // `self` parameters can't have a wrong case.
+            let pat_ptr = match source_ptr.value.left() {
+                Some(it) => it,
+                None => continue,
+            };
+            let root = match db.parse_or_expand(source_ptr.file_id) {
+                Some(it) => it,
+                None => continue,
+            };
+            let ident_pat = match pat_ptr.to_node(&root) {
+                ast::Pat::IdentPat(it) => it,
+                _ => continue,
+            };
+            let ident_type = if body.params.contains(&pat_id) {
+                IdentType::Argument
+            } else {
+                IdentType::Variable
+            };
+            self.push(
+                source_ptr.file_id,
+                ident_pat.name(),
+                ident_type,
+                CaseType::LowerSnakeCase,
+                name.to_string(),
+                replacement,
+            );
+        }
+    }
+
+    /// Checks the methods and constants declared in a trait, which trait impls
+    /// then have to use the names of.
+    fn validate_trait(&mut self, db: &dyn HirDatabase, trait_id: TraitId) {
+        let data = db.trait_data(trait_id);
+        let module = trait_id.lookup(db.upcast()).container.module(db.upcast());
+        let allow_non_snake_case = is_allowed(db, trait_id.into(), module, allow::NON_SNAKE_CASE);
+        let allow_non_upper_case =
+            is_allowed(db, trait_id.into(), module, allow::NON_UPPER_CASE_GLOBAL);
+        for (_, item) in data.items.iter() {
+            match *item {
+                AssocItemId::FunctionId(func) if !allow_non_snake_case => {
+                    self.validate_func(db, func)
+                }
+                AssocItemId::ConstId(konst) if !allow_non_upper_case => {
+                    self.validate_const(db, konst)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn validate_struct(&mut self, db: &dyn HirDatabase, struct_id: StructId) {
+        let data = db.struct_data(struct_id);
+        let module = AdtId::from(struct_id).module(db.upcast());
+
+        if !is_allowed(db, struct_id.into(), module, allow::NON_CAMEL_CASE_TYPES) {
+            if let Some(replacement) = to_camel_case(&data.name.to_string()) {
+                let struct_src = struct_id.lookup(db.upcast()).source(db.upcast());
+                self.push(
+                    struct_src.file_id,
+                    struct_src.value.name(),
+                    IdentType::Structure,
+                    CaseType::UpperCamelCase,
+                    data.name.to_string(),
+                    replacement,
+                );
+            }
+        }
+
+        if is_allowed(db, struct_id.into(), module, allow::NON_SNAKE_CASE) {
+            return;
+        }
+        let fields = match &*data.variant_data {
+            VariantData::Record(fields) => fields,
+            _ => return,
+        };
+        let variant_id = struct_id.into();
+        let mut fields_src = None;
+        for (field_id, field) in fields.iter() {
+            let replacement = match to_lower_snake_case(&field.name.to_string()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let attr_owner =
+                AttrDefId::FieldId(hir_def::FieldId { parent: variant_id, local_id: field_id });
+            if has_allow_attr(&db.attrs(attr_owner), allow::NON_SNAKE_CASE) {
+                continue;
+            }
+            let fields_src = fields_src.get_or_insert_with(|| variant_id.child_source(db.upcast()));
+            let name = match fields_src.value[field_id].as_ref().right() {
+                Some(record_field) => record_field.name(),
+                None => continue,
+            };
+            self.push(
+                fields_src.file_id,
+                name,
+                IdentType::Field,
+                CaseType::LowerSnakeCase,
+                field.name.to_string(),
+                replacement,
+            );
+        }
+    }
+
+    fn validate_enum(&mut self, db: &dyn HirDatabase, enum_id: EnumId) {
+        let data = db.enum_data(enum_id);
+        let module = AdtId::from(enum_id).module(db.upcast());
+        if is_allowed(db, enum_id.into(), module, allow::NON_CAMEL_CASE_TYPES) {
+            return;
+        }
+
+        if let Some(replacement) = to_camel_case(&data.name.to_string()) {
+            let enum_src = enum_id.lookup(db.upcast()).source(db.upcast());
+            self.push(
+                enum_src.file_id,
+                enum_src.value.name(),
+                IdentType::Enum,
+                CaseType::UpperCamelCase,
+                data.name.to_string(),
+                replacement,
+            );
+        }
+
+        let mut variants_src = None;
+        for (variant_id, variant) in data.variants.iter() {
+            let replacement = match to_camel_case(&variant.name.to_string()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let attr_owner = AttrDefId::EnumVariantId(hir_def::EnumVariantId {
+                parent: enum_id,
+                local_id: variant_id,
+            });
+            if has_allow_attr(&db.attrs(attr_owner), allow::NON_CAMEL_CASE_TYPES) {
+                continue;
+            }
+            let variants_src =
+                variants_src.get_or_insert_with(|| enum_id.child_source(db.upcast()));
+            self.push(
+                variants_src.file_id,
+                variants_src.value[variant_id].name(),
+                IdentType::Variant,
+                CaseType::UpperCamelCase,
+                variant.name.to_string(),
+                replacement,
+            );
+        }
+    }
+
+    fn validate_const(&mut self, db: &dyn HirDatabase, const_id: ConstId) {
+        let data = db.const_data(const_id);
+        let loc = const_id.lookup(db.upcast());
+        if is_allowed(
+            db,
+            const_id.into(),
+            loc.container.module(db.upcast()),
+            allow::NON_UPPER_CASE_GLOBAL,
+        ) {
+            return;
+        }
+
+        // Like methods, the names of trait impl constants are dictated by the
+        // trait.
+        if let AssocContainerId::ImplId(impl_id) = loc.container {
+            if db.impl_data(impl_id).target_trait.is_some() {
+                return;
+            }
+        }
+
+        // Unnamed constants (`const _: () = ();`) are fine.
+        let name = match &data.name {
+            Some(it) => it,
+            None => return,
+        };
+        if let Some(replacement) = to_upper_snake_case(&name.to_string()) {
+            let const_src = loc.source(db.upcast());
+            self.push(
+                const_src.file_id,
+                const_src.value.name(),
+                IdentType::Constant,
+                CaseType::UpperSnakeCase,
+                name.to_string(),
+                replacement,
+            );
+        }
+    }
+
+    fn validate_static(&mut self, db: &dyn HirDatabase, static_id: StaticId) {
+        let data = db.static_data(static_id);
+        let loc = static_id.lookup(db.upcast());
+        if is_allowed(db, static_id.into(), loc.module(db.upcast()), allow::NON_UPPER_CASE_GLOBAL) {
+            return;
+        }
+
+        let name = match &data.name {
+            Some(it) => it,
+            None => return,
+        };
+        if let Some(replacement) = to_upper_snake_case(&name.to_string()) {
+            let static_src = loc.source(db.upcast());
+            // Statics from `extern` blocks are named after the foreign symbol.
+            let is_foreign = is_in_extern_block(static_src.value.syntax());
+            if is_foreign {
+                return;
+            }
+            self.push(
+                static_src.file_id,
+                static_src.value.name(),
+                IdentType::StaticVariable,
+                CaseType::UpperSnakeCase,
+                name.to_string(),
+                replacement,
+            );
+        }
+    }
+
+    fn push(
+        &mut self,
+        file: HirFileId,
+        name: Option<ast::Name>,
+        ident_type: IdentType,
+        expected_case: CaseType,
+        ident_text: String,
+        suggested_text: String,
+    ) {
+        let name = match name {
+            Some(it) => it,
+            // Nothing to rename if the declaration is malformed.
+            None => return,
+        };
+        self.sink.push(IncorrectCase {
+            file,
+            ident: AstPtr::new(&name),
+            expected_case,
+            ident_type,
+            ident_text,
+            suggested_text,
+        });
+    }
+}
+
+/// Checks whether `lint` (or the `nonstandard_style` group) is allowed for
+/// `owner` itself or any of the modules containing it.
+fn is_allowed(db: &dyn HirDatabase, owner: AttrDefId, module: ModuleId, lint: &str) -> bool {
+    if has_allow_attr(&db.attrs(owner), lint) {
+        return true;
+    }
+
+    let def_map = db.crate_def_map(module.krate);
+    let mut local_id = Some(module.local_id);
+    while let Some(id) = local_id {
+        let module = ModuleId { krate: module.krate, local_id: id };
+        if has_allow_attr(&db.attrs(module.into()), lint) {
+            return true;
+        }
+        // `db.attrs` only sees the outer attributes of inline modules, so
+        // check the inner ones (`mod m { #![allow(...)] }`) separately.
+        let src = def_map[id].definition_source(db.upcast());
+        if let ModuleSource::Module(it) = &src.value {
+            if let Some(item_list) = it.item_list() {
+                let item_list = src.with_value(&item_list as &dyn ast::AttrsOwner);
+                if has_allow_attr(&Attrs::from_attrs_owner(db.upcast(), item_list), lint) {
+                    return true;
+                }
+            }
+        }
+        local_id = def_map[id].parent;
+    }
+    false
+}
+
+fn is_in_extern_block(node: &SyntaxNode) -> bool {
+    matches!(node.parent().map(|it| it.kind()), Some(SyntaxKind::EXTERN_ITEM_LIST))
+}
+
+fn has_allow_attr(attrs: &Attrs, lint: &str) -> bool {
+    attrs.by_key("allow").tt_values().any(|tt| {
+        tt.to_string()
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|it| it == lint || it == allow::NONSTANDARD_STYLE)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::tests::check_diagnostics;
+
+    #[test]
+    fn incorrect_function_name() {
+        check_diagnostics(
+            r#"
+fn NonSnakeCaseName() {}
+// ^^^^^^^^^^^^^^^^ Function `NonSnakeCaseName` should have snake_case name, e.g. `non_snake_case_name`
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_function_params() {
+        check_diagnostics(
+            r#"
+fn foo(SomeParam: u8) {}
+    // ^^^^^^^^^ Argument `SomeParam` should have snake_case name, e.g. `some_param`
+
+fn foo2(ok_param: &str, CAPS_PARAM: u8) {}
+                     // ^^^^^^^^^^ Argument `CAPS_PARAM` should have snake_case name, e.g. `caps_param`
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_variable_names() {
+        check_diagnostics(
+            r#"
+fn foo() {
+    let SOME_VALUE = 10;
+     // ^^^^^^^^^^ Variable `SOME_VALUE` should have snake_case name, e.g. `some_value`
+    let AnotherValue = 20;
+     // ^^^^^^^^^^^^ Variable `AnotherValue` should have snake_case name, e.g. `another_value`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_struct_names() {
+        check_diagnostics(
+            r#"
+struct non_camel_case_name {}
+    // ^^^^^^^^^^^^^^^^^^^ Structure `non_camel_case_name` should have CamelCase name, e.g. `NonCamelCaseName`
+
+struct SCREAMING_CASE {}
+    // ^^^^^^^^^^^^^^ Structure `SCREAMING_CASE` should have CamelCase name, e.g. `ScreamingCase`
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_camel_cased_acronyms_in_struct_name() {
+        check_diagnostics(
+            r#"
+struct AABB {}
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_struct_field() {
+        check_diagnostics(
+            r#"
+struct SomeStruct { SomeField: u8 }
+                 // ^^^^^^^^^ Field `SomeField` should have snake_case name, e.g. `some_field`
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_enum_names() {
+        check_diagnostics(
+            r#"
+enum some_enum { Val(u8) }
+  // ^^^^^^^^^ Enum `some_enum` should have CamelCase name, e.g. `SomeEnum`
+
+enum SOME_ENUM {}
+  // ^^^^^^^^^ Enum `SOME_ENUM` should have CamelCase name, e.g. `SomeEnum`
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_enum_variant_name() {
+        check_diagnostics(
+            r#"
+enum SomeEnum { SOME_VARIANT(u8) }
+             // ^^^^^^^^^^^^ Variant `SOME_VARIANT` should have CamelCase name, e.g. `SomeVariant`
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_const_name() {
+        check_diagnostics(
+            r#"
+const some_weird_const: u8 = 10;
+   // ^^^^^^^^^^^^^^^^ Constant `some_weird_const` should have UPPER_SNAKE_CASE name, e.g. `SOME_WEIRD_CONST`
+"#,
+        );
+    }
+
+    #[test]
+    fn incorrect_static_name() {
+        check_diagnostics(
+            r#"
+static some_weird_const: u8 = 10;
+    // ^^^^^^^^^^^^^^^^ Static variable `some_weird_const` should have UPPER_SNAKE_CASE name, e.g. `SOME_WEIRD_CONST`
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_inside_impl_struct() {
+        check_diagnostics(
+            r#"
+struct someStruct;
+    // ^^^^^^^^^^ Structure `someStruct` should have CamelCase name, e.g. `SomeStruct`
+
+impl someStruct {
+    fn SomeFunc(&self) {
+    // ^^^^^^^^ Function `SomeFunc` should have snake_case name, e.g. `some_func`
+        let WHY_VAR_IS_CAPS = 10;
+         // ^^^^^^^^^^^^^^^ Variable `WHY_VAR_IS_CAPS` should have snake_case name, e.g. `why_var_is_caps`
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn impl_consts() {
+        check_diagnostics(
+            r#"
+trait Trait { const lowerCase: u8; }
+                 // ^^^^^^^^^ Constant `lowerCase` should have UPPER_SNAKE_CASE name, e.g. `LOWER_CASE`
+struct S;
+impl S {
+    const foo: u32 = 0;
+       // ^^^ Constant `foo` should have UPPER_SNAKE_CASE name, e.g. `FOO`
+}
+impl Trait for S {
+    const lowerCase: u8 = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_for_trait_impl_methods_and_extern_items() {
+        check_diagnostics(
+            r#"
+trait Trait { fn camelCase(&self); }
+              // ^^^^^^^^^ Function `camelCase` should have snake_case name, e.g. `camel_case`
+struct S;
+impl Trait for S {
+    fn camelCase(&self) {}
+}
+
+extern "C" {
+    fn CamelCaseFn();
+    static lowercase_static: u8;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_items() {
+        check_diagnostics(
+            r#"
+trait Trait {
+    const lowerCase: u8;
+       // ^^^^^^^^^ Constant `lowerCase` should have UPPER_SNAKE_CASE name, e.g. `LOWER_CASE`
+    fn DefaultMethod(&self) {
+    // ^^^^^^^^^^^^^ Function `DefaultMethod` should have snake_case name, e.g. `default_method`
+        let CamelVar = 0;
+         // ^^^^^^^^ Variable `CamelVar` should have snake_case name, e.g. `camel_var`
+    }
+}
+
+#[allow(non_snake_case)]
+trait Allowed {
+    fn CamelCase(&self);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn allow_attributes() {
+        check_diagnostics(
+            r#"
+#[allow(non_snake_case)]
+fn NonSnakeCaseName(SOME_VAR: u8) -> u8 {
+    let OtherVar = SOME_VAR + 1;
+    OtherVar
+}
+
+#[allow(non_camel_case_types)]
+struct lowercase_struct;
+
+#[allow(nonstandard_style)]
+const lowercase_const: u8 = 0;
+
+struct S {
+    #[allow(non_snake_case)]
+    CamelField: u8,
+}
+
+mod m {
+    #![allow(non_upper_case_globals)]
+    static lowercase_static: u8 = 0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn crate_level_allow_attribute() {
+        check_diagnostics(
+            r#"
+#![allow(non_snake_case)]
+
+fn NonSnakeCaseName() {}
+"#,
+        );
+    }
+}
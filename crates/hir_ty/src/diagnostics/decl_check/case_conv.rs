@@ -0,0 +1,195 @@
+//! Functions for string case manipulation, such as detecting the identifier case,
+//! and converting it into appropriate form.
+//!
+//! The rules mirror the ones used by `rustc`'s `nonstandard_style` lints.
+
+/// Converts an identifier to an UpperCamelCase form.
+/// Returns `None` if the string is already in UpperCamelCase.
+pub(crate) fn to_camel_case(ident: &str) -> Option<String> {
+    if is_camel_case(ident) {
+        return None;
+    }
+
+    let mut output = String::new();
+    let mut prev_component_end: Option<char> = None;
+    for component in ident.trim_matches('_').split('_').filter(|it| !it.is_empty()) {
+        let mut camel_cased = String::new();
+        let mut new_word = true;
+        let mut prev_is_lower_case = true;
+        for c in component.chars() {
+            // Preserve the case if an uppercase letter follows a lowercase
+            // letter, so that `camelCase` is converted to `CamelCase`.
+            if prev_is_lower_case && c.is_uppercase() {
+                new_word = true;
+            }
+            if new_word {
+                camel_cased.extend(c.to_uppercase());
+            } else {
+                camel_cased.extend(c.to_lowercase());
+            }
+            prev_is_lower_case = c.is_lowercase();
+            new_word = false;
+        }
+
+        // Separate two components with an underscore if their boundary
+        // cannot be distinguished using the letter case.
+        if let (Some(last), Some(first)) = (prev_component_end, camel_cased.chars().next()) {
+            if !char_has_case(last) && !char_has_case(first) {
+                output.push('_');
+            }
+        }
+        prev_component_end = camel_cased.chars().last();
+        output.push_str(&camel_cased);
+    }
+
+    Some(output)
+}
+
+/// Converts an identifier to a lower_snake_case form.
+/// Returns `None` if the string is already in lower_snake_case.
+pub(crate) fn to_lower_snake_case(ident: &str) -> Option<String> {
+    if is_lower_snake_case(ident) {
+        return None;
+    }
+
+    Some(to_snake_case(ident))
+}
+
+/// Converts an identifier to an UPPER_SNAKE_CASE form.
+/// Returns `None` if the string is already in UPPER_SNAKE_CASE.
+pub(crate) fn to_upper_snake_case(ident: &str) -> Option<String> {
+    if is_upper_snake_case(ident) {
+        return None;
+    }
+
+    Some(to_snake_case(ident).to_uppercase())
+}
+
+fn is_camel_case(name: &str) -> bool {
+    let name = name.trim_matches('_');
+    let first = match name.chars().next() {
+        Some(it) => it,
+        None => return true,
+    };
+    // Start with a non-lowercase letter rather than a non-uppercase one, as
+    // some scripts don't have a concept of upper/lowercase.
+    if first.is_lowercase() || name.contains("__") {
+        return false;
+    }
+    // A letter with a case must not be followed or preceded by an underscore.
+    let chars = name.chars().collect::<Vec<_>>();
+    !chars.windows(2).any(|pair| match *pair {
+        [fst, '_'] => char_has_case(fst),
+        ['_', snd] => char_has_case(snd),
+        _ => false,
+    })
+}
+
+fn is_lower_snake_case(ident: &str) -> bool {
+    is_snake_case(ident, char::is_uppercase)
+}
+
+fn is_upper_snake_case(ident: &str) -> bool {
+    is_snake_case(ident, char::is_lowercase)
+}
+
+fn is_snake_case<F: Fn(char) -> bool>(ident: &str, wrong_case: F) -> bool {
+    let ident = ident.trim_matches('_');
+    let mut prev_is_underscore = false;
+    for c in ident.chars() {
+        if c == '_' {
+            if prev_is_underscore {
+                return false;
+            }
+            prev_is_underscore = true;
+        } else {
+            // It would be more obvious to check for the right case, but some
+            // characters do not have a lowercase or uppercase form.
+            if wrong_case(c) {
+                return false;
+            }
+            prev_is_underscore = false;
+        }
+    }
+    true
+}
+
+fn to_snake_case(ident: &str) -> String {
+    let mut words = Vec::new();
+
+    // Preserve leading underscores.
+    let trimmed = ident.trim_start_matches('_');
+    for _ in 0..ident.len() - trimmed.len() {
+        words.push(String::new());
+    }
+
+    for part in trimmed.split('_').filter(|it| !it.is_empty()) {
+        let mut last_upper = false;
+        let mut buf = String::new();
+        for c in part.chars() {
+            if !buf.is_empty() && c.is_uppercase() && !last_upper {
+                words.push(std::mem::take(&mut buf));
+            }
+            last_upper = c.is_uppercase();
+            buf.extend(c.to_lowercase());
+        }
+        words.push(buf);
+    }
+
+    words.join("_")
+}
+
+fn char_has_case(c: char) -> bool {
+    c.is_lowercase() || c.is_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<F: Fn(&str) -> Option<String>>(fun: F, input: &str, expected: &str) {
+        // `None` is translated to empty string, meaning that there is nothing to fix.
+        let output = fun(input).unwrap_or_default();
+
+        assert_eq!(output, expected, "input: {:?}", input);
+    }
+
+    #[test]
+    fn test_to_lower_snake_case() {
+        check(to_lower_snake_case, "lower_snake_case", "");
+        check(to_lower_snake_case, "UPPER_SNAKE_CASE", "upper_snake_case");
+        check(to_lower_snake_case, "Weird_Case", "weird_case");
+        check(to_lower_snake_case, "CamelCase", "camel_case");
+        check(to_lower_snake_case, "lowerCamelCase", "lower_camel_case");
+        check(to_lower_snake_case, "_PrivateCamel", "_private_camel");
+        check(to_lower_snake_case, "a", "");
+        check(to_lower_snake_case, "__", "");
+        check(to_lower_snake_case, "x86_64", "");
+    }
+
+    #[test]
+    fn test_to_camel_case() {
+        check(to_camel_case, "CamelCase", "");
+        check(to_camel_case, "CamelCase_", "");
+        check(to_camel_case, "_CamelCase", "");
+        check(to_camel_case, "lowerCamelCase", "LowerCamelCase");
+        check(to_camel_case, "lower_snake_case", "LowerSnakeCase");
+        check(to_camel_case, "UPPER_SNAKE_CASE", "UpperSnakeCase");
+        check(to_camel_case, "Weird_Case", "WeirdCase");
+        check(to_camel_case, "name", "Name");
+        check(to_camel_case, "A", "");
+        check(to_camel_case, "AABB", "");
+        check(to_camel_case, "X86_64", "");
+        check(to_camel_case, "x86__64", "X86_64");
+    }
+
+    #[test]
+    fn test_to_upper_snake_case() {
+        check(to_upper_snake_case, "UPPER_SNAKE_CASE", "");
+        check(to_upper_snake_case, "lower_snake_case", "LOWER_SNAKE_CASE");
+        check(to_upper_snake_case, "Weird_Case", "WEIRD_CASE");
+        check(to_upper_snake_case, "CamelCase", "CAMEL_CASE");
+        check(to_upper_snake_case, "A", "");
+        check(to_upper_snake_case, "X86_64", "");
+    }
+}
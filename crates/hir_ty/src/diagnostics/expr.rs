@@ -2,7 +2,9 @@
 
 use std::sync::Arc;
 
-use hir_def::{path::path, resolver::HasResolver, AdtId, DefWithBodyId};
+use hir_def::{
+    path::path, resolver::HasResolver, type_ref::Mutability, AdtId, DefWithBodyId, HasModule,
+};
 use hir_expand::diagnostics::DiagnosticSink;
 use rustc_hash::FxHashSet;
 use syntax::{ast, AstPtr};
@@ -12,9 +14,13 @@ use crate::{
     diagnostics::{
         match_check::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
         MismatchedArgCount, MissingFields, MissingMatchArms, MissingOkInTailExpr, MissingPatFields,
+        TypeMismatch, TypeMismatchFix,
     },
+    display::HirDisplay,
+    infer::TypeMismatch as InferTypeMismatch,
     utils::variant_data,
-    ApplicationTy, InferenceResult, Ty, TypeCtor,
+    ApplicationTy, Canonical, InEnvironment, InferenceResult, Obligation, Substs, TraitEnvironment,
+    TraitRef, Ty, TypeCtor, TypeWalk,
 };
 
 pub use hir_def::{
@@ -83,9 +89,11 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
         let body_expr = &body[body.body_expr];
+        let mut missing_ok_reported = false;
         if let Expr::Block { tail: Some(t), .. } = body_expr {
-            self.validate_results_in_tail_expr(body.body_expr, *t, db);
+            missing_ok_reported = self.validate_results_in_tail_expr(body.body_expr, *t, db);
         }
+        self.validate_type_mismatches(db, &body, missing_ok_reported);
     }
 
     fn create_record_literal_missing_fields_diagnostic(
@@ -292,11 +300,17 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         }
     }
 
-    fn validate_results_in_tail_expr(&mut self, body_id: ExprId, id: ExprId, db: &dyn HirDatabase) {
+    /// Returns `true` if a `MissingOkInTailExpr` diagnostic was emitted.
+    fn validate_results_in_tail_expr(
+        &mut self,
+        body_id: ExprId,
+        id: ExprId,
+        db: &dyn HirDatabase,
+    ) -> bool {
         // the mismatch will be on the whole block currently
         let mismatch = match self.infer.type_mismatch_for_expr(body_id) {
             Some(m) => m,
-            None => return,
+            None => return false,
         };
 
         let core_result_path = path![core::result::Result];
@@ -304,7 +318,7 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         let resolver = self.owner.resolver(db.upcast());
         let core_result_enum = match resolver.resolve_known_enum(db.upcast(), &core_result_path) {
             Some(it) => it,
-            _ => return,
+            _ => return false,
         };
 
         let core_result_ctor = TypeCtor::Adt(AdtId::EnumId(core_result_enum));
@@ -312,7 +326,7 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             Ty::Apply(ApplicationTy { ctor, parameters }) if ctor == &core_result_ctor => {
                 parameters
             }
-            _ => return,
+            _ => return false,
         };
 
         if params.len() == 2 && params[0] == mismatch.actual {
@@ -321,9 +335,120 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             if let Ok(source_ptr) = source_map.expr_syntax(id) {
                 self.sink
                     .push(MissingOkInTailExpr { file: source_ptr.file_id, expr: source_ptr.value });
+                return true;
+            }
+        }
+        false
+    }
+
+    fn validate_type_mismatches(
+        &mut self,
+        db: &dyn HirDatabase,
+        body: &Body,
+        missing_ok_reported: bool,
+    ) {
+        let mut source_map = None;
+        let mut reported = FxHashSet::default();
+        if missing_ok_reported {
+            reported.insert(tail_expr(body, body.body_expr));
+        }
+        for (id, mismatch) in self.infer.type_mismatches.iter() {
+            // Unknown types are most likely caused by unimplemented inference
+            // rather than by an actual error in the code.
+            if contains_unknown(&mismatch.expected) || contains_unknown(&mismatch.actual) {
+                continue;
+            }
+
+            // The mismatch of a block is caused by its tail expression, so
+            // point at that instead of the whole block.
+            let expr = tail_expr(body, id);
+            if let Expr::Block { tail: None, .. } = &body[expr] {
+                // FIXME: this should point at the end of the block (or at the
+                // trailing semicolon) rather than at the whole block.
+                continue;
+            }
+            // Both a block and its tail may have a mismatch recorded.
+            if !reported.insert(expr) {
+                continue;
+            }
+
+            let (_, source_map) =
+                source_map.get_or_insert_with(|| db.body_with_source_map(self.owner));
+            if let Ok(source_ptr) = source_map.expr_syntax(expr) {
+                self.sink.push(TypeMismatch {
+                    file: source_ptr.file_id,
+                    expr: source_ptr.value,
+                    expected: mismatch.expected.display(db).to_string(),
+                    actual: mismatch.actual.display(db).to_string(),
+                    fix: self.type_mismatch_fix(db, mismatch),
+                });
             }
         }
     }
+
+    fn type_mismatch_fix(
+        &self,
+        db: &dyn HirDatabase,
+        mismatch: &InferTypeMismatch,
+    ) -> Option<TypeMismatchFix> {
+        let InferTypeMismatch { expected, actual } = mismatch;
+        if let Some((inner, mutability)) = expected.as_reference() {
+            if inner == actual {
+                let mutable = mutability == Mutability::Mut;
+                return Some(TypeMismatchFix::AddReference { mutable });
+            }
+        }
+
+        let resolver = self.owner.resolver(db.upcast());
+        if let Some(option_enum) =
+            resolver.resolve_known_enum(db.upcast(), &path![core::option::Option])
+        {
+            if let Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(adt), parameters }) = expected {
+                if *adt == AdtId::EnumId(option_enum) && parameters.as_single() == actual {
+                    return Some(TypeMismatchFix::WrapInSome);
+                }
+            }
+        }
+
+        let into_trait = resolver.resolve_known_trait(db.upcast(), &path![core::convert::Into])?;
+        let trait_ref = TraitRef {
+            trait_: into_trait,
+            substs: Substs::build_for_def(db, into_trait)
+                .push(actual.clone())
+                .push(expected.clone())
+                .build(),
+        };
+        let goal = Canonical {
+            value: InEnvironment::new(
+                TraitEnvironment::lower(db, &resolver),
+                Obligation::Trait(trait_ref),
+            ),
+            kinds: Arc::new([]),
+        };
+        let krate = self.owner.module(db.upcast()).krate;
+        if db.trait_solve(krate, goal).is_some() {
+            return Some(TypeMismatchFix::CallInto);
+        }
+        None
+    }
+}
+
+/// Returns the innermost tail expression of `expr` if it is a block.
+fn tail_expr(body: &Body, mut expr: ExprId) -> ExprId {
+    while let Expr::Block { tail: Some(tail), .. } = &body[expr] {
+        expr = *tail;
+    }
+    expr
+}
+
+fn contains_unknown(ty: &Ty) -> bool {
+    let mut res = false;
+    ty.walk(&mut |ty| {
+        if let Ty::Unknown | Ty::Infer(_) = ty {
+            res = true;
+        }
+    });
+    res
 }
 
 pub fn record_literal_missing_fields(
@@ -1077,6 +1077,7 @@ fn main() {
     match (true, false) {
         (true, false, true) => (),
         (true) => (),
+       //^^^^ Type mismatch: expected `(bool, bool)`, found `bool`
     }
     match (0) { () => () }
     match Unresolved::Bar { Unresolved::Baz => () }
@@ -1341,6 +1342,7 @@ fn panic(a: Category, b: Category) {
             r#"
 enum Option<T> { Some(T), None }
 
+#[allow(non_snake_case)]
 fn main() {
     // `Never` is deliberately not defined so that it's an uninferred type.
     match Option::<Never>::None {
@@ -3,8 +3,8 @@
 use std::fmt;
 
 use crate::{
-    db::HirDatabase, utils::generics, ApplicationTy, CallableDefId, FnSig, GenericPredicate,
-    Obligation, OpaqueTyId, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
+    db::HirDatabase, utils::generics, ApplicationTy, CallableDefId, ConstScalar, FnSig,
+    GenericPredicate, Obligation, OpaqueTyId, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
 };
 use hir_def::{
     find_path, generics::TypeParamProvenance, item_scope::ItemInNs, AdtId, AssocContainerId,
@@ -216,9 +216,22 @@ impl HirDisplay for ApplicationTy {
                 write!(f, "[{}]", t.display(f.db))?;
             }
             TypeCtor::Array => {
-                let t = self.parameters.as_single();
-                write!(f, "[{}; _]", t.display(f.db))?;
+                let t = &self.parameters[0];
+                write!(f, "[{}; ", t.display(f.db))?;
+                match &self.parameters[1] {
+                    len @ Ty::Apply(ApplicationTy { ctor: TypeCtor::Const(_), .. }) => {
+                        len.hir_fmt(f)?
+                    }
+                    _ => write!(f, "_")?,
+                }
+                write!(f, "]")?;
+            }
+            TypeCtor::Const(ConstScalar::Usize(value)) => write!(f, "{}", value)?,
+            TypeCtor::Const(ConstScalar::Param(id)) => {
+                let params = f.db.generic_params(id.parent);
+                write!(f, "{}", params.consts[id.local_id].name)?;
             }
+            TypeCtor::ClosureKind(kind) => write!(f, "{:?}", kind)?,
             TypeCtor::RawPtr(m) => {
                 let t = self.parameters.as_single();
                 write!(f, "*{}{}", m.as_keyword_for_ptr(), t.display(f.db))?;
@@ -278,7 +291,7 @@ impl HirDisplay for ApplicationTy {
                 };
                 if self.parameters.len() > 0 {
                     let generics = generics(f.db.upcast(), def.into());
-                    let (parent_params, self_param, type_params, _impl_trait_params) =
+                    let (parent_params, self_param, type_params, _impl_trait_params, _consts) =
                         generics.provenance_split();
                     let total_len = parent_params + self_param + type_params;
                     // We print all params except implicit impl Trait params. Still a bit weird; should we leave out parent and self?
@@ -46,6 +46,8 @@ use crate::{
 
 pub(crate) use unify::unify;
 
+pub use closure::{CaptureKind, CapturedItem, CapturedPlace, ClosureCaptures, ClosureKind};
+
 macro_rules! ty_app {
     ($ctor:pat, $param:pat) => {
         crate::Ty::Apply(crate::ApplicationTy { ctor: $ctor, parameters: $param })
@@ -60,6 +62,7 @@ mod path;
 mod expr;
 mod pat;
 mod coerce;
+mod closure;
 
 /// The entry point of type inference.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
@@ -134,6 +137,9 @@ pub struct InferenceResult {
     pub type_of_expr: ArenaMap<ExprId, Ty>,
     pub type_of_pat: ArenaMap<PatId, Ty>,
     pub(super) type_mismatches: ArenaMap<ExprId, TypeMismatch>,
+    /// For each closure expr, records what it captures and which `Fn*` traits
+    /// it implements.
+    closure_captures: FxHashMap<ExprId, ClosureCaptures>,
 }
 
 impl InferenceResult {
@@ -164,6 +170,9 @@ impl InferenceResult {
     pub fn type_mismatch_for_expr(&self, expr: ExprId) -> Option<&TypeMismatch> {
         self.type_mismatches.get(expr)
     }
+    pub fn closure_captures(&self, expr: ExprId) -> Option<&ClosureCaptures> {
+        self.closure_captures.get(&expr)
+    }
     pub fn add_diagnostics(
         &self,
         db: &dyn HirDatabase,
@@ -257,6 +266,19 @@ impl<'a> InferenceContext<'a> {
             let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
             *ty = resolved;
         }
+        for mismatch in result.type_mismatches.values_mut() {
+            mismatch.expected =
+                self.table.resolve_ty_completely(mem::replace(&mut mismatch.expected, Ty::Unknown));
+            mismatch.actual =
+                self.table.resolve_ty_completely(mem::replace(&mut mismatch.actual, Ty::Unknown));
+        }
+        for captures in result.closure_captures.values_mut() {
+            for capture in captures.captures.iter_mut() {
+                let resolved =
+                    self.table.resolve_ty_completely(mem::replace(&mut capture.ty, Ty::Unknown));
+                capture.ty = resolved;
+            }
+        }
         result
     }
 
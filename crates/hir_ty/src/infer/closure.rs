@@ -0,0 +1,446 @@
+//! Capture analysis for closures: computes which places a closure captures,
+//! how it captures them, and which of the `Fn*` traits it implements.
+//!
+//! This is a simplified version of rustc's upvar analysis
+//! (librustc_typeck/check/upvar.rs). It runs right after the body of a closure
+//! has been inferred, so that the types of the captured places are (mostly)
+//! known.
+
+use hir_def::{
+    expr::{BinaryOp, BindingAnnotation, Expr, ExprId, Pat, PatId, Statement, UnaryOp},
+    lang_item::LangItemTarget,
+    resolver::{resolver_for_expr, ValueNs},
+    type_ref::{Mutability, TypeRef},
+};
+use hir_expand::name::Name;
+use rustc_hash::FxHashSet;
+
+use super::{InferenceContext, Obligation};
+use crate::{traits::FnTrait, ApplicationTy, InEnvironment, Substs, TraitRef, Ty, TypeCtor};
+
+/// The most general of the `Fn*` traits a closure implements. Every closure
+/// implementing `Fn` also implements `FnMut`, and every closure implements
+/// `FnOnce`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClosureKind {
+    Fn,
+    FnMut,
+    FnOnce,
+}
+
+impl ClosureKind {
+    /// The kind of a closure is stored in its substitutions, as the second
+    /// parameter of `TypeCtor::Closure`. This allows it to be an inference
+    /// variable until the body of the closure has been analyzed.
+    pub(crate) fn to_ty(self) -> Ty {
+        Ty::simple(TypeCtor::ClosureKind(self))
+    }
+
+    pub(crate) fn from_ty(ty: &Ty) -> Option<ClosureKind> {
+        match ty {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::ClosureKind(kind), .. }) => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+/// How a place is captured by a closure. The order of the variants matters:
+/// a capture which is used in several ways needs the largest of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CaptureKind {
+    SharedRef,
+    MutableRef,
+    ByValue,
+}
+
+/// A local variable of the closure's owner, together with the (possibly
+/// empty) sequence of fields which is accessed on it, like `a.b.0`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CapturedPlace {
+    pub local: PatId,
+    pub projections: Vec<Name>,
+}
+
+impl CapturedPlace {
+    fn is_ancestor_or_equal(&self, other: &CapturedPlace) -> bool {
+        self.local == other.local && other.projections.starts_with(&self.projections)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedItem {
+    pub place: CapturedPlace,
+    pub kind: CaptureKind,
+    /// The type of the captured place (not of the capture, which can be a
+    /// reference to it).
+    pub ty: Ty,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosureCaptures {
+    pub kind: ClosureKind,
+    pub captures: Vec<CapturedItem>,
+}
+
+impl<'a> InferenceContext<'a> {
+    pub(super) fn analyze_closure(&mut self, closure: ExprId) -> ClosureCaptures {
+        let (args, body, is_move) = match &self.body[closure] {
+            Expr::Lambda { args, body, is_move, .. } => (args.clone(), *body, *is_move),
+            _ => unreachable!("not a closure"),
+        };
+
+        let mut locals = FxHashSet::default();
+        for &arg in &args {
+            self.collect_bindings(arg, &mut locals);
+        }
+        self.collect_bindings_in_expr(body, &mut locals);
+
+        let mut usages = Vec::new();
+        self.walk_expr(body, CaptureKind::ByValue, &locals, &mut usages);
+
+        let kind = match usages.iter().map(|it| it.kind).max() {
+            Some(CaptureKind::ByValue) => ClosureKind::FnOnce,
+            Some(CaptureKind::MutableRef) => ClosureKind::FnMut,
+            Some(CaptureKind::SharedRef) | None => ClosureKind::Fn,
+        };
+
+        // A place is captured only once, together with all the places derived
+        // from it, which are accessible through it anyway.
+        let mut captures: Vec<CapturedItem> = Vec::new();
+        for mut usage in usages {
+            if let Some(existing) =
+                captures.iter_mut().find(|it| it.place.is_ancestor_or_equal(&usage.place))
+            {
+                existing.kind = existing.kind.max(usage.kind);
+                continue;
+            }
+            captures.retain(|it| {
+                if usage.place.is_ancestor_or_equal(&it.place) {
+                    usage.kind = usage.kind.max(it.kind);
+                    false
+                } else {
+                    true
+                }
+            });
+            captures.push(usage);
+        }
+        if is_move {
+            captures.iter_mut().for_each(|it| it.kind = CaptureKind::ByValue);
+        }
+
+        ClosureCaptures { kind, captures }
+    }
+
+    /// Walks `expr`, whose value is used as described by `usage`, and records
+    /// all uses of places which are not local to the closure.
+    fn walk_expr(
+        &mut self,
+        expr: ExprId,
+        usage: CaptureKind,
+        locals: &FxHashSet<PatId>,
+        acc: &mut Vec<CapturedItem>,
+    ) {
+        if let Some((place, place_expr, through_deref)) = self.place_of_expr(expr) {
+            if !locals.contains(&place.local) {
+                self.record_usage(place, place_expr, through_deref, usage, acc);
+                return;
+            }
+        }
+
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Ref { expr, mutability, .. } => {
+                self.walk_expr(*expr, ref_usage(*mutability), locals, acc);
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                let usage = if usage == CaptureKind::MutableRef {
+                    CaptureKind::MutableRef
+                } else {
+                    CaptureKind::SharedRef
+                };
+                self.walk_expr(*expr, usage, locals, acc);
+            }
+            Expr::Field { expr, .. } => self.walk_expr(*expr, usage, locals, acc),
+            Expr::Index { base, index } => {
+                let usage = if usage == CaptureKind::MutableRef {
+                    CaptureKind::MutableRef
+                } else {
+                    CaptureKind::SharedRef
+                };
+                self.walk_expr(*base, usage, locals, acc);
+                self.walk_expr(*index, CaptureKind::ByValue, locals, acc);
+            }
+            Expr::BinaryOp { lhs, rhs, op } => {
+                let (lhs_usage, rhs_usage) = match op {
+                    Some(BinaryOp::Assignment { .. }) => {
+                        (CaptureKind::MutableRef, CaptureKind::ByValue)
+                    }
+                    Some(BinaryOp::CmpOp(_)) => (CaptureKind::SharedRef, CaptureKind::SharedRef),
+                    Some(BinaryOp::ArithOp(_)) | Some(BinaryOp::LogicOp(_)) | None => {
+                        (CaptureKind::ByValue, CaptureKind::ByValue)
+                    }
+                };
+                self.walk_expr(*lhs, lhs_usage, locals, acc);
+                self.walk_expr(*rhs, rhs_usage, locals, acc);
+            }
+            Expr::Call { callee, args } => {
+                let callee_usage = call_usage(self.callee_kind(*callee, args));
+                self.walk_expr(*callee, callee_usage, locals, acc);
+                for &arg in args {
+                    self.walk_expr(arg, CaptureKind::ByValue, locals, acc);
+                }
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                let receiver_usage = match self.result.method_resolution(expr) {
+                    Some(func) => match self.db.function_data(func).params.first() {
+                        Some(TypeRef::Reference(_, mutability)) => ref_usage(*mutability),
+                        _ => CaptureKind::ByValue,
+                    },
+                    None => CaptureKind::SharedRef,
+                };
+                self.walk_expr(*receiver, receiver_usage, locals, acc);
+                for &arg in args {
+                    self.walk_expr(arg, CaptureKind::ByValue, locals, acc);
+                }
+            }
+            Expr::Match { expr: scrutinee, arms } => {
+                let binds_by_value = arms.iter().any(|arm| self.binds_by_value(arm.pat));
+                self.walk_expr(*scrutinee, pattern_usage(binds_by_value), locals, acc);
+                for arm in arms {
+                    if let Some(guard) = arm.guard {
+                        self.walk_expr(guard, CaptureKind::ByValue, locals, acc);
+                    }
+                    self.walk_expr(arm.expr, CaptureKind::ByValue, locals, acc);
+                }
+            }
+            Expr::Block { statements, tail, .. } => {
+                for stmt in statements {
+                    match stmt {
+                        Statement::Let { pat, initializer, .. } => {
+                            if let Some(initializer) = initializer {
+                                let usage = pattern_usage(self.binds_by_value(*pat));
+                                self.walk_expr(*initializer, usage, locals, acc);
+                            }
+                        }
+                        Statement::Expr(expr) => {
+                            self.walk_expr(*expr, CaptureKind::ByValue, locals, acc)
+                        }
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.walk_expr(*tail, CaptureKind::ByValue, locals, acc);
+                }
+            }
+            Expr::Lambda { .. } => {
+                // The captures of a nested closure have already been computed,
+                // and the outer closure needs to capture them as well.
+                let captures = match self.result.closure_captures.get(&expr) {
+                    Some(it) => it.captures.clone(),
+                    None => return,
+                };
+                for capture in captures {
+                    if !locals.contains(&capture.place.local) {
+                        let usage =
+                            if capture.kind == CaptureKind::ByValue && self.is_copy(&capture.ty) {
+                                CaptureKind::SharedRef
+                            } else {
+                                capture.kind
+                            };
+                        acc.push(CapturedItem { kind: usage, ..capture });
+                    }
+                }
+            }
+            e => e.walk_child_exprs(|it| self.walk_expr(it, CaptureKind::ByValue, locals, acc)),
+        }
+    }
+
+    fn record_usage(
+        &mut self,
+        place: CapturedPlace,
+        place_expr: ExprId,
+        through_deref: bool,
+        usage: CaptureKind,
+        acc: &mut Vec<CapturedItem>,
+    ) {
+        let ty = self.resolve_ty_as_possible(self.result[place_expr].clone());
+        let kind = match usage {
+            // Nothing can be moved out of a reference, so this is a copy.
+            CaptureKind::ByValue if through_deref => CaptureKind::SharedRef,
+            CaptureKind::ByValue if self.is_copy(&ty) => CaptureKind::SharedRef,
+            it => it,
+        };
+        acc.push(CapturedItem { place, kind, ty });
+    }
+
+    /// If `expr` is a place expression based on a local variable, returns the
+    /// place, the expression of the place and whether the place expression
+    /// goes through a dereference, in which case the place is truncated before
+    /// the dereference.
+    fn place_of_expr(&mut self, expr: ExprId) -> Option<(CapturedPlace, ExprId, bool)> {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Path(path) => {
+                let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr);
+                match resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path())? {
+                    ValueNs::LocalBinding(pat) => {
+                        Some((CapturedPlace { local: pat, projections: Vec::new() }, expr, false))
+                    }
+                    _ => None,
+                }
+            }
+            Expr::Field { expr: base, name } => {
+                let (mut place, place_expr, through_deref) = self.place_of_expr(*base)?;
+                if through_deref {
+                    return Some((place, place_expr, true));
+                }
+                let base_ty = self.resolve_ty_as_possible(self.result[*base].clone());
+                match base_ty {
+                    Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(_), .. })
+                    | Ty::Apply(ApplicationTy { ctor: TypeCtor::Tuple { .. }, .. }) => {
+                        place.projections.push(name.clone());
+                        Some((place, expr, false))
+                    }
+                    // Autoderef, e.g. through a reference or a `Box`.
+                    _ => Some((place, place_expr, true)),
+                }
+            }
+            Expr::UnaryOp { expr: base, op: UnaryOp::Deref } => {
+                let (place, place_expr, _) = self.place_of_expr(*base)?;
+                Some((place, place_expr, true))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_copy(&mut self, ty: &Ty) -> bool {
+        let krate = match self.resolver.krate() {
+            Some(it) => it,
+            None => return true,
+        };
+        let copy_trait = match self.db.lang_item(krate, "copy".into()) {
+            Some(LangItemTarget::TraitId(trait_)) => trait_,
+            // Without a `Copy` trait, don't pretend that anything is moved.
+            _ => return true,
+        };
+        let ty = self.resolve_ty_as_possible(ty.clone());
+        if let Ty::Unknown = ty {
+            return true;
+        }
+        self.implements(TraitRef { trait_: copy_trait, substs: Substs::single(ty) })
+    }
+
+    /// Returns the most general of the `Fn*` traits through which `callee` can
+    /// be called with `args`.
+    fn callee_kind(&mut self, callee: ExprId, args: &[ExprId]) -> ClosureKind {
+        let krate = match self.resolver.krate() {
+            Some(it) => it,
+            None => return ClosureKind::FnOnce,
+        };
+        let callee_ty = self.resolve_ty_as_possible(self.result[callee].clone());
+        let arg_tys: Vec<Ty> =
+            args.iter().map(|&arg| self.resolve_ty_as_possible(self.result[arg].clone())).collect();
+        let args_ty =
+            Ty::apply(TypeCtor::Tuple { cardinality: args.len() as u16 }, Substs(arg_tys.into()));
+        for &(fn_trait, kind) in
+            &[(FnTrait::Fn, ClosureKind::Fn), (FnTrait::FnMut, ClosureKind::FnMut)]
+        {
+            let trait_ = match fn_trait.get_id(self.db, krate) {
+                Some(it) => it,
+                None => continue,
+            };
+            let substs = Substs(vec![callee_ty.clone(), args_ty.clone()].into());
+            if self.implements(TraitRef { trait_, substs }) {
+                return kind;
+            }
+        }
+        ClosureKind::FnOnce
+    }
+
+    fn implements(&mut self, trait_ref: TraitRef) -> bool {
+        let krate = match self.resolver.krate() {
+            Some(it) => it,
+            None => return true,
+        };
+        let goal = InEnvironment::new(self.trait_env.clone(), Obligation::Trait(trait_ref));
+        let canonicalized = self.canonicalizer().canonicalize_obligation(goal);
+        // An ambiguous answer most likely comes from an unresolved type, don't
+        // pretend that anything is moved in that case either.
+        self.db.trait_solve(krate, canonicalized.value).is_some()
+    }
+
+    fn binds_by_value(&self, pat: PatId) -> bool {
+        let mut res = false;
+        self.walk_pat(pat, &mut |pat| {
+            if let Pat::Bind { mode: BindingAnnotation::Unannotated, .. }
+            | Pat::Bind { mode: BindingAnnotation::Mutable, .. } = pat
+            {
+                res = true;
+            }
+        });
+        res
+    }
+
+    fn collect_bindings(&self, pat: PatId, acc: &mut FxHashSet<PatId>) {
+        let body = self.body.clone();
+        let mut stack = vec![pat];
+        while let Some(pat) = stack.pop() {
+            acc.insert(pat);
+            body[pat].walk_child_pats(|it| stack.push(it));
+        }
+    }
+
+    fn collect_bindings_in_expr(&self, expr: ExprId, acc: &mut FxHashSet<PatId>) {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Block { statements, .. } => {
+                for stmt in statements {
+                    if let Statement::Let { pat, .. } = stmt {
+                        self.collect_bindings(*pat, acc);
+                    }
+                }
+            }
+            Expr::Match { arms, .. } => {
+                for arm in arms {
+                    self.collect_bindings(arm.pat, acc);
+                }
+            }
+            Expr::For { pat, .. } => self.collect_bindings(*pat, acc),
+            Expr::Lambda { args, .. } => {
+                for &arg in args {
+                    self.collect_bindings(arg, acc);
+                }
+            }
+            _ => {}
+        }
+        body[expr].walk_child_exprs(|it| self.collect_bindings_in_expr(it, acc));
+    }
+
+    fn walk_pat(&self, pat: PatId, f: &mut impl FnMut(&Pat)) {
+        let body = self.body.clone();
+        f(&body[pat]);
+        body[pat].walk_child_pats(|it| self.walk_pat(it, f));
+    }
+}
+
+fn ref_usage(mutability: Mutability) -> CaptureKind {
+    match mutability {
+        Mutability::Shared => CaptureKind::SharedRef,
+        Mutability::Mut => CaptureKind::MutableRef,
+    }
+}
+
+fn call_usage(kind: ClosureKind) -> CaptureKind {
+    match kind {
+        ClosureKind::Fn => CaptureKind::SharedRef,
+        ClosureKind::FnMut => CaptureKind::MutableRef,
+        ClosureKind::FnOnce => CaptureKind::ByValue,
+    }
+}
+
+fn pattern_usage(binds_by_value: bool) -> CaptureKind {
+    if binds_by_value {
+        CaptureKind::ByValue
+    } else {
+        CaptureKind::SharedRef
+    }
+}
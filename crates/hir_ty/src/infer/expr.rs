@@ -14,7 +14,9 @@ use hir_expand::name::{name, Name};
 use syntax::ast::RangeOp;
 
 use crate::{
-    autoderef, method_resolution, op,
+    autoderef,
+    consteval::{repeat_len, usize_const},
+    method_resolution, op,
     traits::{FnTrait, InEnvironment},
     utils::{generics, variant_data, Generics},
     ApplicationTy, Binders, CallableDefId, InferTy, IntTy, Mutability, Obligation, Rawness, Substs,
@@ -198,7 +200,7 @@ impl<'a> InferenceContext<'a> {
                 self.diverges = Diverges::Maybe;
                 Ty::unit()
             }
-            Expr::Lambda { body, args, ret_type, arg_types } => {
+            Expr::Lambda { body, args, ret_type, arg_types, .. } => {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
@@ -223,8 +225,13 @@ impl<'a> InferenceContext<'a> {
                     TypeCtor::FnPtr { num_args: sig_tys.len() as u16 - 1, is_varargs: false },
                     Substs(sig_tys.clone().into()),
                 );
-                let closure_ty =
-                    Ty::apply_one(TypeCtor::Closure { def: self.owner, expr: tgt_expr }, sig_ty);
+                // The closure kind is only known after the body has been
+                // analyzed, see `analyze_closure`.
+                let kind_ty = self.table.new_type_var();
+                let closure_ty = Ty::apply(
+                    TypeCtor::Closure { def: self.owner, expr: tgt_expr },
+                    Substs(vec![sig_ty, kind_ty.clone()].into()),
+                );
 
                 // Eagerly try to relate the closure type with the expected
                 // type, otherwise we often won't have enough information to
@@ -245,6 +252,10 @@ impl<'a> InferenceContext<'a> {
                 self.diverges = prev_diverges;
                 self.return_ty = prev_ret_ty;
 
+                let captures = self.analyze_closure(tgt_expr);
+                self.unify(&kind_ty, &captures.kind.to_ty());
+                self.result.closure_captures.insert(tgt_expr, captures);
+
                 closure_ty
             }
             Expr::Call { callee, args } => {
@@ -617,17 +628,16 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Array(array) => {
                 let elem_ty = match &expected.ty {
-                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => {
-                        st.as_single().clone()
-                    }
+                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => st[0].clone(),
                     _ => self.table.new_type_var(),
                 };
 
-                match array {
+                let len = match array {
                     Array::ElementList(items) => {
                         for expr in items.iter() {
                             self.infer_expr_coerce(*expr, &Expectation::has_type(elem_ty.clone()));
                         }
+                        usize_const(Some(items.len() as u64))
                     }
                     Array::Repeat { initializer, repeat } => {
                         self.infer_expr_coerce(
@@ -638,19 +648,22 @@ impl<'a> InferenceContext<'a> {
                             *repeat,
                             &Expectation::has_type(Ty::simple(TypeCtor::Int(IntTy::usize()))),
                         );
+                        repeat_len(self.db, self.owner, &self.body, *repeat)
                     }
-                }
+                };
 
-                Ty::apply_one(TypeCtor::Array, elem_ty)
+                Ty::apply(TypeCtor::Array, Substs(vec![elem_ty, len].into()))
             }
             Expr::Literal(lit) => match lit {
                 Literal::Bool(..) => Ty::simple(TypeCtor::Bool),
                 Literal::String(..) => {
                     Ty::apply_one(TypeCtor::Ref(Mutability::Shared), Ty::simple(TypeCtor::Str))
                 }
-                Literal::ByteString(..) => {
+                Literal::ByteString(bytes) => {
                     let byte_type = Ty::simple(TypeCtor::Int(IntTy::u8()));
-                    let array_type = Ty::apply_one(TypeCtor::Array, byte_type);
+                    let len = usize_const(Some(bytes.len() as u64));
+                    let array_type =
+                        Ty::apply(TypeCtor::Array, Substs(vec![byte_type, len].into()));
                     Ty::apply_one(TypeCtor::Ref(Mutability::Shared), array_type)
                 }
                 Literal::Char(..) => Ty::simple(TypeCtor::Char),
@@ -807,10 +820,10 @@ impl<'a> InferenceContext<'a> {
         generic_args: Option<&GenericArgs>,
         receiver_ty: &Ty,
     ) -> Substs {
-        let (parent_params, self_params, type_params, impl_trait_params) =
-            def_generics.as_ref().map_or((0, 0, 0, 0), |g| g.provenance_split());
+        let (parent_params, self_params, type_params, impl_trait_params, const_params) =
+            def_generics.as_ref().map_or((0, 0, 0, 0, 0), |g| g.provenance_split());
         assert_eq!(self_params, 0); // method shouldn't have another Self param
-        let total_len = parent_params + type_params + impl_trait_params;
+        let total_len = parent_params + type_params + impl_trait_params + const_params;
         let mut substs = Vec::with_capacity(total_len);
         // Parent arguments are unknown, except for the receiver type
         if let Some(parent_generics) = def_generics.as_ref().map(|p| p.iter_parent()) {
@@ -821,6 +834,8 @@ impl<'a> InferenceContext<'a> {
                     substs.push(Ty::Unknown);
                 }
             }
+            // the parent's const parameters come after its type parameters
+            substs.resize(parent_params, Ty::Unknown);
         }
         // handle provided type arguments
         if let Some(generic_args) = generic_args {
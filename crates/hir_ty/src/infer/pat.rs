@@ -13,7 +13,11 @@ use hir_expand::name::Name;
 use test_utils::mark;
 
 use super::{BindingMode, Expectation, InferenceContext};
-use crate::{utils::variant_data, Substs, Ty, TypeCtor};
+use crate::{
+    consteval::{usize_const, ConstScalar},
+    utils::variant_data,
+    Substs, Ty, TypeCtor,
+};
 
 impl<'a> InferenceContext<'a> {
     fn infer_tuple_struct_pat(
@@ -185,19 +189,32 @@ impl<'a> InferenceContext<'a> {
                 return inner_ty;
             }
             Pat::Slice { prefix, slice, suffix } => {
-                let (container_ty, elem_ty) = match &expected {
-                    ty_app!(TypeCtor::Array, st) => (TypeCtor::Array, st.as_single().clone()),
-                    ty_app!(TypeCtor::Slice, st) => (TypeCtor::Slice, st.as_single().clone()),
-                    _ => (TypeCtor::Slice, Ty::Unknown),
+                let (elem_ty, array_len) = match &expected {
+                    ty_app!(TypeCtor::Array, st) => (st[0].clone(), Some(st[1].clone())),
+                    ty_app!(TypeCtor::Slice, st) => (st.as_single().clone(), None),
+                    _ => (Ty::Unknown, None),
                 };
 
                 for pat_id in prefix.iter().chain(suffix) {
                     self.infer_pat(*pat_id, &elem_ty, default_bm);
                 }
 
-                let pat_ty = Ty::apply_one(container_ty, elem_ty);
+                let array_of_len =
+                    |len| Ty::apply(TypeCtor::Array, Substs(vec![elem_ty.clone(), len].into()));
+                let pat_ty = match &array_len {
+                    Some(len) => array_of_len(len.clone()),
+                    None => Ty::apply_one(TypeCtor::Slice, elem_ty.clone()),
+                };
                 if let Some(slice_pat_id) = slice {
-                    self.infer_pat(*slice_pat_id, &pat_ty, default_bm);
+                    let rest_ty = match &array_len {
+                        Some(ty_app!(TypeCtor::Const(ConstScalar::Usize(len)))) => {
+                            let matched = (prefix.len() + suffix.len()) as u64;
+                            array_of_len(usize_const(len.checked_sub(matched)))
+                        }
+                        Some(_) => array_of_len(Ty::Unknown),
+                        None => pat_ty.clone(),
+                    };
+                    self.infer_pat(*slice_pat_id, &rest_ty, default_bm);
                 }
 
                 pat_ty
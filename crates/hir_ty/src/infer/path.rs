@@ -64,6 +64,7 @@ impl<'a> InferenceContext<'a> {
                 let ty = self.resolve_ty_as_possible(ty);
                 return Some(ty);
             }
+            ValueNs::GenericParam(it) => return Some(self.db.const_param_ty(it)),
             ValueNs::FunctionId(it) => it.into(),
             ValueNs::ConstId(it) => it.into(),
             ValueNs::StaticId(it) => it.into(),
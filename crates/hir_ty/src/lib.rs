@@ -12,6 +12,7 @@ pub mod traits;
 pub mod method_resolution;
 mod op;
 mod lower;
+mod consteval;
 pub(crate) mod infer;
 pub(crate) mod utils;
 
@@ -27,6 +28,7 @@ mod test_db;
 use std::{iter, mem, ops::Deref, sync::Arc};
 
 use base_db::{salsa, CrateId};
+use either::Either;
 use hir_def::{
     expr::ExprId,
     type_ref::{Mutability, Rawness},
@@ -43,7 +45,11 @@ use crate::{
 };
 
 pub use autoderef::autoderef;
-pub use infer::{InferTy, InferenceResult};
+pub use consteval::ConstScalar;
+pub use infer::{
+    CaptureKind, CapturedItem, CapturedPlace, ClosureCaptures, ClosureKind, InferTy,
+    InferenceResult,
+};
 pub use lower::CallableDefId;
 pub use lower::{
     associated_type_shorthand_candidates, callable_item_sig, ImplTraitLoweringMode, TyDefId,
@@ -80,7 +86,8 @@ pub enum TypeCtor {
     /// The pointee of an array slice.  Written as `[T]`.
     Slice,
 
-    /// An array with the given length. Written as `[T; n]`.
+    /// An array with the given length. Written as `[T; n]`. The length is the
+    /// second parameter, see `TypeCtor::Const`.
     Array,
 
     /// A raw pointer. Written as `*mut T` or `*const T`
@@ -136,8 +143,18 @@ pub enum TypeCtor {
     /// The type of a specific closure.
     ///
     /// The closure signature is stored in a `FnPtr` type in the first type
-    /// parameter.
+    /// parameter, and its kind in the second one, see `TypeCtor::ClosureKind`.
     Closure { def: DefWithBodyId, expr: ExprId },
+
+    /// The kind of a closure. Like `Const`, this isn't a real type: it only
+    /// appears as the second parameter of `Closure`, where it can be an
+    /// inference variable until the body of the closure has been analyzed.
+    ClosureKind(ClosureKind),
+
+    /// A constant used as a generic argument, like the length of an array.
+    /// This isn't a real type: it only ever appears in `Substs`, in the
+    /// positions of const parameters.
+    Const(ConstScalar),
 }
 
 impl TypeCtor {
@@ -148,13 +165,14 @@ impl TypeCtor {
             | TypeCtor::Int(_)
             | TypeCtor::Float(_)
             | TypeCtor::Str
-            | TypeCtor::Never => 0,
-            TypeCtor::Slice
-            | TypeCtor::Array
-            | TypeCtor::RawPtr(_)
-            | TypeCtor::Ref(_)
-            | TypeCtor::Closure { .. } // 1 param representing the signature of the closure
-            => 1,
+            | TypeCtor::Never
+            | TypeCtor::Const(_)
+            | TypeCtor::ClosureKind(_) => 0,
+            TypeCtor::Slice | TypeCtor::RawPtr(_) | TypeCtor::Ref(_) => 1,
+            // 2 params: the element type, and the length
+            TypeCtor::Array => 2,
+            // 2 params: the signature of the closure, and its `ClosureKind`
+            TypeCtor::Closure { .. } => 2,
             TypeCtor::Adt(adt) => {
                 let generic_params = generics(db.upcast(), adt.into());
                 generic_params.len()
@@ -167,14 +185,12 @@ impl TypeCtor {
                 let generic_params = generics(db.upcast(), type_alias.into());
                 generic_params.len()
             }
-            TypeCtor::OpaqueType(opaque_ty_id) => {
-                match opaque_ty_id {
-                    OpaqueTyId::ReturnTypeImplTrait(func, _) => {
-                        let generic_params = generics(db.upcast(), func.into());
-                        generic_params.len()
-                    }
+            TypeCtor::OpaqueType(opaque_ty_id) => match opaque_ty_id {
+                OpaqueTyId::ReturnTypeImplTrait(func, _) => {
+                    let generic_params = generics(db.upcast(), func.into());
+                    generic_params.len()
                 }
-            }
+            },
             TypeCtor::FnPtr { num_args, is_varargs: _ } => num_args as usize + 1,
             TypeCtor::Tuple { cardinality } => cardinality as usize,
         }
@@ -193,7 +209,9 @@ impl TypeCtor {
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
-            | TypeCtor::Tuple { .. } => None,
+            | TypeCtor::Tuple { .. }
+            | TypeCtor::Const(_)
+            | TypeCtor::ClosureKind(_) => None,
             // Closure's krate is irrelevant for coherence I would think?
             TypeCtor::Closure { .. } => None,
             TypeCtor::Adt(adt) => Some(adt.module(db.upcast()).krate),
@@ -223,7 +241,9 @@ impl TypeCtor {
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
             | TypeCtor::Tuple { .. }
-            | TypeCtor::Closure { .. } => None,
+            | TypeCtor::Closure { .. }
+            | TypeCtor::Const(_)
+            | TypeCtor::ClosureKind(_) => None,
             TypeCtor::Adt(adt) => Some(adt.into()),
             TypeCtor::FnDef(callable) => Some(callable.into()),
             TypeCtor::AssociatedType(type_alias) => Some(type_alias.into()),
@@ -387,7 +407,15 @@ impl Substs {
 
     /// Return Substs that replace each parameter by itself (i.e. `Ty::Param`).
     pub(crate) fn type_params_for_generics(generic_params: &Generics) -> Substs {
-        Substs(generic_params.iter().map(|(id, _)| Ty::Placeholder(id)).collect())
+        Substs(
+            generic_params
+                .iter_id()
+                .map(|id| match id {
+                    Either::Left(id) => Ty::Placeholder(id),
+                    Either::Right(id) => ConstScalar::Param(id).to_ty(),
+                })
+                .collect(),
+        )
     }
 
     /// Return Substs that replace each parameter by itself (i.e. `Ty::Param`).
@@ -399,11 +427,7 @@ impl Substs {
     /// Return Substs that replace each parameter by a bound variable.
     pub(crate) fn bound_vars(generic_params: &Generics, debruijn: DebruijnIndex) -> Substs {
         Substs(
-            generic_params
-                .iter()
-                .enumerate()
-                .map(|(idx, _)| Ty::Bound(BoundVar::new(debruijn, idx)))
-                .collect(),
+            (0..generic_params.len()).map(|idx| Ty::Bound(BoundVar::new(debruijn, idx))).collect(),
         )
     }
 
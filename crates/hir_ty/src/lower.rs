@@ -9,16 +9,17 @@ use std::{iter, sync::Arc};
 
 use arena::map::ArenaMap;
 use base_db::CrateId;
+use either::Either;
 use hir_def::{
     adt::StructKind,
     builtin_type::BuiltinType,
     generics::{TypeParamProvenance, WherePredicate, WherePredicateTarget},
     path::{GenericArg, Path, PathSegment, PathSegments},
-    resolver::{HasResolver, Resolver, TypeNs},
-    type_ref::{TypeBound, TypeRef},
-    AdtId, AssocContainerId, AssocItemId, ConstId, EnumId, EnumVariantId, FunctionId, GenericDefId,
-    HasModule, ImplId, LocalFieldId, Lookup, StaticId, StructId, TraitId, TypeAliasId, TypeParamId,
-    UnionId, VariantId,
+    resolver::{HasResolver, Resolver, TypeNs, ValueNs},
+    type_ref::{ConstRef, TypeBound, TypeRef},
+    AdtId, AssocContainerId, AssocItemId, ConstId, ConstParamId, EnumId, EnumVariantId, FunctionId,
+    GenericDefId, HasModule, ImplId, LocalFieldId, Lookup, StaticId, StructId, TraitId,
+    TypeAliasId, TypeParamId, UnionId, VariantId,
 };
 use hir_expand::name::Name;
 use smallvec::SmallVec;
@@ -26,6 +27,7 @@ use stdx::impl_from;
 use test_utils::mark;
 
 use crate::{
+    consteval::{eval_const_ref, usize_const, ConstScalar},
     db::HirDatabase,
     primitive::{FloatTy, IntTy},
     utils::{
@@ -163,9 +165,10 @@ impl Ty {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, len) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
-                Ty::apply_one(TypeCtor::Array, inner_ty)
+                let len = const_from_hir(ctx, len);
+                Ty::apply(TypeCtor::Array, Substs(vec![inner_ty, len].into()))
             }
             TypeRef::Slice(inner) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
@@ -252,12 +255,12 @@ impl Ty {
                         let idx = ctx.impl_trait_counter.get();
                         // FIXME we're probably doing something wrong here
                         ctx.impl_trait_counter.set(idx + count_impl_traits(type_ref) as u16);
-                        let (parent_params, self_params, list_params, _impl_trait_params) =
+                        let (parent_params, self_params, list_params, _impl_trait_params, _) =
                             if let Some(def) = ctx.resolver.generic_def() {
                                 let generics = generics(ctx.db.upcast(), def);
                                 generics.provenance_split()
                             } else {
-                                (0, 0, 0, 0)
+                                (0, 0, 0, 0, 0)
                             };
                         Ty::Bound(BoundVar::new(
                             ctx.in_binders,
@@ -540,6 +543,29 @@ impl Ty {
     }
 }
 
+/// Lowers a constant in a type, like the length of an array, to its
+/// representation as a generic argument.
+fn const_from_hir(ctx: &TyLoweringContext<'_>, const_ref: &ConstRef) -> Ty {
+    if let ConstRef::Path(path) = const_ref {
+        let resolved =
+            ctx.resolver.resolve_path_in_value_ns_fully(ctx.db.upcast(), path.mod_path());
+        if let Some(ValueNs::GenericParam(param_id)) = resolved {
+            return match ctx.type_param_mode {
+                TypeParamLoweringMode::Placeholder => ConstScalar::Param(param_id).to_ty(),
+                TypeParamLoweringMode::Variable => {
+                    let generics = generics(
+                        ctx.db.upcast(),
+                        ctx.resolver.generic_def().expect("generics in scope"),
+                    );
+                    let idx = generics.const_param_idx(param_id).expect("matching generics");
+                    Ty::Bound(BoundVar::new(ctx.in_binders, idx))
+                }
+            };
+        }
+    }
+    usize_const(eval_const_ref(ctx.db, ctx.resolver, const_ref))
+}
+
 fn substs_from_path_segment(
     ctx: &TyLoweringContext<'_>,
     segment: PathSegment<'_>,
@@ -549,9 +575,9 @@ fn substs_from_path_segment(
     let mut substs = Vec::new();
     let def_generics = def_generic.map(|def| generics(ctx.db.upcast(), def));
 
-    let (parent_params, self_params, type_params, impl_trait_params) =
-        def_generics.map_or((0, 0, 0, 0), |g| g.provenance_split());
-    let total_len = parent_params + self_params + type_params + impl_trait_params;
+    let (parent_params, self_params, type_params, impl_trait_params, const_params) =
+        def_generics.map_or((0, 0, 0, 0, 0), |g| g.provenance_split());
+    let total_len = parent_params + self_params + type_params + impl_trait_params + const_params;
 
     substs.extend(iter::repeat(Ty::Unknown).take(parent_params));
 
@@ -948,6 +974,14 @@ pub(crate) fn generic_predicates_query(
         .collect()
 }
 
+/// Lowers the type of a const generic parameter, like `usize` in `const N: usize`.
+pub(crate) fn const_param_ty_query(db: &dyn HirDatabase, def: ConstParamId) -> Ty {
+    let resolver = def.parent.resolver(db.upcast());
+    let ctx = TyLoweringContext::new(db, &resolver);
+    let params = db.generic_params(def.parent);
+    Ty::from_hir(&ctx, &params.consts[def.local_id].ty)
+}
+
 /// Resolve the default type params from generics
 pub(crate) fn generic_defaults_query(
     db: &dyn HirDatabase,
@@ -959,10 +993,20 @@ pub(crate) fn generic_defaults_query(
     let generic_params = generics(db.upcast(), def);
 
     let defaults = generic_params
-        .iter()
+        .iter_id()
         .enumerate()
-        .map(|(idx, (_, p))| {
-            let mut ty = p.default.as_ref().map_or(Ty::Unknown, |t| Ty::from_hir(&ctx, t));
+        .map(|(idx, id)| {
+            let mut ty = match id {
+                Either::Left(id) => {
+                    let params = db.generic_params(id.parent);
+                    params.types[id.local_id]
+                        .default
+                        .as_ref()
+                        .map_or(Ty::Unknown, |t| Ty::from_hir(&ctx, t))
+                }
+                // FIXME: lower the defaults of const parameters
+                Either::Right(_) => Ty::Unknown,
+            };
 
             // Each default can only refer to previous parameters.
             ty.walk_mut_binders(
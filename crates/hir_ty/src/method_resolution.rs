@@ -762,7 +762,7 @@ fn autoderef_method_receiver(
         deref_chain.last().map(|ty| &ty.value)
     {
         let kinds = deref_chain.last().unwrap().kinds.clone();
-        let unsized_ty = Ty::apply(TypeCtor::Slice, parameters.clone());
+        let unsized_ty = Ty::apply_one(TypeCtor::Slice, parameters[0].clone());
         deref_chain.push(Canonical { value: unsized_ty, kinds })
     }
     deref_chain
@@ -0,0 +1,236 @@
+use base_db::fixture::WithFixture;
+use expect::{expect, Expect};
+use hir_def::{db::DefDatabase, expr::Pat, DefWithBodyId};
+use hir_expand::db::AstDatabase;
+use stdx::format_to;
+use syntax::AstNode;
+
+use super::{ellipsize, visit_module};
+use crate::{db::HirDatabase, display::HirDisplay, test_db::TestDB, CaptureKind};
+
+fn check_closure_captures(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let crate_def_map = db.crate_def_map(module.krate);
+
+    let mut defs: Vec<DefWithBodyId> = Vec::new();
+    visit_module(&db, &crate_def_map, module.local_id, &mut |it| defs.push(it));
+
+    let mut closures = Vec::new();
+    for def in defs {
+        let (body, source_map) = db.body_with_source_map(def);
+        let infer = db.infer(def);
+        for (expr, _) in body.exprs.iter() {
+            let captures = match infer.closure_captures(expr) {
+                Some(it) => it,
+                None => continue,
+            };
+            let node = match source_map.expr_syntax(expr) {
+                Ok(it) => it,
+                Err(_) => continue,
+            };
+            let root = db.parse_or_expand(node.file_id).unwrap();
+            let node = node.value.to_node(&root);
+            let text = node.syntax().text().to_string().replace("\n", " ");
+
+            let mut buf = format!(
+                "{:?} '{}': {:?}\n",
+                node.syntax().text_range(),
+                ellipsize(text, 15),
+                captures.kind
+            );
+            for capture in &captures.captures {
+                let mut place = match &body[capture.place.local] {
+                    Pat::Bind { name, .. } => name.to_string(),
+                    _ => "?".to_string(),
+                };
+                for field in &capture.place.projections {
+                    format_to!(place, ".{}", field);
+                }
+                let kind = match capture.kind {
+                    CaptureKind::SharedRef => "by shared ref",
+                    CaptureKind::MutableRef => "by mutable ref",
+                    CaptureKind::ByValue => "by value",
+                };
+                format_to!(buf, "    {} {}: {}\n", place, kind, capture.ty.display(&db));
+            }
+            closures.push((node.syntax().text_range().start(), buf));
+        }
+    }
+    closures.sort_by_key(|(offset, _)| *offset);
+    let actual = closures.into_iter().map(|(_, it)| it).collect::<String>();
+    expect.assert_eq(&actual);
+}
+
+const PRELUDE: &str = r#"
+#[lang = "copy"]
+trait Copy {}
+impl Copy for i32 {}
+impl Copy for usize {}
+impl<T: ?Sized> Copy for &T {}
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+
+struct String;
+impl String {
+    fn len(&self) -> usize { 0 }
+    fn push(&mut self, c: char) {}
+    fn into_bytes(self) {}
+}
+"#;
+
+fn check(ra_fixture: &str, expect: Expect) {
+    check_closure_captures(&format!("{}{}", ra_fixture, PRELUDE), expect)
+}
+
+#[test]
+fn captures_by_shared_ref() {
+    check(
+        r#"
+fn test() {
+    let s = String;
+    let n = 92;
+    let c = || s.len() + n;
+}
+"#,
+        expect![[r#"
+            60..74 '|| s.len() + n': Fn
+                s by shared ref: String
+                n by shared ref: usize
+        "#]],
+    );
+}
+
+#[test]
+fn captures_by_mutable_ref() {
+    check(
+        r#"
+fn test() {
+    let mut s = String;
+    let mut n = 0;
+    let c = || { s.push('a'); n += 1; };
+}
+"#,
+        expect![[r#"
+            67..94 '|| { s...= 1; }': FnMut
+                s by mutable ref: String
+                n by mutable ref: i32
+        "#]],
+    );
+}
+
+#[test]
+fn captures_by_value() {
+    check(
+        r#"
+fn test() {
+    let s = String;
+    let t = String;
+    let c = || { s.into_bytes(); let u = t; };
+}
+"#,
+        expect![[r#"
+            64..97 '|| { s...= t; }': FnOnce
+                s by value: String
+                t by value: String
+        "#]],
+    );
+}
+
+#[test]
+fn move_closure_captures_by_value() {
+    check(
+        r#"
+fn test() {
+    let s = String;
+    let c = move || s.len();
+}
+"#,
+        expect![[r#"
+            44..59 'move || s.len()': Fn
+                s by value: String
+        "#]],
+    );
+}
+
+#[test]
+fn captures_fields_separately() {
+    check(
+        r#"
+struct Pair { a: String, b: String }
+fn test(p: Pair, r: &mut Pair) {
+    let c = || { p.a.len(); p.b.into_bytes(); };
+    let d = || { r.a.push('a'); };
+}
+"#,
+        expect![[r#"
+            82..117 '|| { p...s(); }': FnOnce
+                p.a by shared ref: String
+                p.b by value: String
+            131..152 '|| { r...a'); }': FnMut
+                r by mutable ref: &mut Pair
+        "#]],
+    );
+}
+
+#[test]
+fn does_not_capture_locals_of_the_closure() {
+    check(
+        r#"
+fn test() {
+    let s = String;
+    let c = |x: String| { let y = x; match y { z => z.len() } + s.len() };
+}
+"#,
+        expect![[r#"
+            44..105 '|x: St...en() }': Fn
+                s by shared ref: String
+        "#]],
+    );
+}
+
+#[test]
+fn nested_closures() {
+    check(
+        r#"
+fn test() {
+    let s = String;
+    let c = || { let d = move || s.into_bytes(); };
+}
+"#,
+        expect![[r#"
+            44..82 '|| { l...s(); }': FnOnce
+                s by value: String
+            57..79 'move |...ytes()': FnOnce
+                s by value: String
+        "#]],
+    );
+}
+
+#[test]
+fn calls_capture_according_to_fn_trait() {
+    check(
+        r#"
+fn test<F: Fn(), G: FnMut(), H: FnOnce()>(f: F, mut g: G, h: H) {
+    let mut s = String;
+    let mut inc = || s.push('a');
+    let c = || { f(); g(); inc(); };
+    let d = || h();
+}
+"#,
+        expect![[r#"
+            108..122 '|| s.push('a')': FnMut
+                s by mutable ref: String
+            136..159 '|| { f...c(); }': FnMut
+                f by shared ref: F
+                g by mutable ref: G
+                inc by mutable ref: || -> ()
+            173..179 '|| h()': FnOnce
+                h by value: H
+        "#]],
+    );
+}
@@ -100,7 +100,7 @@ fn recursive_vars() {
             10..47 '{     ...&y]; }': ()
             20..21 'y': &{unknown}
             24..31 'unknown': &{unknown}
-            37..44 '[y, &y]': [&&{unknown}; _]
+            37..44 '[y, &y]': [&&{unknown}; 2]
             38..39 'y': &{unknown}
             41..43 '&y': &&{unknown}
             42..43 'y': &{unknown}
@@ -124,7 +124,7 @@ fn recursive_vars_2() {
             24..31 'unknown': &&{unknown}
             41..42 'y': &&{unknown}
             45..52 'unknown': &&{unknown}
-            58..76 '[(x, y..., &x)]': [(&&&{unknown}, &&&{unknown}); _]
+            58..76 '[(x, y..., &x)]': [(&&&{unknown}, &&&{unknown}); 2]
             59..65 '(x, y)': (&&&{unknown}, &&&{unknown})
             60..61 'x': &&{unknown}
             63..64 'y': &&{unknown}
@@ -176,8 +176,8 @@ fn infer_std_crash_2() {
         "#,
         expect![[r#"
             22..52 '{     ...n']; }': ()
-            28..49 '&[0, b...b'\n']': &[u8; _]
-            29..49 '[0, b'...b'\n']': [u8; _]
+            28..49 '&[0, b...b'\n']': &[u8; 4]
+            29..49 '[0, b'...b'\n']': [u8; 4]
             30..31 '0': u8
             33..38 'b'\n'': u8
             40..41 '1': u8
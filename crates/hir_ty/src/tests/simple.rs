@@ -11,7 +11,7 @@ fn test() {
     let x = box 1;
     let t = (x, box x, box &1, box [1]);
     t;
-} //^ (Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; _]>)
+} //^ (Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; 1]>)
 
 //- /std.rs crate:std
 #[prelude_import] use prelude::*;
@@ -472,7 +472,7 @@ fn infer_literals() {
             26..30 '5f32': f32
             36..40 '5f64': f64
             46..53 '"hello"': &str
-            59..67 'b"bytes"': &[u8; _]
+            59..67 'b"bytes"': &[u8; 5]
             73..76 ''c'': char
             82..86 'b'b'': u8
             92..96 '3.14': f64
@@ -480,11 +480,24 @@ fn infer_literals() {
             112..117 'false': bool
             123..127 'true': bool
             133..197 'r#"   ...    "#': &str
-            203..213 'br#"yolo"#': &[u8; _]
+            203..213 'br#"yolo"#': &[u8; 4]
         "##]],
     );
 }
 
+#[test]
+fn infer_byte_string_len() {
+    check_types(
+        r##"
+fn test() {
+    let escaped = b"a\n\x00";
+    let raw = br#"a\n"#;
+    (escaped, raw);
+} //^ (&[u8; 3], &[u8; 3])
+"##,
+    );
+}
+
 #[test]
 fn infer_unary_op() {
     check_infer(
@@ -1206,55 +1219,55 @@ fn infer_array() {
             8..9 'x': &str
             17..18 'y': isize
             27..292 '{     ... []; }': ()
-            37..38 'a': [&str; _]
-            41..44 '[x]': [&str; _]
+            37..38 'a': [&str; 1]
+            41..44 '[x]': [&str; 1]
             42..43 'x': &str
-            54..55 'b': [[&str; _]; _]
-            58..64 '[a, a]': [[&str; _]; _]
-            59..60 'a': [&str; _]
-            62..63 'a': [&str; _]
-            74..75 'c': [[[&str; _]; _]; _]
-            78..84 '[b, b]': [[[&str; _]; _]; _]
-            79..80 'b': [[&str; _]; _]
-            82..83 'b': [[&str; _]; _]
-            95..96 'd': [isize; _]
-            99..111 '[y, 1, 2, 3]': [isize; _]
+            54..55 'b': [[&str; 1]; 2]
+            58..64 '[a, a]': [[&str; 1]; 2]
+            59..60 'a': [&str; 1]
+            62..63 'a': [&str; 1]
+            74..75 'c': [[[&str; 1]; 2]; 2]
+            78..84 '[b, b]': [[[&str; 1]; 2]; 2]
+            79..80 'b': [[&str; 1]; 2]
+            82..83 'b': [[&str; 1]; 2]
+            95..96 'd': [isize; 4]
+            99..111 '[y, 1, 2, 3]': [isize; 4]
             100..101 'y': isize
             103..104 '1': isize
             106..107 '2': isize
             109..110 '3': isize
-            121..122 'd': [isize; _]
-            125..137 '[1, y, 2, 3]': [isize; _]
+            121..122 'd': [isize; 4]
+            125..137 '[1, y, 2, 3]': [isize; 4]
             126..127 '1': isize
             129..130 'y': isize
             132..133 '2': isize
             135..136 '3': isize
-            147..148 'e': [isize; _]
-            151..154 '[y]': [isize; _]
+            147..148 'e': [isize; 1]
+            151..154 '[y]': [isize; 1]
             152..153 'y': isize
-            164..165 'f': [[isize; _]; _]
-            168..174 '[d, d]': [[isize; _]; _]
-            169..170 'd': [isize; _]
-            172..173 'd': [isize; _]
-            184..185 'g': [[isize; _]; _]
-            188..194 '[e, e]': [[isize; _]; _]
-            189..190 'e': [isize; _]
-            192..193 'e': [isize; _]
-            205..206 'h': [i32; _]
-            209..215 '[1, 2]': [i32; _]
+            164..165 'f': [[isize; 4]; 2]
+            168..174 '[d, d]': [[isize; 4]; 2]
+            169..170 'd': [isize; 4]
+            172..173 'd': [isize; 4]
+            184..185 'g': [[isize; 1]; 2]
+            188..194 '[e, e]': [[isize; 1]; 2]
+            189..190 'e': [isize; 1]
+            192..193 'e': [isize; 1]
+            205..206 'h': [i32; 2]
+            209..215 '[1, 2]': [i32; 2]
             210..211 '1': i32
             213..214 '2': i32
-            225..226 'i': [&str; _]
-            229..239 '["a", "b"]': [&str; _]
+            225..226 'i': [&str; 2]
+            229..239 '["a", "b"]': [&str; 2]
             230..233 '"a"': &str
             235..238 '"b"': &str
-            250..251 'b': [[&str; _]; _]
-            254..264 '[a, ["b"]]': [[&str; _]; _]
-            255..256 'a': [&str; _]
-            258..263 '["b"]': [&str; _]
+            250..251 'b': [[&str; 1]; 2]
+            254..264 '[a, ["b"]]': [[&str; 1]; 2]
+            255..256 'a': [&str; 1]
+            258..263 '["b"]': [&str; 1]
             259..262 '"b"': &str
-            274..275 'x': [u8; _]
-            287..289 '[]': [u8; _]
+            274..275 'x': [u8; 0]
+            287..289 '[]': [u8; 0]
         "#]],
     );
 }
@@ -2216,3 +2229,60 @@ fn generic_default_depending_on_other_type_arg_forward() {
         "#]],
     );
 }
+
+#[test]
+fn array_length_from_const_item() {
+    check_types(
+        r#"
+const SIZE: usize = 2 * (1 + 2);
+const DOUBLED: usize = SIZE << 1;
+
+fn test(a: [u8; SIZE], b: [u8; DOUBLED - 1]) {
+    a;
+  //^ [u8; 6]
+    b;
+  //^ [u8; 11]
+    let c = [0u8; SIZE + 1];
+    c;
+  //^ [u8; 7]
+}
+"#,
+    );
+}
+
+#[test]
+fn array_length_unknown() {
+    check_types(
+        r#"
+fn len() -> usize { 4 }
+
+fn test() {
+    let a = [0; len()];
+    a;
+  //^ [i32; _]
+}
+"#,
+    );
+}
+
+#[test]
+fn array_length_from_const_generic_param() {
+    check_types(
+        r#"
+struct ArrayVec<T, const CAP: usize> { xs: [T; CAP] }
+
+fn test<const N: usize>(a: [u8; N], v: ArrayVec<u16, 4>) {
+    a;
+  //^ [u8; N]
+    let b = [0u8; N];
+    b;
+  //^ [u8; N]
+    N;
+  //^ usize
+    // FIXME: const arguments in paths are not lowered yet
+    v.xs;
+  //^^^^ [u16; _]
+}
+"#,
+    );
+}
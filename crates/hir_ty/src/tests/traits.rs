@@ -502,7 +502,7 @@ fn indexing_arrays() {
         expect![[r#"
             10..26 '{ &mut...[2]; }': ()
             12..23 '&mut [9][2]': &mut {unknown}
-            17..20 '[9]': [i32; _]
+            17..20 '[9]': [i32; 1]
             17..23 '[9][2]': {unknown}
             18..19 '9': i32
             21..22 '2': i32
@@ -3111,3 +3111,78 @@ fn test() {
     "#,
     );
 }
+
+#[test]
+fn closure_kind_selects_fn_traits() {
+    check_types(
+        r#"
+#[lang = "copy"]
+trait Copy {}
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+
+struct S;
+trait IsFn { fn is_fn(&self) -> u32; }
+impl<F: Fn()> IsFn for F {}
+trait IsFnMut { fn is_fn_mut(&self) -> u32; }
+impl<F: FnMut()> IsFnMut for F {}
+
+fn test() {
+    let mut s = S;
+    let a = || { let t = &s; };
+    a.is_fn();
+  //^^^^^^^^^ u32
+    let b = || { let t = &mut s; };
+    b.is_fn();
+  //^^^^^^^^^ {unknown}
+    b.is_fn_mut();
+  //^^^^^^^^^^^^^ u32
+    let c = || { let t = s; };
+    c.is_fn_mut();
+  //^^^^^^^^^^^^^ {unknown}
+}
+"#,
+    );
+}
+
+#[test]
+fn impl_selection_by_array_length() {
+    check_types(
+        r#"
+trait Trait { type Out; }
+impl Trait for [u8; 4] { type Out = u32; }
+impl Trait for [u8; 8] { type Out = u64; }
+
+fn foo<T: Trait>(t: T) -> T::Out { loop {} }
+
+fn test() {
+    foo([0u8; 4]);
+  //^^^^^^^^^^^^^ u32
+    foo([0, 1, 2, 3, 4, 5, 6, 7]);
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ u64
+}
+"#,
+    );
+}
+
+#[test]
+fn impl_for_array_with_const_generic_param() {
+    check_types(
+        r#"
+trait First { type Item; fn first(&self) -> Self::Item; }
+impl<T, const N: usize> First for [T; N] {
+    type Item = T;
+    fn first(&self) -> T { loop {} }
+}
+
+fn test(a: [u16; 3]) {
+    a.first();
+  //^^^^^^^^^ u16
+}
+"#,
+    );
+}
@@ -18,7 +18,7 @@ use crate::{
     display::HirDisplay,
     method_resolution::{TyFingerprint, ALL_FLOAT_FPS, ALL_INT_FPS},
     utils::generics,
-    CallableDefId, DebruijnIndex, FnSig, GenericPredicate, Substs, Ty, TypeCtor,
+    CallableDefId, ClosureKind, DebruijnIndex, FnSig, GenericPredicate, Substs, Ty, TypeCtor,
 };
 use mapping::{
     convert_where_clauses, generic_predicate_to_inline_bound, make_binders, TypeAliasAsValue,
@@ -202,10 +202,19 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
     fn closure_kind(
         &self,
         _closure_id: chalk_ir::ClosureId<Interner>,
-        _substs: &chalk_ir::Substitution<Interner>,
+        substs: &chalk_ir::Substitution<Interner>,
     ) -> rust_ir::ClosureKind {
-        // Fn is the closure kind that implements all three traits
-        rust_ir::ClosureKind::Fn
+        let kind = substs.as_slice(&Interner).get(1).and_then(|it| {
+            let kind_ty: Ty = from_chalk(self.db, it.assert_ty_ref(&Interner).clone());
+            ClosureKind::from_ty(&kind_ty)
+        });
+        match kind {
+            Some(ClosureKind::FnOnce) => rust_ir::ClosureKind::FnOnce,
+            Some(ClosureKind::FnMut) => rust_ir::ClosureKind::FnMut,
+            // Fn is the closure kind that implements all three traits, so it is
+            // the default while the closure body has not been analyzed yet.
+            Some(ClosureKind::Fn) | None => rust_ir::ClosureKind::Fn,
+        }
     }
     fn closure_inputs_and_output(
         &self,
@@ -10,14 +10,18 @@ use chalk_ir::{
 use chalk_solve::rust_ir;
 
 use base_db::salsa::InternKey;
-use hir_def::{type_ref::Mutability, AssocContainerId, GenericDefId, Lookup, TypeAliasId};
+use hir_def::{
+    type_ref::Mutability, AssocContainerId, GenericDefId, Lookup, TypeAliasId, TypeParamId,
+};
 
 use crate::{
+    consteval::ConstScalar,
     db::HirDatabase,
     primitive::{FloatBitness, FloatTy, IntBitness, IntTy, Signedness},
     traits::{Canonical, Obligation},
-    ApplicationTy, CallableDefId, GenericPredicate, InEnvironment, OpaqueTy, OpaqueTyId,
-    ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TyKind, TypeCtor,
+    ApplicationTy, CallableDefId, ClosureKind, GenericPredicate, InEnvironment, OpaqueTy,
+    OpaqueTyId, ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TyKind,
+    TypeCtor,
 };
 
 use super::interner::*;
@@ -29,7 +33,10 @@ impl ToChalk for Ty {
         match self {
             Ty::Apply(apply_ty) => match apply_ty.ctor {
                 TypeCtor::Ref(m) => ref_to_chalk(db, m, apply_ty.parameters),
-                TypeCtor::Array => array_to_chalk(db, apply_ty.parameters),
+                TypeCtor::Const(value) => const_placeholder(db, value).to_ty::<Interner>(&Interner),
+                TypeCtor::ClosureKind(kind) => {
+                    closure_kind_placeholder(kind).to_ty::<Interner>(&Interner)
+                }
                 TypeCtor::FnPtr { num_args: _, is_varargs } => {
                     let substitution = apply_ty.parameters.to_chalk(db).shifted_in(&Interner);
                     chalk_ir::TyData::Function(chalk_ir::FnPointer {
@@ -57,14 +64,7 @@ impl ToChalk for Ty {
                 .cast(&Interner)
                 .intern(&Interner)
             }
-            Ty::Placeholder(id) => {
-                let interned_id = db.intern_type_param_id(id);
-                PlaceholderIndex {
-                    ui: UniverseIndex::ROOT,
-                    idx: interned_id.as_intern_id().as_usize(),
-                }
-                .to_ty::<Interner>(&Interner)
-            }
+            Ty::Placeholder(id) => type_param_placeholder(db, id).to_ty::<Interner>(&Interner),
             Ty::Bound(idx) => chalk_ir::TyData::BoundVar(idx).intern(&Interner),
             Ty::Infer(_infer_ty) => panic!("uncanonicalized infer ty"),
             Ty::Dyn(predicates) => {
@@ -99,20 +99,13 @@ impl ToChalk for Ty {
             chalk_ir::TyData::Apply(apply_ty) => match apply_ty.name {
                 TypeName::Error => Ty::Unknown,
                 TypeName::Ref(m) => ref_from_chalk(db, m, apply_ty.substitution),
-                TypeName::Array => array_from_chalk(db, apply_ty.substitution),
                 _ => {
                     let ctor = from_chalk(db, apply_ty.name);
                     let parameters = from_chalk(db, apply_ty.substitution);
                     Ty::Apply(ApplicationTy { ctor, parameters })
                 }
             },
-            chalk_ir::TyData::Placeholder(idx) => {
-                assert_eq!(idx.ui, UniverseIndex::ROOT);
-                let interned_id = crate::db::GlobalTypeParamId::from_intern_id(
-                    crate::salsa::InternId::from(idx.idx),
-                );
-                Ty::Placeholder(db.lookup_intern_type_param_id(interned_id))
-            }
+            chalk_ir::TyData::Placeholder(idx) => placeholder_from_chalk(db, idx),
             chalk_ir::TyData::Alias(chalk_ir::AliasTy::Projection(proj)) => {
                 let associated_ty = from_chalk(db, proj.associated_ty_id);
                 let parameters = from_chalk(db, proj.substitution);
@@ -193,33 +186,59 @@ fn ref_from_chalk(
     Ty::apply(TypeCtor::Ref(from_chalk(db, mutability)), Substs(tys))
 }
 
-/// We currently don't model constants, but Chalk does. So, we have to insert a
-/// fake constant here, because Chalks built-in logic may expect it to be there.
-fn array_to_chalk(db: &dyn HirDatabase, subst: Substs) -> chalk_ir::Ty<Interner> {
-    let arg = subst[0].clone().to_chalk(db);
-    let usize_ty = chalk_ir::ApplicationTy {
-        name: TypeName::Scalar(Scalar::Uint(chalk_ir::UintTy::Usize)),
-        substitution: chalk_ir::Substitution::empty(&Interner),
-    }
-    .intern(&Interner);
-    let const_ = FAKE_PLACEHOLDER.to_const(&Interner, usize_ty);
-    chalk_ir::ApplicationTy {
-        name: TypeName::Array,
-        substitution: chalk_ir::Substitution::from_iter(
-            &Interner,
-            vec![arg.cast(&Interner), const_.cast(&Interner)],
-        ),
-    }
-    .intern(&Interner)
+/// Type parameters, constants and closure kinds are all represented as
+/// placeholders in Chalk, since they only unify with themselves. All of them
+/// live in the root universe, and the index encodes which one it is:
+///
+/// - `idx * 2` for the type parameter with interned id `idx`,
+/// - `idx * 2 + 1` for the constant with interned id `idx`,
+/// - `usize::MAX - 1`, `usize::MAX - 2` and `usize::MAX - 3` for the closure
+///   kinds `Fn`, `FnMut` and `FnOnce` (see `closure_kind_placeholder`).
+///
+/// `placeholder_from_chalk` decodes this again. Note that Chalk does have a
+/// notion of constants, but we treat them as types everywhere (see
+/// `TypeCtor::Const`), so Chalk never needs to know.
+fn type_param_placeholder(db: &dyn HirDatabase, id: TypeParamId) -> PlaceholderIndex {
+    let interned_id = db.intern_type_param_id(id);
+    PlaceholderIndex { ui: UniverseIndex::ROOT, idx: interned_id.as_intern_id().as_usize() * 2 }
 }
 
-/// Here we remove the const from the type we got from Chalk.
-fn array_from_chalk(db: &dyn HirDatabase, subst: chalk_ir::Substitution<Interner>) -> Ty {
-    let tys = subst
-        .iter(&Interner)
-        .filter_map(|p| Some(from_chalk(db, p.ty(&Interner)?.clone())))
-        .collect();
-    Ty::apply(TypeCtor::Array, Substs(tys))
+/// See `type_param_placeholder` for the encoding of placeholder indices.
+fn const_placeholder(db: &dyn HirDatabase, value: ConstScalar) -> PlaceholderIndex {
+    let interned_id = db.intern_const_scalar(value);
+    PlaceholderIndex { ui: UniverseIndex::ROOT, idx: interned_id.as_intern_id().as_usize() * 2 + 1 }
+}
+
+/// Closure kinds are placeholders too, with indices at the very end of the
+/// range which interned ids never get close to, see `type_param_placeholder`.
+fn closure_kind_placeholder(kind: ClosureKind) -> PlaceholderIndex {
+    let offset = match kind {
+        ClosureKind::Fn => 1,
+        ClosureKind::FnMut => 2,
+        ClosureKind::FnOnce => 3,
+    };
+    PlaceholderIndex { ui: UniverseIndex::ROOT, idx: usize::MAX - offset }
+}
+
+/// Decodes a placeholder index, as encoded by `type_param_placeholder`,
+/// `const_placeholder` and `closure_kind_placeholder`.
+fn placeholder_from_chalk(db: &dyn HirDatabase, idx: PlaceholderIndex) -> Ty {
+    assert_eq!(idx.ui, UniverseIndex::ROOT);
+    let closure_kinds = [ClosureKind::Fn, ClosureKind::FnMut, ClosureKind::FnOnce];
+    if let Some(kind) = closure_kinds.iter().find(|&&it| closure_kind_placeholder(it) == idx) {
+        return kind.to_ty();
+    }
+    let intern_id = crate::salsa::InternId::from(idx.idx / 2);
+    match idx.idx % 2 {
+        0 => {
+            let interned_id = crate::db::GlobalTypeParamId::from_intern_id(intern_id);
+            Ty::Placeholder(db.lookup_intern_type_param_id(interned_id))
+        }
+        _ => {
+            let interned_id = crate::db::InternedConstScalarId::from_intern_id(intern_id);
+            db.lookup_intern_const_scalar(interned_id).to_ty()
+        }
+    }
 }
 
 impl ToChalk for Substs {
@@ -333,6 +352,12 @@ impl ToChalk for TypeCtor {
                 // function pointers with TypeName
                 unreachable!()
             }
+
+            TypeCtor::Const(_) | TypeCtor::ClosureKind(_) => {
+                // This should not be reached either, since constants and
+                // closure kinds are represented as placeholders
+                unreachable!()
+            }
         }
     }
 
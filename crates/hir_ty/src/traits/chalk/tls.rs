@@ -27,6 +27,8 @@ impl DebugContext<'_> {
             TypeCtor::Str => write!(f, "str")?,
             TypeCtor::Slice => write!(f, "slice")?,
             TypeCtor::Array => write!(f, "array")?,
+            TypeCtor::Const(_) => write!(f, "const")?,
+            TypeCtor::ClosureKind(kind) => write!(f, "{:?}", kind)?,
             TypeCtor::RawPtr(m) => write!(f, "*{}", m.as_keyword_for_ptr())?,
             TypeCtor::Ref(m) => write!(f, "&{}", m.as_keyword_for_ref())?,
             TypeCtor::Never => write!(f, "!")?,
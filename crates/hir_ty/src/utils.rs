@@ -2,6 +2,7 @@
 //! query, but can't be computed directly from `*Data` (ie, which need a `db`).
 use std::sync::Arc;
 
+use either::Either;
 use hir_def::generics::WherePredicateTarget;
 use hir_def::{
     adt::VariantData,
@@ -10,7 +11,8 @@ use hir_def::{
     path::Path,
     resolver::{HasResolver, TypeNs},
     type_ref::TypeRef,
-    AssocContainerId, GenericDefId, Lookup, TraitId, TypeAliasId, TypeParamId, VariantId,
+    AssocContainerId, ConstParamId, GenericDefId, Lookup, TraitId, TypeAliasId, TypeParamId,
+    VariantId,
 };
 use hir_expand::name::{name, Name};
 
@@ -184,6 +186,27 @@ impl Generics {
         })
     }
 
+    /// Iterates over all parameters in the order they appear in `Substs`.
+    pub(crate) fn iter_id<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = Either<TypeParamId, ConstParamId>> + 'a {
+        let parent = self.parent_generics.as_ref().into_iter().flat_map(|it| it.iter_own_id());
+        parent.chain(self.iter_own_id())
+    }
+
+    fn iter_own_id<'a>(&'a self) -> impl Iterator<Item = Either<TypeParamId, ConstParamId>> + 'a {
+        let types = self
+            .params
+            .types
+            .iter()
+            .map(move |(local_id, _)| Either::Left(TypeParamId { parent: self.def, local_id }));
+        let consts =
+            self.params.consts.iter().map(move |(local_id, _)| {
+                Either::Right(ConstParamId { parent: self.def, local_id })
+            });
+        types.chain(consts)
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.len_split().0
     }
@@ -191,12 +214,12 @@ impl Generics {
     /// (total, parents, child)
     pub(crate) fn len_split(&self) -> (usize, usize, usize) {
         let parent = self.parent_generics.as_ref().map_or(0, |p| p.len());
-        let child = self.params.types.len();
+        let child = self.params.types.len() + self.params.consts.len();
         (parent + child, parent, child)
     }
 
-    /// (parent total, self param, type param list, impl trait)
-    pub(crate) fn provenance_split(&self) -> (usize, usize, usize, usize) {
+    /// (parent total, self param, type param list, impl trait, const params)
+    pub(crate) fn provenance_split(&self) -> (usize, usize, usize, usize, usize) {
         let parent = self.parent_generics.as_ref().map_or(0, |p| p.len());
         let self_params = self
             .params
@@ -216,13 +239,24 @@ impl Generics {
             .iter()
             .filter(|(_, p)| p.provenance == TypeParamProvenance::ArgumentImplTrait)
             .count();
-        (parent, self_params, list_params, impl_trait_params)
+        let const_params = self.params.consts.len();
+        (parent, self_params, list_params, impl_trait_params, const_params)
     }
 
     pub(crate) fn param_idx(&self, param: TypeParamId) -> Option<usize> {
         Some(self.find_param(param)?.0)
     }
 
+    pub(crate) fn const_param_idx(&self, param: ConstParamId) -> Option<usize> {
+        if param.parent == self.def {
+            let idx = self.params.consts.iter().position(|(id, _)| id == param.local_id)?;
+            let (_total, parent_len, _child) = self.len_split();
+            Some(parent_len + self.params.types.len() + idx)
+        } else {
+            self.parent_generics.as_ref().and_then(|g| g.const_param_idx(param))
+        }
+    }
+
     fn find_param(&self, param: TypeParamId) -> Option<(usize, &TypeParamData)> {
         if param.parent == self.def {
             let (idx, (_local_id, data)) = self
@@ -15,6 +15,7 @@ mod complete_keyword;
 mod complete_snippet;
 mod complete_qualified_path;
 mod complete_unqualified_path;
+mod complete_flyimport;
 mod complete_postfix;
 mod complete_macro_in_item_position;
 mod complete_trait_impl;
@@ -123,6 +124,7 @@ pub(crate) fn completions(
     complete_snippet::complete_item_snippet(&mut acc, &ctx);
     complete_qualified_path::complete_qualified_path(&mut acc, &ctx);
     complete_unqualified_path::complete_unqualified_path(&mut acc, &ctx);
+    complete_flyimport::complete_flyimport(&mut acc, &ctx);
     complete_dot::complete_dot(&mut acc, &ctx);
     complete_record::complete_record(&mut acc, &ctx);
     complete_pattern::complete_pattern(&mut acc, &ctx);
@@ -0,0 +1,249 @@
+//! Completion of names which are not in scope yet, together with an import.
+
+use assists::utils::insert_use_statement;
+use either::Either;
+use hir::{ModuleDef, ScopeDef};
+use ide_db::imports_locator;
+use syntax::AstNode;
+use text_edit::TextEdit;
+
+use crate::completion::{CompletionContext, CompletionItem, Completions};
+
+/// Upper bound on the number of items looked up in the current crate and in
+/// its dependencies, to keep the latency of the completion request in check.
+const MAX_IMPORT_CANDIDATES: usize = 40;
+
+/// Upper bound on the number of completions with an import, as each of them
+/// has to compute the edit which inserts its `use` item.
+const MAX_IMPORT_COMPLETIONS: usize = 20;
+
+// Feature: Completion With Autoimport
+//
+// When completing names in the current scope, proposes additional imports
+// from other modules or crates, if they can be imported and the name typed
+// so far matches theirs.
+//
+// ```
+// fn main() {
+//     pda<|>
+// }
+// # pub mod std { pub mod marker { pub struct PhantomData { } } }
+// ```
+// ->
+// ```
+// use std::marker::PhantomData;
+//
+// fn main() {
+//     PhantomData
+// }
+// # pub mod std { pub mod marker { pub struct PhantomData { } } }
+// ```
+//
+// The feature can be turned off with the `rust-analyzer.completion.autoimport.enable` setting.
+pub(super) fn complete_flyimport(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
+    if !ctx.config.enable_autoimport_completions {
+        return None;
+    }
+    if !ctx.is_trivial_path || ctx.is_pat_binding_or_const {
+        return None;
+    }
+    if ctx.use_item_syntax.is_some()
+        || ctx.record_lit_syntax.is_some()
+        || ctx.record_pat_syntax.is_some()
+        || ctx.attribute_under_caret.is_some()
+    {
+        return None;
+    }
+    let _p = profile::span("complete_flyimport");
+
+    let anchor = ctx.name_ref_syntax.as_ref()?;
+    let potential_import_name = anchor.text().to_string();
+    if potential_import_name.is_empty() {
+        return None;
+    }
+    let current_module = ctx.scope.module()?;
+
+    let mut candidates = imports_locator::find_similar_imports(
+        &ctx.sema,
+        ctx.krate?,
+        &potential_import_name,
+        MAX_IMPORT_CANDIDATES,
+    )
+    .into_iter()
+    .filter_map(|candidate| {
+        let (import_path, resolution) = match candidate {
+            // Modules are rarely what one wants outside of a `use`, and there
+            // are a lot of them.
+            Either::Left(ModuleDef::Module(_)) => return None,
+            Either::Left(module_def) => {
+                (current_module.find_use_path(ctx.db, module_def)?, ScopeDef::ModuleDef(module_def))
+            }
+            Either::Right(macro_def) => {
+                (current_module.find_use_path(ctx.db, macro_def)?, ScopeDef::MacroDef(macro_def))
+            }
+        };
+        // A single segment path means that the item is in scope already.
+        if import_path.segments.len() < 2 {
+            return None;
+        }
+        let name = import_path.segments.last()?.to_string();
+        Some((import_path, name, resolution))
+    })
+    .collect::<Vec<_>>();
+    // The candidates come in no particular order, so they are sorted to keep
+    // the same ones when there are too many: exact matches first, then the
+    // shortest paths.
+    candidates.sort_by_cached_key(|(import_path, name, _)| {
+        (*name != potential_import_name, import_path.segments.len(), import_path.to_string())
+    });
+    candidates.truncate(MAX_IMPORT_COMPLETIONS);
+
+    for (import_path, name, resolution) in candidates {
+        let mut items = Completions::default();
+        items.add_resolution(ctx, name, &resolution);
+        let items: Vec<CompletionItem> = items.into();
+        if items.is_empty() {
+            continue;
+        }
+
+        let mut builder = TextEdit::builder();
+        insert_use_statement(anchor.syntax(), &import_path.to_string(), &ctx.sema, &mut builder);
+        let import_edit = builder.finish();
+        acc.add_all(
+            items
+                .into_iter()
+                .filter_map(|item| item.with_import(&import_path, import_edit.clone())),
+        );
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect::{expect, Expect};
+
+    use crate::completion::{
+        test_utils::{check_edit, completion_list, completion_list_with_config},
+        CompletionConfig, CompletionKind,
+    };
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture, CompletionKind::Reference);
+        expect.assert_eq(&actual)
+    }
+
+    #[test]
+    fn completes_items_from_other_modules() {
+        check(
+            r#"
+fn main() {
+    HashM<|>
+}
+pub mod collections {
+    pub struct HashMap;
+    pub struct HashSet;
+}
+"#,
+            expect![[r#"
+                st HashMap (collections::HashMap)
+                md collections
+                fn main()           fn main()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completion_inserts_import() {
+        check_edit(
+            "HashMap",
+            r#"
+fn main() {
+    HashM<|>
+}
+pub mod collections {
+    pub struct HashMap;
+}
+"#,
+            r#"
+use collections::HashMap;
+
+fn main() {
+    HashMap
+}
+pub mod collections {
+    pub struct HashMap;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_items_from_dependencies() {
+        check_edit(
+            "swap",
+            r#"
+//- /main.rs crate:main deps:std
+fn main() {
+    swa<|>
+}
+//- /std/lib.rs crate:std
+pub mod mem {
+    pub fn swap() {}
+}
+"#,
+            r#"
+use std::mem::swap;
+
+fn main() {
+    swap()$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn does_not_complete_items_in_scope() {
+        check(
+            r#"
+use collections::HashMap;
+
+fn main() {
+    HashM<|>
+}
+pub mod collections {
+    pub struct HashMap;
+}
+"#,
+            expect![[r#"
+                st HashMap
+                md collections
+                fn main()      fn main()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn can_be_disabled() {
+        let config = CompletionConfig {
+            enable_autoimport_completions: false,
+            ..CompletionConfig::default()
+        };
+        let actual = completion_list_with_config(
+            config,
+            r#"
+fn main() {
+    HashM<|>
+}
+pub mod collections {
+    pub struct HashMap;
+}
+"#,
+            CompletionKind::Reference,
+        );
+        expect![[r#"
+            md collections
+            fn main()      fn main()
+        "#]]
+        .assert_eq(&actual);
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use hir::Documentation;
+use hir::{Documentation, ModPath};
 use syntax::TextRange;
 use text_edit::TextEdit;
 
@@ -240,6 +240,25 @@ impl CompletionItem {
     pub fn trigger_call_info(&self) -> bool {
         self.trigger_call_info
     }
+
+    /// Turns this into a completion of an item which is not in scope yet:
+    /// `import_edit` brings the item into scope, and the label shows where it
+    /// is imported from.
+    ///
+    /// Returns `None` if `import_edit` would touch the completed identifier.
+    pub(crate) fn with_import(
+        mut self,
+        import_path: &ModPath,
+        import_edit: TextEdit,
+    ) -> Option<CompletionItem> {
+        if import_edit.iter().any(|indel| indel.delete.intersect(self.source_range).is_some()) {
+            return None;
+        }
+        self.text_edit.union(import_edit).ok()?;
+        self.lookup = Some(self.lookup().to_string());
+        self.label = format!("{} ({})", self.label, import_path);
+        Some(self)
+    }
 }
 
 /// A helper to make `CompletionItem`s.
@@ -56,6 +56,12 @@ pub(crate) fn diagnostics(
         .on::<hir::diagnostics::UnresolvedModule, _>(|d| {
             res.borrow_mut().push(diagnostic_with_fix(d, &sema));
         })
+        .on::<hir::diagnostics::UnresolvedMacroCall, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+        })
+        .on::<hir::diagnostics::UnresolvedPath, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+        })
         .on::<hir::diagnostics::MissingFields, _>(|d| {
             res.borrow_mut().push(diagnostic_with_fix(d, &sema));
         })
@@ -65,6 +71,12 @@ pub(crate) fn diagnostics(
         .on::<hir::diagnostics::NoSuchField, _>(|d| {
             res.borrow_mut().push(diagnostic_with_fix(d, &sema));
         })
+        .on::<hir::diagnostics::TypeMismatch, _>(|d| {
+            res.borrow_mut().push(diagnostic_with_fix(d, &sema));
+        })
+        .on::<hir::diagnostics::IncorrectCase, _>(|d| {
+            res.borrow_mut().push(warning_with_fix(d, &sema));
+        })
         // Only collect experimental diagnostics when they're enabled.
         .filter(|diag| !diag.is_experimental() || enable_experimental);
 
@@ -103,6 +115,16 @@ fn diagnostic_with_fix<D: DiagnosticWithFix>(d: &D, sema: &Semantics<RootDatabas
     }
 }
 
+fn warning_with_fix<D: DiagnosticWithFix>(d: &D, sema: &Semantics<RootDatabase>) -> Diagnostic {
+    Diagnostic {
+        name: Some(d.name().into()),
+        range: sema.diagnostics_display_range(d).range,
+        message: d.message(),
+        severity: Severity::WeakWarning,
+        fix: d.fix(sema),
+    }
+}
+
 fn check_unnecessary_braces_in_use_statement(
     acc: &mut Vec<Diagnostic>,
     file_id: FileId,
@@ -190,27 +212,39 @@ mod tests {
     use std::collections::HashSet;
     use stdx::trim_indent;
     use test_utils::assert_eq_text;
+    use text_edit::TextEdit;
 
     use crate::mock_analysis::{analysis_and_position, single_file, MockAnalysis};
     use expect::{expect, Expect};
 
     /// Takes a multi-file input fixture with annotated cursor positions,
     /// and checks that:
-    ///  * a diagnostic is produced
-    ///  * this diagnostic fix trigger range touches the input cursor position
+    ///  * a diagnostic with a fix is produced
+    ///  * the fix trigger range of the last such diagnostic touches the input cursor position
     ///  * that the contents of the file containing the cursor match `after` after the diagnostic fix is applied
     fn check_fix(ra_fixture_before: &str, ra_fixture_after: &str) {
         let after = trim_indent(ra_fixture_after);
 
         let (analysis, file_position) = analysis_and_position(ra_fixture_before);
-        let diagnostic =
-            analysis.diagnostics(file_position.file_id, true, None).unwrap().pop().unwrap();
-        let mut fix = diagnostic.fix.unwrap();
-        let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
+        let fix = analysis
+            .diagnostics(file_position.file_id, true, None)
+            .unwrap()
+            .into_iter()
+            .rev()
+            .find_map(|it| it.fix)
+            .expect("expected a diagnostic with a fix");
         let target_file_contents = analysis.file_text(file_position.file_id).unwrap();
         let actual = {
+            // Fixes like renames may consist of several edits to the same file.
+            let mut builder = TextEdit::builder();
+            for edit in fix.source_change.source_file_edits {
+                assert_eq!(edit.file_id, file_position.file_id);
+                for indel in edit.edit.into_iter() {
+                    builder.replace(indel.delete, indel.insert);
+                }
+            }
             let mut actual = target_file_contents.to_string();
-            edit.apply(&mut actual);
+            builder.finish().apply(&mut actual);
             actual
         };
 
@@ -224,6 +258,17 @@ mod tests {
         );
     }
 
+    /// Takes a multi-file input fixture with annotated cursor position and checks that the
+    /// file containing the cursor has diagnostics, none of which has a fix.
+    fn check_no_fix(ra_fixture: &str) {
+        let (analysis, file_position) = analysis_and_position(ra_fixture);
+        let diagnostics = analysis.diagnostics(file_position.file_id, true, None).unwrap();
+        assert!(!diagnostics.is_empty(), "expected a diagnostic");
+        for diagnostic in diagnostics {
+            assert!(diagnostic.fix.is_none(), "got a fix when none was expected: {:?}", diagnostic);
+        }
+    }
+
     /// Checks that a diagnostic applies to the file containing the `<|>` cursor marker
     /// which has a fix that can apply to other files.
     fn check_apply_diagnostic_fix_in_other_file(ra_fixture_before: &str, ra_fixture_after: &str) {
@@ -244,18 +289,33 @@ mod tests {
     }
 
     /// Takes a multi-file input fixture with annotated cursor position and checks that no diagnostics
-    /// apply to the file containing the cursor.
+    /// apply to the file containing the cursor, with experimental diagnostics disabled.
     fn check_no_diagnostics(ra_fixture: &str) {
         let mock = MockAnalysis::with_files(ra_fixture);
         let files = mock.files().map(|(it, _)| it).collect::<Vec<_>>();
         let analysis = mock.analysis();
         let diagnostics = files
             .into_iter()
-            .flat_map(|file_id| analysis.diagnostics(file_id, true, None).unwrap())
+            .flat_map(|file_id| analysis.diagnostics(file_id, false, None).unwrap())
             .collect::<Vec<_>>();
         assert_eq!(diagnostics.len(), 0, "unexpected diagnostics:\n{:#?}", diagnostics);
     }
 
+    /// Takes a multi-file input fixture and checks that exactly the diagnostics with the
+    /// given names are produced, in any of the files.
+    fn check_diagnostic_names(ra_fixture: &str, expected: &[&str]) {
+        let mock = MockAnalysis::with_files(ra_fixture);
+        let files = mock.files().map(|(it, _)| it).collect::<Vec<_>>();
+        let analysis = mock.analysis();
+        let diagnostics = files
+            .into_iter()
+            .flat_map(|file_id| analysis.diagnostics(file_id, true, None).unwrap())
+            .collect::<Vec<_>>();
+        let names =
+            diagnostics.iter().map(|it| it.name.as_deref().unwrap_or("")).collect::<Vec<_>>();
+        assert_eq!(names, expected, "unexpected diagnostics:\n{:#?}", diagnostics);
+    }
+
     /// Takes a multi-file input fixture with annotated cursor position and the list of disabled diagnostics,
     /// and checks that provided diagnostics aren't spawned during analysis.
     fn check_disabled_diagnostics(ra_fixture: &str, disabled_diagnostics: &[&'static str]) {
@@ -405,7 +465,7 @@ fn div(x: i32, y: i32) -> MyResult<i32> {
 
     #[test]
     fn test_wrap_return_type_not_applicable_when_expr_type_does_not_match_ok_type() {
-        check_no_diagnostics(
+        check_diagnostic_names(
             r#"
 //- /main.rs
 use core::result::Result::{self, Ok, Err};
@@ -417,12 +477,13 @@ pub mod result {
     pub enum Result<T, E> { Ok(T), Err(E) }
 }
 "#,
+            &["type-mismatch"],
         );
     }
 
     #[test]
     fn test_wrap_return_type_not_applicable_when_return_type_is_not_result() {
-        check_no_diagnostics(
+        check_diagnostic_names(
             r#"
 //- /main.rs
 use core::result::Result::{self, Ok, Err};
@@ -436,6 +497,7 @@ pub mod result {
     pub enum Result<T, E> { Ok(T), Err(E) }
 }
 "#,
+            &["type-mismatch"],
         );
     }
 
@@ -579,6 +641,7 @@ fn test_fn() {
                                                 1,
                                             ),
                                             dst: "foo.rs",
+                                            initial_contents: "",
                                         },
                                     ],
                                     is_snippet: false,
@@ -592,6 +655,109 @@ fn test_fn() {
         );
     }
 
+    #[test]
+    fn test_unresolved_import_diagnostic() {
+        check_expect(
+            r#"use does_not_exist;"#,
+            expect![[r#"
+                [
+                    Diagnostic {
+                        name: Some(
+                            "unresolved-import",
+                        ),
+                        message: "unresolved import",
+                        range: 4..18,
+                        severity: Error,
+                        fix: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_unresolved_path_import_fix() {
+        check_fix(
+            r#"
+fn main() {
+    let map = HashMap<|>::new();
+}
+pub mod collections { pub struct HashMap; }
+"#,
+            r#"
+use collections::HashMap;
+
+fn main() {
+    let map = HashMap::new();
+}
+pub mod collections { pub struct HashMap; }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_path_import_fix_from_dependency() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:std
+fn main() {
+    let it = <|>swap;
+}
+//- /std/lib.rs crate:std
+pub mod mem {
+    pub fn swap() {}
+}
+"#,
+            r#"
+use std::mem::swap;
+
+fn main() {
+    let it = swap;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_path_no_fix_when_qualifier_resolves() {
+        check_no_fix(
+            r#"
+fn main() {
+    collections::Nope<|>;
+}
+pub mod collections { pub struct HashMap; }
+pub mod other { pub struct Nope; }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_macro_call_import_fix() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:foo
+<|>bar!();
+//- /foo/lib.rs crate:foo
+#[macro_export]
+macro_rules! bar { () => {} }
+"#,
+            r#"
+use foo::bar;
+
+bar!();
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_path_is_experimental() {
+        let (analysis, file_id) = single_file(r#"fn main() { nope; }"#);
+        let diagnostics = analysis.diagnostics(file_id, false, None).unwrap();
+        assert!(diagnostics.is_empty(), "unexpected diagnostics:\n{:#?}", diagnostics);
+        let diagnostics = analysis.diagnostics(file_id, true, None).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+    }
+
     #[test]
     fn range_mapping_out_of_macros() {
         // FIXME: this is very wrong, but somewhat tricky to fix.
@@ -645,13 +811,13 @@ use a::{c, d::e};
         check_no_diagnostics(
             r#"
 struct A { a: &'static str }
-fn main() { A { a: "hello" } }
+fn main() { A { a: "hello" }; }
 "#,
         );
         check_no_diagnostics(
             r#"
 struct A(usize);
-fn main() { A { 0: 0 } }
+fn main() { A { 0: 0 }; }
 "#,
         );
 
@@ -660,14 +826,14 @@ fn main() { A { 0: 0 } }
 struct A { a: &'static str }
 fn main() {
     let a = "haha";
-    A { a<|>: a }
+    A { a<|>: a };
 }
 "#,
             r#"
 struct A { a: &'static str }
 fn main() {
     let a = "haha";
-    A { a }
+    A { a };
 }
 "#,
         );
@@ -678,7 +844,7 @@ struct A { a: &'static str, b: &'static str }
 fn main() {
     let a = "haha";
     let b = "bb";
-    A { a<|>: a, b }
+    A { a<|>: a, b };
 }
 "#,
             r#"
@@ -686,7 +852,7 @@ struct A { a: &'static str, b: &'static str }
 fn main() {
     let a = "haha";
     let b = "bb";
-    A { a, b }
+    A { a, b };
 }
 "#,
         );
@@ -743,4 +909,284 @@ struct Foo {
     fn test_disabled_diagnostics() {
         check_disabled_diagnostics(r#"mod foo;"#, &["unresolved-module"]);
     }
+
+    #[test]
+    fn test_type_mismatch_add_reference() {
+        check_fix(
+            r#"
+fn takes_ref(x: &i32) {}
+fn main() {
+    let x = 92;
+    takes_ref(x<|>);
+}
+"#,
+            r#"
+fn takes_ref(x: &i32) {}
+fn main() {
+    let x = 92;
+    takes_ref(&x);
+}
+"#,
+        );
+        check_fix(
+            r#"
+fn takes_mut(x: &mut i32) {}
+fn main() {
+    takes_mut(<|>1 + 2);
+}
+"#,
+            r#"
+fn takes_mut(x: &mut i32) {}
+fn main() {
+    takes_mut(&mut (1 + 2));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_wrap_in_some() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:core
+use core::option::Option::{self, Some};
+
+fn first(x: u8) -> Option<u8> {
+    x<|>
+}
+//- /core/lib.rs crate:core
+pub mod option {
+    pub enum Option<T> { Some(T), None }
+}
+"#,
+            r#"
+use core::option::Option::{self, Some};
+
+fn first(x: u8) -> Option<u8> {
+    Some(x)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_call_into() {
+        check_fix(
+            r#"
+//- /main.rs crate:main deps:core
+struct Meters(u32);
+impl core::convert::From<u32> for Meters {
+    fn from(it: u32) -> Meters { Meters(it) }
+}
+
+fn distance(x: u32) -> Meters {
+    x<|>
+}
+//- /core/lib.rs crate:core
+pub mod convert {
+    pub trait From<T> { fn from(t: T) -> Self; }
+    pub trait Into<T> { fn into(self) -> T; }
+    impl<T, U: From<T>> Into<U> for T {
+        fn into(self) -> U { U::from(self) }
+    }
+}
+"#,
+            r#"
+struct Meters(u32);
+impl core::convert::From<u32> for Meters {
+    fn from(it: u32) -> Meters { Meters(it) }
+}
+
+fn distance(x: u32) -> Meters {
+    x.into()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_in_macro_call() {
+        check_fix(
+            r#"
+macro_rules! id { ($e:expr) => { $e } }
+fn takes_ref(x: &i32) {}
+fn main() {
+    let x = 92;
+    takes_ref(id!(x<|> + 1));
+}
+"#,
+            r#"
+macro_rules! id { ($e:expr) => { $e } }
+fn takes_ref(x: &i32) {}
+fn main() {
+    let x = 92;
+    takes_ref(id!(&(x + 1)));
+}
+"#,
+        );
+        check_no_fix(
+            r#"
+macro_rules! num { () => { 92 } }
+fn takes_ref(x: &i32) {}
+fn main() {
+    takes_ref(num!()<|>);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_is_experimental() {
+        let (analysis, file_id) = single_file(
+            r#"
+fn main() {
+    let x: u32 = "hello";
+}
+"#,
+        );
+        let diagnostics = analysis.diagnostics(file_id, false, None).unwrap();
+        assert!(diagnostics.is_empty(), "unexpected diagnostics:\n{:#?}", diagnostics);
+        let diagnostics = analysis.diagnostics(file_id, true, None).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_rename_incorrect_case() {
+        check_fix(
+            r#"
+pub struct test_struct<|> { one: i32 }
+
+pub fn some_fn(val: test_struct) -> test_struct {
+    test_struct { one: val.one + 1 }
+}
+"#,
+            r#"
+pub struct TestStruct { one: i32 }
+
+pub fn some_fn(val: TestStruct) -> TestStruct {
+    TestStruct { one: val.one + 1 }
+}
+"#,
+        );
+
+        check_fix(
+            r#"
+pub fn some_fn(NonSnakeCase<|>: u8) -> u8 {
+    NonSnakeCase
+}
+"#,
+            r#"
+pub fn some_fn(non_snake_case: u8) -> u8 {
+    non_snake_case
+}
+"#,
+        );
+
+        check_fix(
+            r#"
+pub fn SomeFn<|>(val: u8) -> u8 {
+    if val != 0 { SomeFn(val - 1) } else { val }
+}
+"#,
+            r#"
+pub fn some_fn(val: u8) -> u8 {
+    if val != 0 { some_fn(val - 1) } else { val }
+}
+"#,
+        );
+
+        check_fix(
+            r#"
+fn some_fn() {
+    let someWeird_Formatting<|> = 10;
+    another_func(someWeird_Formatting);
+}
+"#,
+            r#"
+fn some_fn() {
+    let some_weird_formatting = 10;
+    another_func(some_weird_formatting);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_uppercase_const_no_diagnostics() {
+        check_no_diagnostics(
+            r#"
+fn foo() {
+    const ANOTHER_ITEM: &str = "some_item";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_incorrect_case_struct_method() {
+        check_fix(
+            r#"
+pub struct TestStruct;
+
+impl TestStruct {
+    pub fn SomeFn<|>() -> TestStruct {
+        TestStruct
+    }
+}
+"#,
+            r#"
+pub struct TestStruct;
+
+impl TestStruct {
+    pub fn some_fn() -> TestStruct {
+        TestStruct
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_incorrect_case_is_a_weak_warning() {
+        check_expect(
+            r#"fn Foo() {}"#,
+            expect![[r#"
+                [
+                    Diagnostic {
+                        name: Some(
+                            "incorrect-ident-case",
+                        ),
+                        message: "Function `Foo` should have snake_case name, e.g. `foo`",
+                        range: 3..6,
+                        severity: WeakWarning,
+                        fix: Some(
+                            Fix {
+                                label: "Rename to foo",
+                                source_change: SourceChange {
+                                    source_file_edits: [
+                                        SourceFileEdit {
+                                            file_id: FileId(
+                                                1,
+                                            ),
+                                            edit: TextEdit {
+                                                indels: [
+                                                    Indel {
+                                                        insert: "foo",
+                                                        delete: 3..6,
+                                                    },
+                                                ],
+                                            },
+                                        },
+                                    ],
+                                    file_system_edits: [],
+                                    is_snippet: false,
+                                },
+                                fix_trigger_range: 3..6,
+                            },
+                        ),
+                    },
+                ]
+            "#]],
+        );
+    }
 }
@@ -1,14 +1,20 @@
 //! Provides a way to attach fixes to the diagnostics.
 //! The same module also has all curret custom fixes for the diagnostics implemented.
-use crate::Fix;
+use crate::{references::rename_with_semantics, FilePosition, Fix};
+use assists::utils::insert_use_statement;
 use ast::{edit::IndentLevel, make};
-use base_db::FileId;
+use base_db::{FileId, SourceDatabaseExt};
+use either::Either;
 use hir::{
     db::AstDatabase,
-    diagnostics::{Diagnostic, MissingFields, MissingOkInTailExpr, NoSuchField, UnresolvedModule},
+    diagnostics::{
+        Diagnostic, IncorrectCase, MissingFields, MissingOkInTailExpr, NoSuchField, TypeMismatch,
+        TypeMismatchFix, UnresolvedMacroCall, UnresolvedModule, UnresolvedPath,
+    },
     HasSource, HirDisplay, Semantics, VariantDef,
 };
 use ide_db::{
+    imports_locator,
     source_change::{FileSystemEdit, SourceFileEdit},
     RootDatabase,
 };
@@ -31,6 +37,7 @@ impl DiagnosticWithFix for UnresolvedModule {
             FileSystemEdit::CreateFile {
                 anchor: self.file.original_file(sema.db),
                 dst: self.candidate.clone(),
+                initial_contents: String::new(),
             }
             .into(),
             unresolved_module.syntax().text_range(),
@@ -38,6 +45,22 @@ impl DiagnosticWithFix for UnresolvedModule {
     }
 }
 
+impl DiagnosticWithFix for UnresolvedPath {
+    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+        let root = sema.db.parse_or_expand(self.file)?;
+        let path = self.node.to_node(&root);
+        auto_import_fix(sema, self.file.original_file(sema.db), &path, false)
+    }
+}
+
+impl DiagnosticWithFix for UnresolvedMacroCall {
+    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+        let root = sema.db.parse_or_expand(self.file)?;
+        let path = self.node.to_node(&root).path()?;
+        auto_import_fix(sema, self.file.original_file(sema.db), &path, true)
+    }
+}
+
 impl DiagnosticWithFix for NoSuchField {
     fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
         let root = sema.db.parse_or_expand(self.file)?;
@@ -95,6 +118,131 @@ impl DiagnosticWithFix for MissingOkInTailExpr {
     }
 }
 
+impl DiagnosticWithFix for TypeMismatch {
+    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+        let fix = self.fix?;
+        let root = sema.db.parse_or_expand(self.file)?;
+        let expr = self.expr.to_node(&root);
+
+        // The expression may come from a macro expansion. Only fix it if it is
+        // written out as is in the file, rather than built by the macro.
+        let frange = sema.original_range(expr.syntax());
+        let file_text = sema.db.file_text(frange.file_id);
+        let text = &file_text[frange.range];
+        let tokens = |text: &str| text.split_whitespace().collect::<String>();
+        if tokens(text) != tokens(&expr.syntax().text().to_string()) {
+            return None;
+        }
+
+        let (label, replacement) = match fix {
+            TypeMismatchFix::AddReference { mutable } => {
+                let prefix = if mutable { "&mut " } else { "&" };
+                let label =
+                    if mutable { "Add mutable reference here" } else { "Add reference here" };
+                (label, format!("{}{}", prefix, parenthesize_for_prefix(&expr, text)))
+            }
+            TypeMismatchFix::CallInto => (
+                "Convert with `.into()`",
+                format!("{}.into()", parenthesize_for_postfix(&expr, text)),
+            ),
+            TypeMismatchFix::WrapInSome => ("Wrap with `Some`", format!("Some({})", text)),
+        };
+
+        let edit = TextEdit::replace(frange.range, replacement);
+        let source_change = SourceFileEdit { file_id: frange.file_id, edit }.into();
+        Some(Fix::new(label, source_change, frange.range))
+    }
+}
+
+/// Wraps `text`, the source of `expr`, in parentheses unless it binds tighter
+/// than a unary operator.
+fn parenthesize_for_prefix(expr: &ast::Expr, text: &str) -> String {
+    match expr {
+        ast::Expr::BinExpr(_) | ast::Expr::CastExpr(_) | ast::Expr::RangeExpr(_) => {
+            format!("({})", text)
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Wraps `text`, the source of `expr`, in parentheses unless it binds as tight
+/// as a method call.
+fn parenthesize_for_postfix(expr: &ast::Expr, text: &str) -> String {
+    match expr {
+        ast::Expr::ArrayExpr(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::Literal(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::PathExpr(_)
+        | ast::Expr::RecordExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::TupleExpr(_) => text.to_string(),
+        _ => format!("({})", text),
+    }
+}
+
+impl DiagnosticWithFix for IncorrectCase {
+    fn fix(&self, sema: &Semantics<RootDatabase>) -> Option<Fix> {
+        let root = sema.db.parse_or_expand(self.file)?;
+        let name_node = self.ident.to_node(&root);
+
+        let frange = sema.original_range(name_node.syntax());
+        let file_position = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+
+        let rename_changes = rename_with_semantics(sema, file_position, &self.suggested_text)?.info;
+
+        let label = format!("Rename to {}", self.suggested_text);
+        Some(Fix::new(label, rename_changes, frange.range))
+    }
+}
+
+/// Imports the first segment of the unresolved `path`, if there is an item
+/// with that name somewhere in the crate graph.
+fn auto_import_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    path: &ast::Path,
+    is_macro: bool,
+) -> Option<Fix> {
+    let mut first_path = path.clone();
+    while let Some(qualifier) = first_path.qualifier() {
+        first_path = qualifier;
+    }
+    // Importing something can't help if the path goes wrong later on.
+    if first_path != *path && sema.resolve_path(&first_path).is_some() {
+        return None;
+    }
+    let name_ref = first_path.segment()?.name_ref()?;
+
+    let module = sema.scope(path.syntax()).module()?;
+    let import = imports_locator::find_imports(sema, module.krate(), name_ref.text())
+        .into_iter()
+        .filter_map(|candidate| match candidate {
+            Either::Left(module_def) if !is_macro => module.find_use_path(sema.db, module_def),
+            Either::Right(macro_def) if is_macro => module.find_use_path(sema.db, macro_def),
+            _ => None,
+        })
+        .filter(|use_path| !use_path.segments.is_empty())
+        .min_by_key(|use_path| (use_path.segments.len(), use_path.to_string()))?;
+
+    let mut builder = TextEdit::builder();
+    insert_use_statement(path.syntax(), &import.to_string(), sema, &mut builder);
+    let edit = builder.finish();
+    if edit.is_empty() {
+        return None;
+    }
+    Some(Fix::new(
+        format!("Import `{}`", import),
+        SourceFileEdit { file_id, edit }.into(),
+        sema.original_range(path.syntax()).range,
+    ))
+}
+
 fn missing_record_expr_field_fix(
     sema: &Semantics<RootDatabase>,
     usage_file_id: FileId,
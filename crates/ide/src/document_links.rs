@@ -0,0 +1,240 @@
+use base_db::FileId;
+use hir::{ModuleSource, Semantics};
+use ide_db::RootDatabase;
+use syntax::{
+    ast::{self, AstNode, AstToken, AttrsOwner, HasQuotes, HasStringValue, NameOwner},
+    NodeOrToken, SyntaxToken, TextRange, TextSize,
+};
+
+#[derive(Debug)]
+pub struct DocumentLink {
+    pub range: TextRange,
+    pub target: DocumentLinkTarget,
+}
+
+#[derive(Debug)]
+pub enum DocumentLinkTarget {
+    File(FileId),
+    /// A file which is not part of the analysis, like the argument of
+    /// `include_str!`. `path` is relative to the directory of `anchor`.
+    Path {
+        anchor: FileId,
+        path: String,
+    },
+    Url(String),
+}
+
+// Feature: Document Links
+//
+// Makes the files referred to by `mod foo;` declarations, `#[path = "..."]`
+// attributes and the arguments of `include!`, `include_str!` and
+// `include_bytes!` clickable, as well as URLs in doc comments.
+pub(crate) fn document_links(db: &RootDatabase, file_id: FileId) -> Vec<DocumentLink> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(file_id);
+
+    let mut res = Vec::new();
+    for element in source_file.syntax().descendants_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                if let Some(module) = ast::Module::cast(node.clone()) {
+                    module_links(&sema, &module, &mut res);
+                } else if let Some(macro_call) = ast::MacroCall::cast(node) {
+                    if let Some(link) = include_link(&sema, file_id, &macro_call) {
+                        res.push(link);
+                    }
+                }
+            }
+            NodeOrToken::Token(token) => {
+                if let Some(comment) = ast::Comment::cast(token) {
+                    if comment.kind().doc.is_some() {
+                        url_links(&comment, &mut res);
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Links the name of an out-of-line module, and its `#[path]` attribute if
+/// there is one, to the file of the module.
+fn module_links(sema: &Semantics<RootDatabase>, module: &ast::Module, acc: &mut Vec<DocumentLink>) {
+    if module.item_list().is_some() {
+        return;
+    }
+    let def = match sema.to_def(module) {
+        Some(it) => it,
+        None => return,
+    };
+    let src = def.definition_source(sema.db);
+    if !matches!(src.value, ModuleSource::SourceFile(_)) {
+        return;
+    }
+    let file_id = src.file_id.original_file(sema.db);
+
+    if let Some(name) = module.name() {
+        let range = name.syntax().text_range();
+        acc.push(DocumentLink { range, target: DocumentLinkTarget::File(file_id) });
+    }
+    let path_attr = module.attrs().find(|attr| attr.simple_name().as_deref() == Some("path"));
+    let literal = path_attr.and_then(|attr| attr.literal()).map(|it| it.token());
+    if let Some((range, _)) = literal.and_then(string_literal) {
+        acc.push(DocumentLink { range, target: DocumentLinkTarget::File(file_id) });
+    }
+}
+
+/// Links the path argument of `include!`, `include_str!` and `include_bytes!`.
+fn include_link(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    macro_call: &ast::MacroCall,
+) -> Option<DocumentLink> {
+    if !sema.resolve_macro_call(macro_call)?.is_include_macro() {
+        return None;
+    }
+    let token = macro_call
+        .token_tree()?
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| ast::String::can_cast(it.kind()) || ast::RawString::can_cast(it.kind()))?;
+    let (range, path) = string_literal(token)?;
+    // Files which are only ever included as text or bytes are usually not
+    // loaded, in which case the link points to the path itself.
+    let target = match sema.resolve_include_path(macro_call, &path) {
+        Some(it) => DocumentLinkTarget::File(it),
+        None => DocumentLinkTarget::Path { anchor: file_id, path },
+    };
+    Some(DocumentLink { range, target })
+}
+
+/// Returns the range between the quotes of a string literal and its value.
+fn string_literal(token: SyntaxToken) -> Option<(TextRange, String)> {
+    if let Some(string) = ast::String::cast(token.clone()) {
+        return Some((string.text_range_between_quotes()?, string.value()?.into_owned()));
+    }
+    let string = ast::RawString::cast(token)?;
+    Some((string.text_range_between_quotes()?, string.value()?.into_owned()))
+}
+
+fn url_links(comment: &ast::Comment, acc: &mut Vec<DocumentLink>) {
+    let text = comment.text().as_str();
+    let offset = comment.syntax().text_range().start();
+    let mut pos = 0;
+    while let Some(idx) = text[pos..].find("http") {
+        let start = pos + idx;
+        let rest = &text[start..];
+        let scheme_len = if rest.starts_with("https://") {
+            "https://".len()
+        } else if rest.starts_with("http://") {
+            "http://".len()
+        } else {
+            pos = start + "http".len();
+            continue;
+        };
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "<>()[]{}\"'`".contains(c))
+            .unwrap_or(rest.len());
+        let url = rest[..len].trim_end_matches(|c| ".,;:!?*".contains(c));
+        pos = start + len;
+
+        let is_word_start =
+            !matches!(text[..start].chars().next_back(), Some(c) if c.is_alphanumeric());
+        if !is_word_start || url.len() == scheme_len {
+            continue;
+        }
+        let range = TextRange::at(offset + TextSize::from(start as u32), TextSize::of(url));
+        acc.push(DocumentLink { range, target: DocumentLinkTarget::Url(url.to_string()) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect::{expect, Expect};
+
+    use crate::mock_analysis::MockAnalysis;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let mock = MockAnalysis::with_files(ra_fixture);
+        let file_id = mock.id_of("/lib.rs");
+        let analysis = mock.analysis();
+        let text = analysis.file_text(file_id).unwrap();
+        let links = analysis
+            .document_links(file_id)
+            .unwrap()
+            .into_iter()
+            .map(|link| format!("{} -> {:?}\n", &text[link.range], link.target))
+            .collect::<String>();
+        expect.assert_eq(&links);
+    }
+
+    #[test]
+    fn links_module_files() {
+        check(
+            r#"
+//- /lib.rs
+mod foo;
+#[path = "other/baz.rs"]
+mod bar;
+mod inline {}
+mod missing;
+
+//- /foo.rs
+//- /other/baz.rs
+"#,
+            expect![[r#"
+                foo -> File(FileId(2))
+                bar -> File(FileId(3))
+                other/baz.rs -> File(FileId(3))
+            "#]],
+        );
+    }
+
+    #[test]
+    fn links_include_arguments() {
+        check(
+            r#"
+//- /lib.rs
+#[rustc_builtin_macro]
+macro_rules! include { () => {} }
+#[rustc_builtin_macro]
+macro_rules! include_str { () => {} }
+#[rustc_builtin_macro]
+macro_rules! include_bytes { () => {} }
+macro_rules! not_include { ($e:expr) => {} }
+
+include!("foo/bar.rs");
+const README: &str = include_str!("../README.md");
+const LOGO: &[u8] = include_bytes!(r"logo.png");
+not_include!("baz.rs");
+
+//- /foo/bar.rs
+//- /baz.rs
+"#,
+            expect![[r#"
+                foo/bar.rs -> File(FileId(2))
+                ../README.md -> Path { anchor: FileId(1), path: "../README.md" }
+                logo.png -> Path { anchor: FileId(1), path: "logo.png" }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn links_urls_in_doc_comments() {
+        check(
+            r#"
+//- /lib.rs
+/// See https://example.com/docs, or [the book](https://doc.rust-lang.org/book/).
+/// <http://example.org/a?b=c> and https:// alone.
+// https://not.a.doc.comment
+fn f() {}
+"#,
+            expect![[r#"
+                https://example.com/docs -> Url("https://example.com/docs")
+                https://doc.rust-lang.org/book/ -> Url("https://doc.rust-lang.org/book/")
+                http://example.org/a?b=c -> Url("http://example.org/a?b=c")
+            "#]],
+        );
+    }
+}
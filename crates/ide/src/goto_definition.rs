@@ -12,7 +12,7 @@ use syntax::{
 
 use crate::{
     display::{ToNav, TryToNav},
-    FilePosition, NavigationTarget, RangeInfo,
+    intra_doc_links, FilePosition, NavigationTarget, RangeInfo,
 };
 
 // Feature: Go to Definition
@@ -34,6 +34,13 @@ pub(crate) fn goto_definition(
     let token = sema.descend_into_macros(original_token.clone());
     let parent = token.parent();
 
+    if let Some((range, def)) =
+        intra_doc_links::doc_link_at(&sema, position.file_id, &token, position.offset)
+    {
+        let nav = def.try_to_nav(sema.db)?;
+        return Some(RangeInfo::new(range, vec![nav]));
+    }
+
     let nav_targets = match_ast! {
         match parent {
             ast::NameRef(name_ref) => {
@@ -983,6 +990,34 @@ trait Iterator {
 }
 
 fn g() -> <() as Iterator<A = (), B<|> = u8>>::A {}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_for_intra_doc_link() {
+        check(
+            r#"
+mod foo {
+    pub struct Bar;
+    impl Bar {
+        pub fn baz(&self) {}
+             //^^^
+    }
+}
+
+/// Calls [`foo::Bar::b<|>az`].
+fn f() {}
+"#,
+        );
+        check(
+            r#"
+//! See [the field][x].
+//!
+//! [x]: Point::<|>x
+
+struct Point { x: u32 }
+             //^
 "#,
         );
     }
@@ -1,7 +1,7 @@
 use base_db::SourceDatabase;
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, Documentation, FieldSource, HasSource, HirDisplay,
-    Module, ModuleDef, ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, CaptureKind, Closure, ClosureKind, Documentation,
+    FieldSource, HasSource, HirDisplay, Module, ModuleDef, ModuleSource, Semantics, Type,
 };
 use ide_db::{
     defs::{classify_name, classify_name_ref, Definition},
@@ -14,6 +14,7 @@ use test_utils::mark;
 
 use crate::{
     display::{macro_label, ShortLabel, ToNav, TryToNav},
+    intra_doc_links,
     markup::Markup,
     runnables::runnable,
     FileId, FilePosition, NavigationTarget, RangeInfo, Runnable,
@@ -92,7 +93,7 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
     };
     if let Some(definition) = definition {
         if let Some(markup) = hover_for_definition(db, definition) {
-            res.markup = markup;
+            res.markup = intra_doc_links::rewrite_links(db, markup.as_str(), &definition).into();
             if let Some(action) = show_implementations_action(db, definition) {
                 res.actions.push(action);
             }
@@ -125,11 +126,50 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         }
     };
 
-    res.markup = Markup::fenced_block(&ty.display(db));
+    res.markup = match ty.as_closure() {
+        Some(closure) => hover_for_closure(db, &ty, closure),
+        None => Markup::fenced_block(&ty.display(db)),
+    };
     let range = sema.original_range(&node).range;
     Some(RangeInfo::new(range, res))
 }
 
+fn hover_for_closure(db: &RootDatabase, ty: &Type, closure: Closure) -> Markup {
+    let trait_name = match closure.kind(db) {
+        ClosureKind::Fn => "Fn",
+        ClosureKind::FnMut => "FnMut",
+        ClosureKind::FnOnce => "FnOnce",
+    };
+    let mut signature = format!("impl {}(", trait_name);
+    if let Some(callable) = ty.as_callable(db) {
+        let params =
+            callable.params(db).into_iter().map(|(_, ty)| ty.display(db).to_string()).join(", ");
+        signature.push_str(&params);
+        signature.push(')');
+        let ret_ty = callable.return_type();
+        if !ret_ty.is_unit() {
+            format_to!(signature, " -> {}", ret_ty.display(db));
+        }
+    } else {
+        signature.push(')');
+    }
+
+    let mut markup = Markup::fenced_block(&signature).to_string();
+    let captures = closure.captures(db);
+    if !captures.is_empty() {
+        markup.push_str("\n\n## Captures");
+        for capture in captures {
+            let kind = match capture.kind() {
+                CaptureKind::SharedRef => "by immutable borrow",
+                CaptureKind::MutableRef => "by mutable borrow",
+                CaptureKind::ByValue => "by value",
+            };
+            format_to!(markup, "\n* `{}` {}", capture.display_place(db), kind);
+        }
+    }
+    markup.into()
+}
+
 fn show_implementations_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
     fn to_action(nav_target: NavigationTarget) -> HoverAction {
         HoverAction::Implementaion(FilePosition {
@@ -400,6 +440,55 @@ fn main() {
         );
     }
 
+    #[test]
+    fn hover_shows_closure_signature_and_captures() {
+        check(
+            r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+
+struct S { a: u32, b: u32 }
+fn main() {
+    let mut s = S { a: 0, b: 0 };
+    let total = 0;
+    let c = <|>|x: u32| { s.a = x + total; s.a };
+}
+"#,
+            expect![[r###"
+                *|x: u32| { s.a = x + total; s.a }*
+                ```rust
+                impl FnMut(u32) -> u32
+                ```
+
+                ## Captures
+                * `s.a` by mutable borrow
+                * `total` by immutable borrow
+            "###]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_array_length() {
+        check(
+            r#"
+const LEN: usize = 2 + 2;
+fn main() {
+    let b<|>uf = [0u8; LEN];
+}
+"#,
+            expect![[r#"
+                *buf*
+                ```rust
+                [u8; 4]
+                ```
+            "#]],
+        );
+    }
+
     #[test]
     fn hover_shows_long_type_of_an_expression() {
         check(
@@ -1238,6 +1327,37 @@ fn foo() { let bar = Ba<|>r; }
         );
     }
 
+    #[test]
+    fn test_hover_rewrites_intra_doc_links() {
+        check(
+            r#"
+pub mod foo {
+    pub struct Baz { pub qux: u32 }
+    impl Baz {
+        pub fn new() -> Baz { Baz { qux: 0 } }
+    }
+}
+use foo::Baz;
+
+/// Wraps a [`Baz`], see [its constructor](Baz::new) and [foo::Baz::qux].
+/// [Unresolved] and [a url](https://example.com) are kept.
+struct Bar;
+
+fn f() { let bar = Ba<|>r; }
+"#,
+            expect![[r#"
+                *Bar*
+                ```rust
+                struct Bar
+                ```
+                ___
+
+                Wraps a [`Baz`](file:///main.rs#L2), see [its constructor](file:///main.rs#L4) and [foo::Baz::qux](file:///main.rs#L2).
+                [Unresolved] and [a url](https://example.com) are kept.
+            "#]],
+        );
+    }
+
     #[test]
     fn test_hover_struct_doc_attr() {
         check(
@@ -1,9 +1,9 @@
-use hir::{Adt, Callable, HirDisplay, Semantics, Type};
+use hir::{Adt, Callable, HirDisplay, Mutability, Semantics, Type};
 use ide_db::RootDatabase;
 use stdx::to_lower_snake_case;
 use syntax::{
-    ast::{self, ArgListOwner, AstNode},
-    match_ast, Direction, NodeOrToken, SmolStr, SyntaxKind, TextRange, T,
+    ast::{self, ArgListOwner, AstNode, GenericParamsOwner},
+    match_ast, Direction, NodeOrToken, SmolStr, SyntaxKind, SyntaxNode, TextRange, T,
 };
 
 use crate::FileId;
@@ -15,12 +15,23 @@ pub struct InlayHintsConfig {
     pub type_hints: bool,
     pub parameter_hints: bool,
     pub chaining_hints: bool,
+    pub lifetime_elision_hints: bool,
+    pub closure_return_type_hints: bool,
+    pub reborrow_hints: bool,
     pub max_length: Option<usize>,
 }
 
 impl Default for InlayHintsConfig {
     fn default() -> Self {
-        Self { type_hints: true, parameter_hints: true, chaining_hints: true, max_length: None }
+        Self {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            lifetime_elision_hints: false,
+            closure_return_type_hints: false,
+            reborrow_hints: false,
+            max_length: None,
+        }
     }
 }
 
@@ -29,6 +40,14 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    /// An elided lifetime or generic lifetime parameter list, the label is
+    /// meant to be inserted verbatim after the range.
+    LifetimeHint,
+    /// The return type of a closure, shown after its parameter list.
+    ClosureReturnTypeHint,
+    /// An implicit reborrow or deref coercion of an argument, the label is
+    /// meant to be inserted verbatim before the range.
+    ReborrowHint,
 }
 
 #[derive(Debug)]
@@ -49,6 +68,12 @@ pub struct InlayHint {
 // * names of function arguments
 // * types of chained expressions
 //
+// Optionally, it can also show
+//
+// * elided lifetimes in function signatures
+// * return types of closures
+// * implicit reborrows and deref coercions of arguments
+//
 // **Note:** VS Code does not have native support for inlay hints https://github.com/microsoft/vscode/issues/16221[yet] and the hints are implemented using decorations.
 // This approach has limitations, the caret movement and bracket highlighting near the edges of the hint may be weird:
 // https://github.com/rust-analyzer/rust-analyzer/issues/1623[1], https://github.com/rust-analyzer/rust-analyzer/issues/3453[2].
@@ -75,9 +100,17 @@ pub(crate) fn inlay_hints(
 
         match_ast! {
             match node {
-                ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
-                ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
+                ast::CallExpr(it) => {
+                    get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it.clone()));
+                    get_reborrow_hints(&mut res, &sema, config, ast::Expr::from(it));
+                },
+                ast::MethodCallExpr(it) => {
+                    get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it.clone()));
+                    get_reborrow_hints(&mut res, &sema, config, ast::Expr::from(it));
+                },
                 ast::IdentPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
+                ast::Fn(it) => { get_lifetime_elision_hints(&mut res, config, it); },
+                ast::ClosureExpr(it) => { get_closure_return_type_hints(&mut res, &sema, config, it); },
                 _ => (),
             }
         }
@@ -201,6 +234,212 @@ fn get_bind_pat_hints(
     Some(())
 }
 
+fn get_lifetime_elision_hints(
+    acc: &mut Vec<InlayHint>,
+    config: &InlayHintsConfig,
+    func: ast::Fn,
+) -> Option<()> {
+    if !config.lifetime_elision_hints {
+        return None;
+    }
+
+    let param_list = func.param_list()?;
+    let mut hints = Vec::new();
+    let mut n_elided = 0;
+    let mut next_name = || {
+        let name = format!("'{}", n_elided);
+        n_elided += 1;
+        name
+    };
+
+    // The lifetime of `&self`, or the only lifetime in the parameters, is
+    // used for the elided lifetimes in the return type.
+    let mut self_lifetime = None;
+    let mut input_lifetimes = Vec::new();
+    if let Some(self_param) = param_list.self_param() {
+        if let Some(amp) = self_param.amp_token() {
+            let name = match self_param.lifetime_token() {
+                Some(lifetime) => lifetime.text().to_string(),
+                None => {
+                    let name = next_name();
+                    hints.push((amp.text_range(), format!("{} ", name)));
+                    name
+                }
+            };
+            self_lifetime = Some(name);
+        }
+    }
+    for ty in param_list.params().filter_map(|param| param.ty()) {
+        for ref_type in elision_scope_ref_types(&ty) {
+            match ref_type.lifetime_token() {
+                Some(lifetime) if lifetime.text() != "'_" => {
+                    input_lifetimes.push(Some(lifetime.text().to_string()))
+                }
+                // `'_` can't be given a name in place, but it still counts
+                Some(_) => input_lifetimes.push(None),
+                None => {
+                    let name = next_name();
+                    if let Some(amp) = ref_type.amp_token() {
+                        hints.push((amp.text_range(), format!("{} ", name)));
+                    }
+                    input_lifetimes.push(Some(name));
+                }
+            }
+        }
+    }
+
+    let output_lifetime = match self_lifetime {
+        Some(it) => Some(it),
+        None if input_lifetimes.len() == 1 => input_lifetimes.pop().flatten(),
+        None => None,
+    };
+    if let Some(output_lifetime) = output_lifetime {
+        let ret_ty = func.ret_type().and_then(|it| it.ty());
+        for ref_type in ret_ty.iter().flat_map(elision_scope_ref_types) {
+            if let (None, Some(amp)) = (ref_type.lifetime_token(), ref_type.amp_token()) {
+                hints.push((amp.text_range(), format!("{} ", output_lifetime)));
+            }
+        }
+    }
+
+    if n_elided > 0 {
+        let names = (0..n_elided).map(|it| format!("'{}", it)).collect::<Vec<_>>().join(", ");
+        match func.generic_param_list() {
+            Some(generic_params) => {
+                let l_angle = generic_params.l_angle_token()?;
+                let label = if generic_params.generic_params().next().is_some() {
+                    format!("{}, ", names)
+                } else {
+                    names
+                };
+                hints.push((l_angle.text_range(), label));
+            }
+            None => hints.push((func.name()?.syntax().text_range(), format!("<{}>", names))),
+        }
+    }
+
+    hints.sort_by_key(|(range, _)| range.start());
+    acc.extend(hints.into_iter().map(|(range, label)| InlayHint {
+        range,
+        kind: InlayKind::LifetimeHint,
+        label: label.into(),
+    }));
+    Some(())
+}
+
+/// Returns the reference types in `ty` which take part in lifetime elision for
+/// the surrounding function, skipping function pointers and `Fn` traits, which
+/// have their own elision rules.
+fn elision_scope_ref_types(ty: &ast::Type) -> Vec<ast::RefType> {
+    fn go(node: &SyntaxNode, acc: &mut Vec<ast::RefType>) {
+        let is_fn_sugar = matches!(
+            ast::PathSegment::cast(node.clone()),
+            Some(segment) if segment.param_list().is_some()
+        );
+        if ast::FnPtrType::can_cast(node.kind()) || is_fn_sugar {
+            return;
+        }
+        if let Some(ref_type) = ast::RefType::cast(node.clone()) {
+            acc.push(ref_type);
+        }
+        node.children().for_each(|child| go(&child, acc));
+    }
+
+    let mut res = Vec::new();
+    go(ty.syntax(), &mut res);
+    res
+}
+
+fn get_closure_return_type_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    closure: ast::ClosureExpr,
+) -> Option<()> {
+    if !config.closure_return_type_hints {
+        return None;
+    }
+    if closure.ret_type().is_some() {
+        return None;
+    }
+
+    let param_list = closure.param_list()?;
+    let callable = sema.type_of_expr(&closure.into())?.as_callable(sema.db)?;
+    let ty = callable.return_type();
+    if ty.is_unknown() {
+        return None;
+    }
+
+    acc.push(InlayHint {
+        range: param_list.syntax().text_range(),
+        kind: InlayKind::ClosureReturnTypeHint,
+        label: ty.display_truncated(sema.db, config.max_length).to_string().into(),
+    });
+    Some(())
+}
+
+fn get_reborrow_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    expr: ast::Expr,
+) -> Option<()> {
+    if !config.reborrow_hints {
+        return None;
+    }
+
+    let args = match &expr {
+        ast::Expr::CallExpr(expr) => expr.arg_list()?.args(),
+        ast::Expr::MethodCallExpr(expr) => expr.arg_list()?.args(),
+        _ => return None,
+    };
+
+    let callable = get_callable(sema, &expr)?;
+    let hints =
+        callable.params(sema.db).into_iter().zip(args).filter_map(|((_, param_ty), arg)| {
+            let label = reborrow_label(sema, &arg, &param_ty)?;
+            Some(InlayHint {
+                range: arg.syntax().text_range(),
+                kind: InlayKind::ReborrowHint,
+                label: label.into(),
+            })
+        });
+
+    acc.extend(hints);
+    Some(())
+}
+
+/// Returns the implicit `&*`, `&mut *` or `&**...` that the compiler inserts
+/// when passing `arg` to a parameter of type `param_ty`, if any.
+fn reborrow_label(
+    sema: &Semantics<RootDatabase>,
+    arg: &ast::Expr,
+    param_ty: &Type,
+) -> Option<String> {
+    let (arg_pointee, arg_mutability) = sema.type_of_expr(arg)?.as_reference()?;
+    let (param_pointee, param_mutability) = param_ty.as_reference()?;
+    if arg_pointee.is_unknown() || param_pointee.is_unknown() {
+        return None;
+    }
+
+    let n_derefs = arg_pointee.autoderef(sema.db).position(|ty| ty == param_pointee)?;
+    if n_derefs == 0 {
+        match (arg_mutability, param_mutability) {
+            // Taking a fresh `&mut` is not a reborrow of anything.
+            (Mutability::Mut, Mutability::Mut) if !matches!(arg, ast::Expr::RefExpr(_)) => {}
+            (Mutability::Mut, Mutability::Shared) => {}
+            _ => return None,
+        }
+    }
+
+    let mut label = String::from("&");
+    if param_mutability == Mutability::Mut {
+        label.push_str("mut ");
+    }
+    label.push_str(&"*".repeat(n_derefs + 1));
+    Some(label)
+}
+
 fn pat_is_enum_variant(db: &RootDatabase, bind_pat: &ast::IdentPat, pat_ty: &Type) -> bool {
     if let Some(Adt::Enum(enum_data)) = pat_ty.as_adt() {
         let pat_text = bind_pat.to_string();
@@ -341,6 +580,17 @@ mod tests {
 
     use crate::{inlay_hints::InlayHintsConfig, mock_analysis::single_file};
 
+    /// All hints disabled, tests enable the ones they are about.
+    const TEST_CONFIG: InlayHintsConfig = InlayHintsConfig {
+        type_hints: false,
+        parameter_hints: false,
+        chaining_hints: false,
+        lifetime_elision_hints: false,
+        closure_return_type_hints: false,
+        reborrow_hints: false,
+        max_length: None,
+    };
+
     fn check(ra_fixture: &str) {
         check_with_config(InlayHintsConfig::default(), ra_fixture);
     }
@@ -363,12 +613,7 @@ mod tests {
     #[test]
     fn param_hints_only() {
         check_with_config(
-            InlayHintsConfig {
-                parameter_hints: true,
-                type_hints: false,
-                chaining_hints: false,
-                max_length: None,
-            },
+            InlayHintsConfig { parameter_hints: true, ..TEST_CONFIG },
             r#"
 fn foo(a: i32, b: i32) -> i32 { a + b }
 fn main() {
@@ -385,12 +630,7 @@ fn main() {
     #[test]
     fn hints_disabled() {
         check_with_config(
-            InlayHintsConfig {
-                type_hints: false,
-                parameter_hints: false,
-                chaining_hints: false,
-                max_length: None,
-            },
+            TEST_CONFIG,
             r#"
 fn foo(a: i32, b: i32) -> i32 { a + b }
 fn main() {
@@ -402,12 +642,7 @@ fn main() {
     #[test]
     fn type_hints_only() {
         check_with_config(
-            InlayHintsConfig {
-                type_hints: true,
-                parameter_hints: false,
-                chaining_hints: false,
-                max_length: None,
-            },
+            InlayHintsConfig { type_hints: true, ..TEST_CONFIG },
             r#"
 fn foo(a: i32, b: i32) -> i32 { a + b }
 fn main() {
@@ -778,12 +1013,7 @@ fn main() {
     #[test]
     fn chaining_hints_ignore_comments() {
         check_expect(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..TEST_CONFIG },
             r#"
 struct A(B);
 impl A { fn into_b(self) -> B { self.0 } }
@@ -817,12 +1047,7 @@ fn main() {
     #[test]
     fn chaining_hints_without_newlines() {
         check_with_config(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..TEST_CONFIG },
             r#"
 struct A(B);
 impl A { fn into_b(self) -> B { self.0 } }
@@ -839,12 +1064,7 @@ fn main() {
     #[test]
     fn struct_access_chaining_hints() {
         check_expect(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..TEST_CONFIG },
             r#"
 struct A { pub b: B }
 struct B { pub c: C }
@@ -883,12 +1103,7 @@ fn main() {
     #[test]
     fn generic_chaining_hints() {
         check_expect(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..TEST_CONFIG },
             r#"
 struct A<T>(T);
 struct B<T>(T);
@@ -924,4 +1139,126 @@ fn main() {
             "#]],
         );
     }
+
+    #[test]
+    fn lifetime_elision_hints() {
+        check_expect(
+            InlayHintsConfig { lifetime_elision_hints: true, ..TEST_CONFIG },
+            r#"
+struct S;
+impl S {
+    fn get(&self, key: &str) -> &u32 { loop {} }
+}
+fn first<T>(xs: &[T], f: fn(&T) -> &T) -> &T { loop {} }
+fn pick(a: &u8, b: &u8) -> u8 { *a }
+fn named<'a>(a: &'a u8) -> &u8 { a }
+fn no_refs(x: u8) -> u8 { x }
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 26..29,
+                        kind: LifetimeHint,
+                        label: "<'0, '1>",
+                    },
+                    InlayHint {
+                        range: 30..31,
+                        kind: LifetimeHint,
+                        label: "'0 ",
+                    },
+                    InlayHint {
+                        range: 42..43,
+                        kind: LifetimeHint,
+                        label: "'1 ",
+                    },
+                    InlayHint {
+                        range: 51..52,
+                        kind: LifetimeHint,
+                        label: "'0 ",
+                    },
+                    InlayHint {
+                        range: 78..79,
+                        kind: LifetimeHint,
+                        label: "'0, ",
+                    },
+                    InlayHint {
+                        range: 86..87,
+                        kind: LifetimeHint,
+                        label: "'0 ",
+                    },
+                    InlayHint {
+                        range: 112..113,
+                        kind: LifetimeHint,
+                        label: "'0 ",
+                    },
+                    InlayHint {
+                        range: 130..134,
+                        kind: LifetimeHint,
+                        label: "<'0, '1>",
+                    },
+                    InlayHint {
+                        range: 138..139,
+                        kind: LifetimeHint,
+                        label: "'0 ",
+                    },
+                    InlayHint {
+                        range: 146..147,
+                        kind: LifetimeHint,
+                        label: "'1 ",
+                    },
+                    InlayHint {
+                        range: 191..192,
+                        kind: LifetimeHint,
+                        label: "'a ",
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn closure_return_type_hints() {
+        check_with_config(
+            InlayHintsConfig { closure_return_type_hints: true, ..TEST_CONFIG },
+            r#"
+fn main() {
+    let double = |x: i32| x * 2;
+               //^^^^^^^^ i32
+    let unit = || {};
+             //^^ ()
+    let explicit = |x: u8| -> u8 { x };
+}"#,
+        );
+    }
+
+    #[test]
+    fn reborrow_hints() {
+        check_with_config(
+            InlayHintsConfig { reborrow_hints: true, ..TEST_CONFIG },
+            r#"
+#[lang = "deref"]
+trait Deref { type Target; fn deref(&self) -> &Self::Target; }
+
+struct Str;
+struct String;
+impl Deref for String { type Target = Str; fn deref(&self) -> &Str { loop {} } }
+
+fn takes_mut(x: &mut u32) {}
+fn takes_ref(x: &u32) {}
+fn takes_str(s: &Str) {}
+
+fn main(r: &mut u32, s: &String) {
+    takes_mut(r);
+            //^ &mut *
+    takes_ref(r);
+            //^ &*
+    takes_mut(&mut 0);
+    takes_ref(&0);
+    takes_str(s);
+            //^ &**
+    takes_str(&s);
+            //^^ &***
+}"#,
+        );
+    }
 }
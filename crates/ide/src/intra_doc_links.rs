@@ -0,0 +1,582 @@
+//! Resolves intra-doc links, like ``[`Vec::push`]`` or `[crate::foo::Bar]`, in
+//! documentation comments.
+
+use std::ops::Range;
+
+use base_db::{FileId, SourceDatabaseExt};
+use hir::{
+    Adt, AsAssocItem, AssocItem, AssocItemContainer, AttrDef, ModuleDef, PathResolution, Semantics,
+    VariantDef,
+};
+use ide_db::{defs::Definition, LineIndexDatabase, RootDatabase};
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, AstToken, CommentIter},
+    match_ast, AstNode, SyntaxNode, SyntaxToken, TextRange, TextSize,
+};
+use url::Url;
+
+use crate::display::TryToNav;
+
+/// Rewrites the intra-doc links in the documentation of `definition` to links
+/// to the local files of their targets, or to their docs.rs pages for library
+/// crates.
+pub(crate) fn rewrite_links(db: &RootDatabase, markdown: &str, definition: &Definition) -> String {
+    let owner = match doc_owner(definition) {
+        Some(it) => it,
+        None => return markdown.to_string(),
+    };
+
+    let mut buf = String::new();
+    let mut pos = 0;
+    for link in intra_doc_links(markdown) {
+        let (range, url) = match link.dest {
+            LinkDest::Inline(range) => (range, "{}"),
+            LinkDest::Shortcut => (link.label.end + 1..link.label.end + 1, "({})"),
+            LinkDest::Reference => continue,
+        };
+        let target = match resolve_doc_link(db, owner, &link.path) {
+            Some(it) => it,
+            None => continue,
+        };
+        let url = match target_url(db, target) {
+            Some(it) => url.replace("{}", &it),
+            None => continue,
+        };
+        buf.push_str(&markdown[pos..range.start]);
+        buf.push_str(&url);
+        pos = range.end;
+    }
+    buf.push_str(&markdown[pos..]);
+    buf
+}
+
+/// Returns the target of the intra-doc link under the cursor in a doc comment,
+/// and the range of the link text.
+pub(crate) fn doc_link_at(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    token: &SyntaxToken,
+    offset: TextSize,
+) -> Option<(TextRange, Definition)> {
+    let comment = ast::Comment::cast(token.clone())?;
+    comment.kind().doc?;
+    let owner_node = comment.syntax().parent();
+    let owner = doc_owner_of_node(sema, file_id, &owner_node)?;
+
+    // Link reference definitions and code blocks can span several comments.
+    let mut text = String::new();
+    let mut comment_ranges = Vec::new();
+    for comment in CommentIter::from_syntax_node(&owner_node) {
+        if comment.kind().doc.is_some() {
+            comment_ranges.push((text.len(), comment.syntax().text_range()));
+            text.push_str(comment.text());
+            text.push('\n');
+        }
+    }
+    let to_text = |offset: TextSize| {
+        let (start, range) = comment_ranges.iter().find(|(_, it)| it.contains_inclusive(offset))?;
+        Some(start + usize::from(offset - range.start()))
+    };
+    let from_text = |pos: usize| {
+        let (start, range) = comment_ranges.iter().rev().find(|(start, _)| *start <= pos)?;
+        Some(range.start() + TextSize::from((pos - start) as u32))
+    };
+
+    let pos = to_text(offset)?;
+    let link = intra_doc_links(&text).into_iter().find(|link| {
+        let dest = match &link.dest {
+            LinkDest::Inline(it) => it.clone(),
+            _ => link.label.clone(),
+        };
+        (link.label.start..=link.label.end).contains(&pos) || (dest.start..=dest.end).contains(&pos)
+    })?;
+    let def = resolve_doc_link(sema.db, owner, &link.path)?;
+    let range = TextRange::new(from_text(link.label.start)?, from_text(link.label.end)?);
+    Some((range, def))
+}
+
+/// A markdown link whose destination is a Rust path. Ranges are byte offsets
+/// into the markdown text.
+#[derive(Debug, PartialEq, Eq)]
+struct IntraDocLink {
+    /// The text between the brackets.
+    label: Range<usize>,
+    dest: LinkDest,
+    path: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LinkDest {
+    /// `[label](dest)`, or a link reference definition `[label]: dest`.
+    Inline(Range<usize>),
+    /// `[label]`, where the label is the path itself.
+    Shortcut,
+    /// `[label][ref]`, or `[label]` with a link reference definition.
+    Reference,
+}
+
+fn intra_doc_links(text: &str) -> Vec<IntraDocLink> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let content = doc_line_content(line);
+        let content_start = line_start + line.len() - content.len();
+        line_start += line.len() + 1;
+        if content.starts_with("```") || content.starts_with("~~~") {
+            in_code_block = !in_code_block;
+        } else if !in_code_block {
+            lines.push((content_start, content));
+        }
+    }
+
+    let mut res = Vec::new();
+    let mut definitions = FxHashMap::default();
+    for &(start, line) in &lines {
+        if let Some((label, dest)) = link_definition(line) {
+            definitions.insert(line[label.clone()].to_lowercase(), line[dest.clone()].to_string());
+            if let Some(path) = doc_path(&line[dest.clone()]) {
+                let label = start + label.start..start + label.end;
+                let dest = LinkDest::Inline(start + dest.start..start + dest.end);
+                res.push(IntraDocLink { label, dest, path });
+            }
+        }
+    }
+    for &(start, line) in &lines {
+        if link_definition(line).is_none() {
+            inline_links(line, start, &definitions, &mut res);
+        }
+    }
+    res.sort_by_key(|link| link.label.start);
+    res
+}
+
+/// Strips the comment prefix from a line of a doc comment.
+fn doc_line_content(line: &str) -> &str {
+    let line = line.trim_start();
+    let line = ["///", "//!", "/**", "/*!", "*"]
+        .iter()
+        .find(|prefix| line.starts_with(*prefix))
+        .map_or(line, |prefix| &line[prefix.len()..]);
+    line.trim_start()
+}
+
+/// Parses a link reference definition, `[label]: dest`.
+fn link_definition(line: &str) -> Option<(Range<usize>, Range<usize>)> {
+    if !line.starts_with('[') {
+        return None;
+    }
+    let label_end = line.find("]:")?;
+    let dest = &line[label_end + 2..];
+    let dest_start = label_end + 2 + dest.len() - dest.trim_start().len();
+    let dest_len = line[dest_start..].find(char::is_whitespace).unwrap_or(line.len() - dest_start);
+    Some((1..label_end, dest_start..dest_start + dest_len))
+}
+
+fn inline_links(
+    line: &str,
+    offset: usize,
+    definitions: &FxHashMap<String, String>,
+    acc: &mut Vec<IntraDocLink>,
+) {
+    let mut pos = 0;
+    while pos < line.len() {
+        let rest = &line[pos..];
+        if rest.starts_with('`') {
+            // Brackets in code spans are not links.
+            let n_ticks = rest.len() - rest.trim_start_matches('`').len();
+            let ticks = &rest[..n_ticks];
+            pos += match rest[n_ticks..].find(ticks) {
+                Some(it) => 2 * n_ticks + it,
+                None => n_ticks,
+            };
+            continue;
+        }
+        if !rest.starts_with('[') {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+        let label_len = match rest[1..].find(&['[', ']'][..]) {
+            Some(it) if rest[1 + it..].starts_with(']') => it,
+            _ => {
+                pos += 1;
+                continue;
+            }
+        };
+        let label = pos + 1..pos + 1 + label_len;
+        let after = &line[label.end + 1..];
+
+        let mut link_end = label.end + 1;
+        let (dest, path) = if after.starts_with('(') {
+            let dest_len = match closing_paren(after) {
+                Some(it) => it - 1,
+                None => {
+                    pos = link_end;
+                    continue;
+                }
+            };
+            let dest = label.end + 2..label.end + 2 + dest_len;
+            link_end = dest.end + 1;
+            let path = doc_path(&line[dest.clone()]);
+            (LinkDest::Inline(offset + dest.start..offset + dest.end), path)
+        } else if after.starts_with('[') {
+            let ref_len = match after.find(']') {
+                Some(it) => it - 1,
+                None => {
+                    pos = link_end;
+                    continue;
+                }
+            };
+            let reference = &after[1..1 + ref_len];
+            let reference = if reference.is_empty() { &line[label.clone()] } else { reference };
+            link_end += ref_len + 2;
+            let path = match definitions.get(&reference.to_lowercase()) {
+                Some(dest) => doc_path(dest),
+                None if ref_len == 0 => doc_path(reference),
+                None => None,
+            };
+            (LinkDest::Reference, path)
+        } else {
+            match definitions.get(&line[label.clone()].to_lowercase()) {
+                Some(dest) => (LinkDest::Reference, doc_path(dest)),
+                None => (LinkDest::Shortcut, doc_path(&line[label.clone()])),
+            }
+        };
+
+        if let Some(path) = path {
+            acc.push(IntraDocLink { label: offset + label.start..offset + label.end, dest, path });
+        }
+        pos = link_end;
+    }
+}
+
+/// Returns the index of the parenthesis closing the one `text` starts with.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => return Some(idx),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the path a link destination refers to, without backticks,
+/// disambiguators like `struct@` and suffixes like `()` and `!`.
+fn doc_path(dest: &str) -> Option<String> {
+    let mut path = dest.trim().trim_matches('`');
+    if let Some(idx) = path.find('@') {
+        if !path[..idx].chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        path = &path[idx + 1..];
+    }
+    let path = path.trim_end_matches("()").trim_end_matches('!');
+    let is_path = path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':');
+    if path.is_empty() || !is_path {
+        return None;
+    }
+    Some(path.to_string())
+}
+
+fn resolve_doc_link(db: &RootDatabase, owner: AttrDef, path: &str) -> Option<Definition> {
+    if let Some(res) = owner.resolve_doc_path(db, path) {
+        return Some(res.into());
+    }
+    // Fields, like `Foo::bar`.
+    let idx = path.rfind("::")?;
+    let (qualifier, field) = (&path[..idx], &path[idx + 2..]);
+    let variant: VariantDef = match owner.resolve_doc_path(db, qualifier)? {
+        PathResolution::Def(ModuleDef::Adt(Adt::Struct(it))) => it.into(),
+        PathResolution::Def(ModuleDef::Adt(Adt::Union(it))) => it.into(),
+        PathResolution::Def(ModuleDef::EnumVariant(it)) => it.into(),
+        _ => return None,
+    };
+    let field = variant.fields(db).into_iter().find(|it| it.name(db).to_string() == field)?;
+    Some(Definition::Field(field))
+}
+
+/// Returns the item whose documentation links in `definition`'s docs are
+/// resolved relative to.
+fn doc_owner(definition: &Definition) -> Option<AttrDef> {
+    let res = match *definition {
+        Definition::Macro(it) => it.into(),
+        Definition::Field(it) => it.into(),
+        Definition::ModuleDef(it) => match it {
+            ModuleDef::Module(it) => it.into(),
+            ModuleDef::Function(it) => it.into(),
+            ModuleDef::Adt(it) => it.into(),
+            ModuleDef::EnumVariant(it) => it.into(),
+            ModuleDef::Const(it) => it.into(),
+            ModuleDef::Static(it) => it.into(),
+            ModuleDef::Trait(it) => it.into(),
+            ModuleDef::TypeAlias(it) => it.into(),
+            ModuleDef::BuiltinType(_) => return None,
+        },
+        Definition::SelfType(_) | Definition::Local(_) | Definition::TypeParam(_) => return None,
+    };
+    Some(res)
+}
+
+/// Returns the item documented by the doc comments in `node`.
+fn doc_owner_of_node(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<AttrDef> {
+    let res = match_ast! {
+        match node {
+            ast::SourceFile(_it) => sema.to_module_def(file_id)?.into(),
+            ast::ItemList(it) => sema.to_def(&ast::Module::cast(it.syntax().parent()?)?)?.into(),
+            ast::Module(it) => sema.to_def(&it)?.into(),
+            ast::Fn(it) => sema.to_def(&it)?.into(),
+            ast::Struct(it) => sema.to_def(&it)?.into(),
+            ast::Enum(it) => sema.to_def(&it)?.into(),
+            ast::Union(it) => sema.to_def(&it)?.into(),
+            ast::Variant(it) => sema.to_def(&it)?.into(),
+            ast::RecordField(it) => sema.to_def(&it)?.into(),
+            ast::Const(it) => sema.to_def(&it)?.into(),
+            ast::Static(it) => sema.to_def(&it)?.into(),
+            ast::Trait(it) => sema.to_def(&it)?.into(),
+            ast::TypeAlias(it) => sema.to_def(&it)?.into(),
+            ast::MacroCall(it) => sema.to_def(&it)?.into(),
+            _ => return None,
+        }
+    };
+    Some(res)
+}
+
+/// Returns a link to the definition in a local file, or to its docs.rs page
+/// if it is defined in a library.
+fn target_url(db: &RootDatabase, def: Definition) -> Option<String> {
+    let nav = def.try_to_nav(db)?;
+    let source_root = db.source_root(db.file_source_root(nav.file_id));
+    if source_root.is_library {
+        return doc_url(db, def);
+    }
+
+    let path = source_root.path_for_file(&nav.file_id)?;
+    let mut url = match path.as_path() {
+        Some(path) => Url::from_file_path(path).ok()?,
+        // Virtual paths, as used in tests, are already `/`-separated.
+        None => {
+            let mut url = Url::parse("file:///").ok()?;
+            url.set_path(&path.to_string());
+            url
+        }
+    };
+    let line = db.line_index(nav.file_id).line_col(nav.focus_or_full_range().start()).line + 1;
+    url.set_fragment(Some(&format!("L{}", line)));
+    Some(url.into_string())
+}
+
+/// Returns the URL of the documentation page of a definition, as rendered by
+/// rustdoc on docs.rs, or on doc.rust-lang.org for the standard library.
+fn doc_url(db: &RootDatabase, def: Definition) -> Option<String> {
+    let (page, fragment) = match def {
+        Definition::ModuleDef(ModuleDef::BuiltinType(it)) => {
+            return Some(format!("https://doc.rust-lang.org/nightly/std/primitive.{}.html", it));
+        }
+        Definition::ModuleDef(ModuleDef::EnumVariant(it)) => {
+            let fragment = format!("variant.{}", it.name(db));
+            (ModuleDef::Adt(it.parent_enum(db).into()), Some(fragment))
+        }
+        Definition::Field(it) => match it.parent_def(db) {
+            VariantDef::Struct(adt) => {
+                (ModuleDef::Adt(adt.into()), Some(format!("structfield.{}", it.name(db))))
+            }
+            VariantDef::Union(adt) => {
+                (ModuleDef::Adt(adt.into()), Some(format!("structfield.{}", it.name(db))))
+            }
+            VariantDef::EnumVariant(variant) => {
+                let fragment = format!("variant.{}.field.{}", variant.name(db), it.name(db));
+                (ModuleDef::Adt(variant.parent_enum(db).into()), Some(fragment))
+            }
+        },
+        Definition::ModuleDef(it) => match assoc_item(db, it) {
+            Some(item) => {
+                let page = match item.container(db) {
+                    AssocItemContainer::Trait(it) => ModuleDef::Trait(it),
+                    AssocItemContainer::ImplDef(it) => ModuleDef::Adt(it.target_ty(db).as_adt()?),
+                };
+                let (kind, name) = match item {
+                    AssocItem::Function(it) => ("method", it.name(db)),
+                    AssocItem::Const(it) => ("associatedconstant", it.name(db)?),
+                    AssocItem::TypeAlias(it) => ("associatedtype", it.name(db)),
+                };
+                (page, Some(format!("{}.{}", kind, name)))
+            }
+            None => (it, None),
+        },
+        Definition::Macro(it) => {
+            let krate = it.module(db)?.krate();
+            let url = format!("{}macro.{}.html", crate_doc_url(db, krate)?, it.name(db)?);
+            return Some(url);
+        }
+        Definition::SelfType(_) | Definition::Local(_) | Definition::TypeParam(_) => return None,
+    };
+
+    let (module, file) = match page {
+        ModuleDef::Module(it) => (it, "index.html".to_string()),
+        it => {
+            let kind = match it {
+                ModuleDef::Adt(Adt::Struct(_)) => "struct",
+                ModuleDef::Adt(Adt::Enum(_)) => "enum",
+                ModuleDef::Adt(Adt::Union(_)) => "union",
+                ModuleDef::Function(_) => "fn",
+                ModuleDef::Const(_) => "constant",
+                ModuleDef::Static(_) => "static",
+                ModuleDef::Trait(_) => "trait",
+                ModuleDef::TypeAlias(_) => "type",
+                _ => return None,
+            };
+            (it.module(db)?, format!("{}.{}.html", kind, it.name(db)?))
+        }
+    };
+    let mut url = crate_doc_url(db, module.krate())?;
+    for module in module.path_to_root(db).into_iter().rev().skip(1) {
+        url.push_str(&format!("{}/", module.name(db)?));
+    }
+    url.push_str(&file);
+    if let Some(fragment) = fragment {
+        url.push_str(&format!("#{}", fragment));
+    }
+    Some(url)
+}
+
+fn assoc_item(db: &RootDatabase, def: ModuleDef) -> Option<AssocItem> {
+    match def {
+        ModuleDef::Function(it) => it.as_assoc_item(db),
+        ModuleDef::Const(it) => it.as_assoc_item(db),
+        ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+        _ => None,
+    }
+}
+
+/// Returns the URL of the root module documentation of a crate, with a
+/// trailing slash.
+fn crate_doc_url(db: &RootDatabase, krate: hir::Crate) -> Option<String> {
+    let name = krate.display_name(db)?;
+    let base = match name.as_str() {
+        "std" | "core" | "alloc" | "proc_macro" | "test" => {
+            "https://doc.rust-lang.org/nightly/".to_string()
+        }
+        _ => format!("https://docs.rs/{}/*/", name),
+    };
+    Some(format!("{}{}/", base, name.replace('-', "_")))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect::{expect, Expect};
+    use hir::Semantics;
+    use ide_db::defs::classify_name;
+    use syntax::{algo::find_node_at_offset, ast, AstNode};
+
+    use crate::mock_analysis::analysis_and_position;
+
+    use super::*;
+
+    fn check_links(text: &str, expect: Expect) {
+        let links = intra_doc_links(text)
+            .into_iter()
+            .map(|link| {
+                let dest = match link.dest {
+                    LinkDest::Inline(it) => format!("({})", &text[it]),
+                    LinkDest::Shortcut => "shortcut".to_string(),
+                    LinkDest::Reference => "reference".to_string(),
+                };
+                format!("[{}] {} -> {}\n", &text[link.label], dest, link.path)
+            })
+            .collect::<String>();
+        expect.assert_eq(&links);
+    }
+
+    fn check_doc_url(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = analysis_and_position(ra_fixture);
+        let url = analysis
+            .with_db(|db| {
+                let sema = Semantics::new(db);
+                let file = sema.parse(position.file_id);
+                let name: ast::Name = find_node_at_offset(file.syntax(), position.offset)?;
+                let def = classify_name(&sema, &name)?.definition(db);
+                doc_url(db, def)
+            })
+            .unwrap();
+        expect.assert_debug_eq(&url);
+    }
+
+    #[test]
+    fn finds_intra_doc_links() {
+        check_links(
+            r#"Pushes with [`Vec::push`], see [the struct](crate::foo::Bar) and [Baz].
+Not [a link](https://example.com) or `[Vec]`, but [Option][opt] and [`Some`](Option::Some())
+
+```
+let v = [Vec];
+```
+
+[opt]: std::option::Option
+[Baz]: <https://example.com>"#,
+            expect![[r#"
+                [`Vec::push`] shortcut -> Vec::push
+                [the struct] (crate::foo::Bar) -> crate::foo::Bar
+                [Option] reference -> std::option::Option
+                [`Some`] (Option::Some()) -> Option::Some
+                [opt] (std::option::Option) -> std::option::Option
+            "#]],
+        );
+    }
+
+    #[test]
+    fn finds_links_in_doc_comments() {
+        check_links(
+            r#"/// See [macro@foo!] and [fn@bar()].
+    /// [`Baz::qux`][]"#,
+            expect![[r#"
+                [macro@foo!] shortcut -> foo
+                [fn@bar()] shortcut -> bar
+                [`Baz::qux`] reference -> Baz::qux
+            "#]],
+        );
+    }
+
+    #[test]
+    fn doc_urls() {
+        check_doc_url(
+            r#"
+//- /main.rs
+//- /foo/lib.rs
+pub mod bar {
+    pub struct Baz { pub qu<|>x: u32 }
+}
+"#,
+            expect![[r#"
+                Some(
+                    "https://docs.rs/foo/*/foo/bar/struct.Baz.html#structfield.qux",
+                )
+            "#]],
+        );
+        check_doc_url(
+            r#"
+//- /main.rs
+//- /core/lib.rs
+pub mod option {
+    pub enum Option<T> { None, Some(T) }
+    impl<T> Option<T> {
+        pub fn is_<|>some(&self) -> bool { true }
+    }
+}
+"#,
+            expect![[r#"
+                Some(
+                    "https://doc.rust-lang.org/nightly/core/option/enum.Option.html#method.is_some",
+                )
+            "#]],
+        );
+    }
+}
@@ -25,6 +25,7 @@ mod call_hierarchy;
 mod call_info;
 mod completion;
 mod diagnostics;
+mod document_links;
 mod expand_macro;
 mod extend_selection;
 mod file_structure;
@@ -34,6 +35,7 @@ mod goto_implementation;
 mod goto_type_definition;
 mod hover;
 mod inlay_hints;
+mod intra_doc_links;
 mod join_lines;
 mod matching_brace;
 mod parent_module;
@@ -42,6 +44,7 @@ mod runnables;
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod test_explorer;
 mod typing;
 
 use std::{collections::HashSet, sync::Arc};
@@ -67,6 +70,7 @@ pub use crate::{
     },
     diagnostics::Severity,
     display::NavigationTarget,
+    document_links::{DocumentLink, DocumentLinkTarget},
     expand_macro::ExpandedMacro,
     file_structure::StructureNode,
     folding_ranges::{Fold, FoldKind},
@@ -78,6 +82,7 @@ pub use crate::{
     syntax_highlighting::{
         Highlight, HighlightModifier, HighlightModifiers, HighlightTag, HighlightedRange,
     },
+    test_explorer::{TestItem, TestItemKind},
 };
 
 pub use assists::{Assist, AssistConfig, AssistId, AssistKind, ResolvedAssist};
@@ -344,6 +349,11 @@ impl Analysis {
         self.with_db(|db| inlay_hints::inlay_hints(db, file_id, config))
     }
 
+    /// Returns the links to other files and URLs in the file.
+    pub fn document_links(&self, file_id: FileId) -> Cancelable<Vec<DocumentLink>> {
+        self.with_db(|db| document_links::document_links(db, file_id))
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancelable<Vec<Fold>> {
         self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
@@ -447,6 +457,11 @@ impl Analysis {
         self.with_db(|db| runnables::runnables(db, file_id))
     }
 
+    /// Returns the tests of all local crates, grouped by crate and module.
+    pub fn discover_tests(&self) -> Cancelable<Vec<TestItem>> {
+        self.with_db(test_explorer::discover_tests)
+    }
+
     /// Computes syntax highlighting for the given file
     pub fn highlight(&self, file_id: FileId) -> Cancelable<Vec<HighlightedRange>> {
         self.with_db(|db| syntax_highlighting::highlight(db, file_id, None, false))
@@ -514,6 +529,31 @@ impl Analysis {
         self.with_db(|db| references::rename(db, position, new_name))
     }
 
+    /// Returns the edit required to update the declaration of the module
+    /// defined in the file and the references to it, when the file is about to
+    /// be renamed to `new_name_stem`.
+    pub fn will_rename_file(
+        &self,
+        file_id: FileId,
+        new_name_stem: &str,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| references::will_rename_file(db, file_id, new_name_stem))
+    }
+
+    /// Returns the edit required to move the module defined in the file into
+    /// the module defined in `new_parent_file_id`, when the file is about to
+    /// be moved to the directory of that module and renamed to `new_name_stem`.
+    pub fn will_move_file(
+        &self,
+        file_id: FileId,
+        new_parent_file_id: FileId,
+        new_name_stem: &str,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| {
+            references::will_move_file(db, file_id, new_parent_file_id, new_name_stem)
+        })
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -23,9 +23,11 @@ use syntax::{
     AstNode, SyntaxKind, SyntaxNode, TextRange, TokenAtOffset,
 };
 
-use crate::{display::TryToNav, FilePosition, FileRange, NavigationTarget, RangeInfo};
+use crate::{
+    display::TryToNav, intra_doc_links, FilePosition, FileRange, NavigationTarget, RangeInfo,
+};
 
-pub(crate) use self::rename::rename;
+pub(crate) use self::rename::{rename, rename_with_semantics, will_move_file, will_rename_file};
 
 pub use ide_db::search::{Reference, ReferenceAccess, ReferenceKind};
 
@@ -134,7 +136,17 @@ fn find_name(
         return Some(RangeInfo::new(range, def));
     }
     let name_ref =
-        sema.find_node_at_offset_with_descend::<ast::NameRef>(&syntax, position.offset)?;
+        match sema.find_node_at_offset_with_descend::<ast::NameRef>(syntax, position.offset) {
+            Some(it) => it,
+            None => {
+                let token = syntax
+                    .token_at_offset(position.offset)
+                    .find(|it| it.kind() == SyntaxKind::COMMENT)?;
+                let (range, def) =
+                    intra_doc_links::doc_link_at(sema, position.file_id, &token, position.offset)?;
+                return Some(RangeInfo::new(range, def));
+            }
+        };
     let def = classify_name_ref(sema, &name_ref)?.definition(sema.db);
     let range = name_ref.syntax().text_range();
     Some(RangeInfo::new(range, def))
@@ -650,6 +662,23 @@ fn main() {
         );
     }
 
+    #[test]
+    fn test_find_all_refs_from_intra_doc_link() {
+        let refs = get_all_refs(
+            r#"
+struct Foo;
+
+/// Makes a [`Fo<|>o`].
+fn new() -> Foo { Foo }
+"#,
+        );
+        check_result(
+            refs,
+            "Foo STRUCT FileId(1) 0..11 7..10 Other",
+            &["FileId(1) 46..49 Other", "FileId(1) 52..55 Other"],
+        );
+    }
+
     fn get_all_refs(ra_fixture: &str) -> ReferenceSearchResult {
         let (analysis, position) = analysis_and_position(ra_fixture);
         analysis.find_all_refs(position, None).unwrap().unwrap()
@@ -1,6 +1,6 @@
 //! FIXME: write short doc here
 
-use base_db::SourceDatabaseExt;
+use base_db::{FileId, SourceDatabaseExt};
 use hir::{Module, ModuleDef, ModuleSource, Semantics};
 use ide_db::{
     defs::{classify_name, classify_name_ref, Definition, NameClass, NameRefClass},
@@ -9,8 +9,9 @@ use ide_db::{
 use std::convert::TryInto;
 use syntax::{
     algo::find_node_at_offset,
-    ast::{self, NameOwner},
-    lex_single_valid_syntax_kind, match_ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken,
+    ast::{self, ModuleItemOwner, NameOwner},
+    lex_single_valid_syntax_kind, match_ast, AstNode, NodeOrToken, SyntaxKind, SyntaxNode,
+    SyntaxToken,
 };
 use test_utils::mark;
 use text_edit::TextEdit;
@@ -26,23 +27,30 @@ pub(crate) fn rename(
     new_name: &str,
 ) -> Option<RangeInfo<SourceChange>> {
     let sema = Semantics::new(db);
+    rename_with_semantics(&sema, position, new_name)
+}
 
+pub(crate) fn rename_with_semantics(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+    new_name: &str,
+) -> Option<RangeInfo<SourceChange>> {
     match lex_single_valid_syntax_kind(new_name)? {
         SyntaxKind::IDENT | SyntaxKind::UNDERSCORE => (),
-        SyntaxKind::SELF_KW => return rename_to_self(&sema, position),
+        SyntaxKind::SELF_KW => return rename_to_self(sema, position),
         _ => return None,
     }
 
     let source_file = sema.parse(position.file_id);
     let syntax = source_file.syntax();
-    if let Some(module) = find_module_at_offset(&sema, position, syntax) {
-        rename_mod(&sema, position, module, new_name)
+    if let Some(module) = find_module_at_offset(sema, position, syntax) {
+        rename_mod(sema, position, module, new_name)
     } else if let Some(self_token) =
         syntax.token_at_offset(position.offset).find(|t| t.kind() == SyntaxKind::SELF_KW)
     {
-        rename_self_to_param(&sema, position, self_token, new_name)
+        rename_self_to_param(sema, position, self_token, new_name)
     } else {
-        rename_reference(&sema, position, new_name)
+        rename_reference(sema, position, new_name)
     }
 }
 
@@ -111,14 +119,29 @@ fn rename_mod(
     let file_id = src.file_id.original_file(sema.db);
     match src.value {
         ModuleSource::SourceFile(..) => {
-            // mod is defined in path/to/dir/mod.rs
-            let dst = if module.is_mod_rs(sema.db) {
-                format!("../{}/mod.rs", new_name)
+            let old_name = module.name(sema.db)?;
+            if module.is_mod_rs(sema.db) {
+                // mod is defined in path/to/dir/mod.rs, the whole directory moves
+                let move_dir = FileSystemEdit::MoveDir {
+                    anchor: file_id,
+                    src: format!("../{}", old_name),
+                    dst: format!("../{}", new_name),
+                };
+                file_system_edits.push(move_dir);
             } else {
-                format!("{}.rs", new_name)
-            };
-            let move_file = FileSystemEdit::MoveFile { src: file_id, anchor: file_id, dst };
-            file_system_edits.push(move_file);
+                let dst = format!("{}.rs", new_name);
+                let move_file = FileSystemEdit::MoveFile { src: file_id, anchor: file_id, dst };
+                file_system_edits.push(move_file);
+                // mod is defined in path/to/dir.rs, with submodules in path/to/dir/
+                if has_child_files(sema.db, module) {
+                    let move_dir = FileSystemEdit::MoveDir {
+                        anchor: file_id,
+                        src: old_name.to_string(),
+                        dst: new_name.to_string(),
+                    };
+                    file_system_edits.push(move_dir);
+                }
+            }
         }
         ModuleSource::Module(..) => {}
     }
@@ -143,6 +166,268 @@ fn rename_mod(
     Some(RangeInfo::new(range, SourceChange::from_edits(source_file_edits, file_system_edits)))
 }
 
+fn has_child_files(db: &RootDatabase, module: Module) -> bool {
+    module
+        .children(db)
+        .any(|child| matches!(child.definition_source(db).value, ModuleSource::SourceFile(..)))
+}
+
+/// Returns the edits to the declaration and the references of the module
+/// defined in `file_id` when the file is renamed, which has to happen before
+/// the file is renamed on disk. Unlike `rename`, the file itself is not moved.
+pub(crate) fn will_rename_file(
+    db: &RootDatabase,
+    file_id: FileId,
+    new_name_stem: &str,
+) -> Option<SourceChange> {
+    match lex_single_valid_syntax_kind(new_name_stem)? {
+        SyntaxKind::IDENT | SyntaxKind::UNDERSCORE => (),
+        _ => return None,
+    }
+    let sema = Semantics::new(db);
+    let module = sema.to_module_def(file_id)?;
+    let decl = module.declaration_source(db)?;
+    let position = FilePosition {
+        file_id: decl.file_id.original_file(db),
+        offset: decl.value.name()?.syntax().text_range().start(),
+    };
+    let change = rename_mod(&sema, position, module, new_name_stem)?.info;
+    Some(SourceChange::from(merge_file_edits(change.source_file_edits)?))
+}
+
+/// Returns the edits needed when the file of a module is moved into the
+/// directory of another module of the same crate, `new_parent_file_id`: the
+/// declaration moves to the new parent, paths to the module are rewritten and
+/// `super` paths in the module itself are made absolute. Paths inside of
+/// nested `use` trees, like `use a::{foo, bar}`, are left alone.
+pub(crate) fn will_move_file(
+    db: &RootDatabase,
+    file_id: FileId,
+    new_parent_file_id: FileId,
+    new_name_stem: &str,
+) -> Option<SourceChange> {
+    match lex_single_valid_syntax_kind(new_name_stem)? {
+        SyntaxKind::IDENT | SyntaxKind::UNDERSCORE => (),
+        _ => return None,
+    }
+    let sema = Semantics::new(db);
+    let module = sema.to_module_def(file_id)?;
+    let old_parent = module.parent(db)?;
+    let new_parent = sema.to_module_def(new_parent_file_id)?;
+    if new_parent == old_parent {
+        return will_rename_file(db, file_id, new_name_stem);
+    }
+    if new_parent.krate() != module.krate() || new_parent.path_to_root(db).contains(&module) {
+        return None;
+    }
+    let decl = module.declaration_source(db)?;
+    if decl.file_id.call_node(db).is_some() {
+        return None;
+    }
+    let decl_file_id = decl.file_id.original_file(db);
+    let decl = decl.value;
+    let mut source_file_edits = Vec::new();
+
+    let mut delete_range = decl.syntax().text_range();
+    if let Some(ws) = decl.syntax().next_sibling_or_token().and_then(|it| it.into_token()) {
+        if ws.kind() == SyntaxKind::WHITESPACE {
+            if let Some(idx) = ws.text().find('\n') {
+                let end = ws.text_range().start() + TextSize::of(&ws.text()[..=idx]);
+                delete_range = TextRange::new(delete_range.start(), end);
+            }
+        }
+    }
+    source_file_edits
+        .push(SourceFileEdit { file_id: decl_file_id, edit: TextEdit::delete(delete_range) });
+
+    let new_parent_source = new_parent.definition_source(db);
+    let new_parent_file = match new_parent_source.value {
+        ModuleSource::SourceFile(it) => it,
+        ModuleSource::Module(_) => return None,
+    };
+    let (offset, text) = new_mod_decl(&new_parent_file, &moved_mod_decl(&decl, new_name_stem));
+    source_file_edits.push(SourceFileEdit {
+        file_id: new_parent_source.file_id.original_file(db),
+        edit: TextEdit::insert(offset, text),
+    });
+
+    for reference in Definition::ModuleDef(ModuleDef::Module(module)).find_usages(&sema, None) {
+        let file_range = reference.file_range;
+        if file_range.file_id == decl_file_id
+            && decl.syntax().text_range().contains_range(file_range.range)
+        {
+            continue;
+        }
+        let source_file = sema.parse(file_range.file_id);
+        let path = match find_node_at_offset::<ast::NameRef>(
+            source_file.syntax(),
+            file_range.range.start(),
+        )
+        .and_then(|it| it.syntax().parent())
+        .and_then(ast::PathSegment::cast)
+        .and_then(|it| it.syntax().parent())
+        .and_then(ast::Path::cast)
+        {
+            Some(it) => it,
+            None => continue,
+        };
+        if path.qualifier().is_none() && is_in_use_tree_list(&path) {
+            continue;
+        }
+        let from = match sema.scope(path.syntax()).module() {
+            Some(it) => it,
+            None => continue,
+        };
+        let new_path = if from == new_parent {
+            new_name_stem.to_string()
+        } else {
+            match from.find_use_path(db, ModuleDef::Module(new_parent)) {
+                Some(it) => format!("{}::{}", it, new_name_stem),
+                None => continue,
+            }
+        };
+        source_file_edits.push(SourceFileEdit {
+            file_id: file_range.file_id,
+            edit: TextEdit::replace(path.syntax().text_range(), new_path),
+        });
+    }
+
+    // `super` now means the new parent, so leading `super`s are replaced by the
+    // absolute path of the module they refer to.
+    let source_file = sema.parse(file_id);
+    let super_paths = source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|path| path.qualifier().is_none() && !is_in_use_tree_list(path))
+        .filter(|path| path.segment().map_or(false, |it| it.super_token().is_some()))
+        .filter(|path| sema.scope(path.syntax()).module() == Some(module));
+    for first in super_paths {
+        let mut last = first.clone();
+        let mut target = old_parent;
+        while let Some(parent_path) = last.syntax().parent().and_then(ast::Path::cast) {
+            if parent_path.segment().map_or(true, |it| it.super_token().is_none()) {
+                break;
+            }
+            target = match target.parent(db) {
+                Some(it) => it,
+                None => return None,
+            };
+            last = parent_path;
+        }
+        let range =
+            TextRange::new(first.syntax().text_range().start(), last.syntax().text_range().end());
+        source_file_edits.push(SourceFileEdit {
+            file_id,
+            edit: TextEdit::replace(range, absolute_module_path(db, target)),
+        });
+    }
+
+    Some(SourceChange::from(merge_file_edits(source_file_edits)?))
+}
+
+/// Combines the edits to each file into one, as clients apply the edits of a
+/// workspace edit one after another and later offsets would be off otherwise.
+/// Returns `None` if the edits to a file overlap.
+fn merge_file_edits(source_file_edits: Vec<SourceFileEdit>) -> Option<Vec<SourceFileEdit>> {
+    let mut res: Vec<SourceFileEdit> = Vec::new();
+    for source_file_edit in source_file_edits {
+        match res.iter_mut().find(|it| it.file_id == source_file_edit.file_id) {
+            Some(it) => it.edit.union(source_file_edit.edit).ok()?,
+            None => res.push(source_file_edit),
+        }
+    }
+    Some(res)
+}
+
+fn absolute_module_path(db: &RootDatabase, module: Module) -> String {
+    std::iter::once("crate".to_string())
+        .chain(
+            module
+                .path_to_root(db)
+                .into_iter()
+                .rev()
+                .filter_map(|it| it.name(db))
+                .map(|it| it.to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn is_in_use_tree_list(path: &ast::Path) -> bool {
+    path.syntax()
+        .parent()
+        .and_then(ast::UseTree::cast)
+        .and_then(|it| it.syntax().parent())
+        .filter(|it| ast::UseTreeList::can_cast(it.kind()))
+        .is_some()
+}
+
+/// The declaration of the module under its new name, without `#[path]`
+/// attributes, as the file ends up where the new parent looks for it.
+fn moved_mod_decl(decl: &ast::Module, new_name: &str) -> String {
+    let mut buf = String::new();
+    let mut skip_ws = false;
+    for element in decl.syntax().children_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                skip_ws = false;
+                if let Some(attr) = ast::Attr::cast(node.clone()) {
+                    if attr.simple_name().as_deref() == Some("path") {
+                        skip_ws = true;
+                        continue;
+                    }
+                }
+                if ast::Name::can_cast(node.kind()) {
+                    buf.push_str(new_name);
+                } else {
+                    buf.push_str(&node.text().to_string());
+                }
+            }
+            NodeOrToken::Token(token) => {
+                if !(skip_ws && token.kind() == SyntaxKind::WHITESPACE) {
+                    buf.push_str(token.text());
+                }
+                skip_ws = false;
+            }
+        }
+    }
+    buf
+}
+
+/// Places the declaration after the last `mod foo;` in the file, or else after
+/// the inner attributes and comments at its start.
+fn new_mod_decl(file: &ast::SourceFile, decl: &str) -> (TextSize, String) {
+    let last_mod = file
+        .items()
+        .filter_map(|it| match it {
+            ast::Item::Module(it) if it.item_list().is_none() => Some(it),
+            _ => None,
+        })
+        .last();
+    if let Some(last_mod) = last_mod {
+        return (last_mod.syntax().text_range().end(), format!("\n{}", decl));
+    }
+    let header_end = file
+        .syntax()
+        .children_with_tokens()
+        .take_while(|it| match it {
+            NodeOrToken::Token(token) => {
+                token.kind() == SyntaxKind::WHITESPACE || token.kind() == SyntaxKind::COMMENT
+            }
+            NodeOrToken::Node(node) => {
+                matches!(ast::Attr::cast(node.clone()), Some(attr) if attr.excl_token().is_some())
+            }
+        })
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE)
+        .last()
+        .map(|it| it.text_range().end());
+    match header_end {
+        Some(end) => (end, format!("\n\n{}", decl)),
+        None => (0.into(), format!("{}\n", decl)),
+    }
+}
+
 fn rename_to_self(
     sema: &Semantics<RootDatabase>,
     position: FilePosition,
@@ -275,7 +560,7 @@ mod tests {
     use test_utils::{assert_eq_text, mark};
     use text_edit::TextEdit;
 
-    use crate::{mock_analysis::analysis_and_position, FileId};
+    use crate::{mock_analysis::analysis_and_position, FileId, SourceFileEdit};
 
     fn check(new_name: &str, ra_fixture_before: &str, ra_fixture_after: &str) {
         let ra_fixture_after = &trim_indent(ra_fixture_after);
@@ -728,14 +1013,12 @@ mod fo<|>o;
                             },
                         ],
                         file_system_edits: [
-                            MoveFile {
-                                src: FileId(
-                                    2,
-                                ),
+                            MoveDir {
                                 anchor: FileId(
                                     2,
                                 ),
-                                dst: "../foo2/mod.rs",
+                                src: "../foo",
+                                dst: "../foo2",
                             },
                         ],
                         is_snippet: false,
@@ -877,6 +1160,209 @@ pub mod foo<|>;
         );
     }
 
+    #[test]
+    fn test_rename_mod_with_child_files() {
+        check_expect(
+            "baz",
+            r#"
+//- /lib.rs
+mod bar<|>;
+
+//- /bar.rs
+mod foo;
+
+//- /bar/foo.rs
+// empty
+"#,
+            expect![[r#"
+                RangeInfo {
+                    range: 4..7,
+                    info: SourceChange {
+                        source_file_edits: [
+                            SourceFileEdit {
+                                file_id: FileId(
+                                    1,
+                                ),
+                                edit: TextEdit {
+                                    indels: [
+                                        Indel {
+                                            insert: "baz",
+                                            delete: 4..7,
+                                        },
+                                    ],
+                                },
+                            },
+                        ],
+                        file_system_edits: [
+                            MoveFile {
+                                src: FileId(
+                                    2,
+                                ),
+                                anchor: FileId(
+                                    2,
+                                ),
+                                dst: "baz.rs",
+                            },
+                            MoveDir {
+                                anchor: FileId(
+                                    2,
+                                ),
+                                src: "bar",
+                                dst: "baz",
+                            },
+                        ],
+                        is_snippet: false,
+                    },
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_will_rename_file() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+//- /lib.rs
+mod foo;
+use foo::Bar;
+
+//- /foo.rs
+pub struct Bar;<|>
+"#,
+        );
+        let source_change = analysis.will_rename_file(position.file_id, "baz").unwrap().unwrap();
+        expect![[r#"
+            SourceChange {
+                source_file_edits: [
+                    SourceFileEdit {
+                        file_id: FileId(
+                            1,
+                        ),
+                        edit: TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "baz",
+                                    delete: 4..7,
+                                },
+                                Indel {
+                                    insert: "baz",
+                                    delete: 13..16,
+                                },
+                            ],
+                        },
+                    },
+                ],
+                file_system_edits: [],
+                is_snippet: false,
+            }
+        "#]]
+        .assert_debug_eq(&source_change);
+        assert!(analysis.will_rename_file(position.file_id, "not valid").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_will_move_file() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+//- /lib.rs
+mod a;
+mod b;
+use a::foo::Foo;
+
+//- /a.rs
+#[path = "foo_impl.rs"]
+pub mod foo;
+fn f() -> self::foo::Foo { foo::Foo }
+
+//- /foo_impl.rs
+use super::f;
+pub struct Foo;<|>
+
+//- /b.rs
+//! Docs
+"#,
+        );
+        let source_change =
+            analysis.will_move_file(position.file_id, FileId(4), "foo").unwrap().unwrap();
+        let mut edits = source_change.source_file_edits;
+        edits.sort_by_key(|it| it.file_id);
+        let file_ids = edits.iter().map(|it| it.file_id).collect::<Vec<_>>();
+        assert_eq!(file_ids, vec![FileId(1), FileId(2), FileId(3), FileId(4)]);
+        let mut buf = String::new();
+        for SourceFileEdit { file_id, edit } in edits {
+            let mut text = analysis.file_text(file_id).unwrap().to_string();
+            edit.apply(&mut text);
+            buf.push_str(&format!("// {:?}\n{}\n", file_id, text));
+        }
+        expect![[r#"
+            // FileId(1)
+            mod a;
+            mod b;
+            use b::foo::Foo;
+
+
+            // FileId(2)
+            fn f() -> crate::b::foo::Foo { crate::b::foo::Foo }
+
+
+            // FileId(3)
+            use crate::a::f;
+            pub struct Foo;
+
+
+            // FileId(4)
+            //! Docs
+
+            pub mod foo;
+
+        "#]]
+        .assert_eq(&buf);
+
+        // Into the module itself.
+        assert!(analysis.will_move_file(FileId(2), position.file_id, "a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_will_move_file_with_nested_super() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+//- /lib.rs
+mod a;
+mod b;
+//- /a.rs
+mod foo;
+//- /a/foo.rs
+use super::super::b::Bar;
+pub struct Foo;<|>
+//- /b.rs
+pub struct Bar;
+"#,
+        );
+        let source_change =
+            analysis.will_move_file(position.file_id, FileId(4), "foo").unwrap().unwrap();
+        let mut edits = source_change.source_file_edits;
+        edits.sort_by_key(|it| it.file_id);
+        let mut buf = String::new();
+        for SourceFileEdit { file_id, edit } in edits {
+            let mut text = analysis.file_text(file_id).unwrap().to_string();
+            edit.apply(&mut text);
+            buf.push_str(&format!("// {:?}\n{}\n", file_id, text));
+        }
+        expect![[r#"
+            // FileId(2)
+
+            // FileId(3)
+            use crate::b::Bar;
+            pub struct Foo;
+
+            // FileId(4)
+            mod foo;
+            pub struct Bar;
+
+        "#]]
+        .assert_eq(&buf);
+    }
+
     #[test]
     fn test_enum_variant_from_module_1() {
         check(
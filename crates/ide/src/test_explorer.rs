@@ -0,0 +1,259 @@
+//! Discovers the tests of all local crates, as a tree of crates, modules and
+//! test functions.
+
+use base_db::{CrateId, SourceDatabaseExt};
+use hir::ModuleSource;
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    display::ToNav,
+    runnables::{runnables, Runnable, RunnableKind, TestId},
+    NavigationTarget,
+};
+
+#[derive(Debug)]
+pub struct TestItem {
+    /// Identifies the item across the workspace. The ids of modules and tests
+    /// are the id of their crate followed by their path, like `foo::tests::bar`.
+    pub id: String,
+    pub parent: Option<String>,
+    pub kind: TestItemKind,
+    pub label: String,
+    pub krate: CrateId,
+    pub nav: NavigationTarget,
+    /// The runnable of a module or test. Its `TestId` is the name libtest
+    /// reports for the test.
+    pub runnable: Option<Runnable>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestItemKind {
+    Crate,
+    Module,
+    Test,
+}
+
+// Feature: Test Explorer
+//
+// Lists the tests of all crates in the workspace, grouped by crate and
+// module, for editors to show in a tree and run individually or as a group.
+pub(crate) fn discover_tests(db: &RootDatabase) -> Vec<TestItem> {
+    let mut res = Vec::new();
+    // Libraries and binaries of the same package usually share a name.
+    let mut n_crates_by_name = FxHashMap::default();
+    for krate in hir::Crate::all(db) {
+        let source_root = db.source_root(db.file_source_root(krate.root_file(db)));
+        if source_root.is_library {
+            continue;
+        }
+        let name = krate.display_name(db).unwrap_or_else(|| "crate".to_string());
+        let n_crates = n_crates_by_name.entry(name.clone()).or_insert(0);
+        *n_crates += 1;
+        let id = match *n_crates {
+            1 => name.clone(),
+            n => format!("{}#{}", name, n),
+        };
+        crate_tests(db, krate, id, name, &mut res);
+    }
+    res
+}
+
+fn crate_tests(
+    db: &RootDatabase,
+    krate: hir::Crate,
+    crate_id: String,
+    label: String,
+    acc: &mut Vec<TestItem>,
+) {
+    // Depth-first, so that modules come before their submodules.
+    let mut modules = Vec::new();
+    let mut stack = vec![krate.root_module(db)];
+    while let Some(module) = stack.pop() {
+        let children = module.children(db).sorted_by_key(|it| it.name(db).map(|it| it.to_string()));
+        stack.extend(children.rev());
+        modules.push((module_path(db, module), module));
+    }
+
+    let mut tests = Vec::new();
+    for (_, module) in &modules {
+        let src = module.definition_source(db);
+        if let ModuleSource::SourceFile(_) = src.value {
+            let file_tests =
+                runnables(db, src.file_id.original_file(db)).into_iter().filter_map(|runnable| {
+                    match &runnable.kind {
+                        RunnableKind::Test { test_id: TestId::Path(path), .. } => {
+                            Some((path.clone(), runnable))
+                        }
+                        _ => None,
+                    }
+                });
+            tests.extend(file_tests);
+        }
+    }
+
+    let module_paths = modules.iter().map(|(path, _)| path.as_str()).collect::<FxHashSet<_>>();
+    let tests = tests
+        .into_iter()
+        .map(|(path, runnable)| (parent_module(&path, &module_paths).to_string(), path, runnable))
+        .collect::<Vec<_>>();
+    let item_id = |path: &str| match path {
+        "" => crate_id.clone(),
+        _ => format!("{}::{}", crate_id, path),
+    };
+
+    for (path, module) in &modules {
+        let has_tests = tests.iter().any(|(parent, _, _)| {
+            path.is_empty() || parent == path || parent.starts_with(&format!("{}::", path))
+        });
+        if !has_tests {
+            continue;
+        }
+        let nav = module.to_nav(db);
+        let item = if path.is_empty() {
+            TestItem {
+                id: crate_id.clone(),
+                parent: None,
+                kind: TestItemKind::Crate,
+                label: label.clone(),
+                krate: krate.into(),
+                nav,
+                runnable: None,
+            }
+        } else {
+            let runnable = Runnable {
+                nav: nav.clone(),
+                kind: RunnableKind::TestMod { path: path.clone() },
+                cfg_exprs: Vec::new(),
+            };
+            TestItem {
+                id: item_id(path),
+                parent: Some(item_id(parent_module(path, &module_paths))),
+                kind: TestItemKind::Module,
+                label: module.name(db).map(|it| it.to_string()).unwrap_or_default(),
+                krate: krate.into(),
+                nav,
+                runnable: Some(runnable),
+            }
+        };
+        acc.push(item);
+
+        for (parent, test_path, runnable) in tests.iter().filter(|(parent, _, _)| parent == path) {
+            let label = match parent.as_str() {
+                "" => test_path.clone(),
+                _ => test_path[parent.len() + "::".len()..].to_string(),
+            };
+            acc.push(TestItem {
+                id: item_id(test_path),
+                parent: Some(item_id(parent)),
+                kind: TestItemKind::Test,
+                label,
+                krate: krate.into(),
+                nav: runnable.nav.clone(),
+                runnable: Some(runnable.clone()),
+            });
+        }
+    }
+}
+
+fn module_path(db: &RootDatabase, module: hir::Module) -> String {
+    module.path_to_root(db).into_iter().rev().filter_map(|it| it.name(db)).join("::")
+}
+
+/// Returns the path of the innermost module containing the item at `path`.
+/// Tests in impls have the name of the type in their path as well.
+fn parent_module<'a>(path: &'a str, module_paths: &FxHashSet<&str>) -> &'a str {
+    let mut end = path.len();
+    while let Some(idx) = path[..end].rfind("::") {
+        if module_paths.contains(&path[..idx]) {
+            return &path[..idx];
+        }
+        end = idx;
+    }
+    ""
+}
+
+#[cfg(test)]
+mod tests {
+    use expect::{expect, Expect};
+
+    use crate::mock_analysis::MockAnalysis;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let analysis = MockAnalysis::with_files(ra_fixture).analysis();
+        let items = analysis
+            .discover_tests()
+            .unwrap()
+            .into_iter()
+            .map(|item| {
+                let parent = item.parent.map(|it| format!(" (in {})", it)).unwrap_or_default();
+                let nav = item.nav;
+                format!(
+                    "{:?} {}{} {:?} {:?}: {}\n",
+                    item.kind,
+                    item.id,
+                    parent,
+                    nav.file_id,
+                    nav.focus_or_full_range(),
+                    item.label
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&items);
+    }
+
+    #[test]
+    fn discovers_tests_of_local_crates() {
+        check(
+            r#"
+//- /lib.rs cfg:test
+mod outer;
+mod no_tests;
+
+#[test]
+fn at_root() {}
+
+//- /outer.rs
+mod inner {
+    mod deeper {
+        #[test]
+        #[ignore]
+        fn deep() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn first() {}
+
+    #[tokio::test]
+    async fn second() {}
+
+    fn helper() {}
+}
+
+//- /no_tests.rs
+fn not_a_test() {}
+
+//- /foo/lib.rs
+#[test]
+fn in_dependency() {}
+"#,
+            expect![[r#"
+                Crate crate FileId(1) 0..51: crate
+                Test crate::at_root (in crate) FileId(1) 37..44: at_root
+                Module crate::outer (in crate) FileId(2) 0..216: outer
+                Module crate::outer::inner (in crate::outer) FileId(2) 4..9: inner
+                Module crate::outer::inner::deeper (in crate::outer::inner) FileId(2) 20..26: deeper
+                Test crate::outer::inner::deeper::deep (in crate::outer::inner::deeper) FileId(2) 74..78: deep
+                Module crate::outer::tests (in crate::outer) FileId(2) 110..115: tests
+                Test crate::outer::tests::first (in crate::outer::tests) FileId(2) 137..142: first
+                Test crate::outer::tests::second (in crate::outer::tests) FileId(2) 181..187: second
+                Crate foo FileId(4) 0..30: foo
+                Test foo::in_dependency (in foo) FileId(4) 11..24: in_dependency
+            "#]],
+        );
+    }
+}
@@ -42,10 +42,13 @@ impl From<Vec<SourceFileEdit>> for SourceChange {
     }
 }
 
+/// Paths in `src` and `dst` are relative to the directory of `anchor`.
 #[derive(Debug, Clone)]
 pub enum FileSystemEdit {
-    CreateFile { anchor: FileId, dst: String },
+    CreateFile { anchor: FileId, dst: String, initial_contents: String },
     MoveFile { src: FileId, anchor: FileId, dst: String },
+    MoveDir { anchor: FileId, src: String, dst: String },
+    DeleteFile { file: FileId },
 }
 
 impl From<FileSystemEdit> for SourceChange {
@@ -58,16 +58,17 @@ impl ProcMacroProcessSrv {
         Ok(result.macros)
     }
 
-    pub fn custom_derive(
+    pub fn expand(
         &self,
         dylib_path: &Path,
         subtree: &Subtree,
-        derive_name: &str,
+        macro_name: &str,
+        attributes: Option<&Subtree>,
     ) -> Result<Subtree, tt::ExpansionError> {
         let task = ExpansionTask {
             macro_body: subtree.clone(),
-            macro_name: derive_name.to_string(),
-            attributes: None,
+            macro_name: macro_name.to_string(),
+            attributes: attributes.cloned(),
             lib: dylib_path.to_path_buf(),
         };
 
@@ -0,0 +1,186 @@
+//! Handles build script specific information
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::Result;
+use cargo_metadata::{BuildScript, Message, PackageId};
+use paths::AbsPathBuf;
+use rustc_hash::FxHashMap;
+
+use crate::{cfg_flag::CfgFlag, CargoConfig};
+
+/// Everything we know about a package after running `cargo check`: the output
+/// of its build script (if it has one) and the proc-macro dylib it produced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct BuildData {
+    /// List of config flags defined by this package's build script
+    pub(crate) cfgs: Vec<CfgFlag>,
+    /// List of cargo-related environment variables with their value
+    ///
+    /// If the package has a build script which defines environment variables,
+    /// they can also be found here.
+    pub(crate) envs: Vec<(String, String)>,
+    /// Directory where a build script might place its output
+    pub(crate) out_dir: Option<AbsPathBuf>,
+    /// Path to the proc-macro library file if this package exposes proc-macros
+    pub(crate) proc_macro_dylib_path: Option<AbsPathBuf>,
+}
+
+pub(crate) type BuildDataMap = FxHashMap<PackageId, BuildData>;
+
+/// Runs `cargo check` for the whole workspace and collects the output of all
+/// build scripts, keyed by package. A failed check still yields the output of
+/// the build scripts which did run.
+pub(crate) fn collect_build_data(
+    cargo_toml: &Path,
+    cargo_features: &CargoConfig,
+) -> Result<BuildDataMap> {
+    let mut cmd = Command::new(toolchain::cargo());
+    cmd.args(&["check", "--workspace", "--message-format=json", "--manifest-path"]).arg(cargo_toml);
+    if let Some(target) = &cargo_features.target {
+        cmd.args(&["--target", target]);
+    }
+    if cargo_features.all_features {
+        cmd.arg("--all-features");
+    } else {
+        if cargo_features.no_default_features {
+            // FIXME: `NoDefaultFeatures` is mutual exclusive with `SomeFeatures`
+            // https://github.com/oli-obk/cargo_metadata/issues/79
+            cmd.arg("--no-default-features");
+        }
+        if !cargo_features.features.is_empty() {
+            cmd.arg("--features");
+            cmd.arg(cargo_features.features.join(" "));
+        }
+    }
+    cmd.stdout(Stdio::piped()).stderr(Stdio::null()).stdin(Stdio::null());
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        log::warn!("`cargo check` failed ({}), build data may be incomplete", output.status);
+    }
+    Ok(parse_build_data(&output.stdout))
+}
+
+/// Parses the JSON messages `cargo check --message-format=json` prints, one
+/// per line. Packages with unexpected build script output are skipped.
+fn parse_build_data(stdout: &[u8]) -> BuildDataMap {
+    let mut res = BuildDataMap::default();
+    for message in Message::parse_stream(stdout) {
+        let message = match message {
+            Ok(it) => it,
+            Err(_) => continue,
+        };
+        match message {
+            Message::BuildScriptExecuted(BuildScript {
+                package_id, out_dir, cfgs, env, ..
+            }) => {
+                let cfgs = match cfgs.iter().map(|cfg| cfg.parse::<CfgFlag>()).collect() {
+                    Ok(it) => it,
+                    Err(err) => {
+                        log::error!("invalid cfg from build script of {}: {}", package_id, err);
+                        continue;
+                    }
+                };
+                let data = res.entry(package_id).or_default();
+                // cargo_metadata crate returns default (empty) path for
+                // older cargos, which is not absolute, so work around that.
+                if out_dir != PathBuf::default() {
+                    let out_dir = AbsPathBuf::assert(out_dir);
+                    // NOTE: cargo and rustc seem to hide non-UTF-8 strings from env! and option_env!()
+                    if let Some(out_dir) = out_dir.to_str().map(|s| s.to_owned()) {
+                        data.envs.push(("OUT_DIR".to_string(), out_dir));
+                    }
+                    data.out_dir = Some(out_dir);
+                }
+                data.cfgs = cfgs;
+                data.envs.extend(env);
+            }
+            Message::CompilerArtifact(message) => {
+                if message.target.kind.contains(&"proc-macro".to_string()) {
+                    let package_id = message.package_id;
+                    // Skip rmeta file
+                    if let Some(filename) = message.filenames.iter().find(|name| is_dylib(name)) {
+                        let filename = AbsPathBuf::assert(filename.clone());
+                        res.entry(package_id).or_default().proc_macro_dylib_path = Some(filename);
+                    }
+                }
+            }
+            Message::CompilerMessage(_) => (),
+            Message::Unknown => (),
+            Message::BuildFinished(_) => {}
+            Message::TextLine(_) => {}
+        }
+    }
+    res
+}
+
+// FIXME: File a better way to know if it is a dylib
+fn is_dylib(path: &Path) -> bool {
+    match path.extension().and_then(OsStr::to_str).map(|it| it.to_string().to_lowercase()) {
+        None => false,
+        Some(ext) => matches!(ext.as_str(), "dll" | "dylib" | "so"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_script_executed(package: &str, cfgs: &str, env: &str, out_dir: &str) -> String {
+        format!(
+            r#"{{"reason":"build-script-executed","package_id":"{} 0.1.0 (path+file:///ws/{})","linked_libs":[],"linked_paths":[],"cfgs":{},"env":{},"out_dir":"{}"}}"#,
+            package, package, cfgs, env, out_dir
+        )
+    }
+
+    #[test]
+    fn parses_build_script_output() {
+        if cfg!(windows) {
+            return;
+        }
+        let stdout = [
+            "   Compiling foo v0.1.0 (/ws/foo)".to_string(),
+            build_script_executed(
+                "foo",
+                r#"["nightly","feature=\"std\""]"#,
+                r#"[["FOO","bar"]]"#,
+                "/ws/target/debug/build/foo-1234/out",
+            ),
+            // Built with an older cargo, which didn't report `out_dir`.
+            build_script_executed("bar", "[]", "[]", ""),
+            // An invalid cfg makes the whole package's output ignored.
+            build_script_executed("baz", r#"["invalid=cfg"]"#, "[]", "/ws/out"),
+        ]
+        .join("\n");
+
+        let res = parse_build_data(stdout.as_bytes());
+        assert_eq!(res.len(), 2);
+        let foo = &res[&PackageId { repr: "foo 0.1.0 (path+file:///ws/foo)".to_string() }];
+        assert_eq!(
+            foo.cfgs,
+            vec![
+                CfgFlag::Atom("nightly".to_string()),
+                CfgFlag::KeyValue { key: "feature".to_string(), value: "std".to_string() }
+            ]
+        );
+        assert_eq!(
+            foo.envs,
+            vec![
+                ("OUT_DIR".to_string(), "/ws/target/debug/build/foo-1234/out".to_string()),
+                ("FOO".to_string(), "bar".to_string())
+            ]
+        );
+        assert_eq!(
+            foo.out_dir,
+            Some(AbsPathBuf::assert(PathBuf::from("/ws/target/debug/build/foo-1234/out")))
+        );
+
+        let bar = &res[&PackageId { repr: "bar 0.1.0 (path+file:///ws/bar)".to_string() }];
+        assert_eq!(bar, &BuildData::default());
+    }
+}
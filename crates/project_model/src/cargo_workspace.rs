@@ -1,20 +1,15 @@
 //! FIXME: write short doc here
 
-use std::{
-    ffi::OsStr,
-    ops,
-    path::{Path, PathBuf},
-    process::Command,
-};
+use std::ops;
 
 use anyhow::{Context, Result};
 use arena::{Arena, Idx};
 use base_db::Edition;
-use cargo_metadata::{BuildScript, CargoOpt, Message, MetadataCommand, PackageId};
+use cargo_metadata::{CargoOpt, MetadataCommand};
 use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::FxHashMap;
 
-use crate::cfg_flag::CfgFlag;
+use crate::{build_data::collect_build_data, cfg_flag::CfgFlag};
 
 /// `CargoWorkspace` represents the logical structure of, well, a Cargo
 /// workspace. It pretty closely mirrors `cargo metadata` output.
@@ -59,7 +54,8 @@ pub struct CargoConfig {
     /// This will be ignored if `cargo_all_features` is true.
     pub features: Vec<String>,
 
-    /// Runs cargo check on launch to figure out the correct values of OUT_DIR
+    /// Runs cargo check on launch to run build scripts and figure out the
+    /// correct values of OUT_DIR, cfgs and env vars they set
     pub load_out_dirs_from_check: bool,
 
     /// rustc target
@@ -80,7 +76,10 @@ pub struct PackageData {
     pub dependencies: Vec<PackageDependency>,
     pub edition: Edition,
     pub features: Vec<String>,
+    /// Cfgs set by the build script via `cargo:rustc-cfg`
     pub cfgs: Vec<CfgFlag>,
+    /// Env vars set by the build script via `cargo:rustc-env`, including `OUT_DIR`
+    pub envs: Vec<(String, String)>,
     pub out_dir: Option<AbsPathBuf>,
     pub proc_macro_dylib_path: Option<AbsPathBuf>,
 }
@@ -108,6 +107,8 @@ pub enum TargetKind {
     Example,
     Test,
     Bench,
+    /// A `build.rs` script.
+    BuildScript,
     Other,
 }
 
@@ -119,6 +120,7 @@ impl TargetKind {
                 "test" => TargetKind::Test,
                 "bench" => TargetKind::Bench,
                 "example" => TargetKind::Example,
+                "custom-build" => TargetKind::BuildScript,
                 "proc-macro" => TargetKind::Lib,
                 _ if kind.contains("lib") => TargetKind::Lib,
                 _ => continue,
@@ -164,14 +166,15 @@ impl CargoWorkspace {
             format!("Failed to run `cargo metadata --manifest-path {}`", cargo_toml.display())
         })?;
 
-        let mut out_dir_by_id = FxHashMap::default();
-        let mut cfgs = FxHashMap::default();
-        let mut proc_macro_dylib_paths = FxHashMap::default();
+        let mut build_data = FxHashMap::default();
         if cargo_features.load_out_dirs_from_check {
-            let resources = load_extern_resources(cargo_toml, cargo_features)?;
-            out_dir_by_id = resources.out_dirs;
-            cfgs = resources.cfgs;
-            proc_macro_dylib_paths = resources.proc_dylib_paths;
+            build_data = match collect_build_data(cargo_toml.as_ref(), cargo_features) {
+                Ok(it) => it,
+                Err(err) => {
+                    log::error!("failed to run `cargo check` to collect build data: {:#}", err);
+                    FxHashMap::default()
+                }
+            };
         }
 
         let mut pkg_by_id = FxHashMap::default();
@@ -188,6 +191,7 @@ impl CargoWorkspace {
             let edition = edition
                 .parse::<Edition>()
                 .with_context(|| format!("Failed to parse edition {}", edition))?;
+            let build_data = build_data.remove(&id).unwrap_or_default();
             let pkg = packages.alloc(PackageData {
                 name,
                 version: version.to_string(),
@@ -197,9 +201,10 @@ impl CargoWorkspace {
                 edition,
                 dependencies: Vec::new(),
                 features: Vec::new(),
-                cfgs: cfgs.get(&id).cloned().unwrap_or_default(),
-                out_dir: out_dir_by_id.get(&id).cloned(),
-                proc_macro_dylib_path: proc_macro_dylib_paths.get(&id).cloned(),
+                cfgs: build_data.cfgs,
+                envs: build_data.envs,
+                out_dir: build_data.out_dir,
+                proc_macro_dylib_path: build_data.proc_macro_dylib_path,
             });
             let pkg_data = &mut packages[pkg];
             pkg_by_id.insert(id, pkg);
@@ -276,87 +281,3 @@ impl CargoWorkspace {
         self.packages.iter().filter(|(_, v)| v.name == name).count() == 1
     }
 }
-
-#[derive(Debug, Clone, Default)]
-pub struct ExternResources {
-    out_dirs: FxHashMap<PackageId, AbsPathBuf>,
-    proc_dylib_paths: FxHashMap<PackageId, AbsPathBuf>,
-    cfgs: FxHashMap<PackageId, Vec<CfgFlag>>,
-}
-
-pub fn load_extern_resources(
-    cargo_toml: &Path,
-    cargo_features: &CargoConfig,
-) -> Result<ExternResources> {
-    let mut cmd = Command::new(toolchain::cargo());
-    cmd.args(&["check", "--message-format=json", "--manifest-path"]).arg(cargo_toml);
-    if cargo_features.all_features {
-        cmd.arg("--all-features");
-    } else {
-        if cargo_features.no_default_features {
-            // FIXME: `NoDefaultFeatures` is mutual exclusive with `SomeFeatures`
-            // https://github.com/oli-obk/cargo_metadata/issues/79
-            cmd.arg("--no-default-features");
-        }
-        if !cargo_features.features.is_empty() {
-            cmd.arg("--features");
-            cmd.arg(cargo_features.features.join(" "));
-        }
-    }
-
-    let output = cmd.output()?;
-
-    let mut res = ExternResources::default();
-
-    for message in cargo_metadata::Message::parse_stream(output.stdout.as_slice()) {
-        if let Ok(message) = message {
-            match message {
-                Message::BuildScriptExecuted(BuildScript { package_id, out_dir, cfgs, .. }) => {
-                    let cfgs = {
-                        let mut acc = Vec::new();
-                        for cfg in cfgs {
-                            match cfg.parse::<CfgFlag>() {
-                                Ok(it) => acc.push(it),
-                                Err(err) => {
-                                    anyhow::bail!("invalid cfg from cargo-metadata: {}", err)
-                                }
-                            };
-                        }
-                        acc
-                    };
-                    // cargo_metadata crate returns default (empty) path for
-                    // older cargos, which is not absolute, so work around that.
-                    if out_dir != PathBuf::default() {
-                        let out_dir = AbsPathBuf::assert(out_dir);
-                        res.out_dirs.insert(package_id.clone(), out_dir);
-                        res.cfgs.insert(package_id, cfgs);
-                    }
-                }
-                Message::CompilerArtifact(message) => {
-                    if message.target.kind.contains(&"proc-macro".to_string()) {
-                        let package_id = message.package_id;
-                        // Skip rmeta file
-                        if let Some(filename) = message.filenames.iter().find(|name| is_dylib(name))
-                        {
-                            let filename = AbsPathBuf::assert(filename.clone());
-                            res.proc_dylib_paths.insert(package_id, filename);
-                        }
-                    }
-                }
-                Message::CompilerMessage(_) => (),
-                Message::Unknown => (),
-                Message::BuildFinished(_) => {}
-                Message::TextLine(_) => {}
-            }
-        }
-    }
-    Ok(res)
-}
-
-// FIXME: File a better way to know if it is a dylib
-fn is_dylib(path: &Path) -> bool {
-    match path.extension().and_then(OsStr::to_str).map(|it| it.to_string().to_lowercase()) {
-        None => false,
-        Some(ext) => matches!(ext.as_str(), "dll" | "dylib" | "so"),
-    }
-}
@@ -1,5 +1,6 @@
 //! FIXME: write short doc here
 
+mod build_data;
 mod cargo_workspace;
 mod project_json;
 mod sysroot;
@@ -12,7 +13,7 @@ use std::{
 };
 
 use anyhow::{bail, Context, Result};
-use base_db::{CrateGraph, CrateId, CrateName, Edition, Env, FileId};
+use base_db::{CrateGraph, CrateId, CrateName, Edition, Env, FileId, ProcMacro, ProcMacroKind};
 use cfg::CfgOptions;
 use paths::{AbsPath, AbsPathBuf};
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -266,7 +267,7 @@ impl ProjectWorkspace {
                         let proc_macro = krate
                             .proc_macro_dylib_path
                             .clone()
-                            .map(|it| proc_macro_client.by_dylib_path(&it));
+                            .map(|it| load_proc_macros(proc_macro_client, &it));
 
                         let target = krate.target.as_deref().or(target);
                         let target_cfgs = cfg_cache
@@ -365,6 +366,11 @@ impl ProjectWorkspace {
                 for pkg in cargo.packages() {
                     let mut lib_tgt = None;
                     for &tgt in cargo[pkg].targets.iter() {
+                        // Build scripts are only tracked to know when to
+                        // re-run them, they don't get a crate of their own.
+                        if cargo[tgt].kind == TargetKind::BuildScript {
+                            continue;
+                        }
                         let root = cargo[tgt].root.as_path();
                         if let Some(file_id) = load(root) {
                             let edition = cargo[pkg].edition;
@@ -377,16 +383,13 @@ impl ProjectWorkspace {
                                 opts
                             };
                             let mut env = Env::default();
-                            if let Some(out_dir) = &cargo[pkg].out_dir {
-                                // NOTE: cargo and rustc seem to hide non-UTF-8 strings from env! and option_env!()
-                                if let Some(out_dir) = out_dir.to_str().map(|s| s.to_owned()) {
-                                    env.set("OUT_DIR", out_dir);
-                                }
+                            for (k, v) in cargo[pkg].envs.iter() {
+                                env.set(k, v.clone());
                             }
                             let proc_macro = cargo[pkg]
                                 .proc_macro_dylib_path
                                 .as_ref()
-                                .map(|it| proc_macro_client.by_dylib_path(&it))
+                                .map(|it| load_proc_macros(proc_macro_client, it))
                                 .unwrap_or_default();
 
                             let crate_id = crate_graph.add_crate_root(
@@ -501,6 +504,21 @@ impl ProjectWorkspace {
     }
 }
 
+fn load_proc_macros(client: &ProcMacroClient, path: &AbsPath) -> Vec<ProcMacro> {
+    client
+        .by_dylib_path(path)
+        .into_iter()
+        .map(|(name, kind, expander)| {
+            let kind = match kind {
+                proc_macro_api::ProcMacroKind::CustomDerive => ProcMacroKind::CustomDerive,
+                proc_macro_api::ProcMacroKind::FuncLike => ProcMacroKind::FuncLike,
+                proc_macro_api::ProcMacroKind::Attr => ProcMacroKind::Attr,
+            };
+            ProcMacro { name, kind, expander }
+        })
+        .collect()
+}
+
 fn get_rustc_cfg_options(target: Option<&str>) -> Vec<CfgFlag> {
     let mut res = Vec::new();
 
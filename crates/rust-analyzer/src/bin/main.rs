@@ -40,6 +40,9 @@ fn try_main() -> Result<()> {
         args::Command::Diagnostics { path, load_output_dirs, with_proc_macro, all } => {
             cli::diagnostics(path.as_ref(), load_output_dirs, with_proc_macro, all)?
         }
+        args::Command::Lsif { path, load_output_dirs, with_proc_macro } => {
+            cli::lsif(path.as_ref(), load_output_dirs, with_proc_macro)?
+        }
         args::Command::Ssr { rules } => {
             cli::apply_ssr_rules(rules)?;
         }
@@ -78,7 +81,8 @@ fn run_server() -> Result<()> {
         }),
     };
 
-    let initialize_result = serde_json::to_value(initialize_result).unwrap();
+    let mut initialize_result = serde_json::to_value(initialize_result).unwrap();
+    initialize_result["capabilities"]["workspace"] = rust_analyzer::workspace_capabilities();
 
     connection.initialize_finish(initialize_id, initialize_result)?;
 
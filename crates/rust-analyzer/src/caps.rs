@@ -3,7 +3,7 @@ use std::env;
 
 use lsp_types::{
     CallHierarchyServerCapability, ClientCapabilities, CodeActionKind, CodeActionOptions,
-    CodeActionProviderCapability, CodeLensOptions, CompletionOptions,
+    CodeActionProviderCapability, CodeLensOptions, CompletionOptions, DocumentLinkOptions,
     DocumentOnTypeFormattingOptions, FoldingRangeProviderCapability, HoverProviderCapability,
     ImplementationProviderCapability, RenameOptions, RenameProviderCapability, SaveOptions,
     SelectionRangeProviderCapability, SemanticTokensDocumentProvider, SemanticTokensLegend,
@@ -11,7 +11,7 @@ use lsp_types::{
     TextDocumentSyncKind, TextDocumentSyncOptions, TypeDefinitionProviderCapability,
     WorkDoneProgressOptions,
 };
-use serde_json::json;
+use serde_json::{json, Value};
 
 use crate::semantic_tokens;
 
@@ -52,7 +52,7 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
         code_action_provider: Some(code_action_provider),
         code_lens_provider: Some(CodeLensOptions { resolve_provider: Some(true) }),
         document_formatting_provider: Some(true),
-        document_range_formatting_provider: None,
+        document_range_formatting_provider: Some(true),
         document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
             first_trigger_character: "=".to_string(),
             more_trigger_character: Some(vec![".".to_string(), ">".to_string()]),
@@ -64,7 +64,10 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
             prepare_provider: Some(true),
             work_done_progress_options: WorkDoneProgressOptions { work_done_progress: None },
         })),
-        document_link_provider: None,
+        document_link_provider: Some(DocumentLinkOptions {
+            resolve_provider: None,
+            work_done_progress_options: WorkDoneProgressOptions { work_done_progress: None },
+        }),
         color_provider: None,
         execute_command_provider: None,
         workspace: None,
@@ -96,6 +99,21 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
     }
 }
 
+/// The `workspace` capabilities, which `lsp_types` can't represent yet. They
+/// are patched into the serialized `ServerCapabilities`.
+pub fn workspace_capabilities() -> Value {
+    let rust_files =
+        json!({ "scheme": "file", "pattern": { "glob": "**/*.rs", "matches": "file" } });
+    let folders = json!({ "scheme": "file", "pattern": { "glob": "**", "matches": "folder" } });
+    json!({
+        "fileOperations": {
+            "willRename": {
+                "filters": [rust_files, folders],
+            },
+        },
+    })
+}
+
 fn code_action_capabilities(client_caps: &ClientCapabilities) -> CodeActionProviderCapability {
     client_caps
         .text_document
@@ -148,7 +148,7 @@ impl CargoTargetSpec {
             TargetKind::Lib => {
                 buf.push("--lib".to_string());
             }
-            TargetKind::Other => (),
+            TargetKind::BuildScript | TargetKind::Other => (),
         }
     }
 }
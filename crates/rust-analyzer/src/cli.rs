@@ -4,6 +4,7 @@ mod load_cargo;
 mod analysis_stats;
 mod analysis_bench;
 mod diagnostics;
+mod lsif;
 mod progress_report;
 mod ssr;
 
@@ -18,6 +19,7 @@ pub use self::{
     analysis_stats::AnalysisStatsCmd,
     diagnostics::diagnostics,
     load_cargo::load_cargo,
+    lsif::lsif,
     ssr::{apply_ssr_rules, search_for_patterns},
 };
 
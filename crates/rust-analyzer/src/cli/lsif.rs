@@ -0,0 +1,679 @@
+//! Dumps an LSIF index of a Cargo project, for consumption by code browsers.
+//!
+//! LSIF ("Language Server Index Format") is a graph of vertices (documents,
+//! ranges, result sets and results) and edges between them, serialized as one
+//! JSON object per line. Each identifier in the project gets a range, which is
+//! linked to a result set shared by all references to the same definition. The
+//! result set in turn holds the precomputed hover, definition and references
+//! results. See <https://microsoft.github.io/language-server-protocol/specifications/lsif/0.5.0/specification/>.
+
+use std::{
+    env,
+    io::{self, Write},
+    path::Path,
+    time::Instant,
+};
+
+use base_db::{FileId, SourceDatabaseExt};
+use hir::{AsAssocItem, AssocItemContainer, Crate, ModuleDef, Semantics};
+use ide::{Analysis, FilePosition, NavigationTarget, RootDatabase};
+use ide_db::{
+    defs::{classify_name, Definition},
+    LineIndexDatabase,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use syntax::{algo, ast, AstNode, SyntaxKind::*, TextRange, T};
+use vfs::{AbsPath, AbsPathBuf, Vfs};
+
+use crate::{
+    cli::{load_cargo::load_cargo, Result},
+    to_proto,
+};
+
+pub fn lsif(path: &Path, load_output_dirs: bool, with_proc_macro: bool) -> Result<()> {
+    let now = Instant::now();
+    let (host, vfs) = load_cargo(path, load_output_dirs, with_proc_macro)?;
+    let project_root = AbsPathBuf::assert(env::current_dir()?.join(path));
+    let stdout = io::stdout();
+    let n_files =
+        index(&host.analysis(), host.raw_database(), &vfs, &project_root, &mut stdout.lock())?;
+    eprintln!("Generated LSIF for {} files in {:?}", n_files, now.elapsed());
+    Ok(())
+}
+
+/// Writes the index of all local files, and returns the number of local files.
+fn index(
+    analysis: &Analysis,
+    db: &RootDatabase,
+    vfs: &Vfs,
+    project_root: &AbsPath,
+    out: &mut dyn Write,
+) -> Result<usize> {
+    let mut work = Vec::new();
+    let mut visited_source_roots = FxHashSet::default();
+    for krate in Crate::all(db) {
+        let file_id = krate.root_module(db).definition_source(db).file_id.original_file(db);
+        let source_root_id = db.file_source_root(file_id);
+        if !visited_source_roots.insert(source_root_id) {
+            continue;
+        }
+        let source_root = db.source_root(source_root_id);
+        if !source_root.is_library {
+            work.extend(source_root.iter());
+        }
+    }
+    work.sort();
+
+    let mut emitter = LsifEmitter::new(analysis, db, vfs, out);
+    emitter.emit(Element::Vertex(Vertex::MetaData(MetaData {
+        version: "0.5.0",
+        project_root: to_proto::url_from_abs_path(project_root),
+        position_encoding: "utf-16",
+        tool_info: ToolInfo { name: "rust-analyzer", version: env!("REV") },
+    })))?;
+    for &file_id in &work {
+        emitter.index_file(file_id)?;
+    }
+    emitter.finish()?;
+    Ok(work.len())
+}
+
+type Id = u64;
+
+struct LsifEmitter<'a> {
+    analysis: &'a Analysis,
+    db: &'a RootDatabase,
+    vfs: &'a Vfs,
+    out: &'a mut dyn Write,
+    next_id: Id,
+    documents: FxHashMap<FileId, Id>,
+    /// Range vertices of each document, in the order they were emitted.
+    document_ranges: FxHashMap<FileId, Vec<Id>>,
+    ranges: FxHashMap<(FileId, TextRange), Id>,
+    /// Ranges which already have a `next` edge to a result set.
+    linked_ranges: FxHashSet<Id>,
+    /// Result sets, keyed by the location of their definition.
+    result_sets: FxHashMap<(FileId, TextRange), Id>,
+}
+
+impl<'a> LsifEmitter<'a> {
+    fn new(
+        analysis: &'a Analysis,
+        db: &'a RootDatabase,
+        vfs: &'a Vfs,
+        out: &'a mut dyn Write,
+    ) -> LsifEmitter<'a> {
+        LsifEmitter {
+            analysis,
+            db,
+            vfs,
+            out,
+            next_id: 0,
+            documents: FxHashMap::default(),
+            document_ranges: FxHashMap::default(),
+            ranges: FxHashMap::default(),
+            linked_ranges: FxHashSet::default(),
+            result_sets: FxHashMap::default(),
+        }
+    }
+
+    fn emit(&mut self, data: Element) -> Result<Id> {
+        self.next_id += 1;
+        let id = self.next_id;
+        writeln!(self.out, "{}", serde_json::to_string(&Entry { id, data })?)?;
+        Ok(id)
+    }
+
+    fn emit_edge(&mut self, edge: Edge) -> Result<Id> {
+        self.emit(Element::Edge(edge))
+    }
+
+    fn index_file(&mut self, file_id: FileId) -> Result<()> {
+        // Files which don't exist on disk, like generated ones, can't be
+        // referred to by an URI.
+        if self.document(file_id)?.is_none() {
+            return Ok(());
+        }
+        let file = self.analysis.parse(file_id)?;
+        let tokens =
+            file.syntax().descendants_with_tokens().filter_map(|it| it.into_token()).filter(|it| {
+                matches!(it.kind(), IDENT | LIFETIME | T![self] | T![super] | T![crate])
+            });
+        for token in tokens {
+            self.index_token(file_id, token.text_range())?;
+        }
+        Ok(())
+    }
+
+    fn index_token(&mut self, file_id: FileId, range: TextRange) -> Result<()> {
+        if let Some(range_id) = self.ranges.get(&(file_id, range)) {
+            if self.linked_ranges.contains(range_id) {
+                return Ok(());
+            }
+        }
+        let position = FilePosition { file_id, offset: range.start() };
+        let nav = match self.analysis.goto_definition(position)? {
+            Some(it) => it.info.into_iter().next(),
+            None => None,
+        };
+        let nav = match nav {
+            Some(it) => it,
+            None => return Ok(()),
+        };
+        let result_set = match self.result_set(&nav)? {
+            Some(it) => it,
+            None => return Ok(()),
+        };
+        let range_id = self.range(file_id, range)?;
+        self.link(range_id, result_set)?;
+        Ok(())
+    }
+
+    /// Returns the result set of the given definition, emitting it together
+    /// with its results the first time the definition is encountered.
+    fn result_set(&mut self, nav: &NavigationTarget) -> Result<Option<Id>> {
+        let def_range = nav.focus_range.unwrap_or(nav.full_range);
+        if let Some(&id) = self.result_sets.get(&(nav.file_id, def_range)) {
+            return Ok(Some(id));
+        }
+        let document = match self.document(nav.file_id)? {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let result_set = self.emit(Element::Vertex(Vertex::ResultSet))?;
+        self.result_sets.insert((nav.file_id, def_range), result_set);
+
+        let def_range_id = self.range(nav.file_id, def_range)?;
+        self.link(def_range_id, result_set)?;
+        let position = FilePosition { file_id: nav.file_id, offset: def_range.start() };
+
+        let definition_result = self.emit(Element::Vertex(Vertex::DefinitionResult))?;
+        self.emit_edge(Edge::Definition(EdgeData { out_v: result_set, in_v: definition_result }))?;
+        self.emit_edge(Edge::Item(Item {
+            document,
+            property: None,
+            edge_data: EdgeDataMultiIn { out_v: definition_result, in_vs: vec![def_range_id] },
+        }))?;
+
+        if let Some(hover) = self.analysis.hover(position)? {
+            let hover_result = self.emit(Element::Vertex(Vertex::HoverResult {
+                result: lsp_types::Hover {
+                    contents: lsp_types::HoverContents::Markup(to_proto::markup_content(
+                        hover.info.markup,
+                    )),
+                    range: None,
+                },
+            }))?;
+            self.emit_edge(Edge::Hover(EdgeData { out_v: result_set, in_v: hover_result }))?;
+        }
+
+        if let Some(refs) = self.analysis.find_all_refs(position, None)? {
+            let reference_result = self.emit(Element::Vertex(Vertex::ReferenceResult))?;
+            self.emit_edge(Edge::References(EdgeData {
+                out_v: result_set,
+                in_v: reference_result,
+            }))?;
+            self.emit_edge(Edge::Item(Item {
+                document,
+                property: Some(ItemKind::Definitions),
+                edge_data: EdgeDataMultiIn { out_v: reference_result, in_vs: vec![def_range_id] },
+            }))?;
+
+            let mut references_by_file: FxHashMap<FileId, Vec<Id>> = FxHashMap::default();
+            for reference in refs.references() {
+                let file_range = reference.file_range;
+                if self.document(file_range.file_id)?.is_none() {
+                    continue;
+                }
+                let range_id = self.range(file_range.file_id, file_range.range)?;
+                self.link(range_id, result_set)?;
+                references_by_file.entry(file_range.file_id).or_default().push(range_id);
+            }
+            let mut references_by_file = references_by_file.into_iter().collect::<Vec<_>>();
+            references_by_file.sort_by_key(|(file_id, _)| *file_id);
+            for (file_id, in_vs) in references_by_file {
+                let document = self.documents[&file_id];
+                self.emit_edge(Edge::Item(Item {
+                    document,
+                    property: Some(ItemKind::References),
+                    edge_data: EdgeDataMultiIn { out_v: reference_result, in_vs },
+                }))?;
+            }
+        }
+
+        if let Some(moniker) = self.moniker(nav) {
+            let moniker = self.emit(Element::Vertex(Vertex::Moniker(moniker)))?;
+            self.emit_edge(Edge::Moniker(EdgeData { out_v: result_set, in_v: moniker }))?;
+        }
+
+        Ok(Some(result_set))
+    }
+
+    /// Computes a name for the definition which is stable across indexes, so
+    /// that definitions from dependencies can be matched with their own index.
+    /// Only items which can be named from outside of their crate get one.
+    fn moniker(&self, nav: &NavigationTarget) -> Option<Moniker> {
+        let sema = Semantics::new(self.db);
+        let file = sema.parse(nav.file_id);
+        let name = algo::find_node_at_offset::<ast::Name>(file.syntax(), nav.focus_range?.start())?;
+        let def = match classify_name(&sema, &name)?.definition(self.db) {
+            Definition::ModuleDef(it) => it,
+            _ => return None,
+        };
+        let identifier = item_path(self.db, def)?;
+
+        let source_root = self.db.source_root(self.db.file_source_root(nav.file_id));
+        let kind = if source_root.is_library { MonikerKind::Import } else { MonikerKind::Export };
+        Some(Moniker { scheme: "rust-analyzer", identifier, kind })
+    }
+
+    /// Returns the document of the file, or `None` if the file doesn't exist on
+    /// disk.
+    fn document(&mut self, file_id: FileId) -> Result<Option<Id>> {
+        if let Some(&id) = self.documents.get(&file_id) {
+            return Ok(Some(id));
+        }
+        let path = self.vfs.file_path(file_id);
+        let path = match path.as_path() {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let uri = to_proto::url_from_abs_path(path);
+        let id =
+            self.emit(Element::Vertex(Vertex::Document(Document { uri, language_id: "rust" })))?;
+        self.documents.insert(file_id, id);
+        Ok(Some(id))
+    }
+
+    fn range(&mut self, file_id: FileId, range: TextRange) -> Result<Id> {
+        if let Some(&id) = self.ranges.get(&(file_id, range)) {
+            return Ok(id);
+        }
+        let line_index = self.db.line_index(file_id);
+        let id = self.emit(Element::Vertex(Vertex::Range(to_proto::range(&line_index, range))))?;
+        self.ranges.insert((file_id, range), id);
+        self.document_ranges.entry(file_id).or_default().push(id);
+        Ok(id)
+    }
+
+    fn link(&mut self, range_id: Id, result_set: Id) -> Result<()> {
+        if self.linked_ranges.insert(range_id) {
+            self.emit_edge(Edge::Next(EdgeData { out_v: range_id, in_v: result_set }))?;
+        }
+        Ok(())
+    }
+
+    /// Connects all documents to their ranges.
+    fn finish(mut self) -> Result<()> {
+        let mut documents = self.documents.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>();
+        documents.sort();
+        for (file_id, document) in documents {
+            let in_vs = self.document_ranges.remove(&file_id).unwrap_or_default();
+            if !in_vs.is_empty() {
+                self.emit_edge(Edge::Contains(EdgeDataMultiIn { out_v: document, in_vs }))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the kind of the item followed by its path, like `fn foo::bar::baz`.
+/// The kind sets apart items with the same path from different namespaces,
+/// like `mod parse` and `fn parse`. Items of impls don't have a path.
+fn item_path(db: &RootDatabase, def: ModuleDef) -> Option<String> {
+    let (kind, mut path) = match def {
+        ModuleDef::Module(it) => return Some(format!("mod {}", module_path(db, it)?.join("::"))),
+        ModuleDef::EnumVariant(it) => {
+            let mut path = module_path(db, it.module(db))?;
+            path.push(it.parent_enum(db).name(db).to_string());
+            ("variant", path)
+        }
+        ModuleDef::Function(_) | ModuleDef::Const(_) | ModuleDef::TypeAlias(_) => {
+            let kind = match def {
+                ModuleDef::Function(_) => "fn",
+                ModuleDef::Const(_) => "const",
+                _ => "type",
+            };
+            let assoc_item = match def {
+                ModuleDef::Function(it) => it.as_assoc_item(db),
+                ModuleDef::Const(it) => it.as_assoc_item(db),
+                ModuleDef::TypeAlias(it) => it.as_assoc_item(db),
+                _ => None,
+            };
+            match assoc_item.map(|it| it.container(db)) {
+                Some(AssocItemContainer::Trait(trait_)) => {
+                    let mut path = module_path(db, trait_.module(db))?;
+                    path.push(trait_.name(db).to_string());
+                    (kind, path)
+                }
+                Some(AssocItemContainer::ImplDef(_)) => return None,
+                None => (kind, module_path(db, def.module(db)?)?),
+            }
+        }
+        ModuleDef::Adt(hir::Adt::Struct(_)) => ("struct", module_path(db, def.module(db)?)?),
+        ModuleDef::Adt(hir::Adt::Union(_)) => ("union", module_path(db, def.module(db)?)?),
+        ModuleDef::Adt(hir::Adt::Enum(_)) => ("enum", module_path(db, def.module(db)?)?),
+        ModuleDef::Static(_) => ("static", module_path(db, def.module(db)?)?),
+        ModuleDef::Trait(_) => ("trait", module_path(db, def.module(db)?)?),
+        ModuleDef::BuiltinType(_) => return None,
+    };
+    path.push(def.name(db)?.to_string());
+    Some(format!("{} {}", kind, path.join("::")))
+}
+
+/// Returns the name of the crate, followed by the names of the modules down
+/// to `module`.
+fn module_path(db: &RootDatabase, module: hir::Module) -> Option<Vec<String>> {
+    let mut path = vec![module.krate().display_name(db)?];
+    path.extend(
+        module
+            .path_to_root(db)
+            .into_iter()
+            .rev()
+            .filter_map(|it| it.name(db))
+            .map(|it| it.to_string()),
+    );
+    Some(path)
+}
+
+#[derive(Serialize)]
+struct Entry {
+    id: Id,
+    #[serde(flatten)]
+    data: Element,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Element {
+    Vertex(Vertex),
+    Edge(Edge),
+}
+
+#[derive(Serialize)]
+#[serde(tag = "label", rename_all = "camelCase")]
+enum Vertex {
+    MetaData(MetaData),
+    Document(Document),
+    Range(lsp_types::Range),
+    ResultSet,
+    HoverResult { result: lsp_types::Hover },
+    DefinitionResult,
+    ReferenceResult,
+    Moniker(Moniker),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MetaData {
+    version: &'static str,
+    project_root: lsp_types::Url,
+    position_encoding: &'static str,
+    tool_info: ToolInfo,
+}
+
+#[derive(Serialize)]
+struct ToolInfo {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    uri: lsp_types::Url,
+    language_id: &'static str,
+}
+
+#[derive(Serialize)]
+struct Moniker {
+    scheme: &'static str,
+    identifier: String,
+    kind: MonikerKind,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum MonikerKind {
+    Import,
+    Export,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "label")]
+enum Edge {
+    #[serde(rename = "contains")]
+    Contains(EdgeDataMultiIn),
+    #[serde(rename = "next")]
+    Next(EdgeData),
+    #[serde(rename = "moniker")]
+    Moniker(EdgeData),
+    #[serde(rename = "item")]
+    Item(Item),
+    #[serde(rename = "textDocument/definition")]
+    Definition(EdgeData),
+    #[serde(rename = "textDocument/hover")]
+    Hover(EdgeData),
+    #[serde(rename = "textDocument/references")]
+    References(EdgeData),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EdgeData {
+    out_v: Id,
+    in_v: Id,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EdgeDataMultiIn {
+    out_v: Id,
+    in_vs: Vec<Id>,
+}
+
+#[derive(Serialize)]
+struct Item {
+    document: Id,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    property: Option<ItemKind>,
+    #[serde(flatten)]
+    edge_data: EdgeDataMultiIn,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ItemKind {
+    Definitions,
+    References,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use base_db::{CrateGraph, CrateName, Edition, Env, SourceRoot};
+    use cfg::CfgOptions;
+    use expect::expect;
+    use ide::{AnalysisChange, AnalysisHost};
+    use vfs::{file_set::FileSet, VfsPath};
+
+    use super::*;
+
+    /// Renders the index with one vertex or edge per line, leaving out hovers.
+    fn render(index: &str) -> String {
+        let mut buf = String::new();
+        for line in index.lines() {
+            let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+            let label = entry["label"].as_str().unwrap();
+            buf.push_str(&format!("{} {}", entry["id"], label));
+            if entry["type"] == "edge" {
+                buf.push_str(&format!(" {} ->", entry["outV"]));
+                match entry.get("inVs") {
+                    Some(in_vs) => buf.push_str(&format!(" {}", in_vs)),
+                    None => buf.push_str(&format!(" {}", entry["inV"])),
+                }
+                if let Some(property) = entry.get("property") {
+                    buf.push_str(&format!(" {}", property.as_str().unwrap()));
+                }
+                buf.push('\n');
+                continue;
+            }
+            match label {
+                "document" => buf.push_str(&format!(" {}", entry["uri"].as_str().unwrap())),
+                "range" => buf.push_str(&format!(
+                    " {}:{}-{}:{}",
+                    entry["start"]["line"],
+                    entry["start"]["character"],
+                    entry["end"]["line"],
+                    entry["end"]["character"]
+                )),
+                "moniker" => buf.push_str(&format!(
+                    " {} {}",
+                    entry["kind"].as_str().unwrap(),
+                    entry["identifier"].as_str().unwrap()
+                )),
+                _ => (),
+            }
+            buf.push('\n');
+        }
+        buf
+    }
+
+    #[test]
+    fn indexes_small_crate() {
+        if cfg!(windows) {
+            return;
+        }
+        let files = [
+            (
+                "/ws/lib.rs",
+                r#"pub mod m {
+    pub struct S;
+    impl S {
+        pub fn new() -> S { S }
+    }
+}
+pub fn new() -> m::S { m::S::new() }
+"#,
+            ),
+            // Not on disk, so left out of the index together with the
+            // reference to `foo::new`.
+            ("/generated.rs", "fn f() { foo::new(); }\n"),
+        ];
+        let mut vfs = Vfs::default();
+        let mut change = AnalysisChange::new();
+        let mut file_sets = vec![FileSet::default(), FileSet::default()];
+        for (file_set, &(path, text)) in file_sets.iter_mut().zip(files.iter()) {
+            let path = match path.strip_prefix("/generated") {
+                Some(_) => VfsPath::new_virtual_path(path.to_string()),
+                None => VfsPath::from(AbsPathBuf::assert(path.into())),
+            };
+            vfs.set_file_contents(path.clone(), Some(text.as_bytes().to_vec()));
+            let file_id = vfs.file_id(&path).unwrap();
+            change.change_file(file_id, Some(Arc::new(text.to_string())));
+            file_set.insert(file_id, path);
+        }
+        change.set_roots(file_sets.into_iter().map(SourceRoot::new_local).collect());
+
+        let mut crate_graph = CrateGraph::default();
+        let crates = (0..files.len())
+            .map(|idx| {
+                crate_graph.add_crate_root(
+                    FileId(idx as u32),
+                    Edition::Edition2018,
+                    Some(["foo", "generated"][idx].to_string()),
+                    CfgOptions::default(),
+                    Env::default(),
+                    Vec::new(),
+                )
+            })
+            .collect::<Vec<_>>();
+        crate_graph.add_dep(crates[1], CrateName::new("foo").unwrap(), crates[0]).unwrap();
+        change.set_crate_graph(crate_graph);
+
+        let mut host = AnalysisHost::default();
+        host.apply_change(change);
+        let mut out = Vec::new();
+        let project_root = AbsPathBuf::assert("/ws".into());
+        let n_files =
+            index(&host.analysis(), host.raw_database(), &vfs, &project_root, &mut out).unwrap();
+        assert_eq!(n_files, 2);
+        expect![[r#"
+            1 metaData
+            2 document file:///ws/lib.rs
+            3 resultSet
+            4 range 0:8-0:9
+            5 next 4 -> 3
+            6 definitionResult
+            7 textDocument/definition 3 -> 6
+            8 item 6 -> [4]
+            9 hoverResult
+            10 textDocument/hover 3 -> 9
+            11 referenceResult
+            12 textDocument/references 3 -> 11
+            13 item 11 -> [4] definitions
+            14 range 6:16-6:17
+            15 next 14 -> 3
+            16 range 6:23-6:24
+            17 next 16 -> 3
+            18 item 11 -> [14,16] references
+            19 moniker export mod foo::m
+            20 moniker 3 -> 19
+            21 resultSet
+            22 range 1:15-1:16
+            23 next 22 -> 21
+            24 definitionResult
+            25 textDocument/definition 21 -> 24
+            26 item 24 -> [22]
+            27 hoverResult
+            28 textDocument/hover 21 -> 27
+            29 referenceResult
+            30 textDocument/references 21 -> 29
+            31 item 29 -> [22] definitions
+            32 range 2:9-2:10
+            33 next 32 -> 21
+            34 range 3:24-3:25
+            35 next 34 -> 21
+            36 range 3:28-3:29
+            37 next 36 -> 21
+            38 range 6:19-6:20
+            39 next 38 -> 21
+            40 range 6:26-6:27
+            41 next 40 -> 21
+            42 item 29 -> [32,34,36,38,40] references
+            43 moniker export struct foo::m::S
+            44 moniker 21 -> 43
+            45 resultSet
+            46 range 3:15-3:18
+            47 next 46 -> 45
+            48 definitionResult
+            49 textDocument/definition 45 -> 48
+            50 item 48 -> [46]
+            51 hoverResult
+            52 textDocument/hover 45 -> 51
+            53 referenceResult
+            54 textDocument/references 45 -> 53
+            55 item 53 -> [46] definitions
+            56 range 6:29-6:32
+            57 next 56 -> 45
+            58 item 53 -> [56] references
+            59 resultSet
+            60 range 6:7-6:10
+            61 next 60 -> 59
+            62 definitionResult
+            63 textDocument/definition 59 -> 62
+            64 item 62 -> [60]
+            65 hoverResult
+            66 textDocument/hover 59 -> 65
+            67 referenceResult
+            68 textDocument/references 59 -> 67
+            69 item 67 -> [60] definitions
+            70 moniker export fn foo::new
+            71 moniker 59 -> 70
+            72 contains 2 -> [4,14,16,22,32,34,36,38,40,46,56,60]
+        "#]]
+        .assert_eq(&render(&String::from_utf8(out).unwrap()));
+    }
+}
@@ -121,7 +121,7 @@ pub struct NotificationsConfig {
 
 #[derive(Debug, Clone)]
 pub enum RustfmtConfig {
-    Rustfmt { extra_args: Vec<String> },
+    Rustfmt { extra_args: Vec<String>, enable_range_formatting: bool },
     CustomCommand { command: String, args: Vec<String> },
 }
 
@@ -155,7 +155,10 @@ impl Config {
 
             cargo_autoreload: true,
             cargo: CargoConfig::default(),
-            rustfmt: RustfmtConfig::Rustfmt { extra_args: Vec::new() },
+            rustfmt: RustfmtConfig::Rustfmt {
+                extra_args: Vec::new(),
+                enable_range_formatting: false,
+            },
             flycheck: Some(FlycheckConfig::CargoCommand {
                 command: "check".to_string(),
                 target_triple: None,
@@ -170,10 +173,14 @@ impl Config {
                 type_hints: true,
                 parameter_hints: true,
                 chaining_hints: true,
+                lifetime_elision_hints: false,
+                closure_return_type_hints: false,
+                reborrow_hints: false,
                 max_length: None,
             },
             completion: CompletionConfig {
                 enable_postfix_completions: true,
+                enable_autoimport_completions: true,
                 add_call_parenthesis: true,
                 add_call_argument_snippets: true,
                 ..CompletionConfig::default()
@@ -232,7 +239,10 @@ impl Config {
                 let command = args.remove(0);
                 RustfmtConfig::CustomCommand { command, args }
             }
-            Some(_) | None => RustfmtConfig::Rustfmt { extra_args: data.rustfmt_extraArgs },
+            Some(_) | None => RustfmtConfig::Rustfmt {
+                extra_args: data.rustfmt_extraArgs,
+                enable_range_formatting: data.rustfmt_enableRangeFormatting,
+            },
         };
 
         self.flycheck = if data.checkOnSave_enable {
@@ -262,10 +272,14 @@ impl Config {
             type_hints: data.inlayHints_typeHints,
             parameter_hints: data.inlayHints_parameterHints,
             chaining_hints: data.inlayHints_chainingHints,
+            lifetime_elision_hints: data.inlayHints_lifetimeElisionHints,
+            closure_return_type_hints: data.inlayHints_closureReturnTypeHints,
+            reborrow_hints: data.inlayHints_reborrowHints,
             max_length: data.inlayHints_maxLength,
         };
 
         self.completion.enable_postfix_completions = data.completion_postfix_enable;
+        self.completion.enable_autoimport_completions = data.completion_autoimport_enable;
         self.completion.add_call_parenthesis = data.completion_addCallParenthesis;
         self.completion.add_call_argument_snippets = data.completion_addCallArgumentSnippets;
 
@@ -414,7 +428,7 @@ config_data! {
         cargo_autoreload: bool           = true,
         cargo_allFeatures: bool          = false,
         cargo_features: Vec<String>      = Vec::new(),
-        cargo_loadOutDirsFromCheck: bool = false,
+        cargo_loadOutDirsFromCheck: bool = true,
         cargo_noDefaultFeatures: bool    = false,
         cargo_target: Option<String>     = None,
 
@@ -431,6 +445,7 @@ config_data! {
         completion_addCallArgumentSnippets: bool = true,
         completion_addCallParenthesis: bool      = true,
         completion_postfix_enable: bool          = true,
+        completion_autoimport_enable: bool       = true,
 
         diagnostics_enable: bool                = true,
         diagnostics_enableExperimental: bool    = true,
@@ -445,10 +460,13 @@ config_data! {
         hoverActions_implementations: bool = true,
         hoverActions_run: bool             = true,
 
-        inlayHints_chainingHints: bool      = true,
-        inlayHints_maxLength: Option<usize> = None,
-        inlayHints_parameterHints: bool     = true,
-        inlayHints_typeHints: bool          = true,
+        inlayHints_chainingHints: bool          = true,
+        inlayHints_closureReturnTypeHints: bool = false,
+        inlayHints_lifetimeElisionHints: bool   = false,
+        inlayHints_maxLength: Option<usize>     = None,
+        inlayHints_parameterHints: bool         = true,
+        inlayHints_reborrowHints: bool          = false,
+        inlayHints_typeHints: bool              = true,
 
         lens_debug: bool           = true,
         lens_enable: bool          = true,
@@ -460,6 +478,7 @@ config_data! {
         notifications_cargoTomlNotFound: bool      = true,
         procMacro_enable: bool                     = false,
 
+        rustfmt_enableRangeFormatting: bool          = false,
         rustfmt_extraArgs: Vec<String>               = Vec::new(),
         rustfmt_overrideCommand: Option<Vec<String>> = None,
 
@@ -0,0 +1,131 @@
+//! Computes a line-based diff between two texts, so that formatting only
+//! touches the lines it actually changes, and cursors and marks on the other
+//! lines stay where they are.
+
+use stdx::lines_with_ends;
+use syntax::{TextRange, TextSize};
+use text_edit::TextEdit;
+
+/// Past this many line pairs, the changed lines are replaced as a whole
+/// instead of computing their longest common subsequence.
+const MAX_LCS_SIZE: usize = 4_000_000;
+
+pub(crate) fn diff(left: &str, right: &str) -> TextEdit {
+    let left = lines_with_ends(left).collect::<Vec<_>>();
+    let right = lines_with_ends(right).collect::<Vec<_>>();
+
+    let prefix = left.iter().zip(&right).take_while(|(l, r)| l == r).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let left_mid = &left[prefix..left.len() - suffix];
+    let right_mid = &right[prefix..right.len() - suffix];
+
+    let mut builder = TextEdit::builder();
+    let mut offset: TextSize = left[..prefix].iter().map(|it| TextSize::of(*it)).sum();
+    for op in line_ops(left_mid, right_mid) {
+        match op {
+            LineOp::Keep(line) => offset += TextSize::of(line),
+            LineOp::Replace { delete, insert } => {
+                let len = delete.iter().map(|it| TextSize::of(*it)).sum();
+                builder.replace(TextRange::at(offset, len), insert.concat());
+                offset += len;
+            }
+        }
+    }
+    builder.finish()
+}
+
+enum LineOp<'a> {
+    Keep(&'a str),
+    Replace { delete: &'a [&'a str], insert: &'a [&'a str] },
+}
+
+fn line_ops<'a>(left: &'a [&'a str], right: &'a [&'a str]) -> Vec<LineOp<'a>> {
+    if left.is_empty() && right.is_empty() {
+        return Vec::new();
+    }
+    if left.len().saturating_mul(right.len()) > MAX_LCS_SIZE {
+        return vec![LineOp::Replace { delete: left, insert: right }];
+    }
+
+    // `lcs[i][j]` is the length of the longest common subsequence of
+    // `left[i..]` and `right[j..]`.
+    let width = right.len() + 1;
+    let mut lcs = vec![0u32; (left.len() + 1) * width];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lcs[i * width + j] = if left[i] == right[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut res = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut hunk_i, mut hunk_j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            if (hunk_i, hunk_j) != (i, j) {
+                res.push(LineOp::Replace { delete: &left[hunk_i..i], insert: &right[hunk_j..j] });
+            }
+            res.push(LineOp::Keep(left[i]));
+            i += 1;
+            j += 1;
+            hunk_i = i;
+            hunk_j = j;
+        } else if j == right.len()
+            || (i < left.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if (hunk_i, hunk_j) != (i, j) {
+        res.push(LineOp::Replace { delete: &left[hunk_i..], insert: &right[hunk_j..] });
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(left: &str, right: &str, expected_edits: &[(&str, &str)]) {
+        let edit = diff(left, right);
+        let edits =
+            edit.iter().map(|it| (&left[it.delete], it.insert.as_str())).collect::<Vec<_>>();
+        assert_eq!(edits, expected_edits);
+
+        let mut actual = left.to_string();
+        edit.apply(&mut actual);
+        assert_eq!(actual, right);
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        check("a\nb\nc\n", "a\nb\nc\n", &[]);
+        check(
+            "fn main() {\n}\n\nstruct S;\nfn f(x:u32) {\n    x;\n}",
+            "fn main() {}\n\nstruct S;\nfn f(x: u32) {\n    x;\n}\n",
+            &[
+                ("fn main() {\n}\n", "fn main() {}\n"),
+                ("fn f(x:u32) {\n", "fn f(x: u32) {\n"),
+                ("}", "}\n"),
+            ],
+        );
+    }
+
+    #[test]
+    fn diffs_inserted_and_deleted_lines() {
+        check("a\nb\nd\n", "a\nc\nd\ne\n", &[("b\n", "c\n"), ("", "e\n")]);
+        check("use a;\nuse b;\n\nfn f() {}\n", "fn f() {}\n", &[("use a;\nuse b;\n\n", "")]);
+        check("", "fn f() {}\n", &[("", "fn f() {}\n")]);
+    }
+}
@@ -23,6 +23,7 @@ use crate::{
     main_loop::Task,
     reload::SourceRootConfig,
     request_metrics::{LatestRequests, RequestMetrics},
+    test_runner::{self, TestRunHandle},
     thread_pool::TaskPool,
     to_proto::url_from_abs_path,
     Result,
@@ -66,6 +67,11 @@ pub(crate) struct GlobalState {
     pub(crate) flycheck: Option<FlycheckHandle>,
     pub(crate) flycheck_sender: Sender<flycheck::Message>,
     pub(crate) flycheck_receiver: Receiver<flycheck::Message>,
+    pub(crate) test_run: Option<TestRunHandle>,
+    /// The id of the latest test run. Messages of earlier runs are dropped.
+    pub(crate) test_run_id: u64,
+    pub(crate) test_run_sender: Sender<test_runner::Message>,
+    pub(crate) test_run_receiver: Receiver<test_runner::Message>,
     pub(crate) config: Config,
     pub(crate) analysis_host: AnalysisHost,
     pub(crate) diagnostics: DiagnosticCollection,
@@ -110,6 +116,7 @@ impl GlobalState {
 
         let analysis_host = AnalysisHost::new(config.lru_capacity);
         let (flycheck_sender, flycheck_receiver) = unbounded();
+        let (test_run_sender, test_run_receiver) = unbounded();
         GlobalState {
             sender,
             req_queue: ReqQueue::default(),
@@ -118,6 +125,10 @@ impl GlobalState {
             flycheck: None,
             flycheck_sender,
             flycheck_receiver,
+            test_run: None,
+            test_run_id: 0,
+            test_run_sender,
+            test_run_receiver,
             config,
             analysis_host,
             diagnostics: Default::default(),
@@ -269,11 +280,17 @@ impl GlobalStateSnapshot {
     }
 
     pub(crate) fn anchored_path(&self, file_id: FileId, path: &str) -> Url {
+        self.try_anchored_path(file_id, path).unwrap()
+    }
+
+    /// Like `anchored_path`, but returns `None` if `path` can't be joined to
+    /// the directory of `file_id`, or doesn't resolve to a path on disk.
+    pub(crate) fn try_anchored_path(&self, file_id: FileId, path: &str) -> Option<Url> {
         let mut base = self.vfs.read().0.file_path(file_id);
         base.pop();
-        let path = base.join(path).unwrap();
-        let path = path.as_path().unwrap();
-        url_from_abs_path(&path)
+        let path = base.join(path)?;
+        let path = path.as_path()?;
+        Some(url_from_abs_path(path))
     }
 
     pub(crate) fn cargo_target_for_crate_root(
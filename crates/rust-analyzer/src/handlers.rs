@@ -4,36 +4,45 @@
 
 use std::{
     io::Write as _,
+    iter::successors,
+    path::Path,
     process::{self, Stdio},
 };
 
 use ide::{
-    FileId, FilePosition, FileRange, HoverAction, HoverGotoTypeData, NavigationTarget, Query,
-    RangeInfo, Runnable, RunnableKind, SearchScope, TextEdit,
+    FileId, FilePosition, FileRange, FileSystemEdit, HoverAction, HoverGotoTypeData,
+    NavigationTarget, Query, RangeInfo, Runnable, RunnableKind, SearchScope, SourceChange,
+    TestItemKind, TextEdit,
 };
+use itertools::Itertools;
 use lsp_server::ErrorCode;
 use lsp_types::{
     CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeActionKind, CodeLens, Command, CompletionItem, Diagnostic, DocumentFormattingParams,
-    DocumentHighlight, DocumentSymbol, FoldingRange, FoldingRangeParams, HoverContents, Location,
-    Position, PrepareRenameResponse, Range, RenameParams, SemanticTokensEditResult,
-    SemanticTokensEditsParams, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation, SymbolTag,
-    TextDocumentIdentifier, Url, WorkspaceEdit,
+    DocumentHighlight, DocumentRangeFormattingParams, DocumentSymbol, FoldingRange,
+    FoldingRangeParams, HoverContents, Location, Position, PrepareRenameResponse, Range,
+    RenameParams, SemanticTokensEditResult, SemanticTokensEditsParams, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
+    SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
 use project_model::TargetKind;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
-use serde_json::to_value;
+use serde_json::{json, to_value};
 use stdx::{format_to, split_once};
-use syntax::{algo, ast, AstNode, SyntaxKind, TextRange, TextSize};
+use syntax::{algo, ast, AstNode, NodeOrToken, SyntaxKind, TextRange, TextSize};
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
     config::RustfmtConfig,
+    diff::diff,
     from_json, from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
     lsp_ext::{self, InlayHint, InlayHintsParams},
+    lsp_utils::is_canceled,
+    main_loop::Task,
+    test_runner::CargoTestCommand,
     to_proto, LspError, Result,
 };
 
@@ -513,6 +522,130 @@ pub(crate) fn handle_runnables(
     Ok(res)
 }
 
+pub(crate) fn handle_discover_tests(
+    snap: GlobalStateSnapshot,
+    _: (),
+) -> Result<Vec<lsp_ext::TestItem>> {
+    let _p = profile::span("handle_discover_tests");
+    snap.analysis.discover_tests()?.into_iter().map(|it| to_proto::test_item(&snap, it)).collect()
+}
+
+pub(crate) fn handle_run_tests(
+    state: &mut GlobalState,
+    params: lsp_ext::RunTestsParams,
+) -> Result<u64> {
+    let _p = profile::span("handle_run_tests");
+    state.test_run_id += 1;
+    let run_id = state.test_run_id;
+    // Cancel the previous run right away, rather than once the tests of the
+    // new one are known.
+    state.test_run = None;
+    // Discovering the tests is slow, so don't block the main loop on it.
+    let snap = state.snapshot();
+    state.task_pool.handle.spawn(move || {
+        let commands = cargo_test_commands(&snap, params.include).unwrap_or_else(|err| {
+            if !is_canceled(&*err) {
+                log::error!("failed to discover tests: {:?}", err);
+            }
+            Vec::new()
+        });
+        Task::TestRun(run_id, commands)
+    });
+    Ok(run_id)
+}
+
+fn cargo_test_commands(
+    snap: &GlobalStateSnapshot,
+    include: Option<Vec<String>>,
+) -> Result<Vec<CargoTestCommand>> {
+    let items = snap.analysis.discover_tests()?;
+    let include = include.map(|it| it.into_iter().collect::<FxHashSet<_>>());
+    let parents = items
+        .iter()
+        .filter_map(|it| Some((it.id.as_str(), it.parent.as_deref()?)))
+        .collect::<FxHashMap<_, _>>();
+    let is_included = |id: &str| match &include {
+        Some(include) => {
+            successors(Some(id), |it| parents.get(it).copied()).any(|it| include.contains(it))
+        }
+        None => true,
+    };
+
+    let mut commands = Vec::new();
+    let tests = items.iter().filter(|it| it.kind == TestItemKind::Test && is_included(&it.id));
+    for (krate, tests) in &tests.group_by(|it| it.krate) {
+        let tests = tests.collect::<Vec<_>>();
+        let crate_item =
+            match items.iter().find(|it| it.kind == TestItemKind::Crate && it.krate == krate) {
+                Some(it) => it,
+                None => continue,
+            };
+        let spec = match CargoTargetSpec::for_file(snap, crate_item.nav.file_id)? {
+            Some(it) => it,
+            None => {
+                log::warn!("can't run tests of {}, which isn't a cargo target", crate_item.label);
+                continue;
+            }
+        };
+        let workspace_root = spec.workspace_root.clone();
+
+        let mut test_names = Vec::new();
+        let mut include_ignored = false;
+        let mut locations = FxHashMap::default();
+        let mut cfgs = Vec::new();
+        for test in &tests {
+            let runnable = match &test.runnable {
+                Some(it) => it,
+                None => continue,
+            };
+            if let RunnableKind::Test { test_id, attr } = &runnable.kind {
+                let nav = &test.nav;
+                let frange = FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() };
+                locations.insert(test_id.to_string(), to_proto::location(snap, frange)?);
+                // Ignored tests only run when they are asked for by name.
+                if attr.ignore {
+                    if !matches!(&include, Some(it) if it.contains(&test.id)) {
+                        continue;
+                    }
+                    include_ignored = true;
+                }
+                test_names.push(test_id.to_string());
+            }
+            cfgs.extend(runnable.cfg_exprs.iter().cloned());
+        }
+        let kind = match tests.iter().find_map(|it| it.runnable.as_ref()) {
+            Some(it) => &it.kind,
+            None => continue,
+        };
+        let (args, _) = CargoTargetSpec::runnable_args(snap, Some(spec), kind, &cfgs)?;
+        let mut test_args = Vec::new();
+        let runs_whole_crate = match &include {
+            Some(include) => include.contains(&crate_item.id),
+            None => true,
+        };
+        if !runs_whole_crate {
+            // Without any names, libtest would run all tests.
+            if test_names.is_empty() {
+                continue;
+            }
+            test_args.extend(test_names);
+            test_args.push("--exact".to_string());
+        }
+        if include_ignored {
+            test_args.push("--include-ignored".to_string());
+        }
+        commands.push(CargoTestCommand {
+            workspace_root,
+            args,
+            test_args,
+            crate_test_id: crate_item.id.clone(),
+            tests: locations,
+        });
+    }
+
+    Ok(commands)
+}
+
 pub(crate) fn handle_completion(
     snap: GlobalStateSnapshot,
     params: lsp_types::CompletionParams,
@@ -572,6 +705,21 @@ pub(crate) fn handle_folding_range(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_document_link(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::DocumentLinkParams,
+) -> Result<Option<Vec<lsp_types::DocumentLink>>> {
+    let _p = profile::span("handle_document_link");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let links = snap.analysis.document_links(file_id)?;
+    let line_index = snap.analysis.file_line_index(file_id)?;
+    let res = links
+        .into_iter()
+        .filter_map(|it| to_proto::document_link(&snap, &line_index, it))
+        .collect();
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_signature_help(
     snap: GlobalStateSnapshot,
     params: lsp_types::SignatureHelpParams,
@@ -656,6 +804,121 @@ pub(crate) fn handle_rename(
     Ok(Some(workspace_edit))
 }
 
+/// Renaming a module's file or directory renames the module, moving it to
+/// another directory moves its declaration to the module of that directory.
+pub(crate) fn handle_will_rename_files(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::RenameFilesParams,
+) -> Result<Option<WorkspaceEdit>> {
+    let _p = profile::span("handle_will_rename_files");
+
+    let file_renames = params
+        .files
+        .iter()
+        .filter_map(|file_rename| {
+            let old_path = Url::parse(&file_rename.old_uri).ok()?.to_file_path().ok()?;
+            let new_path = Url::parse(&file_rename.new_uri).ok()?.to_file_path().ok()?;
+            Some((old_path, new_path))
+        })
+        .collect::<Vec<_>>();
+    let file_id_of = |path: &Path| {
+        let url = Url::from_file_path(path).ok()?;
+        snap.url_to_file_id(&url).ok()
+    };
+
+    let mut source_change = SourceChange::default();
+    for (old_path, new_path) in &file_renames {
+        let (file_id, new_name, move_file) = if old_path.extension() == Some("rs".as_ref()) {
+            if new_path.extension() != Some("rs".as_ref())
+                || old_path.file_name() == Some("mod.rs".as_ref())
+            {
+                continue;
+            }
+            match file_id_of(old_path) {
+                Some(file_id) => (file_id, new_path.file_stem(), false),
+                None => continue,
+            }
+        } else if let Some(file_id) = file_id_of(&old_path.join("mod.rs")) {
+            // A directory with a `mod.rs` is renamed along with its module.
+            (file_id, new_path.file_name(), false)
+        } else {
+            // The submodules of `foo.rs` live in `foo/`, so `foo.rs` has to be
+            // renamed with the directory, unless the client renames it already.
+            let module_path = old_path.with_extension("rs");
+            if file_renames.iter().any(|(old_path, _)| *old_path == module_path) {
+                continue;
+            }
+            match file_id_of(&module_path) {
+                Some(file_id) => (file_id, new_path.file_name(), true),
+                None => continue,
+            }
+        };
+        let new_name = match new_name.and_then(|it| it.to_str()) {
+            Some(it) => it,
+            None => continue,
+        };
+        let change = if old_path.parent() == new_path.parent() {
+            snap.analysis.will_rename_file(file_id, new_name)?
+        } else {
+            let new_parent = match new_path.parent().and_then(|dir| module_of_dir(&snap, dir)) {
+                Some(it) => it,
+                None => continue,
+            };
+            snap.analysis.will_move_file(file_id, new_parent, new_name)?
+        };
+        if let Some(change) = change {
+            // Several renames can touch the same file, like the parent of two
+            // moved modules, so their edits are merged as well.
+            let edits = &mut source_change.source_file_edits;
+            for source_file_edit in change.source_file_edits {
+                match edits.iter_mut().find(|it| it.file_id == source_file_edit.file_id) {
+                    Some(it) => {
+                        if it.edit.union(source_file_edit.edit).is_err() {
+                            log::warn!("overlapping edits for renamed files");
+                        }
+                    }
+                    None => edits.push(source_file_edit),
+                }
+            }
+            if move_file {
+                let dst = match new_path.with_extension("rs").to_str() {
+                    Some(it) => it.to_string(),
+                    None => continue,
+                };
+                let move_file = FileSystemEdit::MoveFile { src: file_id, anchor: file_id, dst };
+                source_change.file_system_edits.push(move_file);
+            }
+        }
+    }
+
+    if source_change.source_file_edits.is_empty() {
+        return Ok(None);
+    }
+    let workspace_edit = to_proto::workspace_edit(&snap, source_change)?;
+    Ok(Some(workspace_edit))
+}
+
+/// Finds the file of the module whose submodules live in `dir`: `dir/mod.rs`,
+/// `dir.rs`, or a `lib.rs` or `main.rs` crate root in `dir`.
+fn module_of_dir(snap: &GlobalStateSnapshot, dir: &Path) -> Option<FileId> {
+    let file_id_of = |path: &Path| {
+        let url = Url::from_file_path(path).ok()?;
+        snap.url_to_file_id(&url).ok()
+    };
+    if let Some(file_id) = file_id_of(&dir.join("mod.rs")) {
+        return Some(file_id);
+    }
+    if dir.parent().is_some() {
+        if let Some(file_id) = file_id_of(&dir.with_extension("rs")) {
+            return Some(file_id);
+        }
+    }
+    ["lib.rs", "main.rs"].iter().filter_map(|name| file_id_of(&dir.join(name))).find(|&file_id| {
+        let crates = snap.analysis.crate_for(file_id).unwrap_or_default();
+        crates.into_iter().any(|krate| snap.analysis.crate_root(krate).ok() == Some(file_id))
+    })
+}
+
 pub(crate) fn handle_references(
     snap: GlobalStateSnapshot,
     params: lsp_types::ReferenceParams,
@@ -688,15 +951,32 @@ pub(crate) fn handle_formatting(
     params: DocumentFormattingParams,
 ) -> Result<Option<Vec<lsp_types::TextEdit>>> {
     let _p = profile::span("handle_formatting");
-    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    run_rustfmt(&snap, params.text_document, None)
+}
+
+pub(crate) fn handle_range_formatting(
+    snap: GlobalStateSnapshot,
+    params: DocumentRangeFormattingParams,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let _p = profile::span("handle_range_formatting");
+    run_rustfmt(&snap, params.text_document, Some(params.range))
+}
+
+fn run_rustfmt(
+    snap: &GlobalStateSnapshot,
+    text_document: TextDocumentIdentifier,
+    range: Option<Range>,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let file_id = from_proto::file_id(snap, &text_document.uri)?;
     let file = snap.analysis.file_text(file_id)?;
     let crate_ids = snap.analysis.crate_for(file_id)?;
 
     let file_line_index = snap.analysis.file_line_index(file_id)?;
-    let end_position = to_proto::position(&file_line_index, TextSize::of(file.as_str()));
+    let range = range.map(|range| from_proto::text_range(&file_line_index, range));
+    let mut formats_range = false;
 
     let mut rustfmt = match &snap.config.rustfmt {
-        RustfmtConfig::Rustfmt { extra_args } => {
+        RustfmtConfig::Rustfmt { extra_args, enable_range_formatting } => {
             let mut cmd = process::Command::new(toolchain::rustfmt());
             cmd.args(extra_args);
             if let Some(&crate_id) = crate_ids.first() {
@@ -705,6 +985,15 @@ pub(crate) fn handle_formatting(
                 cmd.arg("--edition");
                 cmd.arg(edition.to_string());
             }
+            if let (Some(range), true) = (range, *enable_range_formatting) {
+                let start_line = file_line_index.line_col(range.start()).line + 1;
+                let end_line = file_line_index.line_col(range.end()).line + 1;
+                let file_lines = json!([{ "file": "stdin", "range": [start_line, end_line] }]);
+                cmd.arg("--unstable-features");
+                cmd.arg("--file-lines");
+                cmd.arg(file_lines.to_string());
+                formats_range = true;
+            }
             cmd
         }
         RustfmtConfig::CustomCommand { command, args } => {
@@ -748,10 +1037,51 @@ pub(crate) fn handle_formatting(
         }
     }
 
-    Ok(Some(vec![lsp_types::TextEdit {
-        range: Range::new(Position::new(0, 0), end_position),
-        new_text: captured_stdout,
-    }]))
+    let mut edit = diff(&file, &captured_stdout);
+    if let (Some(range), false) = (range, formats_range) {
+        // Without `--file-lines`, the whole file was formatted, so only keep
+        // the changes to the items around the range.
+        let items_range = enclosing_items_range(snap, file_id, &file, range)?;
+        let mut builder = TextEdit::builder();
+        for indel in edit {
+            if items_range.contains_range(indel.delete) {
+                builder.replace(indel.delete, indel.insert);
+            }
+        }
+        edit = builder.finish();
+    }
+
+    let line_endings = snap.file_line_endings(file_id);
+    Ok(Some(to_proto::text_edit_vec(&file_line_index, line_endings, edit)))
+}
+
+/// Returns the whole lines of the innermost item containing `range`, or of
+/// the items `range` overlaps if there is none.
+fn enclosing_items_range(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    text: &str,
+    range: TextRange,
+) -> Result<TextRange> {
+    let source_file = snap.analysis.parse(file_id)?;
+    let covering = match source_file.syntax().covering_element(range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let items_range = match covering.ancestors().find(|it| ast::Item::can_cast(it.kind())) {
+        Some(item) => item.text_range(),
+        None => covering
+            .children()
+            .map(|it| it.text_range())
+            .filter(|it| it.intersect(range).is_some())
+            .fold(range, |acc, it| acc.cover(it)),
+    };
+
+    let start = usize::from(items_range.start());
+    let end = usize::from(items_range.end());
+    let start = text[..start].rfind('\n').map_or(0, |it| it + 1);
+    let end = text[end..].find('\n').map_or(text.len(), |it| end + it + 1);
+    Ok(TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32)))
 }
 
 fn handle_fixes(
@@ -30,9 +30,11 @@ mod semantic_tokens;
 mod markdown;
 mod diagnostics;
 mod line_endings;
+mod diff;
 mod request_metrics;
 mod lsp_utils;
 mod thread_pool;
+mod test_runner;
 mod document;
 pub mod lsp_ext;
 pub mod config;
@@ -40,7 +42,10 @@ pub mod config;
 use serde::de::DeserializeOwned;
 
 pub type Result<T, E = Box<dyn std::error::Error + Send + Sync>> = std::result::Result<T, E>;
-pub use crate::{caps::server_capabilities, main_loop::main_loop};
+pub use crate::{
+    caps::{server_capabilities, workspace_capabilities},
+    main_loop::main_loop,
+};
 use ide::AnalysisHost;
 use std::fmt;
 use vfs::Vfs;
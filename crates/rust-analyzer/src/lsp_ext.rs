@@ -175,6 +175,96 @@ pub struct CargoRunnable {
     pub expect_test: Option<bool>,
 }
 
+pub enum DiscoverTests {}
+
+impl Request for DiscoverTests {
+    type Params = ();
+    type Result = Vec<TestItem>;
+    const METHOD: &'static str = "rust-analyzer/discoverTests";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestItem {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    pub kind: TestItemKind,
+    pub label: String,
+    pub location: lsp_types::Location,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runnable: Option<Runnable>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum TestItemKind {
+    Crate,
+    Module,
+    Test,
+}
+
+pub enum RunTests {}
+
+impl Request for RunTests {
+    type Params = RunTestsParams;
+    /// The id of the new run.
+    type Result = u64;
+    const METHOD: &'static str = "rust-analyzer/runTests";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTestsParams {
+    /// The ids of the tests, modules and crates to run, or all tests if null.
+    pub include: Option<Vec<String>>,
+}
+
+pub enum ChangeTestState {}
+
+impl Notification for ChangeTestState {
+    type Params = ChangeTestStateParams;
+    const METHOD: &'static str = "rust-analyzer/changeTestState";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeTestStateParams {
+    pub run_id: u64,
+    pub test_id: String,
+    pub state: TestState,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(tag = "tag", rename_all = "camelCase")]
+pub enum TestState {
+    Started,
+    #[serde(rename_all = "camelCase")]
+    Passed {
+        duration_ms: Option<f64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Failed {
+        duration_ms: Option<f64>,
+        message: String,
+        location: Option<lsp_types::Location>,
+    },
+    Skipped,
+}
+
+pub enum EndRunTests {}
+
+impl Notification for EndRunTests {
+    type Params = EndRunTestsParams;
+    const METHOD: &'static str = "rust-analyzer/endRunTests";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EndRunTestsParams {
+    pub run_id: u64,
+}
+
 pub enum InlayHints {}
 
 impl Request for InlayHints {
@@ -194,6 +284,9 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    LifetimeHint,
+    ClosureReturnTypeHint,
+    ReborrowHint,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -226,6 +319,27 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum WillRenameFiles {}
+
+impl Request for WillRenameFiles {
+    type Params = RenameFilesParams;
+    type Result = Option<lsp_types::WorkspaceEdit>;
+    const METHOD: &'static str = "workspace/willRenameFiles";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameFilesParams {
+    pub files: Vec<FileRename>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRename {
+    pub old_uri: String,
+    pub new_uri: String,
+}
+
 pub enum StatusNotification {}
 
 #[serde(rename_all = "camelCase")]
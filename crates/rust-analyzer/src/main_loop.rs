@@ -19,6 +19,7 @@ use crate::{
     global_state::{file_id_to_url, url_to_file_id, GlobalState, Status},
     handlers, lsp_ext,
     lsp_utils::{apply_document_changes, is_canceled, notification_is, Progress},
+    test_runner::{self, CargoTestCommand, TestRunHandle},
     Result,
 };
 use project_model::ProjectWorkspace;
@@ -54,6 +55,7 @@ enum Event {
     Task(Task),
     Vfs(vfs::loader::Message),
     Flycheck(flycheck::Message),
+    TestRun(test_runner::Message),
 }
 
 #[derive(Debug)]
@@ -61,6 +63,7 @@ pub(crate) enum Task {
     Response(Response),
     Diagnostics(Vec<(FileId, Vec<lsp_types::Diagnostic>)>),
     Workspaces(Vec<anyhow::Result<ProjectWorkspace>>),
+    TestRun(u64, Vec<CargoTestCommand>),
     Unit,
 }
 
@@ -92,6 +95,7 @@ impl fmt::Debug for Event {
             Event::Task(it) => fmt::Debug::fmt(it, f),
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Flycheck(it) => fmt::Debug::fmt(it, f),
+            Event::TestRun(it) => fmt::Debug::fmt(it, f),
         }
     }
 }
@@ -166,6 +170,9 @@ impl GlobalState {
 
             recv(self.flycheck_receiver) -> task =>
                 Some(Event::Flycheck(task.unwrap())),
+
+            recv(self.test_run_receiver) -> task =>
+                Some(Event::TestRun(task.unwrap())),
         }
     }
 
@@ -198,6 +205,17 @@ impl GlobalState {
                         }
                     }
                     Task::Workspaces(workspaces) => self.switch_workspaces(workspaces),
+                    Task::TestRun(run_id, commands) => {
+                        // A newer run may have been requested in the meantime.
+                        if run_id == self.test_run_id {
+                            let sender = self.test_run_sender.clone();
+                            self.test_run = Some(TestRunHandle::spawn(
+                                run_id,
+                                Box::new(move |msg| sender.send(msg).unwrap()),
+                                commands,
+                            ));
+                        }
+                    }
                     Task::Unit => (),
                 }
                 self.analysis_host.maybe_collect_garbage();
@@ -287,6 +305,16 @@ impl GlobalState {
                     self.report_progress("cargo check", state, message, None);
                 }
             },
+            // Messages of a canceled run may still be queued.
+            Event::TestRun(task) if task.run_id() != self.test_run_id => (),
+            Event::TestRun(task) => match task {
+                test_runner::Message::ChangeTestState(params) => {
+                    self.send_notification::<lsp_ext::ChangeTestState>(params)
+                }
+                test_runner::Message::Finished(params) => {
+                    self.send_notification::<lsp_ext::EndRunTests>(params)
+                }
+            },
         }
 
         let state_changed = self.process_changes();
@@ -371,11 +399,13 @@ impl GlobalState {
                 handlers::handle_matching_brace(s.snapshot(), p)
             })?
             .on_sync::<lsp_ext::MemoryUsage>(|s, p| handlers::handle_memory_usage(s, p))?
+            .on_sync::<lsp_ext::RunTests>(handlers::handle_run_tests)?
             .on::<lsp_ext::AnalyzerStatus>(handlers::handle_analyzer_status)?
             .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)?
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)?
             .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)?
             .on::<lsp_ext::Runnables>(handlers::handle_runnables)?
+            .on::<lsp_ext::DiscoverTests>(handlers::handle_discover_tests)?
             .on::<lsp_ext::InlayHints>(handlers::handle_inlay_hints)?
             .on::<lsp_ext::CodeActionRequest>(handlers::handle_code_action)?
             .on::<lsp_ext::ResolveCodeActionRequest>(handlers::handle_resolve_code_action)?
@@ -390,11 +420,14 @@ impl GlobalState {
             .on::<lsp_types::request::CodeLensRequest>(handlers::handle_code_lens)?
             .on::<lsp_types::request::CodeLensResolve>(handlers::handle_code_lens_resolve)?
             .on::<lsp_types::request::FoldingRangeRequest>(handlers::handle_folding_range)?
+            .on::<lsp_types::request::DocumentLinkRequest>(handlers::handle_document_link)?
             .on::<lsp_types::request::SignatureHelpRequest>(handlers::handle_signature_help)?
             .on::<lsp_types::request::PrepareRenameRequest>(handlers::handle_prepare_rename)?
             .on::<lsp_types::request::Rename>(handlers::handle_rename)?
+            .on::<lsp_ext::WillRenameFiles>(handlers::handle_will_rename_files)?
             .on::<lsp_types::request::References>(handlers::handle_references)?
             .on::<lsp_types::request::Formatting>(handlers::handle_formatting)?
+            .on::<lsp_types::request::RangeFormatting>(handlers::handle_range_formatting)?
             .on::<lsp_types::request::DocumentHighlightRequest>(
                 handlers::handle_document_highlight,
             )?
@@ -4,7 +4,7 @@ use std::{mem, sync::Arc};
 use base_db::{CrateGraph, SourceRoot, VfsPath};
 use flycheck::FlycheckHandle;
 use ide::AnalysisChange;
-use project_model::{ProcMacroClient, ProjectWorkspace};
+use project_model::{ProcMacroClient, ProjectWorkspace, TargetKind};
 use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
 
 use crate::{
@@ -29,7 +29,12 @@ impl GlobalState {
         }
     }
     pub(crate) fn maybe_refresh(&mut self, changes: &[(AbsPathBuf, ChangeKind)]) {
-        if !changes.iter().any(|(path, kind)| is_interesting(path, *kind)) {
+        let load_build_data = self.config.cargo.load_out_dirs_from_check;
+        let needs_reload = changes.iter().any(|(path, kind)| {
+            is_interesting(path, *kind)
+                || (load_build_data && is_build_script(&self.workspaces, path))
+        });
+        if !needs_reload {
             return;
         }
         match self.status {
@@ -76,6 +81,19 @@ impl GlobalState {
             }
             false
         }
+
+        // Build scripts may emit different cfgs, env vars or generated files
+        // after an edit, so they need to be re-run.
+        fn is_build_script(workspaces: &[ProjectWorkspace], path: &AbsPath) -> bool {
+            workspaces.iter().any(|ws| match ws {
+                ProjectWorkspace::Cargo { cargo, .. } => cargo.packages().any(|pkg| {
+                    cargo[pkg].targets.iter().any(|&tgt| {
+                        cargo[tgt].kind == TargetKind::BuildScript && *cargo[tgt].root == *path
+                    })
+                }),
+                ProjectWorkspace::Json { .. } => false,
+            })
+        }
     }
     pub(crate) fn transition(&mut self, new_status: Status) {
         self.status = new_status;
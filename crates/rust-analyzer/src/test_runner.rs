@@ -0,0 +1,552 @@
+//! Runs `cargo test` in a background thread, and reports the states of the
+//! individual tests from libtest's output: the JSON one on nightly toolchains,
+//! and the human-readable one otherwise.
+
+use std::{
+    io::{BufRead, BufReader, Read},
+    process::{self, Command, Stdio},
+};
+
+use crossbeam_channel::{select, unbounded, Receiver, Sender, TryRecvError};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use vfs::{AbsPath, AbsPathBuf};
+
+use crate::{
+    lsp_ext::{ChangeTestStateParams, EndRunTestsParams, TestState},
+    to_proto,
+};
+
+/// A `cargo test` invocation running tests of a single crate.
+#[derive(Debug)]
+pub(crate) struct CargoTestCommand {
+    pub(crate) workspace_root: AbsPathBuf,
+    /// The arguments of `cargo test`.
+    pub(crate) args: Vec<String>,
+    /// The arguments of the test binary, passed after `--`.
+    pub(crate) test_args: Vec<String>,
+    /// The id of the crate's test item, which prefixes the names libtest
+    /// reports to form the ids of the tests.
+    pub(crate) crate_test_id: String,
+    /// The names of the tests this command runs, and their locations, for
+    /// failures which don't point anywhere else.
+    pub(crate) tests: FxHashMap<String, lsp_types::Location>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Message {
+    ChangeTestState(ChangeTestStateParams),
+    /// All commands have run. Not sent if the run was canceled.
+    Finished(EndRunTestsParams),
+}
+
+impl Message {
+    pub(crate) fn run_id(&self) -> u64 {
+        match self {
+            Message::ChangeTestState(params) => params.run_id,
+            Message::Finished(params) => params.run_id,
+        }
+    }
+}
+
+/// Runs the commands one after another. The running command is killed when
+/// this struct is dropped, without waiting for the tests it started.
+#[derive(Debug)]
+pub(crate) struct TestRunHandle {
+    // XXX: drop order is significant
+    /// Dropping the sender cancels the run.
+    #[allow(unused)]
+    cancel: Sender<()>,
+    #[allow(unused)]
+    thread: jod_thread::JoinHandle,
+}
+
+impl TestRunHandle {
+    pub(crate) fn spawn(
+        run_id: u64,
+        sender: Box<dyn Fn(Message) + Send>,
+        commands: Vec<CargoTestCommand>,
+    ) -> TestRunHandle {
+        let (cancel, canceled) = unbounded();
+        let thread = jod_thread::spawn(move || {
+            for command in &commands {
+                if canceled.try_recv() != Err(TryRecvError::Empty) {
+                    return;
+                }
+                if !run_command(run_id, &*sender, command, &canceled) {
+                    return;
+                }
+            }
+            sender(Message::Finished(EndRunTestsParams { run_id }));
+        });
+        TestRunHandle { cancel, thread }
+    }
+}
+
+/// Returns `false` if the run was canceled.
+fn run_command(
+    run_id: u64,
+    sender: &dyn Fn(Message),
+    command: &CargoTestCommand,
+    canceled: &Receiver<()>,
+) -> bool {
+    let mut output = if is_nightly(&command.workspace_root) {
+        LibtestOutput::Json
+    } else {
+        LibtestOutput::Human(HumanOutput::default())
+    };
+    let mut cmd = Command::new(toolchain::cargo());
+    cmd.args(&command.args).arg("--");
+    if let LibtestOutput::Json = output {
+        cmd.args(["-Z", "unstable-options", "--format=json", "--report-time"].iter());
+    }
+    cmd.args(&command.test_args)
+        .current_dir(&command.workspace_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::null());
+    log::info!("running tests {:?}", cmd);
+    let mut child = match cmd.spawn() {
+        Ok(it) => JodChild(it),
+        Err(err) => {
+            log::error!("failed to run cargo test: {}", err);
+            report_error(run_id, sender, command, &format!("Failed to run `cargo test`: {}", err));
+            return true;
+        }
+    };
+
+    let (line_sender, lines) = unbounded();
+    let stdout = child.0.stdout.take().unwrap();
+    let reader = jod_thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let line = match line {
+                Ok(it) => it,
+                Err(_) => break,
+            };
+            if line_sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let mut stderr = child.0.stderr.take().unwrap();
+    let stderr_reader = jod_thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
+    });
+
+    if let LibtestOutput::Human(_) = output {
+        // The human-readable output only reports finished tests.
+        for name in command.tests.keys() {
+            let params = test_state_params(run_id, command, name, TestState::Started);
+            sender(Message::ChangeTestState(params));
+        }
+    }
+
+    let mut ran_tests = false;
+    loop {
+        select! {
+            recv(canceled) -> _ => {
+                // Killing cargo doesn't kill the test binary, which keeps the
+                // pipes open until it exits. Don't wait for it.
+                drop(child);
+                reader.detach();
+                stderr_reader.detach();
+                return false;
+            },
+            recv(lines) -> line => match line {
+                Ok(line) => {
+                    ran_tests |= is_test_run_start(&line);
+                    for params in output.test_states(run_id, command, &line) {
+                        sender(Message::ChangeTestState(params));
+                    }
+                }
+                Err(_) => break,
+            },
+        }
+    }
+    for params in output.finish(run_id, command) {
+        sender(Message::ChangeTestState(params));
+    }
+
+    let stderr = stderr_reader.join();
+    match child.0.wait() {
+        Ok(status) if !ran_tests && !status.success() => {
+            // Most likely, the tests failed to compile.
+            let message = format!(
+                "`cargo test` failed before running the tests ({})\n\n{}",
+                status,
+                stderr.trim_end()
+            );
+            report_error(run_id, sender, command, &message);
+        }
+        Ok(_) => (),
+        Err(err) => log::error!("failed to wait for cargo test: {}", err),
+    }
+    true
+}
+
+/// libtest's JSON output is unstable, so it is only requested from nightly
+/// toolchains.
+fn is_nightly(workspace_root: &AbsPath) -> bool {
+    let output = Command::new(toolchain::rustc()).arg("-V").current_dir(workspace_root).output();
+    match output {
+        Ok(output) => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            version.contains("-nightly") || version.contains("-dev")
+        }
+        Err(err) => {
+            log::error!("failed to query the rustc version: {}", err);
+            false
+        }
+    }
+}
+
+/// Whether `line` shows that the test binary started, either as
+/// `running 2 tests` or as the JSON suite event.
+fn is_test_run_start(line: &str) -> bool {
+    line.starts_with("running ") || line.starts_with(r#"{ "type": "suite""#)
+}
+
+fn report_error(run_id: u64, sender: &dyn Fn(Message), command: &CargoTestCommand, message: &str) {
+    for (name, location) in &command.tests {
+        let state = TestState::Failed {
+            duration_ms: None,
+            message: message.to_string(),
+            location: Some(location.clone()),
+        };
+        let test_id = format!("{}::{}", command.crate_test_id, name);
+        sender(Message::ChangeTestState(ChangeTestStateParams { run_id, test_id, state }));
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum LibtestMessage {
+    Suite {},
+    Test {
+        event: String,
+        name: String,
+        /// In seconds, only reported with `--report-time`.
+        exec_time: Option<f64>,
+        stdout: Option<String>,
+        message: Option<String>,
+    },
+}
+
+enum LibtestOutput {
+    Json,
+    Human(HumanOutput),
+}
+
+impl LibtestOutput {
+    fn test_states(
+        &mut self,
+        run_id: u64,
+        command: &CargoTestCommand,
+        line: &str,
+    ) -> Vec<ChangeTestStateParams> {
+        match self {
+            LibtestOutput::Json => json_test_state(run_id, command, line).into_iter().collect(),
+            LibtestOutput::Human(it) => it.test_states(run_id, command, line),
+        }
+    }
+
+    /// Reports the failures whose output hasn't been seen completely.
+    fn finish(&mut self, run_id: u64, command: &CargoTestCommand) -> Vec<ChangeTestStateParams> {
+        match self {
+            LibtestOutput::Json => Vec::new(),
+            LibtestOutput::Human(it) => it.finish(run_id, command),
+        }
+    }
+}
+
+fn json_test_state(
+    run_id: u64,
+    command: &CargoTestCommand,
+    line: &str,
+) -> Option<ChangeTestStateParams> {
+    // Anything else is output of cargo or of the tests themselves.
+    let (event, name, exec_time, stdout, message) = match serde_json::from_str(line).ok()? {
+        LibtestMessage::Test { event, name, exec_time, stdout, message } => {
+            (event, name, exec_time, stdout, message)
+        }
+        LibtestMessage::Suite {} => return None,
+    };
+    let duration_ms = exec_time.map(|it| it * 1000.0);
+    let state = match event.as_str() {
+        "started" => TestState::Started,
+        "ok" => TestState::Passed { duration_ms },
+        "ignored" => TestState::Skipped,
+        "failed" => {
+            let output = stdout.unwrap_or_default();
+            let message = match message {
+                Some(message) => format!("{}\n{}", message, output),
+                None => output.clone(),
+            };
+            failed_state(command, &name, duration_ms, message, &output)
+        }
+        _ => return None,
+    };
+    Some(test_state_params(run_id, command, &name, state))
+}
+
+/// Parses the human-readable output of libtest, which reports the result of
+/// each test as `test tests::a ... ok`. The output of failed tests follows
+/// later, in sections starting with `---- tests::a stdout ----`.
+#[derive(Default)]
+struct HumanOutput {
+    /// Failed tests whose output section hasn't been seen yet.
+    failed: Vec<String>,
+    /// The failed test whose output section is being read, and the output.
+    failure: Option<(String, String)>,
+}
+
+impl HumanOutput {
+    fn test_states(
+        &mut self,
+        run_id: u64,
+        command: &CargoTestCommand,
+        line: &str,
+    ) -> Vec<ChangeTestStateParams> {
+        let mut res = Vec::new();
+        let section = output_section(line);
+        if let Some((name, output)) = &mut self.failure {
+            // The list of failures after the sections starts with `failures:`.
+            if section.is_none() && line != "failures:" {
+                output.push_str(line);
+                output.push('\n');
+                return res;
+            }
+            let state = failed_state(command, name, None, output.trim_end().to_string(), output);
+            res.push(test_state_params(run_id, command, name, state));
+            self.failure = None;
+        }
+
+        if let Some(name) = section {
+            if let Some(idx) = self.failed.iter().position(|it| it == name) {
+                self.failed.swap_remove(idx);
+                self.failure = Some((name.to_string(), String::new()));
+            }
+            return res;
+        }
+        let (name, result) = match line.strip_prefix("test ").and_then(|it| {
+            let idx = it.rfind(" ... ")?;
+            Some((&it[..idx], &it[idx + " ... ".len()..]))
+        }) {
+            Some(it) => it,
+            None => return res,
+        };
+        let state = match result {
+            "ok" => TestState::Passed { duration_ms: None },
+            "FAILED" => {
+                self.failed.push(name.to_string());
+                return res;
+            }
+            _ if result.starts_with("ignored") => TestState::Skipped,
+            _ => return res,
+        };
+        res.push(test_state_params(run_id, command, name, state));
+        res
+    }
+
+    fn finish(&mut self, run_id: u64, command: &CargoTestCommand) -> Vec<ChangeTestStateParams> {
+        let mut res = Vec::new();
+        if let Some((name, output)) = self.failure.take() {
+            let state = failed_state(command, &name, None, output.trim_end().to_string(), &output);
+            res.push(test_state_params(run_id, command, &name, state));
+        }
+        for name in self.failed.drain(..) {
+            let state = failed_state(command, &name, None, String::new(), "");
+            res.push(test_state_params(run_id, command, &name, state));
+        }
+        res
+    }
+}
+
+/// Returns the name of the test in `---- tests::a stdout ----`.
+fn output_section(line: &str) -> Option<&str> {
+    line.strip_prefix("---- ")?.strip_suffix(" stdout ----")
+}
+
+fn failed_state(
+    command: &CargoTestCommand,
+    name: &str,
+    duration_ms: Option<f64>,
+    message: String,
+    output: &str,
+) -> TestState {
+    let location = panic_location(output, &command.workspace_root)
+        .or_else(|| command.tests.get(name).cloned());
+    TestState::Failed { duration_ms, message, location }
+}
+
+fn test_state_params(
+    run_id: u64,
+    command: &CargoTestCommand,
+    name: &str,
+    state: TestState,
+) -> ChangeTestStateParams {
+    let test_id = format!("{}::{}", command.crate_test_id, name);
+    ChangeTestStateParams { run_id, test_id, state }
+}
+
+/// Finds the location of a panic in the output of a test, which looks like
+/// `thread 'tests::foo' panicked at src/lib.rs:10:5:`, or like
+/// `thread 'tests::foo' panicked at 'message', src/lib.rs:10:5` with older
+/// toolchains. Paths are relative to the workspace root.
+fn panic_location(output: &str, workspace_root: &AbsPath) -> Option<lsp_types::Location> {
+    let idx = output.find("panicked at ")?;
+    output[idx + "panicked at ".len()..].lines().find_map(|line| {
+        let location = line.trim_end_matches(':').rsplit(", ").next()?;
+        let mut parts = location.rsplitn(3, ':');
+        let col = parts.next()?.parse::<u64>().ok()?;
+        let line = parts.next()?.parse::<u64>().ok()?;
+        let path = workspace_root.join(parts.next()?);
+        let position = lsp_types::Position::new(line.checked_sub(1)?, col.checked_sub(1)?);
+        let url = to_proto::url_from_abs_path(&path);
+        Some(lsp_types::Location::new(url, lsp_types::Range::new(position, position)))
+    })
+}
+
+struct JodChild(process::Child);
+
+impl Drop for JodChild {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use expect::{expect, Expect};
+
+    use super::*;
+
+    fn command() -> CargoTestCommand {
+        let workspace_root = if cfg!(windows) { "C:\\ws" } else { "/ws" };
+        CargoTestCommand {
+            workspace_root: AbsPathBuf::try_from(workspace_root).unwrap(),
+            args: Vec::new(),
+            test_args: Vec::new(),
+            crate_test_id: "foo".to_string(),
+            tests: FxHashMap::default(),
+        }
+    }
+
+    fn check(line: &str, expect: Expect) {
+        let params = json_test_state(1, &command(), line);
+        expect.assert_eq(&serde_json::to_string_pretty(&params).unwrap());
+    }
+
+    #[test]
+    fn parses_libtest_events() {
+        let command = command();
+        let suite = r#"{ "type": "suite", "event": "started", "test_count": 2 }"#;
+        assert!(json_test_state(1, &command, suite).is_none());
+        assert!(json_test_state(1, &command, "running 2 tests").is_none());
+        check(
+            r#"{ "type": "test", "event": "started", "name": "tests::a" }"#,
+            expect![[r#"
+                {
+                  "runId": 1,
+                  "testId": "foo::tests::a",
+                  "state": {
+                    "tag": "started"
+                  }
+                }"#]],
+        );
+        check(
+            r#"{ "type": "test", "name": "tests::a", "event": "ok", "exec_time": 0.25 }"#,
+            expect![[r#"
+                {
+                  "runId": 1,
+                  "testId": "foo::tests::a",
+                  "state": {
+                    "tag": "passed",
+                    "durationMs": 250.0
+                  }
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn maps_failures_to_panic_locations() {
+        if cfg!(windows) {
+            return;
+        }
+        check(
+            r#"{ "type": "test", "name": "tests::b", "event": "failed", "exec_time": 0.001, "stdout": "\nthread 'tests::b' panicked at crates/foo/src/lib.rs:10:5:\nassertion failed: false\n" }"#,
+            expect![[r#"
+                {
+                  "runId": 1,
+                  "testId": "foo::tests::b",
+                  "state": {
+                    "tag": "failed",
+                    "durationMs": 1.0,
+                    "message": "\nthread 'tests::b' panicked at crates/foo/src/lib.rs:10:5:\nassertion failed: false\n",
+                    "location": {
+                      "uri": "file:///ws/crates/foo/src/lib.rs",
+                      "range": {
+                        "start": {
+                          "line": 9,
+                          "character": 4
+                        },
+                        "end": {
+                          "line": 9,
+                          "character": 4
+                        }
+                      }
+                    }
+                  }
+                }"#]],
+        );
+        let output = "thread 'c' panicked at 'assertion failed: `(left == right)`\n  left: `1`,\n right: `2`', src/lib.rs:3:5\nnote: run with `RUST_BACKTRACE=1`";
+        let location = panic_location(output, &command().workspace_root).unwrap();
+        assert_eq!(location.uri.as_str(), "file:///ws/src/lib.rs");
+        assert_eq!(location.range.start, lsp_types::Position::new(2, 4));
+    }
+
+    #[test]
+    fn parses_human_readable_output() {
+        if cfg!(windows) {
+            return;
+        }
+        let command = command();
+        let output = "
+running 3 tests
+test tests::a ... ok
+test tests::b ... FAILED
+test tests::c ... ignored
+
+failures:
+
+---- tests::b stdout ----
+thread 'tests::b' panicked at src/lib.rs:10:5:
+assertion failed: false
+
+failures:
+    tests::b
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+";
+        let mut parser = LibtestOutput::Human(HumanOutput::default());
+        let mut states = Vec::new();
+        for line in output.lines() {
+            states.extend(parser.test_states(1, &command, line));
+        }
+        states.extend(parser.finish(1, &command));
+        let actual = states
+            .into_iter()
+            .map(|it| format!("{} {}\n", it.test_id, serde_json::to_string(&it.state).unwrap()))
+            .collect::<String>();
+        expect![[r#"
+            foo::tests::a {"tag":"passed","durationMs":null}
+            foo::tests::c {"tag":"skipped"}
+            foo::tests::b {"tag":"failed","durationMs":null,"message":"thread 'tests::b' panicked at src/lib.rs:10:5:\nassertion failed: false","location":{"uri":"file:///ws/src/lib.rs","range":{"start":{"line":9,"character":4},"end":{"line":9,"character":4}}}}
+        "#]]
+        .assert_eq(&actual);
+    }
+}
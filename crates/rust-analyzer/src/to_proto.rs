@@ -6,10 +6,11 @@ use std::{
 
 use base_db::{FileId, FileRange};
 use ide::{
-    Assist, AssistKind, CallInfo, CompletionItem, CompletionItemKind, Documentation,
-    FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier, HighlightTag, HighlightedRange,
-    Indel, InlayHint, InlayKind, InsertTextFormat, LineIndex, Markup, NavigationTarget,
-    ReferenceAccess, ResolvedAssist, Runnable, Severity, SourceChange, SourceFileEdit, TextEdit,
+    Assist, AssistKind, CallInfo, CompletionItem, CompletionItemKind, DocumentLink,
+    DocumentLinkTarget, Documentation, FileSystemEdit, Fold, FoldKind, Highlight,
+    HighlightModifier, HighlightTag, HighlightedRange, Indel, InlayHint, InlayKind,
+    InsertTextFormat, LineIndex, Markup, NavigationTarget, ReferenceAccess, ResolvedAssist,
+    Runnable, Severity, SourceChange, SourceFileEdit, TestItem, TestItemKind, TextEdit,
 };
 use itertools::Itertools;
 use syntax::{SyntaxKind, TextRange, TextSize};
@@ -302,6 +303,9 @@ pub(crate) fn inlay_int(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_ex
             InlayKind::ParameterHint => lsp_ext::InlayKind::ParameterHint,
             InlayKind::TypeHint => lsp_ext::InlayKind::TypeHint,
             InlayKind::ChainingHint => lsp_ext::InlayKind::ChainingHint,
+            InlayKind::LifetimeHint => lsp_ext::InlayKind::LifetimeHint,
+            InlayKind::ClosureReturnTypeHint => lsp_ext::InlayKind::ClosureReturnTypeHint,
+            InlayKind::ReborrowHint => lsp_ext::InlayKind::ReborrowHint,
         },
     }
 }
@@ -408,6 +412,24 @@ fn semantic_token_type_and_modifiers(
     (type_, mods)
 }
 
+pub(crate) fn document_link(
+    snap: &GlobalStateSnapshot,
+    line_index: &LineIndex,
+    link: DocumentLink,
+) -> Option<lsp_types::DocumentLink> {
+    let target = match link.target {
+        DocumentLinkTarget::File(file_id) => snap.file_id_to_url(file_id),
+        DocumentLinkTarget::Path { anchor, path } => snap.try_anchored_path(anchor, &path)?,
+        DocumentLinkTarget::Url(url) => lsp_types::Url::parse(&url).ok()?,
+    };
+    Some(lsp_types::DocumentLink {
+        range: range(line_index, link.range),
+        target: Some(target),
+        tooltip: None,
+        data: None,
+    })
+}
+
 pub(crate) fn folding_range(
     text: &str,
     line_index: &LineIndex,
@@ -603,7 +625,7 @@ pub(crate) fn resource_op(
     file_system_edit: FileSystemEdit,
 ) -> lsp_types::ResourceOp {
     match file_system_edit {
-        FileSystemEdit::CreateFile { anchor, dst } => {
+        FileSystemEdit::CreateFile { anchor, dst, .. } => {
             let uri = snap.anchored_path(anchor, &dst);
             lsp_types::ResourceOp::Create(lsp_types::CreateFile { uri, options: None })
         }
@@ -612,6 +634,15 @@ pub(crate) fn resource_op(
             let new_uri = snap.anchored_path(anchor, &dst);
             lsp_types::ResourceOp::Rename(lsp_types::RenameFile { old_uri, new_uri, options: None })
         }
+        FileSystemEdit::MoveDir { anchor, src, dst } => {
+            let old_uri = snap.anchored_path(anchor, &src);
+            let new_uri = snap.anchored_path(anchor, &dst);
+            lsp_types::ResourceOp::Rename(lsp_types::RenameFile { old_uri, new_uri, options: None })
+        }
+        FileSystemEdit::DeleteFile { file } => {
+            let uri = snap.file_id_to_url(file);
+            lsp_types::ResourceOp::Delete(lsp_types::DeleteFile { uri, options: None })
+        }
     }
 }
 
@@ -621,8 +652,28 @@ pub(crate) fn snippet_workspace_edit(
 ) -> Result<lsp_ext::SnippetWorkspaceEdit> {
     let mut document_changes: Vec<lsp_ext::SnippetDocumentChangeOperation> = Vec::new();
     for op in source_change.file_system_edits {
+        // The contents of a new file are sent as an edit of the empty document.
+        let initial_contents = match &op {
+            FileSystemEdit::CreateFile { anchor, dst, initial_contents }
+                if !initial_contents.is_empty() =>
+            {
+                let uri = snap.anchored_path(*anchor, dst);
+                let text_document =
+                    lsp_types::VersionedTextDocumentIdentifier { uri, version: None };
+                let edit = lsp_ext::SnippetTextEdit {
+                    range: lsp_types::Range::default(),
+                    new_text: initial_contents.clone(),
+                    insert_text_format: None,
+                };
+                Some(lsp_ext::SnippetTextDocumentEdit { text_document, edits: vec![edit] })
+            }
+            _ => None,
+        };
         let op = resource_op(&snap, op);
         document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Op(op));
+        if let Some(edit) = initial_contents {
+            document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Edit(edit));
+        }
     }
     for edit in source_change.source_file_edits {
         let edit = snippet_text_document_edit(&snap, source_change.is_snippet, edit)?;
@@ -754,6 +805,26 @@ pub(crate) fn runnable(
     })
 }
 
+pub(crate) fn test_item(snap: &GlobalStateSnapshot, item: TestItem) -> Result<lsp_ext::TestItem> {
+    let runnable = match item.runnable {
+        Some(it) => Some(runnable(snap, item.nav.file_id, it)?),
+        None => None,
+    };
+    let kind = match item.kind {
+        TestItemKind::Crate => lsp_ext::TestItemKind::Crate,
+        TestItemKind::Module => lsp_ext::TestItemKind::Module,
+        TestItemKind::Test => lsp_ext::TestItemKind::Test,
+    };
+    Ok(lsp_ext::TestItem {
+        id: item.id,
+        parent: item.parent,
+        kind,
+        label: item.label,
+        location: location_from_nav(snap, item.nav)?,
+        runnable,
+    })
+}
+
 pub(crate) fn markup_content(markup: Markup) -> lsp_types::MarkupContent {
     let value = crate::markdown::format_docs(markup.as_str());
     lsp_types::MarkupContent { kind: lsp_types::MarkupKind::Markdown, value }
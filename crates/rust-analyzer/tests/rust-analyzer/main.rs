@@ -15,13 +15,18 @@ use std::{collections::HashMap, path::PathBuf, time::Instant};
 
 use lsp_types::{
     notification::DidOpenTextDocument,
-    request::{CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest},
+    request::{
+        CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest,
+        RangeFormatting,
+    },
     CodeActionContext, CodeActionParams, CompletionParams, DidOpenTextDocumentParams,
-    DocumentFormattingParams, FormattingOptions, GotoDefinitionParams, HoverParams,
-    PartialResultParams, Position, Range, TextDocumentItem, TextDocumentPositionParams,
-    WorkDoneProgressParams,
+    DocumentFormattingParams, DocumentRangeFormattingParams, FormattingOptions,
+    GotoDefinitionParams, HoverParams, PartialResultParams, Position, Range, TextDocumentItem,
+    TextDocumentPositionParams, WorkDoneProgressParams,
+};
+use rust_analyzer::lsp_ext::{
+    FileRename, OnEnter, RenameFilesParams, Runnables, RunnablesParams, WillRenameFiles,
 };
-use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
 use test_utils::skip_slow_tests;
 
@@ -184,15 +189,10 @@ pub use std::collections::HashMap;
         },
         json!([
             {
-                "newText": r#"mod bar;
-
-fn main() {}
-
-pub use std::collections::HashMap;
-"#,
+                "newText": "fn main() {}\n",
                 "range": {
-                    "end": { "character": 0, "line": 6 },
-                    "start": { "character": 0, "line": 0 }
+                    "end": { "character": 0, "line": 4 },
+                    "start": { "character": 0, "line": 2 }
                 }
             }
         ]),
@@ -242,17 +242,67 @@ pub use std::collections::HashMap;
         },
         json!([
             {
-                "newText": r#"mod bar;
+                "newText": "async fn test() {}\n",
+                "range": {
+                    "end": { "character": 0, "line": 4 },
+                    "start": { "character": 0, "line": 2 }
+                }
+            },
+            {
+                "newText": "fn main() {}\n",
+                "range": {
+                    "end": { "character": 0, "line": 7 },
+                    "start": { "character": 0, "line": 5 }
+                }
+            }
+        ]),
+    );
+}
 
-async fn test() {}
+#[test]
+fn test_format_document_range() {
+    if skip_slow_tests() {
+        return;
+    }
 
-fn main() {}
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
 
-pub use std::collections::HashMap;
+//- /src/lib.rs
+fn foo() {
+}
+
+fn main() {
+    let x  =  1;
+}
 "#,
+    );
+    server.wait_until_workspace_is_loaded();
+
+    server.request::<RangeFormatting>(
+        DocumentRangeFormattingParams {
+            text_document: server.doc_id("src/lib.rs"),
+            range: Range::new(Position::new(4, 4), Position::new(4, 8)),
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: false,
+                insert_final_newline: None,
+                trim_final_newlines: None,
+                trim_trailing_whitespace: None,
+                properties: HashMap::new(),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([
+            {
+                "newText": "    let x = 1;\n",
                 "range": {
-                    "end": { "character": 0, "line": 9 },
-                    "start": { "character": 0, "line": 0 }
+                    "end": { "character": 0, "line": 5 },
+                    "start": { "character": 0, "line": 4 }
                 }
             }
         ]),
@@ -387,6 +437,186 @@ fn main() {{}}
     );
 }
 
+#[test]
+fn will_rename_module_directory() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+mod bar;
+use bar::baz::f;
+
+//- /src/bar.rs
+pub mod baz;
+
+//- /src/bar/baz.rs
+pub fn f() {}
+"#,
+    );
+    server.wait_until_workspace_is_loaded();
+    let file_rename = |old: &str, new: &str| FileRename {
+        old_uri: server.doc_id(old).uri.to_string(),
+        new_uri: server.doc_id(new).uri.to_string(),
+    };
+    server.request::<WillRenameFiles>(
+        RenameFilesParams { files: vec![file_rename("src/bar", "src/qux")] },
+        json!({
+            "documentChanges": [
+                {
+                    "kind": "rename",
+                    "oldUri": "file:///[..]/src/bar.rs",
+                    "newUri": "file:///[..]/src/qux.rs"
+                },
+                {
+                    "textDocument": { "uri": "file:///[..]/src/lib.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 0, "character": 4 },
+                                "end": { "line": 0, "character": 7 }
+                            },
+                            "newText": "qux"
+                        }
+                    ]
+                },
+                {
+                    "textDocument": { "uri": "file:///[..]/src/lib.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 1, "character": 4 },
+                                "end": { "line": 1, "character": 7 }
+                            },
+                            "newText": "qux"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+
+    // `bar.rs` is renamed by the client itself.
+    server.request::<WillRenameFiles>(
+        RenameFilesParams {
+            files: vec![file_rename("src/bar", "src/qux"), file_rename("src/bar.rs", "src/qux.rs")],
+        },
+        json!({
+            "documentChanges": [
+                {
+                    "textDocument": { "uri": "file:///[..]/src/lib.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 0, "character": 4 },
+                                "end": { "line": 0, "character": 7 }
+                            },
+                            "newText": "qux"
+                        }
+                    ]
+                },
+                {
+                    "textDocument": { "uri": "file:///[..]/src/lib.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 1, "character": 4 },
+                                "end": { "line": 1, "character": 7 }
+                            },
+                            "newText": "qux"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn will_move_module_file() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+mod a;
+mod b;
+use a::bar::f;
+
+//- /src/a.rs
+pub mod bar;
+
+//- /src/a/bar.rs
+pub fn f() {}
+
+//- /src/b.rs
+"#,
+    );
+    server.wait_until_workspace_is_loaded();
+    server.request::<WillRenameFiles>(
+        RenameFilesParams {
+            files: vec![FileRename {
+                old_uri: server.doc_id("src/a/bar.rs").uri.to_string(),
+                new_uri: server.doc_id("src/b/baz.rs").uri.to_string(),
+            }],
+        },
+        json!({
+            "documentChanges": [
+                {
+                    "textDocument": { "uri": "file:///[..]/src/a.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 0, "character": 0 },
+                                "end": { "line": 1, "character": 0 }
+                            },
+                            "newText": ""
+                        }
+                    ]
+                },
+                {
+                    "textDocument": { "uri": "file:///[..]/src/b.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 0, "character": 0 },
+                                "end": { "line": 0, "character": 0 }
+                            },
+                            "newText": "pub mod baz;\n"
+                        }
+                    ]
+                },
+                {
+                    "textDocument": { "uri": "file:///[..]/src/lib.rs", "version": null },
+                    "edits": [
+                        {
+                            "range": {
+                                "start": { "line": 2, "character": 4 },
+                                "end": { "line": 2, "character": 10 }
+                            },
+                            "newText": "b::baz"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
 #[test]
 fn diagnostics_dont_block_typing() {
     if skip_slow_tests() {
@@ -1,5 +1,7 @@
 //! Various extension methods to ast Expr Nodes, which are hard to code-generate.
 
+use rustc_lexer::unescape::{unescape_byte_literal, Mode};
+
 use crate::{
     ast::{self, support, AstChildren, AstNode},
     SmolStr,
@@ -358,6 +360,54 @@ impl ast::Literal {
             _ => unreachable!(),
         }
     }
+
+    /// Returns the value of an integer literal like `0x_ff_u8`, or `None` if
+    /// this isn't an integer literal or the value doesn't fit into `u128`.
+    pub fn int_value(&self) -> Option<u128> {
+        let suffix = match self.kind() {
+            LiteralKind::IntNumber { suffix } => suffix,
+            _ => return None,
+        };
+        let token = self.token();
+        let text = token.text().as_str();
+        let text = &text[..text.len() - suffix.map_or(0, |it| it.len())];
+        let (radix, digits) = match text.get(..2) {
+            Some("0x") => (16, &text[2..]),
+            Some("0o") => (8, &text[2..]),
+            Some("0b") => (2, &text[2..]),
+            _ => (10, text),
+        };
+        let digits = digits.replace('_', "");
+        u128::from_str_radix(&digits, radix).ok()
+    }
+
+    /// Returns the bytes of a (raw) byte string literal like `b"a\n"`, or
+    /// `None` if this isn't a byte string literal or it has invalid escapes.
+    pub fn byte_string_value(&self) -> Option<Vec<u8>> {
+        let token = self.token();
+        let text = token.text().as_str();
+        match token.kind() {
+            BYTE_STRING => {
+                let text = text.get(2..text.len() - 1)?;
+                let mut buf = Vec::with_capacity(text.len());
+                let mut has_error = false;
+                unescape_byte_literal(text, Mode::ByteStr, &mut |_, byte| match byte {
+                    Ok(b) => buf.push(b),
+                    Err(_) => has_error = true,
+                });
+                if has_error {
+                    return None;
+                }
+                Some(buf)
+            }
+            RAW_BYTE_STRING => {
+                let text = &text[2..];
+                let hashes = text.len() - text.trim_start_matches('#').len();
+                Some(text.get(hashes + 1..text.len() - hashes - 1)?.as_bytes().to_vec())
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
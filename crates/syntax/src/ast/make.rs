@@ -137,6 +137,14 @@ pub fn expr_prefix(op: SyntaxKind, expr: ast::Expr) -> ast::Expr {
 pub fn expr_call(f: ast::Expr, arg_list: ast::ArgList) -> ast::Expr {
     expr_from_text(&format!("{}{}", f, arg_list))
 }
+pub fn expr_tuple(elements: impl IntoIterator<Item = ast::Expr>) -> ast::Expr {
+    let expr = elements.into_iter().format(", ");
+    expr_from_text(&format!("({})", expr))
+}
+pub fn expr_literal(text: &str) -> ast::Literal {
+    assert_eq!(text.trim(), text);
+    ast_from_text(&format!("fn f() {{ let _ = {}; }}", text))
+}
 fn expr_from_text(text: &str) -> ast::Expr {
     ast_from_text(&format!("const C: () = {};", text))
 }
@@ -166,6 +174,14 @@ pub fn ident_pat(name: ast::Name) -> ast::IdentPat {
     }
 }
 
+pub fn ident_mut_pat(name: ast::Name) -> ast::IdentPat {
+    return from_text(name.text());
+
+    fn from_text(text: &str) -> ast::IdentPat {
+        ast_from_text(&format!("fn f(mut {}: ())", text))
+    }
+}
+
 pub fn wildcard_pat() -> ast::WildcardPat {
     return from_text("_");
 
@@ -174,6 +190,10 @@ pub fn wildcard_pat() -> ast::WildcardPat {
     }
 }
 
+pub fn rest_pat() -> ast::RestPat {
+    ast_from_text("fn f((..): ())")
+}
+
 /// Creates a tuple of patterns from an interator of patterns.
 ///
 /// Invariant: `pats` must be length > 1
@@ -209,6 +229,30 @@ pub fn record_pat(path: ast::Path, pats: impl IntoIterator<Item = ast::Pat>) ->
     }
 }
 
+pub fn record_pat_with_fields(path: ast::Path, fields: ast::RecordPatFieldList) -> ast::RecordPat {
+    ast_from_text(&format!("fn f({} {}: ())", path, fields))
+}
+
+pub fn record_pat_field_list(
+    fields: impl IntoIterator<Item = ast::RecordPatField>,
+    has_rest: bool,
+) -> ast::RecordPatFieldList {
+    let mut fields = fields.into_iter().map(|it| it.to_string()).collect::<Vec<_>>();
+    if has_rest {
+        fields.push("..".to_string());
+    }
+    ast_from_text(&format!("fn f(S {{ {} }}: ())", fields.join(", ")))
+}
+
+/// Returns the shorthand `pat` if there is no `name_ref`, `name_ref: pat`
+/// otherwise.
+pub fn record_pat_field(name_ref: Option<ast::NameRef>, pat: ast::Pat) -> ast::RecordPatField {
+    match name_ref {
+        None => ast_from_text(&format!("fn f(S {{ {} }}: ())", pat)),
+        Some(name_ref) => ast_from_text(&format!("fn f(S {{ {}: {} }}: ())", name_ref, pat)),
+    }
+}
+
 /// Returns a `BindPat` if the path has just one segment, a `PathPat` otherwise.
 pub fn path_pat(path: ast::Path) -> ast::Pat {
     return from_text(&path.to_string());
@@ -289,11 +333,27 @@ pub fn param(name: String, ty: String) -> ast::Param {
     ast_from_text(&format!("fn f({}: {}) {{ }}", name, ty))
 }
 
-pub fn param_list(pats: impl IntoIterator<Item = ast::Param>) -> ast::ParamList {
-    let args = pats.into_iter().join(", ");
+pub fn self_param(by_ref: bool) -> ast::SelfParam {
+    let text = if by_ref { "&self" } else { "self" };
+    ast_from_text(&format!("fn f({}) {{ }}", text))
+}
+
+pub fn param_list(
+    self_param: Option<ast::SelfParam>,
+    pats: impl IntoIterator<Item = ast::Param>,
+) -> ast::ParamList {
+    let args = self_param
+        .map(|it| it.to_string())
+        .into_iter()
+        .chain(pats.into_iter().map(|it| it.to_string()))
+        .join(", ");
     ast_from_text(&format!("fn f({}) {{ }}", args))
 }
 
+pub fn ret_type(ty: ast::Type) -> ast::RetType {
+    ast_from_text(&format!("fn f() -> {} {{ }}", ty))
+}
+
 pub fn visibility_pub_crate() -> ast::Visibility {
     ast_from_text("pub(crate) struct S")
 }
@@ -303,6 +363,7 @@ pub fn fn_(
     fn_name: ast::Name,
     type_params: Option<ast::GenericParamList>,
     params: ast::ParamList,
+    ret_type: Option<ast::RetType>,
     body: ast::BlockExpr,
 ) -> ast::Fn {
     let type_params =
@@ -311,7 +372,14 @@ pub fn fn_(
         None => String::new(),
         Some(it) => format!("{} ", it),
     };
-    ast_from_text(&format!("{}fn {}{}{} {}", visibility, fn_name, type_params, params, body))
+    let ret_type = match ret_type {
+        None => String::new(),
+        Some(it) => format!("{} ", it),
+    };
+    ast_from_text(&format!(
+        "{}fn {}{}{} {}{}",
+        visibility, fn_name, type_params, params, ret_type, body
+    ))
 }
 
 fn ast_from_text<N: AstNode>(text: &str) -> N {
@@ -146,13 +146,28 @@ export function createClient(serverPath: string, cwd: string): lc.LanguageClient
     // implementations are still in the "proposed" category for 3.16.
     client.registerFeature(new CallHierarchyFeature(client));
     client.registerFeature(new SemanticTokensFeature(client));
-    client.registerFeature(new ExperimentalFeatures());
+    client.registerFeature(new ExperimentalFeatures(client));
 
     return client;
 }
 
+const clientsSendingWillRenameFiles = new WeakSet<lc.LanguageClient>();
+
+// Whether the client sends `workspace/willRenameFiles` on its own, which
+// newer versions of the language client do.
+export function sendsWillRenameFiles(client: lc.LanguageClient): boolean {
+    return clientsSendingWillRenameFiles.has(client);
+}
+
 class ExperimentalFeatures implements lc.StaticFeature {
+    constructor(private readonly client: lc.LanguageClient) {
+    }
     fillClientCapabilities(capabilities: lc.ClientCapabilities): void {
+        // Called after the built-in features filled in their capabilities.
+        const workspace: any = capabilities.workspace;
+        if (workspace?.fileOperations?.willRename) {
+            clientsSendingWillRenameFiles.add(this.client);
+        }
         const caps: any = capabilities.experimental ?? {};
         caps.snippetTextEdit = true;
         caps.codeActionGroup = true;
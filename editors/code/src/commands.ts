@@ -422,6 +422,13 @@ export function resolveCodeAction(ctx: Ctx): Cmd {
             return;
         }
         const edit = client.protocol2CodeConverter.asWorkspaceEdit(item);
+        // Edits which create or delete files span several documents and
+        // never contain snippets.
+        const hasFileOperations = item.documentChanges?.some((change) => 'kind' in change);
+        if (hasFileOperations) {
+            await vscode.workspace.applyEdit(edit);
+            return;
+        }
         await applySnippetWorkspaceEdit(edit);
     };
 }
@@ -102,6 +102,9 @@ export class Config {
             typeHints: this.get<boolean>("inlayHints.typeHints"),
             parameterHints: this.get<boolean>("inlayHints.parameterHints"),
             chainingHints: this.get<boolean>("inlayHints.chainingHints"),
+            lifetimeElisionHints: this.get<boolean>("inlayHints.lifetimeElisionHints"),
+            closureReturnTypeHints: this.get<boolean>("inlayHints.closureReturnTypeHints"),
+            reborrowHints: this.get<boolean>("inlayHints.reborrowHints"),
             maxLength: this.get<null | number>("inlayHints.maxLength"),
         };
     }
@@ -72,13 +72,53 @@ export interface Runnable {
 }
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>("experimental/runnables");
 
-export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
+export interface TestItem {
+    id: string;
+    parent?: string;
+    kind: "crate" | "module" | "test";
+    label: string;
+    location: lc.Location;
+    runnable?: Runnable;
+}
+export const discoverTests = new lc.RequestType<null, TestItem[], void>("rust-analyzer/discoverTests");
+
+export interface RunTestsParams {
+    include: string[] | null;
+}
+export const runTests = new lc.RequestType<RunTestsParams, number, void>("rust-analyzer/runTests");
+
+export type TestState =
+    | { tag: "started" }
+    | { tag: "passed"; durationMs: number | null }
+    | { tag: "failed"; durationMs: number | null; message: string; location: lc.Location | null }
+    | { tag: "skipped" };
+export interface ChangeTestStateParams {
+    runId: number;
+    testId: string;
+    state: TestState;
+}
+export const changeTestState = new lc.NotificationType<ChangeTestStateParams>("rust-analyzer/changeTestState");
+export interface EndRunTestsParams {
+    runId: number;
+}
+export const endRunTests = new lc.NotificationType<EndRunTestsParams>("rust-analyzer/endRunTests");
+
+export type InlayHint =
+    | InlayHint.TypeHint
+    | InlayHint.ParamHint
+    | InlayHint.ChainingHint
+    | InlayHint.LifetimeHint
+    | InlayHint.ClosureReturnTypeHint
+    | InlayHint.ReborrowHint;
 
 export namespace InlayHint {
     export const enum Kind {
         TypeHint = "TypeHint",
         ParamHint = "ParameterHint",
         ChainingHint = "ChainingHint",
+        LifetimeHint = "LifetimeHint",
+        ClosureReturnTypeHint = "ClosureReturnTypeHint",
+        ReborrowHint = "ReborrowHint",
     }
     interface Common {
         range: lc.Range;
@@ -87,6 +127,9 @@ export namespace InlayHint {
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
+    export type LifetimeHint = Common & { kind: Kind.LifetimeHint };
+    export type ClosureReturnTypeHint = Common & { kind: Kind.ClosureReturnTypeHint };
+    export type ReborrowHint = Common & { kind: Kind.ReborrowHint };
 }
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;
@@ -102,6 +145,15 @@ export interface SsrParams {
 }
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>('experimental/ssr');
 
+export interface FileRename {
+    oldUri: string;
+    newUri: string;
+}
+export interface RenameFilesParams {
+    files: FileRename[];
+}
+export const willRenameFiles = new lc.RequestType<RenameFilesParams, lc.WorkspaceEdit | null, void>('workspace/willRenameFiles');
+
 export interface CommandLink extends lc.Command {
     /**
      * A tooltip for the command, when represented in the UI.
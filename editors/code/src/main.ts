@@ -4,8 +4,10 @@ import * as os from "os";
 import { promises as fs, PathLike } from "fs";
 
 import * as commands from './commands';
+import * as ra from './lsp_ext';
 import { activateInlayHints } from './inlay_hints';
 import { Ctx } from './ctx';
+import { sendsWillRenameFiles } from './client';
 import { Config, NIGHTLY_TAG } from './config';
 import { log, assert, isValidExecutable } from './util';
 import { PersistentState } from './persistent_state';
@@ -132,6 +134,27 @@ async function tryActivate(context: vscode.ExtensionContext) {
         null,
         ctx.subscriptions,
     );
+
+    // Older language clients don't send `workspace/willRenameFiles`, so we ask
+    // the server to fix up module declarations before files are moved.
+    vscode.workspace.onWillRenameFiles(
+        event => event.waitUntil(willRenameFiles(event)),
+        null,
+        ctx.subscriptions,
+    );
+}
+
+async function willRenameFiles(event: vscode.FileWillRenameEvent): Promise<vscode.WorkspaceEdit> {
+    const client = ctx?.client;
+    if (!client || sendsWillRenameFiles(client)) return new vscode.WorkspaceEdit();
+
+    const files = event.files.map(({ oldUri, newUri }) => ({
+        oldUri: oldUri.toString(),
+        newUri: newUri.toString(),
+    }));
+    const edit = await client.sendRequest(ra.willRenameFiles, { files });
+    if (!edit) return new vscode.WorkspaceEdit();
+    return client.protocol2CodeConverter.asWorkspaceEdit(edit);
 }
 
 export async function deactivate() {